@@ -33,6 +33,7 @@ use deno_core::url::Url;
 use deno_graph::GraphKind;
 use deno_lib::args::CaData;
 use deno_lib::args::UnstableConfig;
+use deno_lib::standalone::binary::WritableVfs;
 use deno_lib::version::DENO_VERSION_INFO;
 use deno_npm::NpmSystemInfo;
 use deno_npm_installer::PackagesAllowedScripts;
@@ -40,10 +41,12 @@ use deno_path_util::normalize_path;
 use deno_path_util::resolve_url_or_path;
 use deno_path_util::url_to_file_path;
 use deno_runtime::UnstableFeatureKind;
+use deno_runtime::deno_permissions::PackagePermissionsOptions;
 use deno_runtime::deno_permissions::SysDescriptor;
 use deno_telemetry::OtelConfig;
 use deno_telemetry::OtelConsoleConfig;
 use deno_telemetry::OtelPropagators;
+use deno_telemetry::OtelSampler;
 use log::Level;
 use log::debug;
 use serde::Deserialize;
@@ -107,6 +110,32 @@ pub struct RemoveFlags {
   pub packages: Vec<String>,
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub enum AuditSeverity {
+  #[default]
+  Low,
+  Moderate,
+  High,
+  Critical,
+}
+
+impl AuditSeverity {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      AuditSeverity::Low => "low",
+      AuditSeverity::Moderate => "moderate",
+      AuditSeverity::High => "high",
+      AuditSeverity::Critical => "critical",
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditFlags {
+  pub level: AuditSeverity,
+  pub advisories: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct BenchFlags {
   pub files: FileFlags,
@@ -139,6 +168,8 @@ pub struct CompileFlags {
   pub icon: Option<String>,
   pub include: Vec<String>,
   pub exclude: Vec<String>,
+  pub compress: bool,
+  pub writable_vfs: Option<WritableVfs>,
   pub eszip: bool,
 }
 
@@ -287,6 +318,18 @@ pub struct JupyterFlags {
   pub force: bool,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KvFlags {
+  Export {
+    database: String,
+    output: Option<String>,
+  },
+  Import {
+    database: String,
+    input: Option<String>,
+  },
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UninstallFlagsGlobal {
   pub name: String,
@@ -440,6 +483,34 @@ pub struct TestFlags {
   pub hide_stacktraces: bool,
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SbomFormat {
+  #[default]
+  CycloneDx,
+  Spdx,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SbomFlags {
+  pub format: SbomFormat,
+  pub output: Option<String>,
+  pub entrypoints: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WhyFlags {
+  pub package: String,
+  pub entrypoints: Vec<String>,
+  pub json: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnpackFlags {
+  pub binary: String,
+  pub json: bool,
+  pub output: Option<String>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UpgradeFlags {
   pub dry_run: bool,
@@ -547,6 +618,7 @@ impl std::fmt::Display for PackageHandling {
 pub enum DenoSubcommand {
   Add(AddFlags),
   Remove(RemoveFlags),
+  Audit(AuditFlags),
   Bench(BenchFlags),
   Bundle(BundleFlags),
   Cache(CacheFlags),
@@ -564,18 +636,22 @@ pub enum DenoSubcommand {
   Install(InstallFlags),
   JSONReference(JSONReferenceFlags),
   Jupyter(JupyterFlags),
+  Kv(KvFlags),
   Uninstall(UninstallFlags),
   Lsp,
   Lint(LintFlags),
   Repl(ReplFlags),
   Run(RunFlags),
+  Sbom(SbomFlags),
   Serve(ServeFlags),
   Task(TaskFlags),
   Test(TestFlags),
   Outdated(OutdatedFlags),
   Types,
+  Unpack(UnpackFlags),
   Upgrade(UpgradeFlags),
   Vendor,
+  Why(WhyFlags),
   Publish(PublishFlags),
   Help(HelpFlags),
 }
@@ -817,6 +893,25 @@ pub struct PermissionFlags {
   pub no_prompt: bool,
   pub allow_import: Option<Vec<String>>,
   pub deny_import: Option<Vec<String>>,
+  /// Name of a permission set declared in the config file.
+  pub permission_set: Option<String>,
+  pub learn_permissions: Option<PermissionLearnFormat>,
+  /// Path of a Unix socket or `fd:<number>` of an inherited socket that
+  /// permission prompts are sent to instead of the terminal.
+  pub permission_broker: Option<String>,
+  /// Restrictions on npm and jsr packages, which can only be declared in
+  /// a permission set.
+  pub packages: Vec<PackagePermissionsOptions>,
+}
+
+/// How to output the permissions collected by `--learn-permissions`.
+#[derive(
+  Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize,
+)]
+pub enum PermissionLearnFormat {
+  #[default]
+  Flags,
+  Config,
 }
 
 impl PermissionFlags {
@@ -838,6 +933,8 @@ impl PermissionFlags {
       || self.deny_write.is_some()
       || self.allow_import.is_some()
       || self.deny_import.is_some()
+      || self.permission_set.is_some()
+      || self.learn_permissions.is_some()
   }
 }
 
@@ -1050,6 +1147,10 @@ impl Flags {
       _ => {}
     }
 
+    if let Some(permission_set) = &self.permissions.permission_set {
+      args.push(format!("--permission-set={permission_set}"));
+    }
+
     args
   }
 
@@ -1093,12 +1194,24 @@ impl Flags {
       HashSet::default()
     };
 
+    let sampler = match std::env::var("OTEL_TRACES_SAMPLER") {
+      Ok(sampler) if !sampler.is_empty() => {
+        let arg = std::env::var("OTEL_TRACES_SAMPLER_ARG").ok();
+        OtelSampler::parse(&sampler, arg.as_deref()).unwrap_or_else(|err| {
+          log::warn!("{err}");
+          OtelSampler::default()
+        })
+      }
+      _ => OtelSampler::default(),
+    };
+
     OtelConfig {
       tracing_enabled: !disabled
         && otel_var("OTEL_DENO_TRACING").unwrap_or(default),
       metrics_enabled: !disabled
         && otel_var("OTEL_DENO_METRICS").unwrap_or(default),
       propagators,
+      sampler,
       console: match std::env::var("OTEL_DENO_CONSOLE").as_deref() {
         Ok(_) if disabled => OtelConsoleConfig::Ignore,
         Ok("ignore") => OtelConsoleConfig::Ignore,
@@ -1275,6 +1388,9 @@ static ENV_VARIABLES_HELP: &str = cstr!(
   <g>DENO_CERT</>              Load certificate authorities from PEM encoded file
   <g>DENO_COMPAT</>            Enable Node.js compatibility mode - extensionless imports, built-in
                                Node.js modules, CommonJS detection and more.
+  <g>DENO_CRON_DB_PATH</>      Persist Deno.cron schedules in the given SQLite database, so that
+                         missed runs are caught up on restart and each run executes in a
+                         single process.
   <g>DENO_DIR</>               Set the cache directory
   <g>DENO_INSTALL_ROOT</>      Set deno install's output directory
                           <p(245)>(defaults to $HOME/.deno/bin)</>
@@ -1283,6 +1399,15 @@ static ENV_VARIABLES_HELP: &str = cstr!(
   <g>DENO_EMIT_CACHE_MODE</>   Control if the transpiled sources should be cached.
   <g>DENO_NO_PACKAGE_JSON</>   Disables auto-resolution of package.json
   <g>DENO_NO_UPDATE_CHECK</>   Set to disable checking if a newer Deno version is available
+  <g>DENO_PERMISSION_AUDIT</>  Append a JSON line for every permission query, check, request
+                         and revoke to this file
+  <g>DENO_PERMISSION_BROKER</> Send permission prompts to a broker listening on this Unix socket
+                         path or an inherited socket, like "fd:3"
+  <g>DENO_PERMISSION_BROKER_TIMEOUT</>
+                         Seconds to wait for a reply from the permission broker
+                         <p(245)>(defaults to 30)</>
+  <g>DENO_PERMISSION_SET</>    Name of the permission set from the config file to use, like
+                         --permission-set
   <g>DENO_SERVE_ADDRESS</>     Override address for Deno.serve
                          Example: "tcp:0.0.0.0:8080", "unix:/tmp/deno.sock", or "vsock:1234:5678"
   <g>DENO_AUTO_SERVE</>        If the entrypoint contains export default { fetch }, `deno run`
@@ -1323,10 +1448,15 @@ static DENO_HELP: &str = cstr!(
   <y>Dependency management:</>
     <g>add</>          Add dependencies
                   <p(245)>deno add jsr:@std/assert  |  deno add npm:express</>
+    <g>audit</>        Report npm dependencies with known vulnerabilities
     <g>install</>      Installs dependencies either in the local project or globally to a bin directory
     <g>uninstall</>    Uninstalls a dependency or an executable script in the installation root's bin directory
     <g>outdated</>     Find and update outdated dependencies
     <g>remove</>       Remove dependencies from the configuration file
+    <g>sbom</>         Generate a software bill of materials
+                  <p(245)>deno sbom  |  deno sbom --format=spdx --output=sbom.json</>
+    <g>why</>          Explain why a package is in the dependency graph
+                  <p(245)>deno why npm:debug  |  deno why @std/path main.ts</>
 
   <y>Tooling:</>
     <g>bench</>        Run benchmarks
@@ -1343,10 +1473,12 @@ static DENO_HELP: &str = cstr!(
                   <p(245)>deno fmt  |  deno fmt main.ts</>
     <g>info</>         Show info about cache or info related to source file
     <g>jupyter</>      Deno kernel for Jupyter notebooks
+    <g>kv</>           Export and import Deno KV databases
     <g>lint</>         Lint source files
     <g>init</>         Initialize a new project
     <g>test</>         Run tests
                   <p(245)>deno test  |  deno test test.ts</>
+    <g>unpack</>       Inspect and extract the contents of an executable produced by deno compile
     <g>publish</>      Publish the current working directory's package or workspace
     <g>upgrade</>      Upgrade deno executable to given version
                   <p(245)>deno upgrade  |  deno upgrade 1.45.0  |  deno upgrade canary</>
@@ -1508,6 +1640,7 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
       match subcommand.as_str() {
         "add" => add_parse(&mut flags, &mut m)?,
         "remove" => remove_parse(&mut flags, &mut m),
+        "audit" => audit_parse(&mut flags, &mut m),
         "bench" => bench_parse(&mut flags, &mut m)?,
         "bundle" => bundle_parse(&mut flags, &mut m)?,
         "cache" => cache_parse(&mut flags, &mut m)?,
@@ -1524,19 +1657,23 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
         "install" => install_parse(&mut flags, &mut m, app)?,
         "json_reference" => json_reference_parse(&mut flags, &mut m, app),
         "jupyter" => jupyter_parse(&mut flags, &mut m),
+        "kv" => kv_parse(&mut flags, &mut m),
         "lint" => lint_parse(&mut flags, &mut m)?,
         "lsp" => lsp_parse(&mut flags, &mut m),
         "outdated" => outdated_parse(&mut flags, &mut m, false)?,
         "repl" => repl_parse(&mut flags, &mut m)?,
+        "sbom" => sbom_parse(&mut flags, &mut m),
         "run" => run_parse(&mut flags, &mut m, app, false)?,
         "serve" => serve_parse(&mut flags, &mut m, app)?,
         "task" => task_parse(&mut flags, &mut m, app)?,
         "test" => test_parse(&mut flags, &mut m)?,
         "types" => types_parse(&mut flags, &mut m),
         "uninstall" => uninstall_parse(&mut flags, &mut m),
+        "unpack" => unpack_parse(&mut flags, &mut m),
         "update" => outdated_parse(&mut flags, &mut m, true)?,
         "upgrade" => upgrade_parse(&mut flags, &mut m),
         "vendor" => vendor_parse(&mut flags, &mut m),
+        "why" => why_parse(&mut flags, &mut m),
         "publish" => publish_parse(&mut flags, &mut m)?,
         _ => unreachable!(),
       }
@@ -1770,6 +1907,7 @@ pub fn clap_root() -> Command {
       let cmd = cmd
         .subcommand(add_subcommand())
         .subcommand(remove_subcommand())
+        .subcommand(audit_subcommand())
         .subcommand(bench_subcommand())
         .subcommand(bundle_subcommand())
         .subcommand(cache_subcommand())
@@ -1787,18 +1925,22 @@ pub fn clap_root() -> Command {
         .subcommand(install_subcommand())
         .subcommand(json_reference_subcommand())
         .subcommand(jupyter_subcommand())
+        .subcommand(kv_subcommand())
         .subcommand(uninstall_subcommand())
         .subcommand(outdated_subcommand())
         .subcommand(lsp_subcommand())
         .subcommand(lint_subcommand())
         .subcommand(publish_subcommand())
         .subcommand(repl_subcommand())
+        .subcommand(sbom_subcommand())
         .subcommand(task_subcommand())
         .subcommand(test_subcommand())
         .subcommand(types_subcommand())
+        .subcommand(unpack_subcommand())
         .subcommand(update_subcommand())
         .subcommand(upgrade_subcommand())
-        .subcommand(vendor_subcommand());
+        .subcommand(vendor_subcommand())
+        .subcommand(why_subcommand());
 
       let help = help_subcommand(&cmd);
       cmd.subcommand(help)
@@ -1910,6 +2052,40 @@ You can remove multiple dependencies at once:
   })
 }
 
+fn audit_subcommand() -> Command {
+  command(
+    "audit",
+    cstr!("Report npm dependencies with known vulnerabilities.
+
+Check the npm packages in the lockfile against the advisories of the configured registry:
+  <p(245)>deno audit</>
+
+Only fail when there are vulnerabilities of at least a certain severity:
+  <p(245)>deno audit --level=high</>
+
+Check against a local directory of OSV advisories instead, for example in offline CI:
+  <p(245)>deno audit --advisories=./advisories</>"),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
+      .arg(
+        Arg::new("level")
+          .long("level")
+          .help("Exit with an error only for vulnerabilities of at least this severity")
+          .value_parser(["low", "moderate", "high", "critical"])
+          .default_value("low"),
+      )
+      .arg(
+        Arg::new("advisories")
+          .long("advisories")
+          .help("Directory of OSV advisory JSON files to use instead of the registry")
+          .value_hint(ValueHint::DirPath),
+      )
+      .args(lock_args())
+  })
+}
+
 fn bench_subcommand() -> Command {
   command(
     "bench",
@@ -2318,6 +2494,25 @@ On the first invocation of `deno compile`, Deno will download the relevant binar
           .value_parser(value_parser!(String))
           .help_heading(COMPILE_HEADING),
       )
+      .arg(
+        Arg::new("writable-vfs")
+          .long("writable-vfs")
+          .value_name("DIR")
+          .help(cstr!("Allow writing to the embedded files by storing the changes in a directory
+  <p(245)>Without a directory, the changes are stored in a temporary directory that's removed on exit.</>"))
+          .num_args(0..=1)
+          .require_equals(true)
+          .value_parser(value_parser!(String))
+          .value_hint(ValueHint::DirPath)
+          .help_heading(COMPILE_HEADING),
+      )
+      .arg(
+        Arg::new("compress")
+          .long("compress")
+          .help("Compress the embedded files with zstd to reduce the size of the executable")
+          .action(ArgAction::SetTrue)
+          .help_heading(COMPILE_HEADING),
+      )
       .arg(executable_ext_arg())
       .arg(env_file_arg())
       .arg(
@@ -3024,6 +3219,55 @@ fn jupyter_subcommand() -> Command {
         .conflicts_with("install"))
 }
 
+fn kv_subcommand() -> Command {
+  command(
+    "kv",
+    cstr!("Export and import Deno KV databases.
+
+Entries are exported as newline-delimited JSON, in the same format as <c>Deno.Kv.prototype.export()</>:
+  <p(245)>deno kv export ./data.sqlite3 --output backup.ndjson</>
+
+Import the entries into another database, overwriting entries with the same keys:
+  <p(245)>deno kv import ./fixtures.sqlite3 --input backup.ndjson</>"),
+    UnstableArgsConfig::None,
+  )
+  .subcommand_required(true)
+  .subcommand(
+    Command::new("export")
+      .about("Export the entries of a database")
+      .arg(
+        Arg::new("database")
+          .help("Path of the SQLite database")
+          .required(true)
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("output")
+          .long("output")
+          .short('o')
+          .help("Write the entries to the given file instead of stdout")
+          .value_hint(ValueHint::FilePath),
+      ),
+  )
+  .subcommand(
+    Command::new("import")
+      .about("Import entries into a database")
+      .arg(
+        Arg::new("database")
+          .help("Path of the SQLite database, which is created if it doesn't exist")
+          .required(true)
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("input")
+          .long("input")
+          .short('i')
+          .help("Read the entries from the given file instead of stdin")
+          .value_hint(ValueHint::FilePath),
+      ),
+  )
+}
+
 fn update_and_outdated_args() -> [Arg; 4] {
   [
     Arg::new("filters")
@@ -3688,6 +3932,125 @@ The declaration file could be saved and used for typing information."
   )
 }
 
+fn unpack_subcommand() -> Command {
+  command(
+    "unpack",
+    cstr!("Inspect and extract the contents of an executable produced by <c>deno compile</>.
+
+Show the metadata of the executable, such as its permissions and arguments, and list the embedded files:
+  <p(245)>deno unpack ./my_app</>
+
+Extract the embedded files, remote modules and source maps to a directory:
+  <p(245)>deno unpack ./my_app --output ./my_app_contents</>"),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
+      .arg(
+        Arg::new("binary")
+          .help("Path of the executable")
+          .required_unless_present("help")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("output")
+          .long("output")
+          .short('o')
+          .help("Extract the contents to the given directory")
+          .value_parser(value_parser!(String))
+          .value_hint(ValueHint::DirPath),
+      )
+      .arg(
+        Arg::new("json")
+          .long("json")
+          .help("Output the metadata and contents in JSON format")
+          .action(ArgAction::SetTrue),
+      )
+  })
+}
+
+fn sbom_subcommand() -> Command {
+  command(
+    "sbom",
+    cstr!("Generate a software bill of materials (SBOM).
+
+List the jsr and npm packages and the remote modules in the lockfile, with their integrity hashes, licenses and dependencies, as CycloneDX JSON:
+  <p(245)>deno sbom</>
+
+Output SPDX JSON instead, to a file:
+  <p(245)>deno sbom --format=spdx --output=sbom.spdx.json</>
+
+Include the dependencies of entrypoints:
+  <p(245)>deno sbom main.ts</>"),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
+      .arg(
+        Arg::new("entrypoints")
+          .help("Modules whose dependencies should also be included")
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("format")
+          .long("format")
+          .help("The SBOM format to output")
+          .value_parser(["cyclonedx", "spdx"])
+          .default_value("cyclonedx"),
+      )
+      .arg(
+        Arg::new("output")
+          .long("output")
+          .short('o')
+          .help("Write the SBOM to a file instead of stdout")
+          .value_hint(ValueHint::FilePath),
+      )
+      .args(lock_args())
+  })
+}
+
+fn why_subcommand() -> Command {
+  command(
+    "why",
+    cstr!("Explain why a package is in the dependency graph.
+
+Show every chain of dependencies from the packages in deno.json / package.json to an npm or jsr package:
+  <p(245)>deno why npm:debug</>
+  <p(245)>deno why @std/path</>
+
+Only show the chains that lead to specific versions:
+  <p(245)>deno why npm:debug@^2</>
+
+Include the dependencies of entrypoints:
+  <p(245)>deno why npm:debug main.ts</>"),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
+      .arg(
+        Arg::new("package")
+          .help("The npm or jsr package, optionally with a version requirement")
+          .required_unless_present("help"),
+      )
+      .arg(
+        Arg::new("entrypoints")
+          .help("Modules whose dependencies should also be searched")
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("json")
+          .long("json")
+          .help("Output the dependency chains in JSON format")
+          .action(ArgAction::SetTrue),
+      )
+      .args(lock_args())
+  })
+}
+
 pub static UPGRADE_USAGE: &str = cstr!(
   "<g>Latest</>
   <bold>deno upgrade</>
@@ -3875,6 +4238,15 @@ fn permission_args(app: Command, requires: Option<&'static str>) -> Command {
   <g>-A, --allow-all</>                          Allow all permissions.
   <g>--no-prompt</>                              Always throw if required permission wasn't passed.
                                              <p(245)>Can also be set via the DENO_NO_PROMPT environment variable.</>
+  <g>-P, --permission-set[=<<NAME>]</>            Use a permission set from the "permissions" of the config file.
+                                             <p(245)>Defaults to the "default" set. Can also be set via the DENO_PERMISSION_SET environment variable.</>
+                                             <p(245)>-P  |  --permission-set=ci</>
+  <g>--learn-permissions[=<<FORMAT>]</>           Allow all permissions that aren't denied and print the permissions that were used on exit.
+                                             <p(245)>Outputs either the permission flags or a permission set for the config file.</>
+                                             <p(245)>--learn-permissions  |  --learn-permissions=config</>
+  <g>--permission-broker=<<ADDRESS></>            Send permission prompts to a broker listening on this Unix socket path or an inherited socket.
+                                             <p(245)>Can also be set via the DENO_PERMISSION_BROKER environment variable.</>
+                                             <p(245)>--permission-broker=/tmp/broker.sock  |  --permission-broker=fd:3</>
   <g>-R, --allow-read[=<<PATH>...]</>             Allow file system read access. Optionally specify allowed paths.
                                              <p(245)>--allow-read  |  --allow-read="/etc,/var/log.txt"</>
   <g>-W, --allow-write[=<<PATH>...]</>            Allow file system write access. Optionally specify allowed paths.
@@ -3910,6 +4282,8 @@ fn permission_args(app: Command, requires: Option<&'static str>) -> Command {
                                              <p(245)>--deny-import  |  --deny-import="example.com:443,github.com:443"</>
       <g>DENO_TRACE_PERMISSIONS</>               Environmental variable to enable stack traces in permission prompts.
                                              <p(245)>DENO_TRACE_PERMISSIONS=1 deno run main.ts</>
+      <g>DENO_PERMISSION_AUDIT</>                Environmental variable to log every permission query, check, request and revoke to a JSON lines file.
+                                             <p(245)>DENO_PERMISSION_AUDIT=./audit.jsonl deno run main.ts</>
 "#))
     .arg(
       {
@@ -4221,6 +4595,67 @@ fn permission_args(app: Command, requires: Option<&'static str>) -> Command {
         arg
       }
     )
+    .arg(
+      {
+        let mut arg = Arg::new("permission-set")
+          .long("permission-set")
+          .short('P')
+          .num_args(0..=1)
+          .require_equals(true)
+          .default_missing_value("default")
+          .value_name("NAME")
+          .long_help("false")
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("learn-permissions")
+          .long("learn-permissions")
+          .num_args(0..=1)
+          .require_equals(true)
+          .default_missing_value("flags")
+          .value_parser(["flags", "config"])
+          .value_name("FORMAT")
+          .conflicts_with_all([
+            "allow-all",
+            "allow-read",
+            "allow-write",
+            "allow-net",
+            "allow-env",
+            "allow-run",
+            "allow-sys",
+            "allow-ffi",
+            "allow-import",
+            "permission-set",
+          ])
+          .long_help("false")
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("permission-broker")
+          .long("permission-broker")
+          .require_equals(true)
+          .value_name("ADDRESS")
+          .value_hint(ValueHint::AnyPath)
+          .long_help("false")
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
     .arg(
       {
         let mut arg = allow_import_arg().hide(true);
@@ -4961,6 +5396,20 @@ fn remove_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   });
 }
 
+fn audit_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  lock_args_parse(flags, matches);
+  let level = match matches.remove_one::<String>("level").as_deref() {
+    Some("moderate") => AuditSeverity::Moderate,
+    Some("high") => AuditSeverity::High,
+    Some("critical") => AuditSeverity::Critical,
+    _ => AuditSeverity::Low,
+  };
+  flags.subcommand = DenoSubcommand::Audit(AuditFlags {
+    level,
+    advisories: matches.remove_one::<String>("advisories"),
+  });
+}
+
 fn outdated_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
@@ -5138,6 +5587,14 @@ fn compile_parse(
   let target = matches.remove_one::<String>("target");
   let icon = matches.remove_one::<String>("icon");
   let no_terminal = matches.get_flag("no-terminal");
+  let compress = matches.get_flag("compress");
+  let writable_vfs = match matches.remove_many::<String>("writable-vfs") {
+    Some(mut dir) => Some(match dir.next() {
+      Some(dir) => WritableVfs::Dir(dir),
+      None => WritableVfs::TempDir,
+    }),
+    None => None,
+  };
   let eszip = matches.get_flag("eszip-internal-do-not-use");
   let include = matches
     .remove_many::<String>("include")
@@ -5160,6 +5617,8 @@ fn compile_parse(
     icon,
     include,
     exclude,
+    compress,
+    writable_vfs,
     eszip,
   });
 
@@ -5613,6 +6072,21 @@ fn jupyter_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   });
 }
 
+fn kv_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let kv_flags = match matches.remove_subcommand() {
+    Some((name, mut matches)) if name == "export" => KvFlags::Export {
+      database: matches.remove_one::<String>("database").unwrap(),
+      output: matches.remove_one::<String>("output"),
+    },
+    Some((name, mut matches)) if name == "import" => KvFlags::Import {
+      database: matches.remove_one::<String>("database").unwrap(),
+      input: matches.remove_one::<String>("input"),
+    },
+    _ => unreachable!(),
+  };
+  flags.subcommand = DenoSubcommand::Kv(kv_flags);
+}
+
 fn uninstall_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   lock_args_parse(flags, matches);
   let name = matches.remove_one::<String>("name-or-package").unwrap();
@@ -6002,6 +6476,42 @@ fn types_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Types;
 }
 
+fn unpack_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Unpack(UnpackFlags {
+    binary: matches.remove_one::<String>("binary").unwrap(),
+    json: matches.get_flag("json"),
+    output: matches.remove_one::<String>("output"),
+  });
+}
+
+fn sbom_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  lock_args_parse(flags, matches);
+  let format = match matches.remove_one::<String>("format").as_deref() {
+    Some("spdx") => SbomFormat::Spdx,
+    _ => SbomFormat::CycloneDx,
+  };
+  flags.subcommand = DenoSubcommand::Sbom(SbomFlags {
+    format,
+    output: matches.remove_one::<String>("output"),
+    entrypoints: matches
+      .remove_many::<String>("entrypoints")
+      .map(|e| e.collect())
+      .unwrap_or_default(),
+  });
+}
+
+fn why_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  lock_args_parse(flags, matches);
+  flags.subcommand = DenoSubcommand::Why(WhyFlags {
+    package: matches.remove_one::<String>("package").unwrap(),
+    entrypoints: matches
+      .remove_many::<String>("entrypoints")
+      .map(|e| e.collect())
+      .unwrap_or_default(),
+    json: matches.get_flag("json"),
+  });
+}
+
 fn upgrade_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   ca_file_arg_parse(flags, matches);
   unsafely_ignore_certificate_errors_parse(flags, matches);
@@ -6257,6 +6767,21 @@ fn allow_and_deny_import_parse(
     let imports_denylist = flags_net::parse(imports_wl.collect())?;
     flags.permissions.deny_import = Some(imports_denylist);
   }
+
+  if let Some(permission_set) = matches.remove_one::<String>("permission-set") {
+    flags.permissions.permission_set = Some(permission_set);
+  }
+
+  if let Some(address) = matches.remove_one::<String>("permission-broker") {
+    flags.permissions.permission_broker = Some(address);
+  }
+
+  if let Some(format) = matches.remove_one::<String>("learn-permissions") {
+    flags.permissions.learn_permissions = Some(match format.as_str() {
+      "config" => PermissionLearnFormat::Config,
+      _ => PermissionLearnFormat::Flags,
+    });
+  }
   Ok(())
 }
 
@@ -8542,6 +9067,105 @@ mod tests {
     );
   }
 
+  #[test]
+  fn permission_set() {
+    let r = flags_from_vec(svec!["deno", "run", "-P", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        permissions: PermissionFlags {
+          permission_set: Some("default".to_string()),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--permission-set=ci",
+      "--allow-net"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(flags.permissions.permission_set, Some("ci".to_string()));
+    assert_eq!(flags.permissions.allow_net, Some(vec![]));
+    assert!(
+      flags
+        .to_permission_args()
+        .contains(&"--permission-set=ci".to_string())
+    );
+  }
+
+  #[test]
+  fn permission_broker() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permission-broker=fd:3",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        permissions: PermissionFlags {
+          permission_broker: Some("fd:3".to_string()),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn learn_permissions() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--learn-permissions",
+      "--deny-env",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        permissions: PermissionFlags {
+          learn_permissions: Some(PermissionLearnFormat::Flags),
+          deny_env: Some(vec![]),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--learn-permissions=config"]);
+    assert_eq!(
+      r.unwrap().permissions.learn_permissions,
+      Some(PermissionLearnFormat::Config)
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--learn-permissions",
+      "--allow-read",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn deny_read_denylist() {
     use test_util::TempDir;
@@ -11023,6 +11647,8 @@ mod tests {
           icon: None,
           include: Default::default(),
           exclude: Default::default(),
+          compress: false,
+          writable_vfs: None,
           eszip: false,
         }),
         type_check_mode: TypeCheckMode::Local,
@@ -11035,7 +11661,7 @@ mod tests {
   #[test]
   fn compile_with_flags() {
     #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "compile", "--include", "include.txt", "--exclude", "exclude.txt", "--import-map", "import_map.json", "--no-code-cache", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--no-terminal", "--icon", "favicon.ico", "--output", "colors", "--env=.example.env", "https://examples.deno.land/color-logging.ts", "foo", "bar", "-p", "8080"]);
+    let r = flags_from_vec(svec!["deno", "compile", "--include", "include.txt", "--exclude", "exclude.txt", "--import-map", "import_map.json", "--no-code-cache", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--no-terminal", "--icon", "favicon.ico", "--compress", "--writable-vfs=data", "--output", "colors", "--env=.example.env", "https://examples.deno.land/color-logging.ts", "foo", "bar", "-p", "8080"]);
     assert_eq!(
       r.unwrap(),
       Flags {
@@ -11049,6 +11675,8 @@ mod tests {
           icon: Some(String::from("favicon.ico")),
           include: vec!["include.txt".to_string()],
           exclude: vec!["exclude.txt".to_string()],
+          compress: true,
+          writable_vfs: Some(WritableVfs::Dir("data".to_string())),
           eszip: false
         }),
         import_map_path: Some("import_map.json".to_string()),
@@ -11075,6 +11703,21 @@ mod tests {
     );
   }
 
+  #[test]
+  fn compile_writable_vfs() {
+    let r =
+      flags_from_vec(svec!["deno", "compile", "--writable-vfs", "main.ts"]);
+    let Ok(Flags {
+      subcommand: DenoSubcommand::Compile(compile_flags),
+      ..
+    }) = r
+    else {
+      panic!("unexpected result: {:?}", r);
+    };
+    assert_eq!(compile_flags.writable_vfs, Some(WritableVfs::TempDir));
+    assert_eq!(compile_flags.source_file, "main.ts");
+  }
+
   #[test]
   fn coverage() {
     let r = flags_from_vec(svec!["deno", "coverage", "foo.json"]);
@@ -12104,6 +12747,44 @@ mod tests {
     }
   }
 
+  #[test]
+  fn audit_subcommand() {
+    let r = flags_from_vec(svec!["deno", "audit"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Audit(AuditFlags {
+          level: AuditSeverity::Low,
+          advisories: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "audit",
+      "--level=high",
+      "--advisories",
+      "./advisories",
+      "--frozen"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Audit(AuditFlags {
+          level: AuditSeverity::High,
+          advisories: Some("./advisories".to_string()),
+        }),
+        frozen_lockfile: Some(true),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "audit", "--level=severe"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn remove_subcommand() {
     let r = flags_from_vec(svec!["deno", "remove"]);
@@ -12693,6 +13374,180 @@ Usage: deno repl [OPTIONS] [-- [ARGS]...]\n"
     }
   }
 
+  #[test]
+  fn kv_subcommand() {
+    let r = flags_from_vec(svec!["deno", "kv", "export", "kv.sqlite3"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Kv(KvFlags::Export {
+          database: "kv.sqlite3".to_string(),
+          output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "kv",
+      "export",
+      "kv.sqlite3",
+      "--output",
+      "backup.ndjson"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Kv(KvFlags::Export {
+          database: "kv.sqlite3".to_string(),
+          output: Some("backup.ndjson".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "kv",
+      "import",
+      "kv.sqlite3",
+      "-i",
+      "backup.ndjson"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Kv(KvFlags::Import {
+          database: "kv.sqlite3".to_string(),
+          input: Some("backup.ndjson".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "kv"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "kv", "export"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn unpack_subcommand() {
+    let r = flags_from_vec(svec!["deno", "unpack", "my_app"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Unpack(UnpackFlags {
+          binary: "my_app".to_string(),
+          json: false,
+          output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno", "unpack", "--json", "-o", "contents", "my_app"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Unpack(UnpackFlags {
+          binary: "my_app".to_string(),
+          json: true,
+          output: Some("contents".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "unpack"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn sbom_subcommand() {
+    let r = flags_from_vec(svec!["deno", "sbom"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Sbom(SbomFlags {
+          format: SbomFormat::CycloneDx,
+          output: None,
+          entrypoints: vec![],
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "sbom",
+      "--format=spdx",
+      "-o",
+      "sbom.json",
+      "--frozen",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Sbom(SbomFlags {
+          format: SbomFormat::Spdx,
+          output: Some("sbom.json".to_string()),
+          entrypoints: svec!["main.ts"],
+        }),
+        frozen_lockfile: Some(true),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "sbom", "--format=swid"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn why_subcommand() {
+    let r = flags_from_vec(svec!["deno", "why", "npm:debug"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Why(WhyFlags {
+          package: "npm:debug".to_string(),
+          entrypoints: vec![],
+          json: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "why",
+      "--json",
+      "--frozen",
+      "@std/path@1",
+      "main.ts",
+      "other.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Why(WhyFlags {
+          package: "@std/path@1".to_string(),
+          entrypoints: svec!["main.ts", "other.ts"],
+          json: true,
+        }),
+        frozen_lockfile: Some(true),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "why"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn clean_subcommand() {
     let cases = [
@@ -12884,6 +13739,8 @@ Usage: deno repl [OPTIONS] [-- [ARGS]...]\n"
           icon: None,
           include: Default::default(),
           exclude: Default::default(),
+          compress: false,
+          writable_vfs: None,
           eszip: false,
         }),
         type_check_mode: TypeCheckMode::Local,
@@ -11,6 +11,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::OnceLock;
 
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
@@ -22,6 +23,10 @@ use deno_config::deno_json::FmtConfig;
 pub use deno_config::deno_json::FmtOptionsConfig;
 pub use deno_config::deno_json::LintRulesConfig;
 use deno_config::deno_json::NodeModulesDirMode;
+use deno_config::deno_json::PackagePermissionsObject;
+use deno_config::deno_json::PermissionConfigValue;
+use deno_config::deno_json::PermissionNameOrObject;
+use deno_config::deno_json::PermissionsObject;
 pub use deno_config::deno_json::ProseWrap;
 use deno_config::deno_json::TestConfig;
 pub use deno_config::glob::FilePatterns;
@@ -45,6 +50,8 @@ use deno_npm_installer::LifecycleScriptsConfig;
 use deno_npm_installer::graph::NpmCachingStrategy;
 use deno_path_util::resolve_url_or_path;
 use deno_resolver::factory::resolve_jsr_url;
+use deno_runtime::deno_permissions::PackageAllowOptions;
+use deno_runtime::deno_permissions::PackagePermissionsOptions;
 use deno_runtime::deno_permissions::PermissionsOptions;
 use deno_runtime::inspector_server::InspectorServer;
 use deno_semver::StackString;
@@ -629,6 +636,31 @@ impl CliOptions {
     self.flags.no_legacy_abort()
   }
 
+  pub fn cron_config(
+    &self,
+  ) -> Result<deno_runtime::deno_cron::CronConfig, AnyError> {
+    let mut builder = deno_runtime::deno_cron::CronConfig::builder();
+    if let Some(config) = self.start_dir.to_cron_config()? {
+      if let Some(max_crons) = config.max_crons {
+        builder.max_crons(max_crons);
+      }
+      if let Some(limit) = config.dispatch_concurrency_limit {
+        builder.dispatch_concurrency_limit(limit);
+      }
+      if let Some(max_backoff_ms) = config.max_backoff_ms {
+        builder.max_backoff_ms(max_backoff_ms);
+      }
+      if let Some(max_backoff_count) = config.max_backoff_count {
+        builder.max_backoff_count(max_backoff_count);
+      }
+    }
+    Ok(builder.build()?)
+  }
+
+  pub fn cron_db_path(&self) -> Option<PathBuf> {
+    env::var_os("DENO_CRON_DB_PATH").map(PathBuf::from)
+  }
+
   pub fn env_file_name(&self) -> Option<&Vec<String>> {
     self.flags.env_file.as_ref()
   }
@@ -1014,7 +1046,7 @@ impl CliOptions {
     self.flags.no_remote
   }
 
-  pub fn permissions_options(&self) -> PermissionsOptions {
+  pub fn permissions_options(&self) -> Result<PermissionsOptions, AnyError> {
     // bury this in here to ensure people use cli_options.permissions_options()
     fn flags_to_options(flags: &PermissionFlags) -> PermissionsOptions {
       fn handle_allow<T: Default>(
@@ -1029,31 +1061,89 @@ impl CliOptions {
         }
       }
 
+      // learning grants everything that isn't denied in order to collect
+      // what's accessed
+      let allow_all = flags.allow_all || flags.learn_permissions.is_some();
       PermissionsOptions {
-        allow_all: flags.allow_all,
-        allow_env: handle_allow(flags.allow_all, flags.allow_env.clone()),
+        allow_all,
+        allow_env: handle_allow(allow_all, flags.allow_env.clone()),
         deny_env: flags.deny_env.clone(),
-        allow_net: handle_allow(flags.allow_all, flags.allow_net.clone()),
+        allow_net: handle_allow(allow_all, flags.allow_net.clone()),
         deny_net: flags.deny_net.clone(),
-        allow_ffi: handle_allow(flags.allow_all, flags.allow_ffi.clone()),
+        allow_ffi: handle_allow(allow_all, flags.allow_ffi.clone()),
         deny_ffi: flags.deny_ffi.clone(),
-        allow_read: handle_allow(flags.allow_all, flags.allow_read.clone()),
+        allow_read: handle_allow(allow_all, flags.allow_read.clone()),
         deny_read: flags.deny_read.clone(),
-        allow_run: handle_allow(flags.allow_all, flags.allow_run.clone()),
+        allow_run: handle_allow(allow_all, flags.allow_run.clone()),
         deny_run: flags.deny_run.clone(),
-        allow_sys: handle_allow(flags.allow_all, flags.allow_sys.clone()),
+        allow_sys: handle_allow(allow_all, flags.allow_sys.clone()),
         deny_sys: flags.deny_sys.clone(),
-        allow_write: handle_allow(flags.allow_all, flags.allow_write.clone()),
+        allow_write: handle_allow(allow_all, flags.allow_write.clone()),
         deny_write: flags.deny_write.clone(),
-        allow_import: handle_allow(flags.allow_all, flags.allow_import.clone()),
+        allow_import: handle_allow(allow_all, flags.allow_import.clone()),
         deny_import: flags.deny_import.clone(),
+        packages: flags.packages.clone(),
         prompt: !resolve_no_prompt(flags),
       }
     }
 
-    let mut permissions_options = flags_to_options(&self.flags.permissions);
+    let permission_flags = self.resolve_permission_flags()?;
+    let mut permissions_options = flags_to_options(&permission_flags);
     self.augment_import_permissions(&mut permissions_options);
-    permissions_options
+    Ok(permissions_options)
+  }
+
+  /// Applies the permission set from the config file, if any, to the
+  /// permission flags. The set is selected by `--permission-set`, the
+  /// `DENO_PERMISSION_SET` environment variable or, when no permission
+  /// flags were provided, the `permissions` of the `test`, `bench` or
+  /// `compile` config.
+  fn resolve_permission_flags(
+    &self,
+  ) -> Result<Cow<'_, PermissionFlags>, AnyError> {
+    let flags = &self.flags.permissions;
+    if flags.learn_permissions.is_some() {
+      // permission sets would only narrow what's being learned
+      return Ok(Cow::Borrowed(flags));
+    }
+    let set_name = flags.permission_set.clone().or_else(|| {
+      // lint plugins always run without permissions
+      if matches!(self.sub_command(), DenoSubcommand::Lint(_)) {
+        return None;
+      }
+      ENV_PERMISSION_SET.get().cloned()
+    });
+    let config_set = match set_name {
+      Some(name) => Some(PermissionNameOrObject::Name(name)),
+      None if flags.has_permission() => None,
+      None => match self.sub_command() {
+        DenoSubcommand::Test(_) => {
+          self.start_dir.to_test_permissions_config()?
+        }
+        DenoSubcommand::Bench(_) => {
+          self.start_dir.to_bench_permissions_config()?
+        }
+        DenoSubcommand::Compile(_) => {
+          self.start_dir.to_compile_permissions_config()?
+        }
+        _ => None,
+      },
+    };
+    let set = match config_set {
+      Some(PermissionNameOrObject::Name(name)) => {
+        let config = self.start_dir.to_permissions_config()?;
+        match config.get(&name) {
+          Some(set) => set.clone(),
+          None => bail!(
+            "Permission set \"{}\" was not found in the \"permissions\" of the config file.",
+            name
+          ),
+        }
+      }
+      Some(PermissionNameOrObject::Object(set)) => *set,
+      None => return Ok(Cow::Borrowed(flags)),
+    };
+    Ok(Cow::Owned(apply_permission_set(&set, flags)))
   }
 
   fn augment_import_permissions(&self, options: &mut PermissionsOptions) {
@@ -1139,6 +1229,10 @@ impl CliOptions {
     self.flags.reload
   }
 
+  pub fn learn_permissions(&self) -> Option<PermissionLearnFormat> {
+    self.flags.permissions.learn_permissions
+  }
+
   pub fn seed(&self) -> Option<u64> {
     self.flags.seed
   }
@@ -1323,6 +1417,7 @@ impl CliOptions {
         InstallFlagsLocal::TopLevel | InstallFlagsLocal::Add(_)
       )) | DenoSubcommand::Add(_)
         | DenoSubcommand::Outdated(_)
+        | DenoSubcommand::Why(_)
     ) {
       NpmCachingStrategy::Manual
     } else if self.flags.unstable_config.npm_lazy_caching {
@@ -1394,6 +1489,133 @@ fn resolve_import_map_specifier(
   }
 }
 
+/// The environment variable used to select a permission set for the
+/// `deno` commands run by a task.
+pub const PERMISSION_SET_ENV_VAR_NAME: &str = "DENO_PERMISSION_SET";
+
+static ENV_PERMISSION_SET: OnceLock<String> = OnceLock::new();
+
+/// Takes the permission set out of the environment so that it only applies
+/// to this process and isn't inherited by its subprocesses.
+///
+/// This must be called before any threads are created.
+pub fn take_permission_set_env_var() {
+  let Some(name) = env::var_os(PERMISSION_SET_ENV_VAR_NAME) else {
+    return;
+  };
+  // SAFETY: The caller ensures that no other threads exist yet.
+  unsafe { env::remove_var(PERMISSION_SET_ENV_VAR_NAME) };
+  if let Some(name) = name.to_str().filter(|name| !name.is_empty()) {
+    _ = ENV_PERMISSION_SET.set(name.to_string());
+  }
+}
+
+/// Combines a permission set from the config file with the permission
+/// flags, where allow flags provided on the command line take precedence
+/// and deny lists from both are applied.
+fn apply_permission_set(
+  set: &PermissionsObject,
+  flags: &PermissionFlags,
+) -> PermissionFlags {
+  fn merge(
+    flag: &Option<Vec<String>>,
+    config: &Option<PermissionConfigValue>,
+  ) -> Option<Vec<String>> {
+    flag
+      .clone()
+      .or_else(|| config.as_ref().and_then(|v| v.to_flag_value()))
+  }
+
+  fn union(
+    flag: &Option<Vec<String>>,
+    config: &Option<PermissionConfigValue>,
+  ) -> Option<Vec<String>> {
+    let config = config.as_ref().and_then(|v| v.to_flag_value());
+    match (flag, config) {
+      // an empty list denies everything
+      (Some(flag), Some(config)) if flag.is_empty() || config.is_empty() => {
+        Some(Vec::new())
+      }
+      (Some(flag), Some(mut config)) => {
+        for value in flag {
+          if !config.contains(value) {
+            config.push(value.clone());
+          }
+        }
+        Some(config)
+      }
+      (flag, config) => flag.clone().or(config),
+    }
+  }
+
+  let allow_all = flags.allow_all || set.all == Some(true);
+  // allow lists are redundant once everything is allowed
+  let allow = |flag: &Option<Vec<String>>,
+               config: &Option<PermissionConfigValue>| {
+    if allow_all { None } else { merge(flag, config) }
+  };
+  PermissionFlags {
+    allow_all,
+    allow_env: allow(&flags.allow_env, &set.env.allow),
+    deny_env: union(&flags.deny_env, &set.env.deny),
+    allow_ffi: allow(&flags.allow_ffi, &set.ffi.allow),
+    deny_ffi: union(&flags.deny_ffi, &set.ffi.deny),
+    allow_net: allow(&flags.allow_net, &set.net.allow),
+    deny_net: union(&flags.deny_net, &set.net.deny),
+    allow_read: allow(&flags.allow_read, &set.read.allow),
+    deny_read: union(&flags.deny_read, &set.read.deny),
+    allow_run: allow(&flags.allow_run, &set.run.allow),
+    deny_run: union(&flags.deny_run, &set.run.deny),
+    allow_sys: allow(&flags.allow_sys, &set.sys.allow),
+    deny_sys: union(&flags.deny_sys, &set.sys.deny),
+    allow_write: allow(&flags.allow_write, &set.write.allow),
+    deny_write: union(&flags.deny_write, &set.write.deny),
+    no_prompt: flags.no_prompt,
+    allow_import: allow(&flags.allow_import, &set.import.allow),
+    deny_import: union(&flags.deny_import, &set.import.deny),
+    permission_set: flags.permission_set.clone(),
+    learn_permissions: flags.learn_permissions,
+    packages: set
+      .packages
+      .iter()
+      .map(|(package, permissions)| {
+        to_package_permissions_options(package, permissions)
+      })
+      .collect(),
+  }
+}
+
+fn to_package_permissions_options(
+  package: &str,
+  permissions: &PackagePermissionsObject,
+) -> PackagePermissionsOptions {
+  fn to_allow(
+    value: &Option<PermissionConfigValue>,
+  ) -> Option<PackageAllowOptions> {
+    Some(match value.as_ref()? {
+      PermissionConfigValue::All(true) => PackageAllowOptions::All,
+      PermissionConfigValue::All(false) => PackageAllowOptions::Nothing,
+      PermissionConfigValue::Some(values) if values.is_empty() => {
+        PackageAllowOptions::Nothing
+      }
+      PermissionConfigValue::Some(values) => {
+        PackageAllowOptions::List(values.clone())
+      }
+    })
+  }
+
+  PackagePermissionsOptions {
+    package: package.to_string(),
+    read: to_allow(&permissions.read),
+    write: to_allow(&permissions.write),
+    net: to_allow(&permissions.net),
+    env: to_allow(&permissions.env),
+    sys: to_allow(&permissions.sys),
+    run: to_allow(&permissions.run),
+    ffi: to_allow(&permissions.ffi),
+  }
+}
+
 /// Resolves the no_prompt value based on the cli flags and environment.
 pub fn resolve_no_prompt(flags: &PermissionFlags) -> bool {
   flags.no_prompt || has_flag_env_var("DENO_NO_PROMPT")
@@ -1506,10 +1728,45 @@ fn allow_import_host_from_url(url: &Url) -> Option<String> {
 
 #[cfg(test)]
 mod test {
+  use deno_config::deno_json::AllowDenyPermissionConfig;
   use pretty_assertions::assert_eq;
 
   use super::*;
 
+  #[test]
+  fn apply_permission_set_unions_deny_lists() {
+    let set = PermissionsObject {
+      env: AllowDenyPermissionConfig {
+        allow: Some(PermissionConfigValue::All(true)),
+        deny: Some(PermissionConfigValue::Some(vec!["SECRET".to_string()])),
+      },
+      net: AllowDenyPermissionConfig {
+        allow: None,
+        deny: Some(PermissionConfigValue::All(true)),
+      },
+      ..Default::default()
+    };
+    let flags = PermissionFlags {
+      allow_env: Some(vec!["HOME".to_string(), "SECRET".to_string()]),
+      deny_env: Some(vec!["TOKEN".to_string()]),
+      deny_net: Some(vec!["deno.land".to_string()]),
+      deny_read: Some(vec![]),
+      ..Default::default()
+    };
+    let flags = apply_permission_set(&set, &flags);
+    assert_eq!(
+      flags.allow_env,
+      Some(vec!["HOME".to_string(), "SECRET".to_string()])
+    );
+    assert_eq!(
+      flags.deny_env,
+      Some(vec!["SECRET".to_string(), "TOKEN".to_string()])
+    );
+    assert_eq!(flags.deny_net, Some(vec![]));
+    assert_eq!(flags.deny_read, Some(vec![]));
+    assert_eq!(flags.deny_write, None);
+  }
+
   #[test]
   fn resolve_import_map_flags_take_precedence() {
     let config_text = r#"{
@@ -21,10 +21,12 @@ use deno_lib::args::npm_process_state;
 use deno_lib::npm::NpmRegistryReadPermissionChecker;
 use deno_lib::npm::NpmRegistryReadPermissionCheckerMode;
 use deno_lib::npm::create_npm_process_state_provider;
+use deno_lib::util::permission_packages::PermissionPackageResolverImpl;
 use deno_lib::worker::LibMainWorkerFactory;
 use deno_lib::worker::LibMainWorkerOptions;
 use deno_lib::worker::LibWorkerFactoryRoots;
 use deno_npm::npm_rc::ResolvedNpmRc;
+use deno_npm_cache::MinimumReleaseAge;
 use deno_npm_cache::NpmCacheSetting;
 use deno_npm_installer::NpmInstallerFactoryOptions;
 use deno_npm_installer::lifecycle_scripts::LifecycleScriptsExecutor;
@@ -47,6 +49,7 @@ use deno_runtime::deno_fs;
 use deno_runtime::deno_fs::RealFs;
 use deno_runtime::deno_permissions::Permissions;
 use deno_runtime::deno_permissions::PermissionsContainer;
+use deno_runtime::deno_permissions::set_package_resolver;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_web::BlobStore;
@@ -57,6 +60,7 @@ use node_resolver::NodeResolverOptions;
 use node_resolver::cache::NodeResolutionThreadLocalCache;
 use once_cell::sync::OnceCell;
 use sys_traits::EnvCurrentDir;
+use sys_traits::EnvHomeDir;
 
 use crate::args::BundleFlags;
 use crate::args::BundlePlatform;
@@ -66,6 +70,8 @@ use crate::args::ConfigFlag;
 use crate::args::DenoSubcommand;
 use crate::args::Flags;
 use crate::args::InstallFlags;
+use crate::args::PermissionLearnFormat;
+use crate::args::jsr_url;
 use crate::cache::Caches;
 use crate::cache::CodeCache;
 use crate::cache::DenoDir;
@@ -523,6 +529,12 @@ impl CliFactory {
     Ok(self.workspace_factory()?.npmrc()?)
   }
 
+  pub fn minimum_release_age(
+    &self,
+  ) -> Result<Option<&Arc<MinimumReleaseAge>>, AnyError> {
+    self.npm_installer_factory()?.minimum_release_age()
+  }
+
   pub async fn npm_graph_resolver(
     &self,
   ) -> Result<&Arc<CliNpmGraphResolver>, AnyError> {
@@ -740,6 +752,7 @@ impl CliFactory {
             self.root_permissions_container()?.clone(),
             self.sys(),
             self.compiler_options_resolver()?.clone(),
+            self.minimum_release_age()?.cloned(),
           )))
         }
         .boxed_local(),
@@ -873,10 +886,32 @@ impl CliFactory {
       .services
       .root_permissions_container
       .get_or_try_init(|| {
+        let cli_options = self.cli_options()?;
+        if let Some(format) = cli_options.learn_permissions() {
+          // paths in a permission set are relative to the config file
+          let base_dir = match format {
+            PermissionLearnFormat::Flags => {
+              cli_options.initial_cwd().to_path_buf()
+            }
+            PermissionLearnFormat::Config => cli_options.start_dir.dir_path(),
+          };
+          crate::tools::permission_learn::init(
+            format,
+            base_dir,
+            self.sys().env_home_dir(),
+          );
+        }
+        let permissions_options = cli_options.permissions_options()?;
+        if !permissions_options.packages.is_empty() {
+          set_package_resolver(Box::new(PermissionPackageResolverImpl::new(
+            self.resolver_factory()?.npm_resolver()?.clone(),
+            jsr_url().clone(),
+          )));
+        }
         let desc_parser = self.permission_desc_parser()?.clone();
         let permissions = Permissions::from_options(
           desc_parser.as_ref(),
-          &self.cli_options()?.permissions_options(),
+          &permissions_options,
         )?;
 
         Ok(PermissionsContainer::new(desc_parser, permissions))
@@ -1052,6 +1087,8 @@ impl CliFactory {
       no_legacy_abort: cli_options.no_legacy_abort(),
       startup_snapshot: deno_snapshots::CLI_SNAPSHOT,
       enable_raw_imports: cli_options.unstable_raw_imports(),
+      cron_config: cli_options.cron_config()?,
+      cron_db_path: cli_options.cron_db_path(),
     })
   }
 
@@ -1214,6 +1251,7 @@ fn new_workspace_factory_options(
       DenoSubcommand::Install(_)
         | DenoSubcommand::Add(_)
         | DenoSubcommand::Remove(_)
+        | DenoSubcommand::Audit(_)
         | DenoSubcommand::Init(_)
         | DenoSubcommand::Outdated(_)
         | DenoSubcommand::Clean(_)
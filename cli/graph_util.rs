@@ -27,6 +27,7 @@ use deno_graph::WorkspaceFastCheckOption;
 use deno_graph::source::Loader;
 use deno_graph::source::ResolveError;
 use deno_lib::util::result::downcast_ref_deno_resolve_error;
+use deno_npm_cache::MinimumReleaseAge;
 use deno_npm_installer::PackageCaching;
 use deno_npm_installer::graph::NpmCachingStrategy;
 use deno_path_util::url_to_file_path;
@@ -56,6 +57,7 @@ use crate::cache::ModuleInfoCache;
 use crate::colors;
 use crate::file_fetcher::CliDenoGraphLoader;
 use crate::file_fetcher::CliFileFetcher;
+use crate::jsr::JsrMinimumReleaseAgeFilter;
 use crate::npm::CliNpmGraphResolver;
 use crate::npm::CliNpmInstaller;
 use crate::npm::CliNpmResolver;
@@ -613,6 +615,7 @@ pub struct ModuleGraphBuilder {
   root_permissions_container: PermissionsContainer,
   sys: CliSys,
   compiler_options_resolver: Arc<CompilerOptionsResolver>,
+  minimum_release_age: Option<Arc<MinimumReleaseAge>>,
 }
 
 impl ModuleGraphBuilder {
@@ -635,6 +638,7 @@ impl ModuleGraphBuilder {
     root_permissions_container: PermissionsContainer,
     sys: CliSys,
     compiler_options_resolver: Arc<CompilerOptionsResolver>,
+    minimum_release_age: Option<Arc<MinimumReleaseAge>>,
   ) -> Self {
     Self {
       caches,
@@ -654,6 +658,7 @@ impl ModuleGraphBuilder {
       root_permissions_container,
       sys,
       compiler_options_resolver,
+      minimum_release_age,
     }
   }
 
@@ -921,6 +926,12 @@ impl ModuleGraphBuilder {
       self.sys.clone(),
       deno_resolver::file_fetcher::DenoGraphLoaderOptions {
         file_header_overrides: self.cli_options.resolve_file_header_overrides(),
+        jsr_package_meta_filter: self.minimum_release_age.as_ref().map(
+          |minimum_release_age| {
+            Arc::new(JsrMinimumReleaseAgeFilter(minimum_release_age.clone()))
+              as _
+          },
+        ),
         permissions: Some(permissions),
       },
     )
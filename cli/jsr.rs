@@ -4,8 +4,12 @@ use std::sync::Arc;
 
 use dashmap::DashMap;
 use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::url::Url;
 use deno_graph::packages::JsrPackageInfo;
 use deno_graph::packages::JsrPackageVersionInfo;
+use deno_npm_cache::MinimumReleaseAge;
+use deno_resolver::file_fetcher::JsrPackageMetaFilter;
 use deno_semver::package::PackageNv;
 use deno_semver::package::PackageReq;
 
@@ -22,15 +26,20 @@ pub struct JsrFetchResolver {
   info_by_nv: DashMap<PackageNv, Option<Arc<JsrPackageVersionInfo>>>,
   info_by_name: DashMap<String, Option<Arc<JsrPackageInfo>>>,
   file_fetcher: Arc<CliFileFetcher>,
+  minimum_release_age: Option<Arc<MinimumReleaseAge>>,
 }
 
 impl JsrFetchResolver {
-  pub fn new(file_fetcher: Arc<CliFileFetcher>) -> Self {
+  pub fn new(
+    file_fetcher: Arc<CliFileFetcher>,
+    minimum_release_age: Option<Arc<MinimumReleaseAge>>,
+  ) -> Self {
     Self {
       nv_by_req: Default::default(),
       info_by_nv: Default::default(),
       info_by_name: Default::default(),
       file_fetcher,
+      minimum_release_age,
     }
   }
 
@@ -71,7 +80,14 @@ impl JsrFetchResolver {
         .fetch_bypass_permissions(&meta_url)
         .await
         .ok()?;
-      serde_json::from_slice::<JsrPackageInfo>(&file.source).ok()
+      match &self.minimum_release_age {
+        Some(minimum_release_age) if minimum_release_age.applies_to(name) => {
+          let mut meta = serde_json::from_slice::<Value>(&file.source).ok()?;
+          filter_jsr_package_meta(&mut meta, minimum_release_age);
+          serde_json::from_value::<JsrPackageInfo>(meta).ok()
+        }
+        _ => serde_json::from_slice::<JsrPackageInfo>(&file.source).ok(),
+      }
     };
     let info = fetch_package_info().await.map(Arc::new);
     self.info_by_name.insert(name.to_string(), info.clone());
@@ -102,6 +118,44 @@ impl JsrFetchResolver {
   }
 }
 
+/// Removes the versions from a package's `meta.json` that were created
+/// more recently than the minimum release age allows.
+fn filter_jsr_package_meta(
+  meta: &mut Value,
+  minimum_release_age: &MinimumReleaseAge,
+) {
+  let Some(versions) = meta.get_mut("versions").and_then(|v| v.as_object_mut())
+  else {
+    return;
+  };
+  versions.retain(|_, info| {
+    match info.get("createdAt").and_then(|c| c.as_str()) {
+      Some(created_at) => !minimum_release_age.is_too_new(created_at),
+      None => true,
+    }
+  });
+}
+
+/// Hides versions of jsr packages that are newer than the minimum release
+/// age from version resolution in deno_graph.
+#[derive(Debug)]
+pub struct JsrMinimumReleaseAgeFilter(pub Arc<MinimumReleaseAge>);
+
+impl JsrPackageMetaFilter for JsrMinimumReleaseAgeFilter {
+  fn filter(&self, specifier: &Url, content: &[u8]) -> Option<Vec<u8>> {
+    let name = specifier
+      .as_str()
+      .strip_prefix(jsr_url().as_str())?
+      .strip_suffix("/meta.json")?;
+    if !self.0.applies_to(name) {
+      return None;
+    }
+    let mut meta = serde_json::from_slice::<Value>(content).ok()?;
+    filter_jsr_package_meta(&mut meta, &self.0);
+    serde_json::to_vec(&meta).ok()
+  }
+}
+
 /// This is a roundabout way of deserializing `JsrPackageVersionInfo`,
 /// because we only want the `exports` field and `module_graph` is large.
 pub fn partial_jsr_package_version_info_from_slice(
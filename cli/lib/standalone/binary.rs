@@ -5,6 +5,10 @@ use std::collections::BTreeMap;
 
 use deno_media_type::MediaType;
 use deno_resolver::workspace::PackageJsonDepResolution;
+use deno_runtime::deno_core::anyhow::Context;
+use deno_runtime::deno_core::anyhow::bail;
+use deno_runtime::deno_core::error::AnyError;
+use deno_runtime::deno_cron::CronConfig;
 use deno_runtime::deno_permissions::PermissionsOptions;
 use deno_runtime::deno_telemetry::OtelConfig;
 use deno_semver::Version;
@@ -14,6 +18,7 @@ use serde::Serialize;
 use url::Url;
 
 use super::virtual_fs::FileSystemCaseSensitivity;
+use super::virtual_fs::VirtualDirectoryEntries;
 use crate::args::UnstableConfig;
 
 pub const MAGIC_BYTES: &[u8; 8] = b"d3n0l4nd";
@@ -89,7 +94,21 @@ pub struct Metadata {
   pub node_modules: Option<NodeModules>,
   pub unstable_config: UnstableConfig,
   pub otel_config: OtelConfig,
+  pub cron_config: CronConfig,
   pub vfs_case_sensitivity: FileSystemCaseSensitivity,
+  /// Allows writing to paths within the vfs by storing the changes in
+  /// a directory on the real file system.
+  pub writable_vfs: Option<WritableVfs>,
+}
+
+/// Where the changes to the vfs are stored when it's writable.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WritableVfs {
+  /// A temporary directory for each process, which is removed on exit.
+  TempDir,
+  /// A directory that persists between runs. A relative path is resolved
+  /// from the current working directory.
+  Dir(String),
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -348,7 +367,8 @@ where
 {
   fn deserialize(input: &'a [u8]) -> std::io::Result<(&'a [u8], Self)> {
     let (input, len) = read_u32_as_usize(input)?;
-    let mut data = IndexMap::with_capacity(len);
+    // the length is untrusted, but each entry takes at least one byte
+    let mut data = IndexMap::with_capacity(len.min(input.len()));
     let mut input = input;
     for _ in 0..len {
       let (new_input, specifier) = SpecifierId::deserialize(input)?;
@@ -360,6 +380,130 @@ where
   }
 }
 
+/// The data section of a standalone binary split into its parts.
+pub struct DataSection<'a> {
+  pub metadata: Metadata,
+  /// The serialized npm resolution snapshot. Empty when the binary
+  /// doesn't use npm packages.
+  pub npm_snapshot: &'a [u8],
+  /// The specifiers as stored in the binary, which are either absolute
+  /// urls or relative to the root directory of the vfs.
+  pub specifiers: Vec<(Cow<'a, str>, SpecifierId)>,
+  pub redirects: SpecifierDataStore<SpecifierId>,
+  pub remote_modules: SpecifierDataStore<RemoteModuleEntry<'a>>,
+  pub vfs_root_entries: VirtualDirectoryEntries,
+  pub vfs_files_data: &'a [u8],
+}
+
+impl<'a> DataSection<'a> {
+  pub fn deserialize(data: &'a [u8]) -> Result<Self, AnyError> {
+    fn read_magic_bytes(input: &[u8]) -> Result<(&[u8], bool), AnyError> {
+      if input.len() < MAGIC_BYTES.len() {
+        bail!("Unexpected end of data. Could not find magic bytes.");
+      }
+      let (magic_bytes, input) = input.split_at(MAGIC_BYTES.len());
+      if magic_bytes != MAGIC_BYTES {
+        return Ok((input, false));
+      }
+      Ok((input, true))
+    }
+
+    fn read_specifiers(
+      input: &[u8],
+    ) -> std::io::Result<(&[u8], Vec<(Cow<str>, SpecifierId)>)> {
+      let (mut input, len) = read_u32_as_usize(input)?;
+      // the length is untrusted, but each entry takes at least one byte
+      let mut specifiers = Vec::with_capacity(len.min(input.len()));
+      for _ in 0..len {
+        let (new_input, specifier) = read_bytes_with_u32_len(input)?;
+        let (new_input, id) = SpecifierId::deserialize(new_input)?;
+        specifiers.push((String::from_utf8_lossy(specifier), id));
+        input = new_input;
+      }
+      Ok((input, specifiers))
+    }
+
+    let (input, found) = read_magic_bytes(data)?;
+    if !found {
+      bail!("Did not find magic bytes.");
+    }
+
+    // 1. Metadata
+    let (input, data) =
+      read_bytes_with_u64_len(input).context("reading metadata")?;
+    let metadata: Metadata =
+      serde_json::from_slice(data).context("deserializing metadata")?;
+    // 2. Npm snapshot
+    let (input, npm_snapshot) =
+      read_bytes_with_u64_len(input).context("reading npm snapshot")?;
+    // 3. Specifiers
+    let (input, specifiers) =
+      read_specifiers(input).context("deserializing specifiers")?;
+    // 4. Redirects
+    let (input, redirects) =
+      SpecifierDataStore::<SpecifierId>::deserialize(input)
+        .context("deserializing redirects")?;
+    // 5. Remote modules
+    let (input, remote_modules) =
+      SpecifierDataStore::<RemoteModuleEntry<'a>>::deserialize(input)
+        .context("deserializing remote modules")?;
+    // 6. VFS
+    let (input, data) = read_bytes_with_u64_len(input).context("vfs")?;
+    let vfs_root_entries: VirtualDirectoryEntries =
+      serde_json::from_slice(data).context("deserializing vfs data")?;
+    let (input, vfs_files_data) =
+      read_bytes_with_u64_len(input).context("reading vfs files data")?;
+
+    // finally ensure we read the magic bytes at the end
+    let (_input, found) = read_magic_bytes(input)?;
+    if !found {
+      bail!("Could not find magic bytes at end of data.");
+    }
+
+    Ok(Self {
+      metadata,
+      npm_snapshot,
+      specifiers,
+      redirects,
+      remote_modules,
+      vfs_root_entries,
+      vfs_files_data,
+    })
+  }
+
+  /// Finds the data section in the bytes of an executable produced by
+  /// `deno compile` without relying on the executable format.
+  pub fn find_in_executable(bytes: &'a [u8]) -> Result<Self, AnyError> {
+    // the magic bytes also appear in the code of the runtime, so try
+    // each occurrence until one deserializes
+    let mut last_err = None;
+    let mut start = 0;
+    while let Some(pos) = bytes[start..]
+      .windows(MAGIC_BYTES.len())
+      .position(|w| w == MAGIC_BYTES)
+    {
+      match Self::deserialize(&bytes[start + pos..]) {
+        Ok(data_section) => return Ok(data_section),
+        Err(err) => last_err = Some(err),
+      }
+      start += pos + 1;
+    }
+    match last_err {
+      Some(err) => Err(err.context("Failed reading standalone binary data.")),
+      None => bail!("Could not find standalone binary data."),
+    }
+  }
+}
+
+fn read_bytes_with_u64_len(input: &[u8]) -> std::io::Result<(&[u8], &[u8])> {
+  let (input, len) = read_u64(input)?;
+  let len = usize::try_from(len).map_err(|_| {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid data length")
+  })?;
+  let (input, data) = read_bytes(input, len)?;
+  Ok((input, data))
+}
+
 fn read_bytes_with_u32_len(input: &[u8]) -> std::io::Result<(&[u8], &[u8])> {
   let (input, len) = read_u32_as_usize(input)?;
   let (input, data) = read_bytes(input, len)?;
@@ -376,6 +520,12 @@ fn read_u32(input: &[u8]) -> std::io::Result<(&[u8], u32)> {
   Ok((input, len))
 }
 
+fn read_u64(input: &[u8]) -> std::io::Result<(&[u8], u64)> {
+  let (input, len_bytes) = read_bytes(input, 8)?;
+  let len = u64::from_le_bytes(len_bytes.try_into().unwrap());
+  Ok((input, len))
+}
+
 fn read_u8(input: &[u8]) -> std::io::Result<(&[u8], u8)> {
   check_has_len(input, 1)?;
   Ok((&input[1..], input[0]))
@@ -208,7 +208,18 @@ pub struct VirtualDirectory {
 #[derive(Debug, Clone, Copy)]
 pub struct OffsetWithLength {
   pub offset: u64,
+  /// The length of the uncompressed data.
   pub len: u64,
+  /// The length of the data stored at the offset when it's compressed
+  /// with zstd.
+  pub compressed_len: Option<u64>,
+}
+
+impl OffsetWithLength {
+  /// The length of the data stored at the offset.
+  pub fn stored_len(&self) -> u64 {
+    self.compressed_len.unwrap_or(self.len)
+  }
 }
 
 // serialize as an array in order to save space
@@ -217,8 +228,12 @@ impl Serialize for OffsetWithLength {
   where
     S: Serializer,
   {
-    let array = [self.offset, self.len];
-    array.serialize(serializer)
+    match self.compressed_len {
+      Some(compressed_len) => {
+        [self.offset, self.len, compressed_len].serialize(serializer)
+      }
+      None => [self.offset, self.len].serialize(serializer),
+    }
   }
 }
 
@@ -233,7 +248,9 @@ impl<'de> Deserialize<'de> for OffsetWithLength {
       type Value = OffsetWithLength;
 
       fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("an array with two elements: [offset, len]")
+        formatter.write_str(
+          "an array with two or three elements: [offset, len, compressed_len?]",
+        )
       }
 
       fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -246,7 +263,12 @@ impl<'de> Deserialize<'de> for OffsetWithLength {
         let len = seq
           .next_element()?
           .ok_or_else(|| de::Error::invalid_length(1, &self))?;
-        Ok(OffsetWithLength { offset, len })
+        let compressed_len = seq.next_element()?;
+        Ok(OffsetWithLength {
+          offset,
+          len,
+          compressed_len,
+        })
       }
     }
 
@@ -379,6 +401,73 @@ pub struct BuiltVfs {
   pub files: Vec<Vec<u8>>,
 }
 
+impl BuiltVfs {
+  /// Compresses the data of each file with zstd. The data is decompressed
+  /// by the runtime when it's first read.
+  ///
+  /// Data that doesn't get smaller, such as already compressed images, is
+  /// kept uncompressed.
+  pub fn compress(&mut self) -> Result<(), AnyError> {
+    const COMPRESSION_LEVEL: i32 = 9;
+
+    fn update_offsets(
+      entries: &mut VirtualDirectoryEntries,
+      new_offsets: &HashMap<u64, OffsetWithLength>,
+    ) {
+      let update = |offset: &mut OffsetWithLength| {
+        if offset.len > 0 {
+          *offset = new_offsets[&offset.offset];
+        }
+      };
+      for entry in entries.iter_mut() {
+        match entry {
+          VfsEntry::Dir(dir) => update_offsets(&mut dir.entries, new_offsets),
+          VfsEntry::File(file) => {
+            update(&mut file.offset);
+            if let Some(offset) = &mut file.transpiled_offset {
+              update(offset);
+            }
+            if let Some(offset) = &mut file.cjs_export_analysis_offset {
+              update(offset);
+            }
+            if let Some(offset) = &mut file.source_map_offset {
+              update(offset);
+            }
+          }
+          VfsEntry::Symlink(_) => {}
+        }
+      }
+    }
+
+    let mut new_offsets = HashMap::with_capacity(self.files.len());
+    let mut offset = 0;
+    let mut new_offset = 0;
+    for data in &mut self.files {
+      let len = data.len() as u64;
+      let compressed = zstd::bulk::compress(data, COMPRESSION_LEVEL)
+        .context("Compressing file data")?;
+      let compressed_len = if compressed.len() < data.len() {
+        *data = compressed;
+        Some(data.len() as u64)
+      } else {
+        None
+      };
+      new_offsets.insert(
+        offset,
+        OffsetWithLength {
+          offset: new_offset,
+          len,
+          compressed_len,
+        },
+      );
+      offset += len;
+      new_offset += data.len() as u64;
+    }
+    update_offsets(&mut self.entries, &new_offsets);
+    Ok(())
+  }
+}
+
 #[derive(Debug, Default)]
 struct FilesData {
   files: Vec<Vec<u8>>,
@@ -421,7 +510,11 @@ impl FilesData {
 
   pub fn add_data(&mut self, data: Vec<u8>) -> OffsetWithLength {
     if data.is_empty() {
-      return OffsetWithLength { offset: 0, len: 0 };
+      return OffsetWithLength {
+        offset: 0,
+        len: 0,
+        compressed_len: None,
+      };
     }
     let checksum = crate::util::checksum::r#gen(&[&data]);
     match self.file_offsets.entry((checksum, data.len())) {
@@ -434,6 +527,7 @@ impl FilesData {
         let offset_and_len = OffsetWithLength {
           offset: self.current_offset,
           len: data.len() as u64,
+          compressed_len: None,
         };
         vacant_entry.insert(offset_and_len);
         self.current_offset += offset_and_len.len;
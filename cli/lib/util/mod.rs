@@ -3,6 +3,9 @@
 pub mod checksum;
 pub mod hash;
 pub mod logger;
+pub mod permission_audit;
+pub mod permission_broker;
+pub mod permission_packages;
 pub mod result;
 pub mod text_encoding;
 pub mod v8;
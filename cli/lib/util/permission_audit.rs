@@ -0,0 +1,83 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use deno_runtime::deno_permissions::PermissionAuditRecord;
+use deno_runtime::deno_permissions::PermissionAuditSink;
+use deno_runtime::deno_permissions::add_audit_sink;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Path of the JSON lines file to append a record of every permission
+/// query, check, request and revoke to.
+pub const PERMISSION_AUDIT_ENV_VAR_NAME: &str = "DENO_PERMISSION_AUDIT";
+
+#[derive(Debug, Error, deno_error::JsError)]
+#[class(inherit)]
+#[error("Failed opening permission audit log at '{}'", path.display())]
+pub struct PermissionAuditInitError {
+  path: PathBuf,
+  #[source]
+  #[inherit]
+  source: std::io::Error,
+}
+
+struct JsonLinesAuditSink {
+  file: File,
+}
+
+impl PermissionAuditSink for JsonLinesAuditSink {
+  fn record(&self, record: &PermissionAuditRecord) {
+    #[derive(Serialize)]
+    struct TimestampedRecord<'a> {
+      /// Milliseconds since the unix epoch.
+      timestamp: u64,
+      #[serde(flatten)]
+      record: &'a PermissionAuditRecord,
+    }
+
+    let timestamp = SystemTime::now()
+      .duration_since(SystemTime::UNIX_EPOCH)
+      .map(|d| d.as_millis() as u64)
+      .unwrap_or(0);
+    let Ok(mut line) =
+      serde_json::to_vec(&TimestampedRecord { timestamp, record })
+    else {
+      return;
+    };
+    line.push(b'\n');
+    // a single write to a file opened for appending keeps lines intact
+    // when several processes write to the same log
+    if let Err(err) = (&self.file).write_all(&line) {
+      log::debug!("Failed writing permission audit record: {:#}", err);
+    }
+  }
+}
+
+/// Sets up the permission audit log when the `DENO_PERMISSION_AUDIT`
+/// environment variable is set.
+pub fn maybe_init_permission_audit_from_env()
+-> Result<(), PermissionAuditInitError> {
+  let Some(path) = std::env::var_os(PERMISSION_AUDIT_ENV_VAR_NAME) else {
+    return Ok(());
+  };
+  if path.is_empty() {
+    return Ok(());
+  }
+  let path = PathBuf::from(path);
+  // the log is always on the real file system, even in standalone binaries
+  #[allow(clippy::disallowed_methods)]
+  let file = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)
+    .map_err(|source| PermissionAuditInitError {
+      path: path.clone(),
+      source,
+    })?;
+  add_audit_sink(Box::new(JsonLinesAuditSink { file }));
+  Ok(())
+}
@@ -0,0 +1,102 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Path of a Unix socket or `fd:<number>` of an inherited socket that
+/// permission prompts are sent to instead of the terminal.
+pub const PERMISSION_BROKER_ENV_VAR_NAME: &str = "DENO_PERMISSION_BROKER";
+/// Seconds to wait for the permission broker to reply to a prompt.
+pub const PERMISSION_BROKER_TIMEOUT_ENV_VAR_NAME: &str =
+  "DENO_PERMISSION_BROKER_TIMEOUT";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error, deno_error::JsError)]
+pub enum PermissionBrokerInitError {
+  #[class(type)]
+  #[error(
+    "Invalid DENO_PERMISSION_BROKER_TIMEOUT '{0}'. Expected a number of seconds."
+  )]
+  InvalidTimeout(String),
+  #[class(inherit)]
+  #[error("Failed connecting to the permission broker at '{address}'")]
+  Connect {
+    address: String,
+    #[source]
+    #[inherit]
+    source: std::io::Error,
+  },
+  #[class(type)]
+  #[error("The permission broker is only supported on Unix.")]
+  Unsupported,
+}
+
+/// Takes the address of the permission broker from the
+/// `DENO_PERMISSION_BROKER` environment variable.
+///
+/// An inherited fd isn't inherited by subprocesses, so the variable is
+/// removed for them in that case. This must be called before any threads
+/// are created.
+pub fn take_permission_broker_env_var() -> Option<String> {
+  let address = std::env::var(PERMISSION_BROKER_ENV_VAR_NAME)
+    .ok()
+    .filter(|address| !address.is_empty())?;
+  if address.starts_with("fd:") {
+    // SAFETY: We're doing this before any threads are created.
+    unsafe {
+      std::env::remove_var(PERMISSION_BROKER_ENV_VAR_NAME);
+    }
+  }
+  Some(address)
+}
+
+/// Sends permission prompts to the broker at the address when one is
+/// provided.
+pub fn maybe_init_permission_broker(
+  address: Option<&str>,
+) -> Result<(), PermissionBrokerInitError> {
+  let Some(address) = address else {
+    return Ok(());
+  };
+  let timeout = match std::env::var(PERMISSION_BROKER_TIMEOUT_ENV_VAR_NAME) {
+    Ok(value) => value
+      .parse::<f64>()
+      .ok()
+      .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+      .filter(|timeout| !timeout.is_zero())
+      .ok_or(PermissionBrokerInitError::InvalidTimeout(value))?,
+    Err(_) => DEFAULT_TIMEOUT,
+  };
+  init_permission_broker(address, timeout)
+}
+
+#[cfg(unix)]
+fn init_permission_broker(
+  address: &str,
+  timeout: Duration,
+) -> Result<(), PermissionBrokerInitError> {
+  use deno_runtime::deno_permissions::broker::BrokerPrompter;
+  use deno_runtime::deno_permissions::broker::PermissionBrokerAddress;
+  use deno_runtime::deno_permissions::set_prompter;
+
+  let parsed_address = PermissionBrokerAddress::parse(address);
+  let prompter =
+    BrokerPrompter::connect(&parsed_address, timeout).map_err(|source| {
+      PermissionBrokerInitError::Connect {
+        address: address.to_string(),
+        source,
+      }
+    })?;
+  set_prompter(Box::new(prompter));
+  Ok(())
+}
+
+#[cfg(not(unix))]
+fn init_permission_broker(
+  _address: &str,
+  _timeout: Duration,
+) -> Result<(), PermissionBrokerInitError> {
+  Err(PermissionBrokerInitError::Unsupported)
+}
@@ -0,0 +1,90 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use deno_resolver::npm::NpmResolver;
+use deno_runtime::deno_permissions::PermissionPackageResolver;
+use url::Url;
+
+use crate::sys::DenoLibSys;
+
+/// Resolves the npm or jsr package that a module belongs to for package
+/// permissions.
+#[derive(Debug)]
+pub struct PermissionPackageResolverImpl<TSys: DenoLibSys> {
+  npm_resolver: NpmResolver<TSys>,
+  jsr_url: Url,
+}
+
+impl<TSys: DenoLibSys> PermissionPackageResolverImpl<TSys> {
+  pub fn new(npm_resolver: NpmResolver<TSys>, jsr_url: Url) -> Self {
+    Self {
+      npm_resolver,
+      jsr_url,
+    }
+  }
+
+  fn resolve_npm_package_name(&self, specifier: &Url) -> Option<String> {
+    match &self.npm_resolver {
+      NpmResolver::Managed(resolver) => {
+        let folder_id = resolver
+          .resolve_package_cache_folder_id_from_specifier(specifier)
+          .ok()??;
+        Some(folder_id.nv.name.to_string())
+      }
+      NpmResolver::Byonm(_) => {
+        // the package is the last one in the path since packages can have
+        // their own node_modules directory
+        let path = specifier.path();
+        let index = path.to_ascii_lowercase().rfind("/node_modules/")?;
+        package_name_from_path(&path[index + "/node_modules/".len()..])
+      }
+    }
+  }
+}
+
+impl<TSys: DenoLibSys> PermissionPackageResolver
+  for PermissionPackageResolverImpl<TSys>
+{
+  fn resolve_package(&self, specifier: &str) -> Option<String> {
+    let specifier = Url::parse(specifier).ok()?;
+    if specifier.scheme() == "file" {
+      let name = self.resolve_npm_package_name(&specifier)?;
+      return Some(format!("npm:{name}"));
+    }
+    let path = specifier.as_str().strip_prefix(self.jsr_url.as_str())?;
+    let name =
+      package_name_from_path(path).filter(|name| name.starts_with('@'))?;
+    Some(format!("jsr:{name}"))
+  }
+}
+
+/// Gets the package name at the start of the path, which includes the
+/// scope when there is one.
+fn package_name_from_path(path: &str) -> Option<String> {
+  let mut parts = path.split('/');
+  let first = parts.next().filter(|part| !part.is_empty())?;
+  if first.starts_with('@') {
+    let name = parts.next().filter(|part| !part.is_empty())?;
+    Some(format!("{first}/{name}"))
+  } else {
+    Some(first.to_string())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_package_name_from_path() {
+    assert_eq!(
+      package_name_from_path("chalk/source/index.js"),
+      Some("chalk".to_string())
+    );
+    assert_eq!(
+      package_name_from_path("@std/path/1.0.0/mod.ts"),
+      Some("@std/path".to_string())
+    );
+    assert_eq!(package_name_from_path("@std"), None);
+    assert_eq!(package_name_from_path(""), None);
+  }
+}
@@ -33,6 +33,7 @@ use deno_runtime::deno_node::NodeExtInitServices;
 use deno_runtime::deno_node::NodeRequireLoader;
 use deno_runtime::deno_node::NodeResolver;
 use deno_runtime::deno_permissions::PermissionsContainer;
+use deno_runtime::deno_permissions::has_package_resolver;
 use deno_runtime::deno_process::NpmProcessStateProviderRc;
 use deno_runtime::deno_telemetry::OtelConfig;
 use deno_runtime::deno_tls::RootCertStoreProvider;
@@ -349,6 +350,8 @@ pub struct LibMainWorkerOptions {
   pub startup_snapshot: Option<&'static [u8]>,
   pub serve_port: Option<u16>,
   pub serve_host: Option<String>,
+  pub cron_config: deno_runtime::deno_cron::CronConfig,
+  pub cron_db_path: Option<PathBuf>,
 }
 
 #[derive(Default, Clone)]
@@ -504,11 +507,15 @@ impl<TSys: DenoLibSys> LibWorkerFactorySharedState<TSys> {
         worker_type: args.worker_type,
         stdio: stdio.clone(),
         cache_storage_dir,
+        cron_config: shared.options.cron_config,
+        cron_db_path: shared.options.cron_db_path.clone(),
         strace_ops: shared.options.strace_ops.clone(),
         close_on_idle: args.close_on_idle,
         maybe_worker_metadata: args.maybe_worker_metadata,
         enable_raw_imports: shared.options.enable_raw_imports,
-        enable_stack_trace_arg_in_ops: has_trace_permissions_enabled(),
+        // the stack is needed to know which package calls an op
+        enable_stack_trace_arg_in_ops: has_trace_permissions_enabled()
+          || has_package_resolver(),
       };
 
       WebWorker::bootstrap_from_options(services, options)
@@ -693,10 +700,13 @@ impl<TSys: DenoLibSys> LibMainWorkerFactory<TSys> {
       strace_ops: shared.options.strace_ops.clone(),
       cache_storage_dir,
       origin_storage_dir,
+      cron_config: shared.options.cron_config,
+      cron_db_path: shared.options.cron_db_path.clone(),
       stdio,
       skip_op_registration: shared.options.skip_op_registration,
       enable_raw_imports: shared.options.enable_raw_imports,
-      enable_stack_trace_arg_in_ops: has_trace_permissions_enabled(),
+      enable_stack_trace_arg_in_ops: has_trace_permissions_enabled()
+        || has_package_resolver(),
       unconfigured_runtime,
     };
 
@@ -7,6 +7,8 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 
+use deno_ast::ParsedSource;
+use deno_ast::ProgramRef;
 use deno_ast::SourceRange;
 use deno_ast::SourceRangedForSpanned;
 use deno_ast::SourceTextInfo;
@@ -53,6 +55,7 @@ use super::documents::DocumentModule;
 use super::documents::DocumentModules;
 use super::language_server;
 use super::resolver::LspResolver;
+use super::text;
 use super::tsc;
 use crate::args::jsr_url;
 use crate::tools::lint::CliLinter;
@@ -1384,6 +1387,33 @@ pub fn source_range_to_lsp_range(
   }
 }
 
+/// Gets the range of the top level statements overlapping the provided range,
+/// which are the nodes formatted for a range formatting request.
+pub fn enclosing_top_level_range(
+  parsed_source: &ParsedSource,
+  range: lsp::Range,
+) -> lsp::Range {
+  let item_ranges = match parsed_source.program_ref() {
+    ProgramRef::Module(module) => {
+      module.body.iter().map(|i| i.range()).collect::<Vec<_>>()
+    }
+    ProgramRef::Script(script) => {
+      script.body.iter().map(|s| s.range()).collect::<Vec<_>>()
+    }
+  };
+  let mut enclosing_range = text::full_lines_range(range);
+  for item_range in item_ranges {
+    let item_range =
+      source_range_to_lsp_range(&item_range, parsed_source.text_info_lazy());
+    if item_range.end < range.start || item_range.start > range.end {
+      continue;
+    }
+    enclosing_range.start = enclosing_range.start.min(item_range.start);
+    enclosing_range.end = enclosing_range.end.max(item_range.end);
+  }
+  enclosing_range
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1504,6 +1534,34 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_enclosing_top_level_range() {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: resolve_url("file:///a.ts").unwrap(),
+      text: "const a = 1;\nfunction b() {\n  return a;\n}\nconst c = 2;\n"
+        .into(),
+      media_type: deno_ast::MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let range = enclosing_top_level_range(
+      &parsed_source,
+      Range {
+        start: Position::new(2, 2),
+        end: Position::new(2, 5),
+      },
+    );
+    assert_eq!(
+      range,
+      Range {
+        start: Position::new(1, 0),
+        end: Position::new(3, 1),
+      }
+    );
+  }
+
   #[test]
   fn test_prepend_whitespace() {
     // Regression test for https://github.com/denoland/deno/issues/23361.
@@ -37,6 +37,70 @@ fn code_action_capabilities(
     .unwrap_or(CodeActionProviderCapability::Simple(true))
 }
 
+pub fn will_rename_files_registration_options()
+-> FileOperationRegistrationOptions {
+  FileOperationRegistrationOptions {
+    filters: vec![FileOperationFilter {
+      scheme: Some("file".to_string()),
+      pattern: FileOperationPattern {
+        glob: "**/*".to_string(),
+        matches: None,
+        options: None,
+      },
+    }],
+  }
+}
+
+/// Clients that support dynamic registration have `workspace/willRenameFiles`
+/// registered after initialization instead.
+fn file_operations_capabilities(
+  client_capabilities: &ClientCapabilities,
+) -> Option<WorkspaceFileOperationsServerCapabilities> {
+  let file_operations = client_capabilities
+    .workspace
+    .as_ref()?
+    .file_operations
+    .as_ref()?;
+  if file_operations.dynamic_registration == Some(true) {
+    return None;
+  }
+  file_operations.will_rename.filter(|w| *w)?;
+  Some(WorkspaceFileOperationsServerCapabilities {
+    will_rename: Some(will_rename_files_registration_options()),
+    ..Default::default()
+  })
+}
+
+/// Diagnostics are only pulled by clients that can also be asked to refresh
+/// them, otherwise they're pushed to the client.
+///
+/// Workspace diagnostics only include dependency and lint diagnostics for
+/// the files that aren't open, since type checking the whole workspace on
+/// each request would be too slow. Type errors are reported once a file is
+/// opened.
+fn diagnostic_capabilities(
+  client_capabilities: &ClientCapabilities,
+) -> Option<DiagnosticServerCapabilities> {
+  client_capabilities
+    .text_document
+    .as_ref()?
+    .diagnostic
+    .as_ref()?;
+  client_capabilities
+    .workspace
+    .as_ref()?
+    .diagnostic
+    .as_ref()?
+    .refresh_support
+    .filter(|s| *s)?;
+  Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
+    identifier: Some("deno".to_string()),
+    inter_file_dependencies: true,
+    workspace_diagnostics: true,
+    work_done_progress_options: Default::default(),
+  }))
+}
+
 pub fn semantic_tokens_registration_options()
 -> SemanticTokensRegistrationOptions {
   const LANGUAGES: [&str; 4] = [
@@ -81,6 +145,7 @@ pub fn server_capabilities(
   client_capabilities: &ClientCapabilities,
 ) -> ServerCapabilities {
   let code_action_provider = code_action_capabilities(client_capabilities);
+  let diagnostic_provider = diagnostic_capabilities(client_capabilities);
   ServerCapabilities {
     text_document_sync: Some(TextDocumentSyncCapability::Options(
       TextDocumentSyncOptions {
@@ -149,14 +214,17 @@ pub fn server_capabilities(
       resolve_provider: Some(true),
     }),
     document_formatting_provider: Some(OneOf::Left(true)),
-    document_range_formatting_provider: None,
+    document_range_formatting_provider: Some(OneOf::Left(true)),
     document_on_type_formatting_provider: None,
     selection_range_provider: Some(SelectionRangeProviderCapability::Simple(
       true,
     )),
     folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
     rename_provider: Some(OneOf::Left(true)),
-    document_link_provider: None,
+    document_link_provider: Some(DocumentLinkOptions {
+      resolve_provider: Some(false),
+      work_done_progress_options: Default::default(),
+    }),
     color_provider: None,
     execute_command_provider: Some(ExecuteCommandOptions {
       commands: vec![
@@ -186,7 +254,7 @@ pub fn server_capabilities(
         supported: Some(true),
         change_notifications: Some(OneOf::Left(true)),
       }),
-      file_operations: None,
+      file_operations: file_operations_capabilities(client_capabilities),
     }),
     linked_editing_range_provider: None,
     moniker_provider: None,
@@ -197,7 +265,7 @@ pub fn server_capabilities(
     })),
     inlay_hint_provider: Some(OneOf::Left(true)),
     position_encoding: None,
-    diagnostic_provider: None,
+    diagnostic_provider,
     inline_value_provider: None,
     inline_completion_provider: None,
     notebook_document_sync: Some(OneOf::Left(NotebookDocumentSyncOptions {
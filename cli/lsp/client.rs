@@ -14,6 +14,7 @@ use tower_lsp::lsp_types::ConfigurationItem;
 
 use super::config::SETTINGS_SECTION;
 use super::config::WorkspaceSettings;
+use super::logging::lsp_warn;
 use super::lsp_custom;
 use super::testing::lsp_custom as testing_lsp_custom;
 use crate::lsp::repl::get_repl_workspace_settings;
@@ -58,6 +59,15 @@ impl Client {
     self.0.publish_diagnostics(uri, diags, version).await;
   }
 
+  /// Asks a client using pull diagnostics to request them again.
+  pub fn refresh_diagnostics(&self) {
+    // do on a task in case the caller currently is in the lsp lock
+    let client = self.0.clone();
+    spawn(async move {
+      client.refresh_diagnostics().await;
+    });
+  }
+
   pub fn send_registry_state_notification(
     &self,
     params: lsp_custom::RegistryStateNotificationParams,
@@ -172,6 +182,7 @@ trait ClientTrait: Send + Sync {
     diagnostics: Vec<lsp::Diagnostic>,
     version: Option<i32>,
   );
+  async fn refresh_diagnostics(&self);
   async fn send_registry_state_notification(
     &self,
     params: lsp_custom::RegistryStateNotificationParams,
@@ -218,6 +229,16 @@ impl ClientTrait for TowerClient {
     self.0.publish_diagnostics(uri, diagnostics, version).await
   }
 
+  async fn refresh_diagnostics(&self) {
+    if let Err(err) = self
+      .0
+      .send_request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+      .await
+    {
+      lsp_warn!("Failed to refresh diagnostics: {}", err);
+    }
+  }
+
   async fn send_registry_state_notification(
     &self,
     params: lsp_custom::RegistryStateNotificationParams,
@@ -380,6 +401,8 @@ impl ClientTrait for ReplClient {
   ) {
   }
 
+  async fn refresh_diagnostics(&self) {}
+
   async fn send_registry_state_notification(
     &self,
     _params: lsp_custom::RegistryStateNotificationParams,
@@ -36,6 +36,7 @@ use deno_core::url::Url;
 use deno_lib::args::has_flag_env_var;
 use deno_lib::util::hash::FastInsecureHasher;
 use deno_npm::npm_rc::ResolvedNpmRc;
+use deno_npm_cache::MinimumReleaseAge;
 use deno_npm_cache::NpmCacheSetting;
 use deno_npm_installer::LifecycleScriptsConfig;
 use deno_npm_installer::NpmInstallerFactory;
@@ -1229,6 +1230,7 @@ pub struct ConfigData {
   pub vendor_dir: Option<PathBuf>,
   pub lockfile: Option<Arc<CliLockfile>>,
   pub npmrc: Option<Arc<ResolvedNpmRc>>,
+  pub minimum_release_age: Option<Arc<MinimumReleaseAge>>,
   pub import_map_from_settings: Option<ModuleSpecifier>,
   pub specified_import_map: Option<SpecifiedImportMap>,
   pub unstable: BTreeSet<String>,
@@ -1485,6 +1487,14 @@ impl ConfigData {
       })
       .ok()
       .map(|value| value.0.clone());
+    let minimum_release_age = npm_installer_factory
+      .minimum_release_age()
+      .inspect_err(|err| {
+        lsp_warn!("  Couldn't read minimum release age for \"{scope}\": {err}");
+      })
+      .ok()
+      .flatten()
+      .cloned();
     let default_file_pattern_base =
       scope.to_file_path().unwrap_or_else(|_| PathBuf::from("/"));
     let fmt_config = Arc::new(
@@ -1652,6 +1662,7 @@ impl ConfigData {
       vendor_dir,
       lockfile,
       npmrc,
+      minimum_release_age,
       import_map_from_settings,
       specified_import_map,
       unstable,
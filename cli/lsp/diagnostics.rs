@@ -5,7 +5,9 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::thread;
 
 use console_static_text::ansi::strip_ansi_codes;
@@ -52,6 +54,7 @@ use super::client::Client;
 use super::documents::Document;
 use super::documents::DocumentModule;
 use super::documents::DocumentModules;
+use super::documents::parse_source;
 use super::language_server;
 use super::language_server::StateSnapshot;
 use super::performance::Performance;
@@ -159,9 +162,17 @@ impl DiagnosticsPublisher {
         .cloned()
         .collect::<Vec<_>>();
 
-      self
-        .state
-        .update(&record.uri, version, &all_specifier_diagnostics);
+      let changed = self.state.update(
+        &record.uri,
+        version,
+        all_specifier_diagnostics.clone(),
+      );
+      if self.state.is_pull_mode() {
+        if changed {
+          messages_sent += 1;
+        }
+        continue;
+      }
       self
         .client
         .publish_diagnostics(
@@ -187,7 +198,14 @@ impl DiagnosticsPublisher {
         uris_to_remove.push(uri.clone());
         if let Some(removed_value) = maybe_removed_value {
           // clear out any diagnostics for this specifier
-          self.state.update(uri, removed_value.version, &[]);
+          let changed =
+            self.state.update(uri, removed_value.version, Vec::new());
+          if self.state.is_pull_mode() {
+            if changed {
+              messages_sent += 1;
+            }
+            continue;
+          }
           self
             .client
             .publish_diagnostics(
@@ -206,6 +224,11 @@ impl DiagnosticsPublisher {
       diagnostics_by_uri.remove(&specifier);
     }
 
+    // pull diagnostic clients need to be told to request the new results
+    if messages_sent > 0 && self.state.is_pull_mode() && !token.is_cancelled() {
+      self.client.refresh_diagnostics();
+    }
+
     messages_sent
   }
 
@@ -306,25 +329,75 @@ struct ChannelUpdateMessage {
 #[derive(Debug)]
 struct DocumentDiagnosticsState {
   version: i32,
+  result_id: String,
+  diagnostics: Vec<lsp::Diagnostic>,
   no_cache_diagnostics: Vec<lsp::Diagnostic>,
 }
 
+#[derive(Debug)]
+struct ClosedDocumentDiagnosticsState {
+  script_version: String,
+  project_version: usize,
+  result_id: String,
+  diagnostics: Vec<lsp::Diagnostic>,
+}
+
+/// Diagnostics that are returned for a `textDocument/diagnostic` or
+/// `workspace/diagnostic` request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PulledDiagnostics {
+  pub uri: Uri,
+  pub version: Option<i32>,
+  pub result_id: String,
+  pub diagnostics: Vec<lsp::Diagnostic>,
+}
+
 #[derive(Debug, Default)]
 pub struct DiagnosticsState {
   documents: RwLock<HashMap<Uri, DocumentDiagnosticsState>>,
+  /// Diagnostics of workspace files that aren't open, which are only
+  /// generated when requested by pull diagnostic clients.
+  closed_documents: RwLock<HashMap<Uri, ClosedDocumentDiagnosticsState>>,
+  pull_mode: AtomicBool,
+  next_result_id: AtomicUsize,
 }
 
 impl DiagnosticsState {
-  fn update(&self, uri: &Uri, version: i32, diagnostics: &[lsp::Diagnostic]) {
+  /// Stops publishing diagnostics and instead stores them until
+  /// they're requested by the client.
+  pub fn enable_pull_mode(&self) {
+    self.pull_mode.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_pull_mode(&self) -> bool {
+    self.pull_mode.load(Ordering::Relaxed)
+  }
+
+  fn next_result_id(&self) -> String {
+    (self.next_result_id.fetch_add(1, Ordering::Relaxed) + 1).to_string()
+  }
+
+  /// Returns `true` if the stored diagnostics changed.
+  fn update(
+    &self,
+    uri: &Uri,
+    version: i32,
+    diagnostics: Vec<lsp::Diagnostic>,
+  ) -> bool {
     let mut specifiers = self.documents.write();
-    let current_version = specifiers.get(uri).map(|s| s.version);
-    if let Some(current_version) = current_version {
-      if version < current_version {
-        return;
+    if let Some(current) = specifiers.get_mut(uri) {
+      if version < current.version {
+        return false;
+      }
+      if current.diagnostics == diagnostics {
+        // keep the result id so pull clients get an unchanged report
+        current.version = version;
+        return false;
       }
     }
+    let result_id = self.next_result_id();
     let mut no_cache_diagnostics = vec![];
-    for diagnostic in diagnostics {
+    for diagnostic in &diagnostics {
       if diagnostic.code
         == Some(lsp::NumberOrString::String("no-cache".to_string()))
         || diagnostic.code
@@ -339,15 +412,89 @@ impl DiagnosticsState {
       uri.clone(),
       DocumentDiagnosticsState {
         version,
+        result_id,
+        diagnostics,
         no_cache_diagnostics,
       },
     );
+    true
   }
 
   pub fn clear(&self, uri: &Uri) {
     self.documents.write().remove(uri);
   }
 
+  /// Gets the latest diagnostics of an open document.
+  pub fn pull(&self, uri: &Uri) -> Option<PulledDiagnostics> {
+    self.documents.read().get(uri).map(|s| PulledDiagnostics {
+      uri: uri.clone(),
+      version: Some(s.version),
+      result_id: s.result_id.clone(),
+      diagnostics: s.diagnostics.clone(),
+    })
+  }
+
+  /// Gets the latest diagnostics of all open documents.
+  pub fn pull_all(&self) -> Vec<PulledDiagnostics> {
+    self
+      .documents
+      .read()
+      .iter()
+      .map(|(uri, s)| PulledDiagnostics {
+        uri: uri.clone(),
+        version: Some(s.version),
+        result_id: s.result_id.clone(),
+        diagnostics: s.diagnostics.clone(),
+      })
+      .collect()
+  }
+
+  /// Gets the diagnostics of a document that isn't open, only calling
+  /// `generate` when the document or the project changed since the last
+  /// request, since the diagnostics also depend on other documents and the
+  /// config. Stale diagnostics can be dropped early with `clear_closed()`.
+  pub fn pull_closed(
+    &self,
+    uri: &Uri,
+    script_version: &str,
+    project_version: usize,
+    generate: impl FnOnce() -> Vec<lsp::Diagnostic>,
+  ) -> PulledDiagnostics {
+    if let Some(state) = self.closed_documents.read().get(uri) {
+      if state.script_version == script_version
+        && state.project_version == project_version
+      {
+        return PulledDiagnostics {
+          uri: uri.clone(),
+          version: None,
+          result_id: state.result_id.clone(),
+          diagnostics: state.diagnostics.clone(),
+        };
+      }
+    }
+    let diagnostics = generate();
+    let result_id = self.next_result_id();
+    self.closed_documents.write().insert(
+      uri.clone(),
+      ClosedDocumentDiagnosticsState {
+        script_version: script_version.to_string(),
+        project_version,
+        result_id: result_id.clone(),
+        diagnostics: diagnostics.clone(),
+      },
+    );
+    PulledDiagnostics {
+      uri: uri.clone(),
+      version: None,
+      result_id,
+      diagnostics,
+    }
+  }
+
+  pub fn clear_closed(&self) {
+    self.closed_documents.write().clear();
+  }
+
   pub fn has_no_cache_diagnostics(&self, uri: &Uri) -> bool {
     self
       .documents
@@ -1939,6 +2086,65 @@ fn generate_deno_diagnostics(
   (diagnostics_vec, deferred_diagnostics)
 }
 
+/// Generates the dependency and lint diagnostics for a workspace module that
+/// isn't open in the client. Type checking is left to open documents.
+pub fn generate_closed_document_diagnostics(
+  snapshot: &StateSnapshot,
+  module: &DocumentModule,
+  token: &CancellationToken,
+) -> Vec<lsp::Diagnostic> {
+  let mut diagnostics = Vec::new();
+  if token.is_cancelled()
+    || !module.is_diagnosable()
+    || module.specifier.scheme() != "file"
+    || !snapshot.config.specifier_enabled(&module.specifier)
+    || snapshot.resolver.in_node_modules(&module.specifier)
+  {
+    return diagnostics;
+  }
+  // diagnostics for ambient modules are only known after type checking
+  let mut deferred = Vec::new();
+  for (dependency_key, dependency) in module.dependencies.iter() {
+    diagnose_dependency(
+      &mut diagnostics,
+      &mut deferred,
+      snapshot,
+      module,
+      dependency_key,
+      dependency,
+    );
+  }
+  let settings = snapshot
+    .config
+    .workspace_settings_for_specifier(&module.specifier);
+  if !settings.lint {
+    return diagnostics;
+  }
+  let linter = snapshot.linter_resolver.for_module(module);
+  if !linter
+    .lint_config
+    .files
+    .matches_specifier(&module.specifier)
+  {
+    return diagnostics;
+  }
+  let parsed_source = parse_source(
+    module.specifier.as_ref().clone(),
+    module.text.to_arc(),
+    module.media_type,
+  );
+  if let Ok(parsed_source) = parsed_source {
+    if let Ok(references) = analysis::get_lint_references(
+      &parsed_source,
+      &linter.inner,
+      token.clone(),
+    ) {
+      diagnostics.extend(references.into_iter().map(|r| r.to_diagnostic()));
+    }
+  }
+  diagnostics
+}
+
 #[cfg(test)]
 mod tests {
   use std::str::FromStr;
@@ -2321,6 +2527,40 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_diagnostics_state_result_ids() {
+    let state = DiagnosticsState::default();
+    let uri = Uri::from_str("file:///a/mod.ts").unwrap();
+    let diagnostic = lsp::Diagnostic {
+      message: "message".to_string(),
+      ..Default::default()
+    };
+    assert!(state.update(&uri, 1, vec![diagnostic.clone()]));
+    let first = state.pull(&uri).unwrap();
+    // unchanged diagnostics keep their result id
+    assert!(!state.update(&uri, 2, vec![diagnostic.clone()]));
+    let second = state.pull(&uri).unwrap();
+    assert_eq!(second.result_id, first.result_id);
+    assert_eq!(second.version, Some(2));
+    // older versions are ignored
+    assert!(!state.update(&uri, 1, vec![]));
+    assert!(state.update(&uri, 3, vec![]));
+    assert_ne!(state.pull(&uri).unwrap().result_id, first.result_id);
+
+    let mut generated = 0;
+    let mut pull_closed = |script_version: &str, project_version: usize| {
+      state.pull_closed(&uri, script_version, project_version, || {
+        generated += 1;
+        vec![diagnostic.clone()]
+      })
+    };
+    let first = pull_closed("1", 1);
+    assert_eq!(pull_closed("1", 1), first);
+    assert_ne!(pull_closed("2", 1).result_id, first.result_id);
+    assert_ne!(pull_closed("2", 2).result_id, first.result_id);
+    assert_eq!(generated, 3);
+  }
+
   #[test]
   fn test_specifier_text_for_redirected() {
     #[track_caller]
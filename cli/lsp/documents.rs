@@ -2003,7 +2003,7 @@ fn parse_and_analyze_module(
 }
 
 #[allow(clippy::result_large_err)]
-fn parse_source(
+pub fn parse_source(
   specifier: ModuleSpecifier,
   text: Arc<str>,
   media_type: MediaType,
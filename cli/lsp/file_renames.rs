@@ -0,0 +1,332 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! Support for `workspace/willRenameFiles` beyond what TypeScript's
+//! `getEditsForFileRename()` provides: directory moves, keeping the
+//! extensions of Deno-style relative specifiers and updating the paths in
+//! `deno.json` files and import maps.
+
+use std::path::Path;
+
+use deno_core::serde_json;
+use deno_core::url::Url;
+use jsonc_parser::ast;
+use tower_lsp::lsp_types as lsp;
+
+use crate::util::path::relative_specifier;
+
+/// The files and directories being renamed, with directory renames
+/// expanded to the known files they contain.
+#[derive(Debug, Default)]
+pub struct FileRenames {
+  pub files: Vec<(Url, Url)>,
+  dirs: Vec<(Url, Url)>,
+}
+
+impl FileRenames {
+  pub fn new<'a>(
+    renames: impl IntoIterator<Item = (Url, Url)>,
+    known_files: impl IntoIterator<Item = &'a Url> + Clone,
+  ) -> Self {
+    let mut result = Self::default();
+    for (old, new) in renames {
+      let old_dir = as_dir_url(&old);
+      let new_dir = as_dir_url(&new);
+      let mut is_dir = old.path().ends_with('/');
+      for file in known_files.clone() {
+        if let Some(rest) = file.as_str().strip_prefix(old_dir.as_str()) {
+          is_dir = true;
+          if let Ok(new_file) = Url::parse(&format!("{new_dir}{rest}")) {
+            result.files.push((file.clone(), new_file));
+          }
+        }
+      }
+      if is_dir {
+        result.dirs.push((old_dir, new_dir));
+      } else {
+        result.files.push((old, new));
+      }
+    }
+    result
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.files.is_empty() && self.dirs.is_empty()
+  }
+
+  /// Gets where the specifier will be located after the rename, if it's
+  /// affected by it.
+  pub fn new_specifier(&self, specifier: &Url) -> Option<Url> {
+    if let Some((_, new)) = self.files.iter().find(|(old, _)| old == specifier)
+    {
+      return Some(new.clone());
+    }
+    self.dirs.iter().find_map(|(old_dir, new_dir)| {
+      let rest = specifier.as_str().strip_prefix(old_dir.as_str())?;
+      Url::parse(&format!("{new_dir}{rest}")).ok()
+    })
+  }
+
+  /// Gets the updated text of a relative specifier in the referrer.
+  ///
+  /// TypeScript picks the ending of the specifiers it updates from its module
+  /// specifier preferences, so this is used to keep them written the way Deno
+  /// resolves them. Specifiers without an extension are left to TypeScript.
+  pub fn update_relative_specifier(
+    &self,
+    specifier_text: &str,
+    referrer: &Url,
+  ) -> Option<String> {
+    if !specifier_text.starts_with("./") && !specifier_text.starts_with("../") {
+      return None;
+    }
+    Path::new(specifier_text).extension()?;
+    let target = referrer.join(specifier_text).ok()?;
+    let new_target = self.new_specifier(&target);
+    let new_referrer = self.new_specifier(referrer);
+    if new_target.is_none() && new_referrer.is_none() {
+      return None;
+    }
+    relative_specifier(
+      new_referrer.as_ref().unwrap_or(referrer),
+      new_target.as_ref().unwrap_or(&target),
+    )
+  }
+
+  /// Gets the edits to a `deno.json` for the paths in its `imports`,
+  /// `scopes`, `exports` and `importMap` properties that are affected by
+  /// the rename.
+  pub fn config_file_edits(
+    &self,
+    config_specifier: &Url,
+    text: &str,
+  ) -> Vec<lsp::TextEdit> {
+    self.json_path_edits(
+      config_specifier,
+      text,
+      &["imports", "scopes", "exports", "importMap"],
+    )
+  }
+
+  /// Gets the edits to an import map that's referenced by a `deno.json` or
+  /// the settings for the paths in its `imports` and `scopes` properties that
+  /// are affected by the rename.
+  pub fn import_map_edits(
+    &self,
+    import_map_specifier: &Url,
+    text: &str,
+  ) -> Vec<lsp::TextEdit> {
+    self.json_path_edits(import_map_specifier, text, &["imports", "scopes"])
+  }
+
+  fn json_path_edits(
+    &self,
+    specifier: &Url,
+    text: &str,
+    property_names: &[&str],
+  ) -> Vec<lsp::TextEdit> {
+    let Ok(parse_result) = jsonc_parser::parse_to_ast(
+      text,
+      &Default::default(),
+      &Default::default(),
+    ) else {
+      return Vec::new();
+    };
+    let Some(ast::Value::Object(root)) = parse_result.value else {
+      return Vec::new();
+    };
+    let mut strings = Vec::new();
+    for prop in &root.properties {
+      if property_names.contains(&prop.name.as_str()) {
+        collect_string_values(&prop.value, &mut strings);
+      }
+    }
+    let new_specifier = self
+      .new_specifier(specifier)
+      .unwrap_or_else(|| specifier.clone());
+    strings
+      .into_iter()
+      .filter_map(|string| {
+        let value = string.value.as_ref();
+        if !value.starts_with("./") && !value.starts_with("../") {
+          return None;
+        }
+        let target = specifier.join(value).ok()?;
+        let new_target = self.new_specifier(&target)?;
+        let new_value = relative_specifier(&new_specifier, &new_target)?;
+        Some(lsp::TextEdit {
+          range: lsp::Range {
+            start: to_lsp_position(text, string.range.start),
+            end: to_lsp_position(text, string.range.end),
+          },
+          new_text: serde_json::to_string(&new_value).ok()?,
+        })
+      })
+      .collect()
+  }
+}
+
+fn as_dir_url(url: &Url) -> Url {
+  if url.path().ends_with('/') {
+    return url.clone();
+  }
+  let mut url = url.clone();
+  url.set_path(&format!("{}/", url.path()));
+  url
+}
+
+fn collect_string_values<'a>(
+  value: &'a ast::Value<'a>,
+  strings: &mut Vec<&'a ast::Str<'a>>,
+) {
+  match value {
+    ast::Value::StringLit(string) => strings.push(string),
+    ast::Value::Object(object) => {
+      for prop in &object.properties {
+        collect_string_values(&prop.value, strings);
+      }
+    }
+    _ => {}
+  }
+}
+
+fn to_lsp_position(text: &str, byte_offset: usize) -> lsp::Position {
+  let text = &text[..byte_offset];
+  let line_start = text.rfind('\n').map(|i| i + 1).unwrap_or(0);
+  lsp::Position {
+    line: text.matches('\n').count() as u32,
+    character: text[line_start..].encode_utf16().count() as u32,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn url(path: &str) -> Url {
+    Url::parse(&format!("file:///project/{path}")).unwrap()
+  }
+
+  #[test]
+  fn test_file_renames_directory() {
+    let known_files = [url("lib/a.ts"), url("lib/b/c.ts"), url("libs/d.ts")];
+    let renames =
+      FileRenames::new([(url("lib"), url("src/lib"))], known_files.iter());
+    assert_eq!(
+      renames.files,
+      vec![
+        (url("lib/a.ts"), url("src/lib/a.ts")),
+        (url("lib/b/c.ts"), url("src/lib/b/c.ts")),
+      ]
+    );
+    assert_eq!(
+      renames.new_specifier(&url("lib/e.json")),
+      Some(url("src/lib/e.json"))
+    );
+    assert_eq!(renames.new_specifier(&url("libs/d.ts")), None);
+  }
+
+  #[test]
+  fn test_update_relative_specifier() {
+    let renames =
+      FileRenames::new([(url("a.ts"), url("sub/a.ts"))], [url("a.ts")].iter());
+    assert_eq!(
+      renames.update_relative_specifier("./a.ts", &url("main.ts")),
+      Some("./sub/a.ts".to_string())
+    );
+    // specifiers in the moved file
+    assert_eq!(
+      renames.update_relative_specifier("./b.ts", &url("a.ts")),
+      Some("../b.ts".to_string())
+    );
+    assert_eq!(
+      renames.update_relative_specifier("./b.ts", &url("main.ts")),
+      None
+    );
+    assert_eq!(
+      renames.update_relative_specifier("./a", &url("main.ts")),
+      None
+    );
+    assert_eq!(
+      renames.update_relative_specifier("jsr:@std/path", &url("main.ts")),
+      None
+    );
+  }
+
+  #[test]
+  fn test_config_file_edits() {
+    let renames = FileRenames::new(
+      [(url("lib"), url("src"))],
+      [url("lib/mod.ts"), url("lib/util.ts")].iter(),
+    );
+    let text = r#"{
+  // comment
+  "imports": { "@lib/": "./lib/", "@std/path": "jsr:@std/path@1" },
+  "exports": { ".": "./lib/mod.ts", "./other": "./other.ts" }
+}"#;
+    let edits = renames.config_file_edits(&url("deno.json"), text);
+    assert_eq!(
+      edits,
+      vec![
+        lsp::TextEdit {
+          range: lsp::Range {
+            start: lsp::Position {
+              line: 2,
+              character: 24,
+            },
+            end: lsp::Position {
+              line: 2,
+              character: 32,
+            },
+          },
+          new_text: "\"./src/\"".to_string(),
+        },
+        lsp::TextEdit {
+          range: lsp::Range {
+            start: lsp::Position {
+              line: 3,
+              character: 20,
+            },
+            end: lsp::Position {
+              line: 3,
+              character: 34,
+            },
+          },
+          new_text: "\"./src/mod.ts\"".to_string(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_import_map_edits() {
+    let renames = FileRenames::new(
+      [(url("lib/mod.ts"), url("src/mod.ts"))],
+      [url("lib/mod.ts")].iter(),
+    );
+    let text = r#"{
+  "imports": { "lib": "./lib/mod.ts" },
+  "exports": { ".": "./lib/mod.ts" }
+}"#;
+    let edits = renames.import_map_edits(&url("maps/import_map.json"), text);
+    assert_eq!(edits, vec![]);
+    let edits = renames.import_map_edits(&url("import_map.json"), text);
+    assert_eq!(
+      edits,
+      vec![lsp::TextEdit {
+        range: lsp::Range {
+          start: lsp::Position {
+            line: 1,
+            character: 22,
+          },
+          end: lsp::Position {
+            line: 1,
+            character: 36,
+          },
+        },
+        new_text: "\"./src/mod.ts\"".to_string(),
+      }]
+    );
+  }
+}
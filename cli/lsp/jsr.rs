@@ -323,7 +323,7 @@ pub struct CliJsrSearchApi {
 
 impl CliJsrSearchApi {
   pub fn new(file_fetcher: Arc<CliFileFetcher>) -> Self {
-    let resolver = JsrFetchResolver::new(file_fetcher.clone());
+    let resolver = JsrFetchResolver::new(file_fetcher.clone(), None);
     Self {
       file_fetcher,
       resolver,
@@ -19,6 +19,7 @@ use deno_cache_dir::file_fetcher::CacheSetting;
 use deno_core::ModuleSpecifier;
 use deno_core::anyhow::anyhow;
 use deno_core::error::AnyError;
+use deno_core::resolve_url;
 use deno_core::serde_json;
 use deno_core::serde_json::Value;
 use deno_core::serde_json::json;
@@ -36,6 +37,7 @@ use deno_path_util::url_to_file_path;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_semver::jsr::JsrPackageReqReference;
+use deno_semver::npm::NpmPackageReqReference;
 use indexmap::Equivalent;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
@@ -56,6 +58,7 @@ use tower_lsp::lsp_types::*;
 use super::analysis::CodeActionCollection;
 use super::analysis::CodeActionData;
 use super::analysis::TsResponseImportMapper;
+use super::analysis::enclosing_top_level_range;
 use super::analysis::fix_ts_import_changes;
 use super::analysis::ts_changes_to_edit;
 use super::cache::LspCache;
@@ -77,6 +80,7 @@ use super::documents::Document;
 use super::documents::DocumentModule;
 use super::documents::DocumentModules;
 use super::documents::LanguageId;
+use super::file_renames::FileRenames;
 use super::jsr::CliJsrSearchApi;
 use super::logging::lsp_log;
 use super::logging::lsp_warn;
@@ -85,6 +89,7 @@ use super::lsp_custom::TaskDefinition;
 use super::npm::CliNpmSearchApi;
 use super::parent_process_checker;
 use super::performance::Performance;
+use super::performance::PerformanceMark;
 use super::refactor;
 use super::registries::ModuleRegistry;
 use super::resolver::LspResolver;
@@ -99,6 +104,7 @@ use super::urls::url_to_uri;
 use crate::args::Flags;
 use crate::args::InternalFlags;
 use crate::args::UnstableFmtOptions;
+use crate::args::jsr_url;
 use crate::factory::CliFactory;
 use crate::file_fetcher::CreateCliFileFetcherOptions;
 use crate::file_fetcher::create_cli_file_fetcher;
@@ -953,6 +959,9 @@ impl Inner {
       self.config.set_client_capabilities(params.capabilities);
     }
 
+    if capabilities.diagnostic_provider.is_some() {
+      self.diagnostics_state.enable_pull_mode();
+    }
     self.diagnostics_server.start();
     self
       .ts_server
@@ -1237,6 +1246,9 @@ impl Inner {
       &self.cache,
       &self.workspace_files,
     );
+    if self.diagnostics_state.is_pull_mode() {
+      self.client.refresh_diagnostics();
+    }
 
     // refresh the npm specifiers because it might have discovered
     // a @types/node package and now's a good time to do that anyway
@@ -1753,6 +1765,216 @@ impl Inner {
     Ok(response)
   }
 
+  #[cfg_attr(feature = "lsp-tracing", tracing::instrument(skip_all))]
+  fn diagnostic(
+    &self,
+    params: DocumentDiagnosticParams,
+    token: &CancellationToken,
+  ) -> LspResult<DocumentDiagnosticReportResult> {
+    let mark = self.performance.mark_with_args("lsp.diagnostic", &params);
+    let uri = &params.text_document.uri;
+    let pulled = match self.diagnostics_state.pull(uri) {
+      Some(pulled) => Some(pulled),
+      None => self
+        .get_document(
+          uri,
+          Enabled::Filter,
+          Exists::Filter,
+          Diagnosable::Filter,
+        )?
+        .map(|document| self.get_primary_module(&document))
+        .transpose()?
+        .flatten()
+        .filter(|module| module.open_data.is_none())
+        .map(|module| {
+          let snapshot = self.snapshot();
+          self.diagnostics_state.pull_closed(
+            uri,
+            &module.script_version,
+            snapshot.project_version,
+            || {
+              diagnostics::generate_closed_document_diagnostics(
+                &snapshot, &module, token,
+              )
+            },
+          )
+        }),
+    };
+    if token.is_cancelled() {
+      return Err(LspError::request_cancelled());
+    }
+    let report = match pulled {
+      Some(pulled)
+        if params.previous_result_id.as_ref() == Some(&pulled.result_id) =>
+      {
+        DocumentDiagnosticReport::Unchanged(
+          RelatedUnchangedDocumentDiagnosticReport {
+            related_documents: None,
+            unchanged_document_diagnostic_report:
+              UnchangedDocumentDiagnosticReport {
+                result_id: pulled.result_id,
+              },
+          },
+        )
+      }
+      pulled => {
+        DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+          related_documents: None,
+          full_document_diagnostic_report: FullDocumentDiagnosticReport {
+            result_id: pulled.as_ref().map(|p| p.result_id.clone()),
+            items: pulled.map(|p| p.diagnostics).unwrap_or_default(),
+          },
+        })
+      }
+    };
+    self.performance.measure(mark);
+    Ok(DocumentDiagnosticReportResult::Report(report))
+  }
+
+  /// Collects what's needed for a workspace diagnostic request, so that the
+  /// closed documents can be linted without holding the lock.
+  #[cfg_attr(feature = "lsp-tracing", tracing::instrument(skip_all))]
+  fn workspace_diagnostic_task(
+    &self,
+    params: WorkspaceDiagnosticParams,
+  ) -> WorkspaceDiagnosticTask {
+    let mark = self
+      .performance
+      .mark_with_args("lsp.workspace_diagnostic", &params);
+    let previous_result_ids = params
+      .previous_result_ids
+      .into_iter()
+      .map(|p| (p.uri, p.value))
+      .collect();
+    let closed_modules = self
+      .document_modules
+      .workspace_file_modules_by_scope()
+      .into_values()
+      .flatten()
+      .filter(|module| module.open_data.is_none() && module.is_diagnosable())
+      .collect();
+    WorkspaceDiagnosticTask {
+      previous_result_ids,
+      pulled: self.diagnostics_state.pull_all(),
+      closed_modules,
+      snapshot: self.snapshot(),
+      diagnostics_state: self.diagnostics_state.clone(),
+      performance: self.performance.clone(),
+      mark,
+    }
+  }
+
+  #[cfg_attr(feature = "lsp-tracing", tracing::instrument(skip_all))]
+  fn document_link(
+    &self,
+    params: DocumentLinkParams,
+    token: &CancellationToken,
+  ) -> LspResult<Option<Vec<DocumentLink>>> {
+    let mark = self
+      .performance
+      .mark_with_args("lsp.document_link", &params);
+    let Some(document) = self.get_document(
+      &params.text_document.uri,
+      Enabled::Filter,
+      Exists::Enforce,
+      Diagnosable::Filter,
+    )?
+    else {
+      return Ok(None);
+    };
+    let Some(module) = self.get_primary_module(&document)? else {
+      return Ok(None);
+    };
+    let mut links = Vec::new();
+    for (specifier_text, dependency) in module.dependencies.iter() {
+      if token.is_cancelled() {
+        return Err(LspError::request_cancelled());
+      }
+      // paths are already navigable through go to definition
+      if specifier_text.starts_with("./")
+        || specifier_text.starts_with("../")
+        || specifier_text.starts_with('/')
+        || specifier_text.starts_with("file:")
+      {
+        continue;
+      }
+      let Some((target, tooltip)) =
+        self.dependency_link_target(dependency, &module)
+      else {
+        continue;
+      };
+      for import in &dependency.imports {
+        links.push(DocumentLink {
+          range: to_lsp_range(&import.specifier_range),
+          target: Some(target.clone()),
+          tooltip: Some(tooltip.clone()),
+          data: None,
+        });
+      }
+    }
+    self.performance.measure(mark);
+    Ok(Some(links))
+  }
+
+  /// Gets the cached source of the dependency to link to, falling back to
+  /// its registry page.
+  fn dependency_link_target(
+    &self,
+    dependency: &deno_graph::Dependency,
+    module: &DocumentModule,
+  ) -> Option<(Uri, String)> {
+    let specifier = dependency.get_code().or_else(|| dependency.get_type())?;
+    let scoped_resolver =
+      self.resolver.get_scoped_resolver(module.scope.as_deref());
+    let cached_module_uri = |specifier: &Url| {
+      self
+        .document_modules
+        .inspect_module_for_specifier(specifier, module.scope.as_deref())
+        .map(|m| m.uri.as_ref().clone())
+    };
+    let (url, tooltip) = match specifier.scheme() {
+      "jsr" => {
+        let req_ref = JsrPackageReqReference::from_specifier(specifier).ok()?;
+        let cached_uri = scoped_resolver
+          .jsr_to_resource_url(&req_ref)
+          .and_then(|url| cached_module_uri(&url));
+        if let Some(uri) = cached_uri {
+          return Some((uri, "Open cached source".to_string()));
+        }
+        let name = &req_ref.req().name;
+        (
+          jsr_url().join(name.as_str()).ok()?,
+          format!("Open {name} on JSR"),
+        )
+      }
+      "npm" => {
+        let req_ref = NpmPackageReqReference::from_specifier(specifier).ok()?;
+        if let Some((url, _)) = scoped_resolver.npm_to_file_url(
+          &req_ref,
+          &module.specifier,
+          NodeResolutionKind::Execution,
+          module.resolution_mode,
+        ) {
+          (url, "Open cached source".to_string())
+        } else {
+          let name = &req_ref.req().name;
+          (
+            Url::parse(&format!("https://www.npmjs.com/package/{name}"))
+              .ok()?,
+            format!("Open {name} on npm"),
+          )
+        }
+      }
+      "http" | "https" => match cached_module_uri(specifier) {
+        Some(uri) => return Some((uri, "Open cached source".to_string())),
+        None => (specifier.clone(), "Open URL".to_string()),
+      },
+      "file" => (specifier.clone(), "Open file".to_string()),
+      _ => return None,
+    };
+    Some((url_to_uri(&url).ok()?, tooltip))
+  }
+
   #[cfg_attr(feature = "lsp-tracing", tracing::instrument(skip_all))]
   async fn formatting(
     &self,
@@ -1760,8 +1982,57 @@ impl Inner {
     _token: &CancellationToken,
   ) -> LspResult<Option<Vec<TextEdit>>> {
     let mark = self.performance.mark_with_args("lsp.formatting", &params);
+    let text_edits = self
+      .get_format_edits(&params.text_document.uri, &params.options)
+      .await?
+      .map(|(text_edits, _)| text_edits);
+    self.performance.measure(mark);
+    Ok(text_edits.filter(|e| !e.is_empty()))
+  }
+
+  #[cfg_attr(feature = "lsp-tracing", tracing::instrument(skip_all))]
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+    _token: &CancellationToken,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let mark = self
+      .performance
+      .mark_with_args("lsp.range_formatting", &params);
+    let text_edits = self
+      .get_format_edits(&params.text_document.uri, &params.options)
+      .await?
+      .map(|(text_edits, module)| {
+        // dprint can only format whole files, so only keep the edits within
+        // the nodes enclosing the selection
+        let range = match module
+          .open_data
+          .as_ref()
+          .and_then(|d| d.parsed_source.as_ref())
+        {
+          Some(Ok(parsed_source)) => {
+            enclosing_top_level_range(parsed_source, params.range)
+          }
+          _ => text::full_lines_range(params.range),
+        };
+        text_edits
+          .into_iter()
+          .filter(|e| e.range.start >= range.start && e.range.end <= range.end)
+          .collect::<Vec<_>>()
+      });
+    self.performance.measure(mark);
+    Ok(text_edits.filter(|e| !e.is_empty()))
+  }
+
+  /// Formats the whole document, returning the edits along with the module
+  /// they apply to.
+  async fn get_format_edits(
+    &self,
+    uri: &Uri,
+    options: &FormattingOptions,
+  ) -> LspResult<Option<(Vec<TextEdit>, Arc<DocumentModule>)>> {
     let Some(document) = self.get_document(
-      &params.text_document.uri,
+      uri,
       Enabled::Ignore,
       Exists::Enforce,
       Diagnosable::Ignore,
@@ -1776,11 +2047,7 @@ impl Inner {
       self.config.tree.fmt_config_for_specifier(&module.specifier);
     // Untitled files are exempt from enabled-checks because they tend not to
     // have meaningful paths, and they won't be auto-formatted on save anyway.
-    let is_untitled = params
-      .text_document
-      .uri
-      .scheme()
-      .is_some_and(|s| s.eq_lowercase("untitled"));
+    let is_untitled = uri.scheme().is_some_and(|s| s.eq_lowercase("untitled"));
     if !is_untitled && !fmt_config.files.matches_specifier(&module.specifier) {
       return Ok(None);
     }
@@ -1797,8 +2064,8 @@ impl Inner {
       let config_data = self.config.tree.data_for_specifier(&module.specifier);
       #[allow(clippy::nonminimal_bool)] // clippy's suggestion is more confusing
       if !config_data.is_some_and(|d| d.maybe_deno_json().is_some()) {
-        fmt_options.use_tabs = Some(!params.options.insert_spaces);
-        fmt_options.indent_width = Some(params.options.tab_size as u8);
+        fmt_options.use_tabs = Some(!options.insert_spaces);
+        fmt_options.indent_width = Some(options.tab_size as u8);
       }
       let unstable_options = UnstableFmtOptions {
         component: config_data
@@ -1808,6 +2075,7 @@ impl Inner {
           .map(|d| d.unstable.contains("fmt-sql"))
           .unwrap_or(false),
       };
+      let module = module.clone();
       move || {
         let format_result = match &module
           .open_data
@@ -1851,16 +2119,7 @@ impl Inner {
     .await
     .unwrap();
 
-    self.performance.measure(mark);
-    if let Some(text_edits) = text_edits {
-      if text_edits.is_empty() {
-        Ok(None)
-      } else {
-        Ok(Some(text_edits))
-      }
-    } else {
-      Ok(None)
-    }
+    Ok(text_edits.map(|text_edits| (text_edits, module)))
   }
 
   #[cfg_attr(feature = "lsp-tracing", tracing::instrument(skip_all))]
@@ -3667,13 +3926,51 @@ impl Inner {
     params: RenameFilesParams,
     token: &CancellationToken,
   ) -> LspResult<Option<WorkspaceEdit>> {
-    if !self.ts_server.is_started() {
-      return Ok(None);
-    }
+    let mark = self
+      .performance
+      .mark_with_args("lsp.will_rename_files", &params);
+    let known_files = self
+      .workspace_files
+      .iter()
+      .filter_map(|path| Url::from_file_path(path).ok())
+      .chain(
+        self
+          .document_modules
+          .documents
+          .open_docs()
+          .map(|d| uri_to_url(&d.uri)),
+      )
+      .collect::<IndexSet<_>>();
+    let renames = FileRenames::new(
+      params.files.iter().filter_map(|rename| {
+        let old = Url::parse(&rename.old_uri).ok()?;
+        let new = Url::parse(&rename.new_uri).ok()?;
+        Some((old, new))
+      }),
+      known_files.iter(),
+    );
     let mut changes_with_modules = IndexMap::new();
-    for rename in params.files {
+    let mut skipped_files = 0;
+    for (old_specifier, new_specifier) in &renames.files {
+      // Note that `Always` and `Prompt` are treated the same in the server, the
+      // client will worry about that after receiving the edits.
+      let options = self
+        .config
+        .language_settings_for_specifier(old_specifier)
+        .map(|s| s.update_imports_on_file_move.clone())
+        .unwrap_or_default();
+      if options.enabled == UpdateImportsOnFileMoveEnabled::Never {
+        skipped_files += 1;
+        continue;
+      }
+      if !self.ts_server.is_started() {
+        continue;
+      }
+      let Ok(old_uri) = url_to_uri(old_specifier) else {
+        continue;
+      };
       let Some(document) = self.get_document(
-        &Uri::from_str(&rename.old_uri).unwrap(),
+        &old_uri,
         Enabled::Ignore,
         Exists::Filter,
         Diagnosable::Ignore,
@@ -3689,22 +3986,12 @@ impl Inner {
         if token.is_cancelled() {
           return Err(LspError::request_cancelled());
         }
-        let options = self
-          .config
-          .language_settings_for_specifier(&module.specifier)
-          .map(|s| s.update_imports_on_file_move.clone())
-          .unwrap_or_default();
-        // Note that `Always` and `Prompt` are treated the same in the server, the
-        // client will worry about that after receiving the edits.
-        if options.enabled == UpdateImportsOnFileMoveEnabled::Never {
-          continue;
-        }
-        let changes = self
+        let mut changes = self
         .ts_server
         .get_edits_for_file_rename(
           self.snapshot(),
           &module,
-          &uri_to_url(&Uri::from_str(&rename.new_uri).unwrap()),
+          new_specifier,
           token,
         )
         .await
@@ -3720,11 +4007,125 @@ impl Inner {
             LspError::internal_error()
           }
         })?;
+        for change in &mut changes {
+          self.keep_specifier_extensions(change, &module, &renames);
+        }
         changes_with_modules
           .extend(changes.into_iter().map(|c| (c, module.clone())));
       }
     }
-    file_text_changes_to_workspace_edit(&changes_with_modules, self, token)
+    let mut workspace_edit =
+      file_text_changes_to_workspace_edit(&changes_with_modules, self, token)?;
+    if !renames.is_empty()
+      && (renames.files.is_empty() || skipped_files < renames.files.len())
+    {
+      let config_file_ops = self.config_file_rename_ops(&renames);
+      if !config_file_ops.is_empty() {
+        let workspace_edit =
+          workspace_edit.get_or_insert_with(WorkspaceEdit::default);
+        match &mut workspace_edit.document_changes {
+          Some(DocumentChanges::Operations(ops)) => ops.extend(config_file_ops),
+          _ => {
+            workspace_edit.document_changes =
+              Some(DocumentChanges::Operations(config_file_ops));
+          }
+        }
+      }
+    }
+    self.performance.measure(mark);
+    Ok(workspace_edit)
+  }
+
+  /// Ensures the specifiers TypeScript rewrote for a rename keep their
+  /// extensions, since that's how Deno resolves them.
+  fn keep_specifier_extensions(
+    &self,
+    change: &mut tsc::FileTextChanges,
+    module: &DocumentModule,
+    renames: &FileRenames,
+  ) {
+    let Ok(referrer) = resolve_url(&change.file_name) else {
+      return;
+    };
+    let Some(referrer_module) = self
+      .document_modules
+      .inspect_module_for_specifier(&referrer, module.scope.as_deref())
+    else {
+      return;
+    };
+    for text_change in &mut change.text_changes {
+      let range = text_change
+        .span
+        .to_range(referrer_module.line_index.clone());
+      let Ok(text_range) = referrer_module.line_index.get_text_range(range)
+      else {
+        continue;
+      };
+      let Some(specifier_text) = referrer_module
+        .text
+        .get(usize::from(text_range.start())..usize::from(text_range.end()))
+      else {
+        continue;
+      };
+      if let Some(new_text) =
+        renames.update_relative_specifier(specifier_text, &referrer)
+      {
+        text_change.new_text = new_text;
+      }
+    }
+  }
+
+  /// Gets the edits to the `deno.json` files and import maps in the
+  /// workspace which refer to renamed files.
+  fn config_file_rename_ops(
+    &self,
+    renames: &FileRenames,
+  ) -> Vec<DocumentChangeOperation> {
+    let mut ops = Vec::new();
+    let mut import_maps = IndexSet::new();
+    for config_data in self.config.tree.data_by_scope().values() {
+      if let Some(import_map) = &config_data.import_map_from_settings {
+        import_maps.insert(import_map.clone());
+      }
+      let Some(config_file) = config_data.maybe_deno_json() else {
+        continue;
+      };
+      if let Ok(Some(import_map)) = config_file.to_import_map_specifier() {
+        import_maps.insert(import_map);
+      }
+      ops.extend(self.rename_edit_op(&config_file.specifier, |text| {
+        renames.config_file_edits(&config_file.specifier, text)
+      }));
+    }
+    for import_map in &import_maps {
+      if import_map.scheme() != "file" {
+        continue;
+      }
+      ops.extend(self.rename_edit_op(import_map, |text| {
+        renames.import_map_edits(import_map, text)
+      }));
+    }
+    ops
+  }
+
+  fn rename_edit_op(
+    &self,
+    specifier: &Url,
+    get_edits: impl FnOnce(&str) -> Vec<TextEdit>,
+  ) -> Option<DocumentChangeOperation> {
+    let uri = url_to_uri(specifier).ok()?;
+    let document = self.document_modules.documents.get(&uri)?;
+    let edits = get_edits(&document.text());
+    if edits.is_empty() {
+      return None;
+    }
+    Some(DocumentChangeOperation::Edit(TextDocumentEdit {
+      text_document: OptionalVersionedTextDocumentIdentifier {
+        uri,
+        version: document.open().map(|d| d.version),
+      },
+      edits: edits.into_iter().map(OneOf::Left).collect(),
+    }))
   }
 
   #[cfg_attr(feature = "lsp-tracing", tracing::instrument(skip_all))]
@@ -3800,6 +4201,8 @@ impl Inner {
     scopes_change: ProjectScopesChange,
   ) {
     self.project_version += 1; // increment before getting the snapshot
+    // diagnostics of closed documents might depend on any of the changes
+    self.diagnostics_state.clear_closed();
     self.ts_server.project_changed(
       self.snapshot(),
       &documents,
@@ -4053,6 +4456,36 @@ impl tower_lsp::LanguageServer for LanguageServer {
       .await
   }
 
+  async fn diagnostic(
+    &self,
+    params: DocumentDiagnosticParams,
+    token: CancellationToken,
+  ) -> LspResult<DocumentDiagnosticReportResult> {
+    self.init_flag.wait_raised().await;
+    self.inner.read().await.diagnostic(params, &token)
+  }
+
+  async fn workspace_diagnostic(
+    &self,
+    params: WorkspaceDiagnosticParams,
+    token: CancellationToken,
+  ) -> LspResult<WorkspaceDiagnosticReportResult> {
+    self.init_flag.wait_raised().await;
+    let task = self.inner.read().await.workspace_diagnostic_task(params);
+    deno_core::unsync::spawn_blocking(move || task.run(&token))
+      .await
+      .unwrap()
+  }
+
+  async fn document_link(
+    &self,
+    params: DocumentLinkParams,
+    token: CancellationToken,
+  ) -> LspResult<Option<Vec<DocumentLink>>> {
+    self.init_flag.wait_raised().await;
+    self.inner.read().await.document_link(params, &token)
+  }
+
   async fn formatting(
     &self,
     params: DocumentFormattingParams,
@@ -4062,6 +4495,20 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.inner.read().await.formatting(params, &token).await
   }
 
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+    token: CancellationToken,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self.init_flag.wait_raised().await;
+    self
+      .inner
+      .read()
+      .await
+      .range_formatting(params, &token)
+      .await
+  }
+
   async fn hover(
     &self,
     params: HoverParams,
@@ -4345,6 +4792,79 @@ struct PrepareCacheResult {
   open_modules: Vec<Arc<DocumentModule>>,
 }
 
+/// A workspace diagnostic request, which is run without holding the lock on
+/// the language server since it lints every closed document.
+struct WorkspaceDiagnosticTask {
+  previous_result_ids: HashMap<Uri, String>,
+  /// The diagnostics of the open documents.
+  pulled: Vec<diagnostics::PulledDiagnostics>,
+  closed_modules: Vec<Arc<DocumentModule>>,
+  snapshot: Arc<StateSnapshot>,
+  diagnostics_state: Arc<DiagnosticsState>,
+  performance: Arc<Performance>,
+  mark: PerformanceMark,
+}
+
+impl WorkspaceDiagnosticTask {
+  fn run(
+    self,
+    token: &CancellationToken,
+  ) -> LspResult<WorkspaceDiagnosticReportResult> {
+    let mut pulled = self.pulled;
+    for module in self.closed_modules {
+      if token.is_cancelled() {
+        return Err(LspError::request_cancelled());
+      }
+      pulled.push(self.diagnostics_state.pull_closed(
+        &module.uri,
+        &module.script_version,
+        self.snapshot.project_version,
+        || {
+          diagnostics::generate_closed_document_diagnostics(
+            &self.snapshot,
+            &module,
+            token,
+          )
+        },
+      ));
+    }
+    let items = pulled
+      .into_iter()
+      .map(|pulled| {
+        let version = pulled.version.map(|v| v as i64);
+        if self.previous_result_ids.get(&pulled.uri) == Some(&pulled.result_id)
+        {
+          WorkspaceDocumentDiagnosticReport::Unchanged(
+            WorkspaceUnchangedDocumentDiagnosticReport {
+              uri: pulled.uri,
+              version,
+              unchanged_document_diagnostic_report:
+                UnchangedDocumentDiagnosticReport {
+                  result_id: pulled.result_id,
+                },
+            },
+          )
+        } else {
+          WorkspaceDocumentDiagnosticReport::Full(
+            WorkspaceFullDocumentDiagnosticReport {
+              uri: pulled.uri,
+              version,
+              full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                result_id: Some(pulled.result_id),
+                items: pulled.diagnostics,
+              },
+            },
+          )
+        }
+      })
+      .collect();
+    self.performance.measure(self.mark);
+    Ok(WorkspaceDiagnosticReportResult::Report(
+      WorkspaceDiagnosticReport { items },
+    ))
+  }
+}
+
 // These are implementations of custom commands supported by the LSP
 impl Inner {
   async fn initialized(&mut self) -> Vec<Registration> {
@@ -4380,16 +4900,7 @@ impl Inner {
       });
     }
     if self.config.will_rename_files_capable() {
-      let options = FileOperationRegistrationOptions {
-        filters: vec![FileOperationFilter {
-          scheme: Some("file".to_string()),
-          pattern: FileOperationPattern {
-            glob: "**/*".to_string(),
-            matches: None,
-            options: None,
-          },
-        }],
-      };
+      let options = capabilities::will_rename_files_registration_options();
       registrations.push(Registration {
         id: "workspace/willRenameFiles".to_string(),
         method: "workspace/willRenameFiles".to_string(),
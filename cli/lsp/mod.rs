@@ -19,6 +19,7 @@ mod completions;
 mod config;
 mod diagnostics;
 mod documents;
+mod file_renames;
 mod jsr;
 pub mod language_server;
 mod lint;
@@ -32,6 +32,7 @@ impl CliNpmSearchApi {
     let resolver = NpmFetchResolver::new(
       file_fetcher.clone(),
       Arc::new(NpmRc::default().as_resolved(npm_registry_url()).unwrap()),
+      None,
     );
     Self {
       file_fetcher,
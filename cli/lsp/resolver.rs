@@ -868,6 +868,7 @@ impl<'a> ResolverFactory<'a> {
         npm_cache.clone(),
         npm_client.clone(),
         npmrc.clone(),
+        self.config_data.and_then(|d| d.minimum_release_age.clone()),
       ));
       let link_packages: Arc<WorkspaceNpmLinkPackages> = self
         .config_data
@@ -132,6 +132,14 @@ pub fn get_edits(a: &str, b: &str, line_index: &LineIndex) -> Vec<TextEdit> {
   text_edits
 }
 
+/// Expands a range to cover the whole lines it's on.
+pub fn full_lines_range(range: lsp::Range) -> lsp::Range {
+  lsp::Range {
+    start: lsp::Position::new(range.start.line, 0),
+    end: lsp::Position::new(range.end.line + 1, 0),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -120,6 +120,9 @@ async fn run_subcommand(
     DenoSubcommand::Remove(remove_flags) => {
       spawn_subcommand(async { tools::pm::remove(flags, remove_flags).await })
     }
+    DenoSubcommand::Audit(audit_flags) => {
+      spawn_subcommand(async { tools::pm::audit(flags, audit_flags).await })
+    }
     DenoSubcommand::Bench(bench_flags) => spawn_subcommand(async {
       if bench_flags.watch.is_some() {
         tools::bench::run_benchmarks_with_watch(flags, bench_flags)
@@ -205,6 +208,9 @@ async fn run_subcommand(
     DenoSubcommand::Jupyter(jupyter_flags) => spawn_subcommand(async {
       tools::jupyter::kernel(flags, jupyter_flags).await
     }),
+    DenoSubcommand::Kv(kv_flags) => {
+      spawn_subcommand(async { tools::kv::kv(kv_flags).await })
+    }
     DenoSubcommand::Uninstall(uninstall_flags) => spawn_subcommand(async {
       tools::installer::uninstall(flags, uninstall_flags).await
     }),
@@ -397,6 +403,9 @@ async fn run_subcommand(
       let types = tsc::get_types_declaration_file_text();
       display::write_to_stdout_ignore_sigpipe(types.as_bytes())
     }),
+    DenoSubcommand::Unpack(unpack_flags) => {
+      spawn_subcommand(async { tools::unpack::unpack(unpack_flags) })
+    }
     #[cfg(feature = "upgrade")]
     DenoSubcommand::Upgrade(upgrade_flags) => spawn_subcommand(async {
       tools::upgrade::upgrade(flags, upgrade_flags).await
@@ -410,6 +419,12 @@ async fn run_subcommand(
       "⚠️ `deno vendor` was removed in Deno 2.\n\nSee the Deno 1.x to 2.x Migration Guide for migration instructions: https://docs.deno.com/runtime/manual/advanced/migrate_deprecations",
       1,
     ),
+    DenoSubcommand::Sbom(sbom_flags) => {
+      spawn_subcommand(async move { tools::pm::sbom(flags, sbom_flags).await })
+    }
+    DenoSubcommand::Why(why_flags) => {
+      spawn_subcommand(async move { tools::pm::why(flags, why_flags).await })
+    }
     DenoSubcommand::Publish(publish_flags) => spawn_subcommand(async {
       tools::publish::publish(flags, publish_flags).await
     }),
@@ -571,6 +586,10 @@ pub fn main() {
     // initialize the V8 platform on a parent thread of all threads that will spawn
     // V8 isolates.
     let flags = resolve_flags_and_init(args).await?;
+    deno_lib::util::permission_audit::maybe_init_permission_audit_from_env()?;
+    deno_lib::util::permission_broker::maybe_init_permission_broker(
+      flags.permissions.permission_broker.as_deref(),
+    )?;
 
     if waited_unconfigured_runtime.is_none() {
       init_v8(&flags);
@@ -606,6 +625,12 @@ async fn resolve_flags_and_init(
   };
 
   load_env_variables_from_env_file(flags.env_file.as_ref(), flags.log_level);
+  args::take_permission_set_env_var();
+  let env_permission_broker =
+    deno_lib::util::permission_broker::take_permission_broker_env_var();
+  if flags.permissions.permission_broker.is_none() {
+    flags.permissions.permission_broker = env_permission_broker;
+  }
   flags.unstable_config.fill_with_env();
   if std::env::var("DENO_COMPAT").is_ok() {
     flags.unstable_config.enable_node_compat();
@@ -68,6 +68,7 @@ use deno_runtime::deno_node::create_host_defined_options;
 use deno_runtime::deno_node::ops::require::UnableToGetCwdError;
 use deno_runtime::deno_permissions::CheckSpecifierKind;
 use deno_runtime::deno_permissions::PermissionsContainer;
+use deno_runtime::deno_permissions::register_module_source;
 use deno_semver::npm::NpmPackageReqReference;
 use eszip::EszipV2;
 use node_resolver::InNpmPackageChecker;
@@ -696,6 +697,10 @@ impl<TGraphContainer: ModuleGraphContainer>
     loaded_module: LoadedModule,
     requested_module_type: &RequestedModuleType,
   ) -> ModuleCodeStringSource {
+    register_module_source(
+      loaded_module.specifier.as_str(),
+      loaded_module.source.as_bytes(),
+    );
     ModuleCodeStringSource {
       code: loaded_module_source_to_module_source_code(loaded_module.source),
       found_url: loaded_module.specifier.into_owned(),
@@ -1309,9 +1314,10 @@ impl<TGraphContainer: ModuleGraphContainer> NodeRequireLoader
       .sys
       .fs_read_to_string_lossy(path)
       .map_err(JsErrorBox::from_err)?;
+    let specifier =
+      deno_path_util::url_from_file_path(path).map_err(JsErrorBox::from_err)?;
+    register_module_source(specifier.as_str(), text.as_bytes());
     if media_type.is_emittable() {
-      let specifier = deno_path_util::url_from_file_path(path)
-        .map_err(JsErrorBox::from_err)?;
       if self.in_npm_pkg_checker.in_npm_package(&specifier) {
         return Err(JsErrorBox::from_err(StrippingTypesNodeModulesError {
           specifier,
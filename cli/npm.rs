@@ -16,8 +16,10 @@ use deno_npm::NpmResolutionPackage;
 use deno_npm::npm_rc::ResolvedNpmRc;
 use deno_npm::registry::NpmPackageInfo;
 use deno_npm::resolution::NpmResolutionSnapshot;
+use deno_npm_cache::MinimumReleaseAge;
 use deno_npm_cache::NpmCacheHttpClientBytesResponse;
 use deno_npm_cache::NpmCacheHttpClientResponse;
+use deno_npm_cache::SerializedCachedPackageInfo;
 use deno_npm_installer::BinEntries;
 use deno_npm_installer::CachedNpmPackageExtraInfoProvider;
 use deno_npm_installer::ExpectedExtraInfo;
@@ -159,18 +161,21 @@ pub struct NpmFetchResolver {
   info_by_name: DashMap<String, Option<Arc<NpmPackageInfo>>>,
   file_fetcher: Arc<CliFileFetcher>,
   npmrc: Arc<ResolvedNpmRc>,
+  minimum_release_age: Option<Arc<MinimumReleaseAge>>,
 }
 
 impl NpmFetchResolver {
   pub fn new(
     file_fetcher: Arc<CliFileFetcher>,
     npmrc: Arc<ResolvedNpmRc>,
+    minimum_release_age: Option<Arc<MinimumReleaseAge>>,
   ) -> Self {
     Self {
       nv_by_req: Default::default(),
       info_by_name: Default::default(),
       file_fetcher,
       npmrc,
+      minimum_release_age,
     }
   }
 
@@ -217,7 +222,15 @@ impl NpmFetchResolver {
         .fetch_bypass_permissions_with_maybe_auth(&info_url, maybe_auth_header)
         .await
         .ok()?;
-      serde_json::from_slice::<NpmPackageInfo>(&file.source).ok()
+      let package_info =
+        serde_json::from_slice::<SerializedCachedPackageInfo>(&file.source)
+          .ok()?;
+      let mut info = package_info.info;
+      if let Some(minimum_release_age) = &self.minimum_release_age {
+        minimum_release_age
+          .filter_npm_package_info(&mut info, &package_info.time);
+      }
+      Some(info)
     };
     let info = fetch_package_info().await.map(Arc::new);
     self.info_by_name.insert(name.to_string(), info.clone());
@@ -15,12 +15,10 @@ use deno_core::ModuleType;
 use deno_core::anyhow::Context;
 use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
-use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_error::JsError;
 use deno_error::JsErrorBox;
-use deno_lib::standalone::binary::DenoRtDeserializable;
-use deno_lib::standalone::binary::MAGIC_BYTES;
+use deno_lib::standalone::binary::DataSection;
 use deno_lib::standalone::binary::Metadata;
 use deno_lib::standalone::binary::RemoteModuleEntry;
 use deno_lib::standalone::binary::SpecifierDataStore;
@@ -205,65 +203,28 @@ pub fn deserialize_binary_data_section(
   root_dir_url: &Url,
   data: &'static [u8],
 ) -> Result<DeserializedDataSection, AnyError> {
-  fn read_magic_bytes(input: &[u8]) -> Result<(&[u8], bool), AnyError> {
-    if input.len() < MAGIC_BYTES.len() {
-      bail!("Unexpected end of data. Could not find magic bytes.");
-    }
-    let (magic_bytes, input) = input.split_at(MAGIC_BYTES.len());
-    if magic_bytes != MAGIC_BYTES {
-      return Ok((input, false));
-    }
-    Ok((input, true))
-  }
-
-  let (input, found) = read_magic_bytes(data)?;
-  if !found {
-    bail!("Did not find magic bytes.");
-  }
+  let DataSection {
+    metadata,
+    npm_snapshot,
+    specifiers,
+    redirects,
+    remote_modules,
+    vfs_root_entries,
+    vfs_files_data,
+  } = DataSection::deserialize(data)?;
 
-  // 1. Metadata
-  let (input, data) =
-    read_bytes_with_u64_len(input).context("reading metadata")?;
-  let metadata: Metadata =
-    serde_json::from_slice(data).context("deserializing metadata")?;
-  // 2. Npm snapshot
-  let (input, data) =
-    read_bytes_with_u64_len(input).context("reading npm snapshot")?;
-  let npm_snapshot = if data.is_empty() {
+  let npm_snapshot = if npm_snapshot.is_empty() {
     None
   } else {
-    Some(deserialize_npm_snapshot(data).context("deserializing npm snapshot")?)
+    Some(
+      deserialize_npm_snapshot(npm_snapshot)
+        .context("deserializing npm snapshot")?,
+    )
   };
-  // 3. Specifiers
-  let (input, specifiers_store) =
-    SpecifierStore::deserialize(root_dir_url, input)
-      .context("deserializing specifiers")?;
-  // 4. Redirects
-  let (input, redirects_store) =
-    SpecifierDataStore::<SpecifierId>::deserialize(input)
-      .context("deserializing redirects")?;
-  // 5. Remote modules
-  let (input, remote_modules_store) =
-    SpecifierDataStore::<RemoteModuleEntry<'static>>::deserialize(input)
-      .context("deserializing remote modules")?;
-  // 6. VFS
-  let (input, data) = read_bytes_with_u64_len(input).context("vfs")?;
-  let vfs_root_entries: VirtualDirectoryEntries =
-    serde_json::from_slice(data).context("deserializing vfs data")?;
-  let (input, vfs_files_data) =
-    read_bytes_with_u64_len(input).context("reading vfs files data")?;
-
-  // finally ensure we read the magic bytes at the end
-  let (_input, found) = read_magic_bytes(input)?;
-  if !found {
-    bail!("Could not find magic bytes at end of data.");
-  }
-
-  let modules_store = RemoteModulesStore::new(
-    specifiers_store,
-    redirects_store,
-    remote_modules_store,
-  );
+  let specifiers_store = SpecifierStore::new(root_dir_url, specifiers)
+    .context("deserializing specifiers")?;
+  let modules_store =
+    RemoteModulesStore::new(specifiers_store, redirects, remote_modules);
 
   Ok(DeserializedDataSection {
     metadata,
@@ -280,16 +241,13 @@ struct SpecifierStore {
 }
 
 impl SpecifierStore {
-  pub fn deserialize<'a>(
+  pub fn new(
     root_dir_url: &Url,
-    input: &'a [u8],
-  ) -> std::io::Result<(&'a [u8], Self)> {
-    let (input, len) = read_u32_as_usize(input)?;
-    let mut data = IndexMap::with_capacity(len);
-    let mut reverse = IndexMap::with_capacity(len);
-    let mut input = input;
-    for _ in 0..len {
-      let (new_input, specifier_str) = read_string_lossy(input)?;
+    specifiers: Vec<(Cow<str>, SpecifierId)>,
+  ) -> std::io::Result<Self> {
+    let mut data = IndexMap::with_capacity(specifiers.len());
+    let mut reverse = IndexMap::with_capacity(specifiers.len());
+    for (specifier_str, id) in specifiers {
       let specifier = match Url::parse(&specifier_str) {
         Ok(url) => url,
         Err(err) => match root_dir_url.join(&specifier_str) {
@@ -302,13 +260,11 @@ impl SpecifierStore {
           }
         },
       };
-      let (new_input, id) = SpecifierId::deserialize(new_input)?;
       let specifier = Arc::new(specifier);
       data.insert(specifier.clone(), id);
       reverse.insert(id, specifier);
-      input = new_input;
     }
-    Ok((input, Self { data, reverse }))
+    Ok(Self { data, reverse })
   }
 
   pub fn get_id(&self, specifier: &Url) -> Option<SpecifierId> {
@@ -744,12 +700,6 @@ fn parse_vec_n_times_with_index<TResult>(
   Ok((input, results))
 }
 
-fn read_bytes_with_u64_len(input: &[u8]) -> std::io::Result<(&[u8], &[u8])> {
-  let (input, len) = read_u64(input)?;
-  let (input, data) = read_bytes(input, len as usize)?;
-  Ok((input, data))
-}
-
 fn read_bytes_with_u32_len(input: &[u8]) -> std::io::Result<(&[u8], &[u8])> {
   let (input, len) = read_u32_as_usize(input)?;
   let (input, data) = read_bytes(input, len)?;
@@ -784,9 +734,3 @@ fn read_u32_as_usize(input: &[u8]) -> std::io::Result<(&[u8], usize)> {
   let len = u32::from_le_bytes(len_bytes.try_into().unwrap());
   Ok((input, len as usize))
 }
-
-fn read_u64(input: &[u8]) -> std::io::Result<(&[u8], u64)> {
-  let (input, len_bytes) = read_bytes(input, 8)?;
-  let len = u64::from_le_bytes(len_bytes.try_into().unwrap());
-  Ok((input, len))
-}
@@ -2,6 +2,7 @@
 
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::ErrorKind;
 use std::io::SeekFrom;
@@ -12,6 +13,7 @@ use std::path::PathBuf;
 use std::process::Stdio as StdStdio;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::Duration;
 use std::time::SystemTime;
 
@@ -47,12 +49,18 @@ use sys_traits::boxed::FsMetadataBoxed;
 use sys_traits::boxed::FsReadDirBoxed;
 use url::Url;
 
+use crate::vfs_overlay::OverlayLookup;
+use crate::vfs_overlay::VfsOverlay;
+
 #[derive(Debug, Clone)]
-pub struct DenoRtSys(Arc<FileBackedVfs>);
+pub struct DenoRtSys(Arc<FileBackedVfs>, Option<Arc<VfsOverlay>>);
 
 impl DenoRtSys {
-  pub fn new(vfs: Arc<FileBackedVfs>) -> Self {
-    Self(vfs)
+  pub fn new(
+    vfs: Arc<FileBackedVfs>,
+    overlay: Option<Arc<VfsOverlay>>,
+  ) -> Self {
+    Self(vfs, overlay)
   }
 
   pub fn as_deno_rt_native_addon_loader(&self) -> DenoRtNativeAddonLoaderRc {
@@ -77,10 +85,155 @@ impl DenoRtSys {
     }
   }
 
-  fn copy_to_real_path(
+  fn overlay(&self) -> FsResult<&VfsOverlay> {
+    self.1.as_deref().ok_or(FsError::NotSupported)
+  }
+
+  /// Gets the path in the overlay to modify when the path is within the
+  /// vfs, erroring when the vfs is not writable.
+  fn overlay_write_path(
+    &self,
+    path: &Path,
+    copy_existing: bool,
+  ) -> FsResult<Option<CheckedPathBuf>> {
+    if !self.0.is_path_within(path) {
+      return Ok(None);
+    }
+    let path = self.overlay()?.prepare_write(path, copy_existing)?;
+    Ok(Some(checked_overlay_path(path)))
+  }
+
+  /// Finds where to read the entry at a path within the vfs.
+  fn lookup(
+    &self,
+    path: &Path,
+    follow: bool,
+  ) -> std::io::Result<OverlayLookup> {
+    match &self.1 {
+      Some(overlay) => overlay.lookup(path, follow),
+      None => Ok(OverlayLookup::Vfs(path.to_path_buf())),
+    }
+  }
+
+  /// Finds where to open the file at a path within the vfs, which is in
+  /// the overlay when opening it for writing.
+  fn lookup_for_open(
+    &self,
+    path: &Path,
+    options: &OpenOptions,
+  ) -> std::io::Result<OverlayLookup> {
+    let write = options.write
+      || options.append
+      || options.create
+      || options.truncate
+      || options.create_new;
+    match &self.1 {
+      Some(overlay) if write => {
+        // copy an existing file for `create_new` so that opening it errors
+        let copy_existing = !options.truncate || options.create_new;
+        Ok(OverlayLookup::Overlay(
+          overlay.prepare_write(path, copy_existing)?,
+        ))
+      }
+      _ => self.lookup(path, true),
+    }
+  }
+
+  fn stat_in_vfs(&self, path: &Path, follow: bool) -> FsResult<FsStat> {
+    match self.lookup(path, follow)? {
+      OverlayLookup::Overlay(path) => {
+        let path = checked_overlay_path(path);
+        if follow {
+          RealFs.stat_sync(&path.as_checked_path())
+        } else {
+          RealFs.lstat_sync(&path.as_checked_path())
+        }
+      }
+      OverlayLookup::Vfs(path) if follow => {
+        Ok(self.0.stat(&path)?.as_fs_stat())
+      }
+      OverlayLookup::Vfs(path) => Ok(self.0.lstat(&path)?.as_fs_stat()),
+    }
+  }
+
+  fn realpath_in_vfs(&self, path: &Path) -> FsResult<PathBuf> {
+    match &self.1 {
+      Some(overlay) => Ok(overlay.canonicalize(path)?),
+      None => Ok(self.0.canonicalize(path)?),
+    }
+  }
+
+  fn read_dir_in_vfs(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
+    match &self.1 {
+      Some(overlay) => Ok(overlay.read_dir(path)?),
+      None => Ok(self.0.read_dir(path)?),
+    }
+  }
+
+  fn read_link_in_vfs(&self, path: &Path) -> FsResult<PathBuf> {
+    match self.lookup(path, false)? {
+      OverlayLookup::Overlay(path) => {
+        RealFs.read_link_sync(&checked_overlay_path(path).as_checked_path())
+      }
+      OverlayLookup::Vfs(path) => Ok(self.0.read_link(&path)?),
+    }
+  }
+
+  fn copy_file_in_vfs(
     &self,
     oldpath: &CheckedPath,
     newpath: &CheckedPath,
+  ) -> FsResult<()> {
+    let newpath = self
+      .overlay_write_path(newpath, false)?
+      .unwrap_or_else(|| newpath.as_owned());
+    if !self.0.is_path_within(oldpath) {
+      return RealFs.copy_file_sync(oldpath, &newpath.as_checked_path());
+    }
+    match self.lookup(oldpath, true)? {
+      OverlayLookup::Overlay(oldpath) => RealFs.copy_file_sync(
+        &checked_overlay_path(oldpath).as_checked_path(),
+        &newpath.as_checked_path(),
+      ),
+      OverlayLookup::Vfs(oldpath) => self
+        .copy_to_real_path(&oldpath, &newpath.as_checked_path())
+        .map(|_| ())
+        .map_err(FsError::Io),
+    }
+  }
+
+  fn rename_in_vfs(
+    &self,
+    oldpath: &CheckedPath,
+    newpath: &CheckedPath,
+  ) -> FsResult<()> {
+    let old_in_vfs = self.0.is_path_within(oldpath);
+    let overlay_oldpath = if old_in_vfs {
+      Some(checked_overlay_path(
+        self.overlay()?.prepare_rename(oldpath)?,
+      ))
+    } else {
+      None
+    };
+    let overlay_newpath = self.overlay_write_path(newpath, false)?;
+    RealFs.rename_sync(
+      &overlay_oldpath
+        .unwrap_or_else(|| oldpath.as_owned())
+        .as_checked_path(),
+      &overlay_newpath
+        .unwrap_or_else(|| newpath.as_owned())
+        .as_checked_path(),
+    )?;
+    if old_in_vfs {
+      self.overlay()?.mark_removed(oldpath);
+    }
+    Ok(())
+  }
+
+  fn copy_to_real_path(
+    &self,
+    oldpath: &Path,
+    newpath: &CheckedPath,
   ) -> std::io::Result<u64> {
     let old_file = self.0.file_entry(oldpath)?;
     let old_file_bytes = self.0.read_file_all(old_file)?;
@@ -105,6 +258,12 @@ impl DenoRtSys {
   }
 }
 
+fn checked_overlay_path(path: PathBuf) -> CheckedPathBuf {
+  // PERMISSIONS: this is ok because the permissions were checked for the
+  // path within the vfs that this path stores the changes for
+  CheckedPathBuf::unsafe_new(path)
+}
+
 #[async_trait::async_trait(?Send)]
 impl FileSystem for DenoRtSys {
   fn cwd(&self) -> FsResult<PathBuf> {
@@ -130,7 +289,11 @@ impl FileSystem for DenoRtSys {
     options: OpenOptions,
   ) -> FsResult<Rc<dyn DenoFile>> {
     if self.0.is_path_within(path) {
-      Ok(Rc::new(self.0.open_file(path)?))
+      match self.lookup_for_open(path, &options)? {
+        OverlayLookup::Overlay(path) => RealFs
+          .open_sync(&checked_overlay_path(path).as_checked_path(), options),
+        OverlayLookup::Vfs(path) => Ok(Rc::new(self.0.open_file(&path)?)),
+      }
     } else {
       RealFs.open_sync(path, options)
     }
@@ -141,7 +304,12 @@ impl FileSystem for DenoRtSys {
     options: OpenOptions,
   ) -> FsResult<Rc<dyn DenoFile>> {
     if self.0.is_path_within(&path) {
-      Ok(Rc::new(self.0.open_file(&path)?))
+      match self.lookup_for_open(&path, &options)? {
+        OverlayLookup::Overlay(path) => {
+          RealFs.open_async(checked_overlay_path(path), options).await
+        }
+        OverlayLookup::Vfs(path) => Ok(Rc::new(self.0.open_file(&path)?)),
+      }
     } else {
       RealFs.open_async(path, options).await
     }
@@ -153,8 +321,11 @@ impl FileSystem for DenoRtSys {
     recursive: bool,
     mode: Option<u32>,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.mkdir_sync(path, recursive, mode)
+    if self.0.is_path_within(path) {
+      Ok(self.overlay()?.create_dir(path, recursive, mode)?)
+    } else {
+      RealFs.mkdir_sync(path, recursive, mode)
+    }
   }
   async fn mkdir_async(
     &self,
@@ -162,16 +333,21 @@ impl FileSystem for DenoRtSys {
     recursive: bool,
     mode: Option<u32>,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
-    RealFs.mkdir_async(path, recursive, mode).await
+    if self.0.is_path_within(&path) {
+      Ok(self.overlay()?.create_dir(&path, recursive, mode)?)
+    } else {
+      RealFs.mkdir_async(path, recursive, mode).await
+    }
   }
 
   fn chmod_sync(&self, path: &CheckedPath, mode: u32) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.chmod_sync(path, mode)
+    match self.overlay_write_path(path, true)? {
+      Some(path) => RealFs.chmod_sync(&path.as_checked_path(), mode),
+      None => RealFs.chmod_sync(path, mode),
+    }
   }
   async fn chmod_async(&self, path: CheckedPathBuf, mode: u32) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
+    let path = self.overlay_write_path(&path, true)?.unwrap_or(path);
     RealFs.chmod_async(path, mode).await
   }
 
@@ -181,8 +357,10 @@ impl FileSystem for DenoRtSys {
     uid: Option<u32>,
     gid: Option<u32>,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.chown_sync(path, uid, gid)
+    match self.overlay_write_path(path, true)? {
+      Some(path) => RealFs.chown_sync(&path.as_checked_path(), uid, gid),
+      None => RealFs.chown_sync(path, uid, gid),
+    }
   }
   async fn chown_async(
     &self,
@@ -190,13 +368,15 @@ impl FileSystem for DenoRtSys {
     uid: Option<u32>,
     gid: Option<u32>,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
+    let path = self.overlay_write_path(&path, true)?.unwrap_or(path);
     RealFs.chown_async(path, uid, gid).await
   }
 
   fn lchmod_sync(&self, path: &CheckedPath, mode: u32) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.lchmod_sync(path, mode)
+    match self.overlay_write_path(path, true)? {
+      Some(path) => RealFs.lchmod_sync(&path.as_checked_path(), mode),
+      None => RealFs.lchmod_sync(path, mode),
+    }
   }
 
   async fn lchmod_async(
@@ -204,7 +384,7 @@ impl FileSystem for DenoRtSys {
     path: CheckedPathBuf,
     mode: u32,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
+    let path = self.overlay_write_path(&path, true)?.unwrap_or(path);
     RealFs.lchmod_async(path, mode).await
   }
 
@@ -214,31 +394,38 @@ impl FileSystem for DenoRtSys {
     uid: Option<u32>,
     gid: Option<u32>,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.lchown_sync(path, uid, gid)
+    match self.overlay_write_path(path, true)? {
+      Some(path) => RealFs.lchown_sync(&path.as_checked_path(), uid, gid),
+      None => RealFs.lchown_sync(path, uid, gid),
+    }
   }
-
   async fn lchown_async(
     &self,
     path: CheckedPathBuf,
     uid: Option<u32>,
     gid: Option<u32>,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
+    let path = self.overlay_write_path(&path, true)?.unwrap_or(path);
     RealFs.lchown_async(path, uid, gid).await
   }
 
   fn remove_sync(&self, path: &CheckedPath, recursive: bool) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.remove_sync(path, recursive)
+    if self.0.is_path_within(path) {
+      Ok(self.overlay()?.remove(path, recursive)?)
+    } else {
+      RealFs.remove_sync(path, recursive)
+    }
   }
   async fn remove_async(
     &self,
     path: CheckedPathBuf,
     recursive: bool,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
-    RealFs.remove_async(path, recursive).await
+    if self.0.is_path_within(&path) {
+      Ok(self.overlay()?.remove(&path, recursive)?)
+    } else {
+      RealFs.remove_async(path, recursive).await
+    }
   }
 
   fn copy_file_sync(
@@ -246,12 +433,8 @@ impl FileSystem for DenoRtSys {
     oldpath: &CheckedPath,
     newpath: &CheckedPath,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(newpath)?;
-    if self.0.is_path_within(oldpath) {
-      self
-        .copy_to_real_path(oldpath, newpath)
-        .map(|_| ())
-        .map_err(FsError::Io)
+    if self.0.is_path_within(oldpath) || self.0.is_path_within(newpath) {
+      self.copy_file_in_vfs(oldpath, newpath)
     } else {
       RealFs.copy_file_sync(oldpath, newpath)
     }
@@ -261,16 +444,13 @@ impl FileSystem for DenoRtSys {
     oldpath: CheckedPathBuf,
     newpath: CheckedPathBuf,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&newpath)?;
-    if self.0.is_path_within(&oldpath) {
+    if self.0.is_path_within(&oldpath) || self.0.is_path_within(&newpath) {
       let fs = self.clone();
       tokio::task::spawn_blocking(move || {
-        fs.copy_to_real_path(
+        fs.copy_file_in_vfs(
           &oldpath.as_checked_path(),
           &newpath.as_checked_path(),
         )
-        .map(|_| ())
-        .map_err(FsError::Io)
       })
       .await?
     } else {
@@ -279,30 +459,30 @@ impl FileSystem for DenoRtSys {
   }
 
   fn cp_sync(&self, from: &CheckedPath, to: &CheckedPath) -> FsResult<()> {
-    self.error_if_in_vfs(to)?;
-
-    RealFs.cp_sync(from, to)
+    match self.overlay_write_path(to, false)? {
+      Some(to) => RealFs.cp_sync(from, &to.as_checked_path()),
+      None => RealFs.cp_sync(from, to),
+    }
   }
   async fn cp_async(
     &self,
     from: CheckedPathBuf,
     to: CheckedPathBuf,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&to)?;
-
+    let to = self.overlay_write_path(&to, false)?.unwrap_or(to);
     RealFs.cp_async(from, to).await
   }
 
   fn stat_sync(&self, path: &CheckedPath) -> FsResult<FsStat> {
     if self.0.is_path_within(path) {
-      Ok(self.0.stat(path)?.as_fs_stat())
+      self.stat_in_vfs(path, true)
     } else {
       RealFs.stat_sync(path)
     }
   }
   async fn stat_async(&self, path: CheckedPathBuf) -> FsResult<FsStat> {
     if self.0.is_path_within(&path) {
-      Ok(self.0.stat(&path)?.as_fs_stat())
+      self.stat_in_vfs(&path, true)
     } else {
       RealFs.stat_async(path).await
     }
@@ -310,14 +490,14 @@ impl FileSystem for DenoRtSys {
 
   fn lstat_sync(&self, path: &CheckedPath) -> FsResult<FsStat> {
     if self.0.is_path_within(path) {
-      Ok(self.0.lstat(path)?.as_fs_stat())
+      self.stat_in_vfs(path, false)
     } else {
       RealFs.lstat_sync(path)
     }
   }
   async fn lstat_async(&self, path: CheckedPathBuf) -> FsResult<FsStat> {
     if self.0.is_path_within(&path) {
-      Ok(self.0.lstat(&path)?.as_fs_stat())
+      self.stat_in_vfs(&path, false)
     } else {
       RealFs.lstat_async(path).await
     }
@@ -325,14 +505,14 @@ impl FileSystem for DenoRtSys {
 
   fn realpath_sync(&self, path: &CheckedPath) -> FsResult<PathBuf> {
     if self.0.is_path_within(path) {
-      Ok(self.0.canonicalize(path)?)
+      self.realpath_in_vfs(path)
     } else {
       RealFs.realpath_sync(path)
     }
   }
   async fn realpath_async(&self, path: CheckedPathBuf) -> FsResult<PathBuf> {
     if self.0.is_path_within(&path) {
-      Ok(self.0.canonicalize(&path)?)
+      self.realpath_in_vfs(&path)
     } else {
       RealFs.realpath_async(path).await
     }
@@ -340,7 +520,7 @@ impl FileSystem for DenoRtSys {
 
   fn read_dir_sync(&self, path: &CheckedPath) -> FsResult<Vec<FsDirEntry>> {
     if self.0.is_path_within(path) {
-      Ok(self.0.read_dir(path)?)
+      self.read_dir_in_vfs(path)
     } else {
       RealFs.read_dir_sync(path)
     }
@@ -350,7 +530,7 @@ impl FileSystem for DenoRtSys {
     path: CheckedPathBuf,
   ) -> FsResult<Vec<FsDirEntry>> {
     if self.0.is_path_within(&path) {
-      Ok(self.0.read_dir(&path)?)
+      self.read_dir_in_vfs(&path)
     } else {
       RealFs.read_dir_async(path).await
     }
@@ -361,18 +541,22 @@ impl FileSystem for DenoRtSys {
     oldpath: &CheckedPath,
     newpath: &CheckedPath,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(oldpath)?;
-    self.error_if_in_vfs(newpath)?;
-    RealFs.rename_sync(oldpath, newpath)
+    if self.0.is_path_within(oldpath) || self.0.is_path_within(newpath) {
+      self.rename_in_vfs(oldpath, newpath)
+    } else {
+      RealFs.rename_sync(oldpath, newpath)
+    }
   }
   async fn rename_async(
     &self,
     oldpath: CheckedPathBuf,
     newpath: CheckedPathBuf,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&oldpath)?;
-    self.error_if_in_vfs(&newpath)?;
-    RealFs.rename_async(oldpath, newpath).await
+    if self.0.is_path_within(&oldpath) || self.0.is_path_within(&newpath) {
+      self.rename_in_vfs(&oldpath.as_checked_path(), &newpath.as_checked_path())
+    } else {
+      RealFs.rename_async(oldpath, newpath).await
+    }
   }
 
   fn link_sync(
@@ -380,17 +564,21 @@ impl FileSystem for DenoRtSys {
     oldpath: &CheckedPath,
     newpath: &CheckedPath,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(oldpath)?;
-    self.error_if_in_vfs(newpath)?;
-    RealFs.link_sync(oldpath, newpath)
+    let oldpath = self
+      .overlay_write_path(oldpath, true)?
+      .unwrap_or_else(|| oldpath.as_owned());
+    let newpath = self
+      .overlay_write_path(newpath, false)?
+      .unwrap_or_else(|| newpath.as_owned());
+    RealFs.link_sync(&oldpath.as_checked_path(), &newpath.as_checked_path())
   }
   async fn link_async(
     &self,
     oldpath: CheckedPathBuf,
     newpath: CheckedPathBuf,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&oldpath)?;
-    self.error_if_in_vfs(&newpath)?;
+    let oldpath = self.overlay_write_path(&oldpath, true)?.unwrap_or(oldpath);
+    let newpath = self.overlay_write_path(&newpath, false)?.unwrap_or(newpath);
     RealFs.link_async(oldpath, newpath).await
   }
 
@@ -401,8 +589,12 @@ impl FileSystem for DenoRtSys {
     file_type: Option<FsFileType>,
   ) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
-    self.error_if_in_vfs(newpath)?;
-    RealFs.symlink_sync(oldpath, newpath, file_type)
+    match self.overlay_write_path(newpath, false)? {
+      Some(newpath) => {
+        RealFs.symlink_sync(oldpath, &newpath.as_checked_path(), file_type)
+      }
+      None => RealFs.symlink_sync(oldpath, newpath, file_type),
+    }
   }
   async fn symlink_async(
     &self,
@@ -411,35 +603,37 @@ impl FileSystem for DenoRtSys {
     file_type: Option<FsFileType>,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&oldpath)?;
-    self.error_if_in_vfs(&newpath)?;
+    let newpath = self.overlay_write_path(&newpath, false)?.unwrap_or(newpath);
     RealFs.symlink_async(oldpath, newpath, file_type).await
   }
 
   fn read_link_sync(&self, path: &CheckedPath) -> FsResult<PathBuf> {
     if self.0.is_path_within(path) {
-      Ok(self.0.read_link(path)?)
+      self.read_link_in_vfs(path)
     } else {
       RealFs.read_link_sync(path)
     }
   }
   async fn read_link_async(&self, path: CheckedPathBuf) -> FsResult<PathBuf> {
     if self.0.is_path_within(&path) {
-      Ok(self.0.read_link(&path)?)
+      self.read_link_in_vfs(&path)
     } else {
       RealFs.read_link_async(path).await
     }
   }
 
   fn truncate_sync(&self, path: &CheckedPath, len: u64) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.truncate_sync(path, len)
+    match self.overlay_write_path(path, true)? {
+      Some(path) => RealFs.truncate_sync(&path.as_checked_path(), len),
+      None => RealFs.truncate_sync(path, len),
+    }
   }
   async fn truncate_async(
     &self,
     path: CheckedPathBuf,
     len: u64,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
+    let path = self.overlay_write_path(&path, true)?.unwrap_or(path);
     RealFs.truncate_async(path, len).await
   }
 
@@ -451,8 +645,16 @@ impl FileSystem for DenoRtSys {
     mtime_secs: i64,
     mtime_nanos: u32,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.utime_sync(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    let path = self
+      .overlay_write_path(path, true)?
+      .unwrap_or_else(|| path.as_owned());
+    RealFs.utime_sync(
+      &path.as_checked_path(),
+      atime_secs,
+      atime_nanos,
+      mtime_secs,
+      mtime_nanos,
+    )
   }
   async fn utime_async(
     &self,
@@ -462,7 +664,7 @@ impl FileSystem for DenoRtSys {
     mtime_secs: i64,
     mtime_nanos: u32,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
+    let path = self.overlay_write_path(&path, true)?.unwrap_or(path);
     RealFs
       .utime_async(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
       .await
@@ -476,8 +678,16 @@ impl FileSystem for DenoRtSys {
     mtime_secs: i64,
     mtime_nanos: u32,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(path)?;
-    RealFs.lutime_sync(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    let path = self
+      .overlay_write_path(path, true)?
+      .unwrap_or_else(|| path.as_owned());
+    RealFs.lutime_sync(
+      &path.as_checked_path(),
+      atime_secs,
+      atime_nanos,
+      mtime_secs,
+      mtime_nanos,
+    )
   }
   async fn lutime_async(
     &self,
@@ -487,7 +697,7 @@ impl FileSystem for DenoRtSys {
     mtime_secs: i64,
     mtime_nanos: u32,
   ) -> FsResult<()> {
-    self.error_if_in_vfs(&path)?;
+    let path = self.overlay_write_path(&path, true)?.unwrap_or(path);
     RealFs
       .lutime_async(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
       .await
@@ -635,6 +845,42 @@ impl sys_traits::FsDirEntry for FileBackedVfsDirEntry {
   }
 }
 
+/// An entry of a directory within the vfs when it's writable.
+struct OverlayDirEntry {
+  sys: DenoRtSys,
+  path: PathBuf,
+  entry: FsDirEntry,
+}
+
+impl sys_traits::FsDirEntry for OverlayDirEntry {
+  type Metadata = BoxedFsMetadataValue;
+
+  fn file_name(&self) -> Cow<std::ffi::OsStr> {
+    Cow::Borrowed(self.entry.name.as_ref())
+  }
+
+  fn file_type(&self) -> std::io::Result<sys_traits::FileType> {
+    Ok(if self.entry.is_symlink {
+      sys_traits::FileType::Symlink
+    } else if self.entry.is_directory {
+      sys_traits::FileType::Dir
+    } else if self.entry.is_file {
+      sys_traits::FileType::File
+    } else {
+      sys_traits::FileType::Unknown
+    })
+  }
+
+  fn metadata(&self) -> std::io::Result<Self::Metadata> {
+    use sys_traits::BaseFsMetadata;
+    self.sys.base_fs_symlink_metadata(&self.path)
+  }
+
+  fn path(&self) -> Cow<Path> {
+    Cow::Borrowed(&self.path)
+  }
+}
+
 impl sys_traits::BaseFsReadDir for DenoRtSys {
   type ReadDirEntry = BoxedFsDirEntry;
 
@@ -645,6 +891,20 @@ impl sys_traits::BaseFsReadDir for DenoRtSys {
     Box<dyn Iterator<Item = std::io::Result<Self::ReadDirEntry>>>,
   > {
     if self.0.is_path_within(path) {
+      if self.1.is_some() {
+        let entries = self
+          .read_dir_in_vfs(path)
+          .map_err(|err| err.into_io_error())?;
+        let sys = self.clone();
+        let parent_path = path.to_path_buf();
+        return Ok(Box::new(entries.into_iter().map(move |entry| {
+          Ok(BoxedFsDirEntry::new(OverlayDirEntry {
+            sys: sys.clone(),
+            path: parent_path.join(&entry.name),
+            entry,
+          }))
+        })));
+      }
       let entries = self.0.read_dir_with_metadata(path)?;
       Ok(Box::new(
         entries.map(|entry| Ok(BoxedFsDirEntry::new(entry))),
@@ -676,7 +936,16 @@ impl sys_traits::BaseFsMetadata for DenoRtSys {
   #[inline]
   fn base_fs_metadata(&self, path: &Path) -> std::io::Result<Self::Metadata> {
     if self.0.is_path_within(path) {
-      Ok(BoxedFsMetadataValue::new(self.0.stat(path)?))
+      match self.lookup(path, true)? {
+        #[allow(clippy::disallowed_types)]
+        // ok because we're implementing the fs
+        OverlayLookup::Overlay(path) => {
+          sys_traits::impls::RealSys.fs_metadata_boxed(&path)
+        }
+        OverlayLookup::Vfs(path) => {
+          Ok(BoxedFsMetadataValue::new(self.0.stat(&path)?))
+        }
+      }
     } else {
       #[allow(clippy::disallowed_types)] // ok because we're implementing the fs
       sys_traits::impls::RealSys.fs_metadata_boxed(path)
@@ -689,7 +958,16 @@ impl sys_traits::BaseFsMetadata for DenoRtSys {
     path: &Path,
   ) -> std::io::Result<Self::Metadata> {
     if self.0.is_path_within(path) {
-      Ok(BoxedFsMetadataValue::new(self.0.lstat(path)?))
+      match self.lookup(path, false)? {
+        #[allow(clippy::disallowed_types)]
+        // ok because we're implementing the fs
+        OverlayLookup::Overlay(path) => {
+          sys_traits::impls::RealSys.fs_symlink_metadata_boxed(&path)
+        }
+        OverlayLookup::Vfs(path) => {
+          Ok(BoxedFsMetadataValue::new(self.0.lstat(&path)?))
+        }
+      }
     } else {
       #[allow(clippy::disallowed_types)] // ok because we're implementing the fs
       sys_traits::impls::RealSys.fs_symlink_metadata_boxed(path)
@@ -700,17 +978,25 @@ impl sys_traits::BaseFsMetadata for DenoRtSys {
 impl sys_traits::BaseFsCopy for DenoRtSys {
   #[inline]
   fn base_fs_copy(&self, from: &Path, to: &Path) -> std::io::Result<u64> {
-    self
-      .error_if_in_vfs(to)
+    let overlay_to = self
+      .overlay_write_path(to, false)
       .map_err(|err| err.into_io_error())?;
+    let to = overlay_to.as_deref().unwrap_or(to);
     if self.0.is_path_within(from) {
-      self.copy_to_real_path(
-        // PERMISSIONS: this is ok because JS code will never use sys_traits. Probably
-        // we should flip this so that the `deno_fs::FileSystem` implementation uses `sys_traits`
-        // rather than this calling into `deno_fs::FileSystem`
-        &CheckedPath::unsafe_new(Cow::Borrowed(from)),
-        &CheckedPath::unsafe_new(Cow::Borrowed(to)),
-      )
+      match self.lookup(from, true)? {
+        #[allow(clippy::disallowed_types)]
+        // ok because we're implementing the fs
+        OverlayLookup::Overlay(from) => {
+          sys_traits::impls::RealSys.fs_copy(&from, to)
+        }
+        OverlayLookup::Vfs(from) => self.copy_to_real_path(
+          &from,
+          // PERMISSIONS: this is ok because JS code will never use sys_traits. Probably
+          // we should flip this so that the `deno_fs::FileSystem` implementation uses `sys_traits`
+          // rather than this calling into `deno_fs::FileSystem`
+          &CheckedPath::unsafe_new(Cow::Borrowed(to)),
+        ),
+      }
     } else {
       #[allow(clippy::disallowed_types)] // ok because we're implementing the fs
       sys_traits::impls::RealSys.fs_copy(from, to)
@@ -954,7 +1240,25 @@ impl sys_traits::BaseFsOpen for DenoRtSys {
     options: &sys_traits::OpenOptions,
   ) -> std::io::Result<Self::File> {
     if self.0.is_path_within(path) {
-      Ok(FsFileAdapter::Vfs(self.0.open_file(path)?))
+      let fs_options = OpenOptions {
+        read: options.read,
+        write: options.write,
+        create: options.create,
+        truncate: options.truncate,
+        append: options.append,
+        create_new: options.create_new,
+        ..Default::default()
+      };
+      match self.lookup_for_open(path, &fs_options)? {
+        #[allow(clippy::disallowed_types)]
+        // ok because we're implementing the fs
+        OverlayLookup::Overlay(path) => Ok(FsFileAdapter::Real(
+          sys_traits::impls::RealSys.base_fs_open(&path, options)?,
+        )),
+        OverlayLookup::Vfs(path) => {
+          Ok(FsFileAdapter::Vfs(self.0.open_file(&path)?))
+        }
+      }
     } else {
       #[allow(clippy::disallowed_types)] // ok because we're implementing the fs
       Ok(FsFileAdapter::Real(
@@ -1441,6 +1745,8 @@ pub struct FileBackedVfs {
   vfs_data: Cow<'static, [u8]>,
   fs_root: VfsRoot,
   case_sensitivity: FileSystemCaseSensitivity,
+  /// Compressed data that has been read, by offset.
+  decompressed_data: Mutex<HashMap<u64, Arc<[u8]>>>,
 }
 
 impl FileBackedVfs {
@@ -1453,6 +1759,7 @@ impl FileBackedVfs {
       vfs_data: data,
       fs_root,
       case_sensitivity,
+      decompressed_data: Default::default(),
     }
   }
 
@@ -1553,6 +1860,9 @@ impl FileBackedVfs {
     &self,
     offset_with_len: OffsetWithLength,
   ) -> std::io::Result<Cow<'static, [u8]>> {
+    if offset_with_len.compressed_len.is_some() {
+      return Ok(Cow::Owned(self.decompress(offset_with_len)?.to_vec()));
+    }
     let read_range =
       self.get_read_range(offset_with_len, 0, offset_with_len.len)?;
     match &self.vfs_data {
@@ -1567,12 +1877,78 @@ impl FileBackedVfs {
     pos: u64,
     buf: &mut [u8],
   ) -> std::io::Result<usize> {
+    if file.offset.compressed_len.is_some() {
+      let data = self.decompress(file.offset)?;
+      let Some(data) =
+        usize::try_from(pos).ok().and_then(|pos| data.get(pos..))
+      else {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::UnexpectedEof,
+          "unexpected EOF",
+        ));
+      };
+      let read_len = std::cmp::min(data.len(), buf.len());
+      buf[..read_len].copy_from_slice(&data[..read_len]);
+      return Ok(read_len);
+    }
     let read_range = self.get_read_range(file.offset, pos, buf.len() as u64)?;
     let read_len = read_range.len();
     buf[..read_len].copy_from_slice(&self.vfs_data[read_range]);
     Ok(read_len)
   }
 
+  /// Decompresses the data at the offset on first read and keeps it, so that
+  /// reading it again is as cheap as reading uncompressed data.
+  fn decompress(
+    &self,
+    offset_with_len: OffsetWithLength,
+  ) -> std::io::Result<Arc<[u8]>> {
+    if let Some(data) = self
+      .decompressed_data
+      .lock()
+      .unwrap()
+      .get(&offset_with_len.offset)
+    {
+      return Ok(data.clone());
+    }
+    let invalid_data = || {
+      std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "invalid compressed file in the virtual file system",
+      )
+    };
+    let stored_data =
+      self.stored_data(offset_with_len).ok_or_else(invalid_data)?;
+    let len =
+      usize::try_from(offset_with_len.len).map_err(|_| invalid_data())?;
+    // decompress without holding the lock so that reading other files isn't
+    // blocked by it
+    let data = zstd::bulk::decompress(stored_data, len)?;
+    if data.len() != len {
+      return Err(invalid_data());
+    }
+    let mut decompressed_data = self.decompressed_data.lock().unwrap();
+    Ok(
+      decompressed_data
+        .entry(offset_with_len.offset)
+        .or_insert_with(|| data.into())
+        .clone(),
+    )
+  }
+
+  /// Gets the data as it's stored at the offset, or `None` when it's out of
+  /// bounds.
+  fn stored_data(&self, offset_with_len: OffsetWithLength) -> Option<&[u8]> {
+    let start = self
+      .fs_root
+      .start_file_offset
+      .checked_add(offset_with_len.offset)?;
+    let end = start.checked_add(offset_with_len.stored_len())?;
+    self
+      .vfs_data
+      .get(usize::try_from(start).ok()?..usize::try_from(end).ok()?)
+  }
+
   fn get_read_range(
     &self,
     file_offset_and_len: OffsetWithLength,
@@ -1625,6 +2001,8 @@ impl DenoRtNativeAddonLoader for FileBackedVfs {
 mod test {
   use std::io::Write;
 
+  use deno_lib::standalone::binary::WritableVfs;
+  use deno_lib::standalone::virtual_fs::BuiltVfs;
   use deno_lib::standalone::virtual_fs::VfsBuilder;
   use test_util::TempDir;
   use test_util::assert_contains;
@@ -1783,9 +2161,15 @@ mod test {
   fn into_virtual_fs(
     builder: VfsBuilder,
     temp_dir: &TempDir,
+  ) -> (PathBuf, FileBackedVfs) {
+    built_into_virtual_fs(builder.build(), temp_dir)
+  }
+
+  fn built_into_virtual_fs(
+    vfs: BuiltVfs,
+    temp_dir: &TempDir,
   ) -> (PathBuf, FileBackedVfs) {
     let virtual_fs_file = temp_dir.path().join("virtual_fs");
-    let vfs = builder.build();
     {
       let mut file = std::fs::File::create(&virtual_fs_file).unwrap();
       for file_data in &vfs.files {
@@ -1886,4 +2270,189 @@ mod test {
       .unwrap();
     assert_eq!(all_buf.to_vec(), b"123456789");
   }
+
+  #[test]
+  fn compressed_virtual_fs() {
+    let temp_dir = TempDir::new();
+    let temp_path = temp_dir.path().canonicalize();
+    let data = "0123456789".repeat(100);
+    let mut builder = VfsBuilder::new();
+    for name in ["a.txt", "b.txt"] {
+      builder
+        .add_file_with_data_raw(
+          temp_path.join(name).as_path(),
+          data.clone().into_bytes(),
+          None,
+        )
+        .unwrap();
+    }
+    // doesn't get smaller when compressed
+    builder
+      .add_file_with_data_raw(
+        temp_path.join("c.txt").as_path(),
+        "c".into(),
+        None,
+      )
+      .unwrap();
+    builder
+      .add_file_with_data_raw(temp_path.join("d.txt").as_path(), vec![], None)
+      .unwrap();
+    let mut vfs = builder.build();
+    vfs.compress().unwrap();
+    assert!(vfs.files.iter().map(|f| f.len()).sum::<usize>() < 100);
+    let (dest_path, virtual_fs) = built_into_virtual_fs(vfs, &temp_dir);
+
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("a.txt")), data);
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("b.txt")), data);
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("c.txt")), "c");
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("d.txt")), "");
+    assert_eq!(virtual_fs.stat(&dest_path.join("a.txt")).unwrap().len, 1000);
+
+    let virtual_fs = Arc::new(virtual_fs);
+    let file = virtual_fs.open_file(&dest_path.join("a.txt")).unwrap();
+    file.seek(SeekFrom::Start(995)).unwrap();
+    let mut buf = vec![0; 10];
+    assert_eq!(file.read_to_buf(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"56789");
+
+    // offsets outside of the data are an error instead of a panic
+    for offset in [1_000_000, u64::MAX] {
+      let err = virtual_fs
+        .read_file_offset_with_len(OffsetWithLength {
+          offset,
+          len: 10,
+          compressed_len: Some(10),
+        })
+        .unwrap_err();
+      assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+  }
+
+  #[test]
+  fn writable_virtual_fs() {
+    let temp_dir = TempDir::new();
+    let src_path = temp_dir.path().canonicalize().join("src");
+    let mut builder = VfsBuilder::new();
+    for (name, data) in [("a.txt", "a"), ("sub/b.txt", "b"), ("sub/c.txt", "c")]
+    {
+      builder
+        .add_file_with_data_raw(
+          src_path.join(name).as_path(),
+          data.into(),
+          None,
+        )
+        .unwrap();
+    }
+    let (dest_path, virtual_fs) = into_virtual_fs(builder, &temp_dir);
+    let virtual_fs = Arc::new(virtual_fs);
+    let overlay_path = temp_dir.path().join("overlay");
+    let overlay = VfsOverlay::new(
+      virtual_fs.clone(),
+      &WritableVfs::Dir(overlay_path.to_string()),
+    )
+    .unwrap();
+    let sys = DenoRtSys::new(virtual_fs.clone(), Some(Arc::new(overlay)));
+    let checked = |path: &str| {
+      CheckedPathBuf::unsafe_new(dest_path.join(path).to_path_buf())
+    };
+    let read = |path: &str| {
+      sys
+        .read_text_file_lossy_sync(&checked(path).as_checked_path())
+        .map(|text| text.into_owned())
+    };
+    let write = |path: &str, data: &str| {
+      sys
+        .write_file_sync(
+          &checked(path).as_checked_path(),
+          OpenOptions::write(true, false, false, None),
+          data.as_bytes(),
+        )
+        .unwrap()
+    };
+    let read_dir = |path: &str| {
+      let mut names = sys
+        .read_dir_sync(&checked(path).as_checked_path())
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>();
+      names.sort();
+      names
+    };
+
+    // modifying a file stores it in the overlay
+    write("a.txt", "changed");
+    assert_eq!(read("a.txt").unwrap(), "changed");
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("a.txt")), "a");
+    assert_eq!(overlay_path.join("a.txt").read_to_string(), "changed");
+
+    // appending copies the existing file
+    sys
+      .write_file_sync(
+        &checked("sub/b.txt").as_checked_path(),
+        OpenOptions::write(false, true, false, None),
+        b"b",
+      )
+      .unwrap();
+    assert_eq!(read("sub/b.txt").unwrap(), "bb");
+
+    // new files are listed with the ones in the vfs
+    write("sub/d.txt", "d");
+    assert_eq!(read_dir("sub"), vec!["b.txt", "c.txt", "d.txt"]);
+    assert_eq!(
+      sys
+        .stat_sync(&checked("sub/d.txt").as_checked_path())
+        .unwrap()
+        .size,
+      1
+    );
+
+    // but can't be created in a directory that doesn't exist
+    assert!(
+      sys
+        .write_file_sync(
+          &checked("new/e.txt").as_checked_path(),
+          OpenOptions::write(true, false, false, None),
+          b"e",
+        )
+        .is_err()
+    );
+    sys
+      .mkdir_sync(&checked("new/dir").as_checked_path(), true, None)
+      .unwrap();
+    write("new/dir/e.txt", "e");
+    assert_eq!(read("new/dir/e.txt").unwrap(), "e");
+
+    // removed files are hidden
+    sys
+      .remove_sync(&checked("sub/c.txt").as_checked_path(), false)
+      .unwrap();
+    assert!(read("sub/c.txt").is_err());
+    assert_eq!(read_dir("sub"), vec!["b.txt", "d.txt"]);
+
+    // renaming a directory moves everything in it
+    sys
+      .rename_sync(
+        &checked("sub").as_checked_path(),
+        &checked("renamed").as_checked_path(),
+      )
+      .unwrap();
+    assert!(sys.stat_sync(&checked("sub").as_checked_path()).is_err());
+    assert_eq!(read_dir("renamed"), vec!["b.txt", "d.txt"]);
+    assert_eq!(read("renamed/b.txt").unwrap(), "bb");
+    assert_eq!(read_dir(""), vec!["a.txt", "new", "renamed"]);
+
+    // recreating a removed directory doesn't restore its entries
+    sys
+      .mkdir_sync(&checked("sub").as_checked_path(), false, None)
+      .unwrap();
+    assert!(read_dir("sub").is_empty());
+
+    // the vfs is read only without an overlay
+    let sys = DenoRtSys::new(virtual_fs.clone(), None);
+    assert!(matches!(
+      sys.remove_sync(&checked("a.txt").as_checked_path(), false),
+      Err(FsError::NotSupported)
+    ));
+  }
 }
@@ -4,7 +4,11 @@ use std::borrow::Cow;
 use std::env;
 use std::sync::Arc;
 
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_lib::util::permission_audit::maybe_init_permission_audit_from_env;
+use deno_lib::util::permission_broker::maybe_init_permission_broker;
+use deno_lib::util::permission_broker::take_permission_broker_env_var;
 use deno_lib::util::result::js_error_downcast_ref;
 use deno_lib::version::otel_runtime_config;
 use deno_runtime::deno_telemetry::OtelConfig;
@@ -15,12 +19,14 @@ use indexmap::IndexMap;
 
 use self::binary::extract_standalone;
 use self::file_system::DenoRtSys;
+use self::vfs_overlay::VfsOverlay;
 
 mod binary;
 mod code_cache;
 mod file_system;
 mod node;
 mod run;
+mod vfs_overlay;
 
 pub(crate) fn unstable_exit_cb(feature: &str, api_name: &str) {
   log::error!(
@@ -87,7 +93,19 @@ fn main() {
           Some(data.metadata.otel_config.clone()),
         );
         load_env_vars(&data.metadata.env_vars_from_env_file);
-        let sys = DenoRtSys::new(data.vfs.clone());
+        maybe_init_permission_audit_from_env()?;
+        maybe_init_permission_broker(
+          take_permission_broker_env_var().as_deref(),
+        )?;
+        let overlay = match &data.metadata.writable_vfs {
+          Some(writable_vfs) => Some(Arc::new(
+            VfsOverlay::new(data.vfs.clone(), writable_vfs).with_context(
+              || "Failed creating the directory for the writable vfs",
+            )?,
+          )),
+          None => None,
+        };
+        let sys = DenoRtSys::new(data.vfs.clone(), overlay);
         let exit_code = run::run(Arc::new(sys.clone()), sys, data).await?;
         deno_runtime::exit(exit_code);
       }
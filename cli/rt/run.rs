@@ -35,6 +35,7 @@ use deno_lib::npm::NpmRegistryReadPermissionCheckerMode;
 use deno_lib::npm::create_npm_process_state_provider;
 use deno_lib::standalone::binary::NodeModules;
 use deno_lib::util::hash::FastInsecureHasher;
+use deno_lib::util::permission_packages::PermissionPackageResolverImpl;
 use deno_lib::util::text_encoding::from_utf8_lossy_cow;
 use deno_lib::util::text_encoding::from_utf8_lossy_owned;
 use deno_lib::util::v8::construct_v8_flags;
@@ -50,6 +51,7 @@ use deno_package_json::PackageJsonDepValue;
 use deno_resolver::DenoResolveErrorKind;
 use deno_resolver::cjs::CjsTracker;
 use deno_resolver::cjs::IsCjsResolutionMode;
+use deno_resolver::factory::resolve_jsr_url;
 use deno_resolver::loader::NpmModuleLoader;
 use deno_resolver::npm::ByonmNpmResolverCreateOptions;
 use deno_resolver::npm::CreateInNpmPkgCheckerOptions;
@@ -73,6 +75,8 @@ use deno_runtime::deno_node::NodeRequireLoader;
 use deno_runtime::deno_node::create_host_defined_options;
 use deno_runtime::deno_permissions::Permissions;
 use deno_runtime::deno_permissions::PermissionsContainer;
+use deno_runtime::deno_permissions::register_module_source;
+use deno_runtime::deno_permissions::set_package_resolver;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_web::BlobStore;
@@ -417,6 +421,10 @@ impl ModuleLoader for EmbeddedModuleLoader {
             )
             .await
             .map_err(JsErrorBox::from_err)?;
+          register_module_source(
+            code_source.specifier.as_str(),
+            code_source.source.as_bytes(),
+          );
           let code_cache_entry = match requested_module_type {
             RequestedModuleType::None => shared.get_code_cache(
               &code_source.specifier,
@@ -519,6 +527,10 @@ impl ModuleLoader for EmbeddedModuleLoader {
                   ModuleSourceCode::String(FastString::from_static(source))
                 }
               };
+              register_module_source(
+                module_specifier.as_str(),
+                module_source.as_bytes(),
+              );
               let code_cache_entry = shared
                 .get_code_cache(&module_specifier, module_source.as_bytes());
               Ok(deno_core::ModuleSource::new_with_redirect(
@@ -533,6 +545,10 @@ impl ModuleLoader for EmbeddedModuleLoader {
           )
         } else {
           let module_source = module_source.into_for_v8();
+          register_module_source(
+            module_specifier.as_str(),
+            module_source.as_bytes(),
+          );
           let code_cache_entry = self
             .shared
             .get_code_cache(module_specifier, module_source.as_bytes());
@@ -639,6 +655,9 @@ impl NodeRequireLoader for EmbeddedModuleLoader {
         file_entry.transpiled_offset.unwrap_or(file_entry.offset),
       )
       .map_err(JsErrorBox::from_err)?;
+    let specifier =
+      deno_path_util::url_from_file_path(path).map_err(JsErrorBox::from_err)?;
+    register_module_source(specifier.as_str(), &file_bytes);
     Ok(match from_utf8_lossy_cow(file_bytes) {
       Cow::Borrowed(s) => FastString::from_static(s),
       Cow::Owned(s) => s.into(),
@@ -981,6 +1000,13 @@ pub async fn run(
       }
     }
 
+    if !permissions.packages.is_empty() {
+      set_package_resolver(Box::new(PermissionPackageResolverImpl::new(
+        npm_resolver.clone(),
+        resolve_jsr_url(&sys),
+      )));
+    }
+
     let desc_parser =
       Arc::new(RuntimePermissionDescriptorParser::new(sys.clone()));
     let permissions =
@@ -1027,6 +1053,8 @@ pub async fn run(
     no_legacy_abort: false,
     startup_snapshot: deno_snapshots::CLI_SNAPSHOT,
     enable_raw_imports: metadata.unstable_config.raw_imports,
+    cron_config: metadata.cron_config,
+    cron_db_path: std::env::var_os("DENO_CRON_DB_PATH").map(PathBuf::from),
   };
   let worker_factory = LibMainWorkerFactory::new(
     Arc::new(BlobStore::default()),
@@ -0,0 +1,564 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use deno_lib::standalone::binary::WritableVfs;
+use deno_runtime::deno_fs::FsDirEntry;
+
+use crate::file_system::FileBackedVfs;
+
+/// The temporary overlay directory to remove when the process exits.
+static TEMP_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// A copy-on-write layer over the read-only vfs.
+///
+/// Writes to paths within the vfs are stored in a directory on the real
+/// file system, which is read before the vfs. Files are copied to the
+/// directory when they're first modified and removed vfs entries are
+/// tracked in memory.
+#[derive(Debug)]
+pub struct VfsOverlay {
+  vfs: Arc<FileBackedVfs>,
+  dir: PathBuf,
+  /// Removes the temporary overlay directory when the overlay is dropped.
+  _temp_dir: Option<tempfile::TempDir>,
+  /// Paths in the vfs that were removed or renamed.
+  removed: Mutex<HashSet<PathBuf>>,
+}
+
+/// Where an entry within the vfs should be read from.
+pub enum OverlayLookup {
+  /// The entry is in the overlay directory at this real path.
+  Overlay(PathBuf),
+  /// The entry, if it exists, is in the vfs at this path.
+  Vfs(PathBuf),
+}
+
+impl VfsOverlay {
+  pub fn new(
+    vfs: Arc<FileBackedVfs>,
+    writable_vfs: &WritableVfs,
+  ) -> std::io::Result<Self> {
+    let (dir, temp_dir) = match writable_vfs {
+      WritableVfs::TempDir => {
+        // a new directory that only the current user can access, so that
+        // another user can't create or read it beforehand
+        let temp_dir = tempfile::Builder::new()
+          .prefix(&format!(
+            "{}-overlay-",
+            vfs.root().file_name().unwrap_or_default().to_string_lossy()
+          ))
+          .tempdir()?;
+        if TEMP_DIR.set(temp_dir.path().to_path_buf()).is_ok() {
+          deno_signals::before_exit(remove_temp_dir);
+        }
+        (temp_dir.path().to_path_buf(), Some(temp_dir))
+      }
+      WritableVfs::Dir(dir) => {
+        let dir = std::env::current_dir()?.join(dir);
+        std::fs::create_dir_all(&dir)?;
+        (dir, None)
+      }
+    };
+    Ok(Self {
+      vfs,
+      dir,
+      _temp_dir: temp_dir,
+      removed: Default::default(),
+    })
+  }
+
+  /// Finds where to read the entry at `path`, following a symlink at
+  /// the path itself when `follow` is true.
+  pub fn lookup(
+    &self,
+    path: &Path,
+    follow: bool,
+  ) -> std::io::Result<OverlayLookup> {
+    let path = self.resolve(path, follow);
+    let overlay_path = self.overlay_path(&path);
+    if overlay_path.symlink_metadata().is_ok() {
+      Ok(OverlayLookup::Overlay(overlay_path))
+    } else if self.is_removed(&path) {
+      Err(ErrorKind::NotFound.into())
+    } else {
+      Ok(OverlayLookup::Vfs(path))
+    }
+  }
+
+  /// Resolves the path of an entry within the vfs or the overlay.
+  pub fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+    let path = self.resolve(path, true);
+    if self.overlay_path(&path).exists()
+      || (!self.is_removed(&path) && self.vfs.stat(&path).is_ok())
+    {
+      Ok(path)
+    } else {
+      Err(ErrorKind::NotFound.into())
+    }
+  }
+
+  /// Lists the entries of the directory in the overlay and the vfs, with
+  /// the entries in the overlay taking precedence.
+  pub fn read_dir(&self, path: &Path) -> std::io::Result<Vec<FsDirEntry>> {
+    let path = self.resolve(path, true);
+    let overlay_entries = match std::fs::read_dir(self.overlay_path(&path)) {
+      Ok(entries) => Some(
+        entries
+          .map(|entry| {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            Ok(FsDirEntry {
+              name: entry.file_name().to_string_lossy().into_owned(),
+              is_file: file_type.is_file(),
+              is_directory: file_type.is_dir(),
+              is_symlink: file_type.is_symlink(),
+            })
+          })
+          .collect::<std::io::Result<Vec<_>>>()?,
+      ),
+      Err(err) if err.kind() == ErrorKind::NotFound => None,
+      Err(err) => return Err(err),
+    };
+    let vfs_entries = if self.is_removed(&path) {
+      None
+    } else {
+      self.vfs.read_dir(&path).ok()
+    };
+    let mut entries = match (overlay_entries, vfs_entries) {
+      (None, None) => return Err(ErrorKind::NotFound.into()),
+      (Some(entries), None) => return Ok(entries),
+      (None, Some(entries)) => {
+        return Ok(self.without_removed(&path, entries));
+      }
+      (Some(overlay_entries), Some(vfs_entries)) => {
+        (overlay_entries, self.without_removed(&path, vfs_entries))
+      }
+    };
+    let names = (entries.0.iter())
+      .map(|entry| entry.name.clone())
+      .collect::<HashSet<_>>();
+    entries
+      .0
+      .extend(entries.1.into_iter().filter(|e| !names.contains(&e.name)));
+    Ok(entries.0)
+  }
+
+  /// Gets the path in the overlay directory to modify for `path`.
+  ///
+  /// The parent directories are created in the overlay directory when they
+  /// exist in the vfs. When `copy_existing` is true, an existing file in
+  /// the vfs is copied to the overlay directory so that it can be modified.
+  pub fn prepare_write(
+    &self,
+    path: &Path,
+    copy_existing: bool,
+  ) -> std::io::Result<PathBuf> {
+    let path = self.resolve(path, false);
+    let overlay_path = self.overlay_path(&path);
+    if overlay_path.symlink_metadata().is_ok() {
+      return Ok(overlay_path);
+    }
+    if let Some(parent) = path.parent() {
+      self.ensure_dir(parent)?;
+    }
+    if copy_existing && !self.is_removed(&path) {
+      self.copy_from_vfs(&path, &overlay_path)?;
+    }
+    self.unmark_removed(&path);
+    Ok(overlay_path)
+  }
+
+  /// Gets the path in the overlay directory to move for `path`, copying
+  /// everything within it from the vfs.
+  pub fn prepare_rename(&self, path: &Path) -> std::io::Result<PathBuf> {
+    let overlay_path = self.prepare_write(path, true)?;
+    self.copy_dir_from_vfs(&self.resolve(path, false), &overlay_path)?;
+    Ok(overlay_path)
+  }
+
+  /// Creates the directory at `path` in the overlay, along with its
+  /// parents when `recursive` is true.
+  pub fn create_dir(
+    &self,
+    path: &Path,
+    recursive: bool,
+    mode: Option<u32>,
+  ) -> std::io::Result<()> {
+    let path = self.resolve(path, false);
+    if self.exists(&path) {
+      return if recursive {
+        Ok(())
+      } else {
+        Err(ErrorKind::AlreadyExists.into())
+      };
+    }
+    if recursive {
+      if let Some(parent) = path.parent() {
+        if self.vfs.is_path_within(parent) && !self.exists(parent) {
+          self.create_dir(parent, true, mode)?;
+        }
+      }
+    }
+    let overlay_path = self.prepare_write(&path, false)?;
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+      use std::os::unix::fs::DirBuilderExt;
+      builder.mode(mode);
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    builder.create(overlay_path)
+  }
+
+  /// Removes the entry at `path` from the overlay and hides it in the vfs.
+  pub fn remove(&self, path: &Path, recursive: bool) -> std::io::Result<()> {
+    let path = self.resolve(path, false);
+    if !recursive
+      && self.vfs.dir_entry(&path).is_ok()
+      && !self.read_dir(&path)?.is_empty()
+    {
+      return Err(ErrorKind::DirectoryNotEmpty.into());
+    }
+    let overlay_path = self.overlay_path(&path);
+    let removed_overlay = match overlay_path.symlink_metadata() {
+      Ok(metadata) => {
+        if metadata.is_dir() {
+          if recursive {
+            std::fs::remove_dir_all(&overlay_path)?;
+          } else {
+            std::fs::remove_dir(&overlay_path)?;
+          }
+        } else {
+          std::fs::remove_file(&overlay_path)?;
+        }
+        true
+      }
+      Err(_) => false,
+    };
+    let in_vfs = !self.is_removed(&path) && self.vfs.lstat(&path).is_ok();
+    if in_vfs {
+      self.removed.lock().unwrap().insert(path);
+    }
+    if removed_overlay || in_vfs {
+      Ok(())
+    } else {
+      Err(ErrorKind::NotFound.into())
+    }
+  }
+
+  /// Hides the entry at `path` in the vfs after it was renamed.
+  pub fn mark_removed(&self, path: &Path) {
+    let path = self.resolve(path, false);
+    if self.vfs.lstat(&path).is_ok() {
+      self.removed.lock().unwrap().insert(path);
+    }
+  }
+
+  /// Resolves the symlinks in the vfs for the ancestors of the path, and
+  /// the path itself when `follow` is true, so that an entry has a single
+  /// location in the overlay directory.
+  fn resolve(&self, path: &Path, follow: bool) -> PathBuf {
+    let skip = if follow { 0 } else { 1 };
+    for ancestor in path.ancestors().skip(skip) {
+      if !self.vfs.is_path_within(ancestor) {
+        break;
+      }
+      if let Ok(resolved) = self.vfs.canonicalize(ancestor) {
+        // unwrap ok because it's an ancestor
+        return resolved.join(path.strip_prefix(ancestor).unwrap());
+      }
+    }
+    path.to_path_buf()
+  }
+
+  fn overlay_path(&self, path: &Path) -> PathBuf {
+    match path.strip_prefix(self.vfs.root()) {
+      Ok(relative) => self.dir.join(relative),
+      Err(_) => self.dir.clone(),
+    }
+  }
+
+  fn exists(&self, path: &Path) -> bool {
+    self.overlay_path(path).symlink_metadata().is_ok()
+      || (!self.is_removed(path) && self.vfs.lstat(path).is_ok())
+  }
+
+  fn is_removed(&self, path: &Path) -> bool {
+    let removed = self.removed.lock().unwrap();
+    !removed.is_empty() && path.ancestors().any(|p| removed.contains(p))
+  }
+
+  /// Stops hiding the entry at `path` once it's created in the overlay.
+  fn unmark_removed(&self, path: &Path) {
+    let mut removed = self.removed.lock().unwrap();
+    if removed.remove(path) {
+      // keep hiding what was in the removed directory
+      if let Ok(entries) = self.vfs.read_dir(path) {
+        removed.extend(entries.into_iter().map(|e| path.join(e.name)));
+      }
+    }
+  }
+
+  fn without_removed(
+    &self,
+    dir: &Path,
+    entries: Vec<FsDirEntry>,
+  ) -> Vec<FsDirEntry> {
+    entries
+      .into_iter()
+      .filter(|entry| !self.is_removed(&dir.join(&entry.name)))
+      .collect()
+  }
+
+  /// Ensures the directory exists in the overlay if it exists in the vfs.
+  fn ensure_dir(&self, dir: &Path) -> std::io::Result<()> {
+    let overlay_path = self.overlay_path(dir);
+    if overlay_path.is_dir() || !self.vfs.is_path_within(dir) {
+      return Ok(());
+    }
+    if self.is_removed(dir) {
+      return Err(ErrorKind::NotFound.into());
+    }
+    self.vfs.dir_entry(dir)?;
+    if let Some(parent) = dir.parent() {
+      self.ensure_dir(parent)?;
+    }
+    match std::fs::create_dir(&overlay_path) {
+      Ok(()) => Ok(()),
+      Err(err) if err.kind() == ErrorKind::AlreadyExists => Ok(()),
+      Err(err) => Err(err),
+    }
+  }
+
+  fn copy_from_vfs(
+    &self,
+    path: &Path,
+    overlay_path: &Path,
+  ) -> std::io::Result<()> {
+    let Ok(metadata) = self.vfs.lstat(path) else {
+      return Ok(()); // nothing to copy
+    };
+    match metadata.file_type {
+      sys_traits::FileType::File => {
+        let file = self.vfs.file_entry(path)?;
+        std::fs::write(overlay_path, self.vfs.read_file_all(file)?)
+      }
+      sys_traits::FileType::Dir => std::fs::create_dir(overlay_path),
+      _ => Err(std::io::Error::new(
+        ErrorKind::Unsupported,
+        "modifying a symlink within the vfs is not supported",
+      )),
+    }
+  }
+
+  fn copy_dir_from_vfs(
+    &self,
+    dir: &Path,
+    overlay_dir: &Path,
+  ) -> std::io::Result<()> {
+    let Ok(entries) = self.vfs.read_dir(dir) else {
+      return Ok(()); // not a directory in the vfs
+    };
+    for entry in entries {
+      let path = dir.join(&entry.name);
+      if self.is_removed(&path) {
+        continue;
+      }
+      let overlay_path = overlay_dir.join(&entry.name);
+      if overlay_path.symlink_metadata().is_err() {
+        self.copy_from_vfs(&path, &overlay_path)?;
+      }
+      if entry.is_directory {
+        self.copy_dir_from_vfs(&path, &overlay_path)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn remove_temp_dir() {
+  if let Some(dir) = TEMP_DIR.get() {
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::borrow::Cow;
+
+  use deno_lib::standalone::virtual_fs::FileSystemCaseSensitivity;
+  use deno_lib::standalone::virtual_fs::VfsBuilder;
+  use deno_lib::standalone::virtual_fs::VirtualDirectory;
+  use test_util::TempDir;
+
+  use super::*;
+  use crate::file_system::VfsRoot;
+
+  fn create_overlay(temp_dir: &TempDir) -> (PathBuf, VfsOverlay) {
+    let src_path = temp_dir.path().canonicalize().join("src");
+    let mut builder = VfsBuilder::new();
+    for (name, data) in [("a.txt", "a"), ("sub/b.txt", "b")] {
+      builder
+        .add_file_with_data_raw(
+          src_path.join(name).as_path(),
+          data.into(),
+          None,
+        )
+        .unwrap();
+    }
+    let vfs = builder.build();
+    let dest_path = temp_dir.path().join("dest").to_path_buf();
+    let vfs = FileBackedVfs::new(
+      Cow::Owned(vfs.files.concat()),
+      VfsRoot {
+        dir: VirtualDirectory {
+          name: "".to_string(),
+          entries: vfs.entries,
+        },
+        root_path: dest_path.clone(),
+        start_file_offset: 0,
+      },
+      FileSystemCaseSensitivity::Sensitive,
+    );
+    let overlay =
+      VfsOverlay::new(Arc::new(vfs), &WritableVfs::TempDir).unwrap();
+    (dest_path, overlay)
+  }
+
+  fn read(overlay: &VfsOverlay, path: &Path) -> std::io::Result<String> {
+    match overlay.lookup(path, true)? {
+      OverlayLookup::Overlay(path) => std::fs::read_to_string(path),
+      OverlayLookup::Vfs(path) => {
+        let file = overlay.vfs.file_entry(&path)?;
+        let data = overlay.vfs.read_file_all(file)?;
+        Ok(String::from_utf8(data.into_owned()).unwrap())
+      }
+    }
+  }
+
+  fn read_dir(overlay: &VfsOverlay, path: &Path) -> Vec<String> {
+    let mut names = (overlay.read_dir(path).unwrap().into_iter())
+      .map(|entry| entry.name)
+      .collect::<Vec<_>>();
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn creates_new_temp_dir() {
+    let temp_dir = TempDir::new();
+    let (_, overlay) = create_overlay(&temp_dir);
+    let (_, other_overlay) = create_overlay(&temp_dir);
+    assert_ne!(overlay.dir, other_overlay.dir);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = std::fs::metadata(&overlay.dir)
+        .unwrap()
+        .permissions()
+        .mode();
+      assert_eq!(mode & 0o777, 0o700);
+    }
+    let dir = overlay.dir.clone();
+    drop(overlay);
+    assert!(!dir.exists());
+  }
+
+  #[test]
+  fn reads_through_to_vfs() {
+    let temp_dir = TempDir::new();
+    let (dest_path, overlay) = create_overlay(&temp_dir);
+    assert!(matches!(
+      overlay.lookup(&dest_path.join("a.txt"), true).unwrap(),
+      OverlayLookup::Vfs(_)
+    ));
+    assert_eq!(read(&overlay, &dest_path.join("a.txt")).unwrap(), "a");
+    assert_eq!(read_dir(&overlay, &dest_path), vec!["a.txt", "sub"]);
+    assert!(
+      overlay
+        .canonicalize(&dest_path.join("missing.txt"))
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn writes_to_overlay() {
+    let temp_dir = TempDir::new();
+    let (dest_path, overlay) = create_overlay(&temp_dir);
+    let path = overlay
+      .prepare_write(&dest_path.join("sub/b.txt"), true)
+      .unwrap();
+    assert!(path.starts_with(&overlay.dir));
+    // the existing file was copied so that it can be modified
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "b");
+    std::fs::write(&path, "changed").unwrap();
+    assert_eq!(
+      read(&overlay, &dest_path.join("sub/b.txt")).unwrap(),
+      "changed"
+    );
+
+    let path = overlay
+      .prepare_write(&dest_path.join("sub/c.txt"), false)
+      .unwrap();
+    std::fs::write(path, "c").unwrap();
+    assert_eq!(
+      read_dir(&overlay, &dest_path.join("sub")),
+      vec!["b.txt", "c.txt"]
+    );
+  }
+
+  #[test]
+  fn removes_vfs_entries() {
+    let temp_dir = TempDir::new();
+    let (dest_path, overlay) = create_overlay(&temp_dir);
+    assert_eq!(
+      overlay
+        .remove(&dest_path.join("sub"), false)
+        .unwrap_err()
+        .kind(),
+      ErrorKind::DirectoryNotEmpty
+    );
+    overlay.remove(&dest_path.join("sub/b.txt"), false).unwrap();
+    assert_eq!(
+      read(&overlay, &dest_path.join("sub/b.txt"))
+        .unwrap_err()
+        .kind(),
+      ErrorKind::NotFound
+    );
+    assert!(read_dir(&overlay, &dest_path.join("sub")).is_empty());
+    assert_eq!(
+      overlay
+        .remove(&dest_path.join("sub/b.txt"), false)
+        .unwrap_err()
+        .kind(),
+      ErrorKind::NotFound
+    );
+  }
+
+  #[test]
+  fn renames_vfs_entries() {
+    let temp_dir = TempDir::new();
+    let (dest_path, overlay) = create_overlay(&temp_dir);
+    let old_path = overlay.prepare_rename(&dest_path.join("sub")).unwrap();
+    let new_path = overlay
+      .prepare_write(&dest_path.join("renamed"), false)
+      .unwrap();
+    std::fs::rename(old_path, new_path).unwrap();
+    overlay.mark_removed(&dest_path.join("sub"));
+
+    assert!(overlay.lookup(&dest_path.join("sub"), true).is_err());
+    assert_eq!(read_dir(&overlay, &dest_path), vec!["a.txt", "renamed"]);
+    assert_eq!(
+      read(&overlay, &dest_path.join("renamed/b.txt")).unwrap(),
+      "b"
+    );
+  }
+}
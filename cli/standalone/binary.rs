@@ -642,7 +642,10 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       vfs.add_cjs_export_analysis(&file_path, analysis);
     }
 
-    let vfs = self.build_vfs_consolidating_global_npm_cache(vfs);
+    let mut vfs = self.build_vfs_consolidating_global_npm_cache(vfs);
+    if compile_flags.compress {
+      vfs.compress()?;
+    }
 
     let root_dir_url = match &vfs.root_path {
       WindowsSystemRootablePath::Path(dir) => {
@@ -722,7 +725,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       seed: self.cli_options.seed(),
       code_cache_key,
       location: self.cli_options.location_flag().clone(),
-      permissions: self.cli_options.permissions_options(),
+      permissions: self.cli_options.permissions_options()?,
       v8_flags: construct_v8_flags(
         &get_default_v8_flags(),
         self.cli_options.v8_flags(),
@@ -791,7 +794,9 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         sloppy_imports: self.cli_options.unstable_sloppy_imports(),
       },
       otel_config: self.cli_options.otel_config(),
+      cron_config: self.cli_options.cron_config()?,
       vfs_case_sensitivity: vfs.case_sensitivity,
+      writable_vfs: compile_flags.writable_vfs.clone(),
     };
 
     let (data_section_bytes, section_sizes) = serialize_binary_data_section(
@@ -24,6 +24,7 @@ use tokio::task::JoinHandle;
 use tokio::task::LocalSet;
 use tokio_util::sync::CancellationToken;
 
+use crate::args::PERMISSION_SET_ENV_VAR_NAME;
 use crate::node::CliNodeResolver;
 use crate::npm::CliManagedNpmResolver;
 use crate::npm::CliNpmResolver;
@@ -291,6 +292,25 @@ impl ShellCommand for DenoCommand {
   }
 }
 
+/// Runs deno with the permission set of a task. It's only passed to the
+/// deno commands that the task runs directly so that their subprocesses
+/// don't inherit it.
+pub struct PermissionSetDenoCommand(pub String);
+
+impl ShellCommand for PermissionSetDenoCommand {
+  fn execute(
+    &self,
+    context: ShellCommandContext,
+  ) -> LocalBoxFuture<'static, ExecuteResult> {
+    let mut state = context.state;
+    state.apply_env_var(
+      OsStr::new(PERMISSION_SET_ENV_VAR_NAME),
+      OsStr::new(&self.0),
+    );
+    DenoCommand::default().execute(ShellCommandContext { state, ..context })
+  }
+}
+
 pub struct NodeCommand;
 
 impl ShellCommand for NodeCommand {
@@ -503,6 +503,8 @@ mod test {
         icon: None,
         include: Default::default(),
         exclude: Default::default(),
+        compress: false,
+        writable_vfs: None,
         eszip: true,
       },
       &std::env::current_dir().unwrap(),
@@ -532,6 +534,8 @@ mod test {
         exclude: Default::default(),
         icon: None,
         no_terminal: false,
+        compress: false,
+        writable_vfs: None,
         eszip: true,
       },
       &std::env::current_dir().unwrap(),
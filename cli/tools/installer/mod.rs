@@ -341,10 +341,15 @@ async fn install_global(
   let npmrc = factory.npmrc()?;
 
   let deps_file_fetcher = Arc::new(deps_file_fetcher);
-  let jsr_resolver = Arc::new(JsrFetchResolver::new(deps_file_fetcher.clone()));
+  let minimum_release_age = factory.minimum_release_age()?.cloned();
+  let jsr_resolver = Arc::new(JsrFetchResolver::new(
+    deps_file_fetcher.clone(),
+    minimum_release_age.clone(),
+  ));
   let npm_resolver = Arc::new(NpmFetchResolver::new(
     deps_file_fetcher.clone(),
     npmrc.clone(),
+    minimum_release_age,
   ));
 
   let entry_text = install_flags_global.module_url.as_str();
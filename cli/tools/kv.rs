@@ -0,0 +1,62 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::PathBuf;
+
+use deno_core::anyhow::Context;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_runtime::deno_kv::KvConfig;
+use deno_runtime::deno_kv::backup;
+use deno_runtime::deno_kv::sqlite;
+
+use crate::args::KvFlags;
+use crate::colors;
+
+pub async fn kv(kv_flags: KvFlags) -> Result<(), AnyError> {
+  match kv_flags {
+    KvFlags::Export { database, output } => export(database, output).await,
+    KvFlags::Import { database, input } => import(database, input).await,
+  }
+}
+
+async fn export(
+  database: String,
+  output: Option<String>,
+) -> Result<(), AnyError> {
+  let path = PathBuf::from(database);
+  if !path.is_file() {
+    bail!("Database not found: {}", path.display());
+  }
+  let mut writer: Box<dyn Write> = match &output {
+    Some(output) => Box::new(BufWriter::new(
+      File::create(output)
+        .with_context(|| format!("Failed to create {}", output))?,
+    )),
+    None => Box::new(BufWriter::new(std::io::stdout())),
+  };
+  let db = sqlite::open_path(path)?;
+  let count = backup::export(&db, &mut writer).await?;
+  log::info!("{} {} entries", colors::green("Exported"), count);
+  Ok(())
+}
+
+async fn import(
+  database: String,
+  input: Option<String>,
+) -> Result<(), AnyError> {
+  let mut reader: Box<dyn std::io::BufRead> = match &input {
+    Some(input) => Box::new(BufReader::new(
+      File::open(input).with_context(|| format!("Failed to open {}", input))?,
+    )),
+    None => Box::new(BufReader::new(std::io::stdin())),
+  };
+  let db = sqlite::open_path(PathBuf::from(database))?;
+  let count =
+    backup::import(&db, &mut reader, &KvConfig::builder().build()).await?;
+  log::info!("{} {} entries", colors::green("Imported"), count);
+  Ok(())
+}
@@ -13,7 +13,9 @@ pub mod info;
 pub mod init;
 pub mod installer;
 pub mod jupyter;
+pub mod kv;
 pub mod lint;
+pub mod permission_learn;
 pub mod pm;
 pub mod publish;
 pub mod repl;
@@ -21,4 +23,5 @@ pub mod run;
 pub mod serve;
 pub mod task;
 pub mod test;
+pub mod unpack;
 pub mod upgrade;
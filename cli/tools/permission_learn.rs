@@ -0,0 +1,401 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! Support for `--learn-permissions`, which grants everything that isn't
+//! denied and prints the permissions that were used when the process exits.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_runtime::deno_permissions::PermissionAuditOperation;
+use deno_runtime::deno_permissions::PermissionAuditRecord;
+use deno_runtime::deno_permissions::PermissionAuditSink;
+use deno_runtime::deno_permissions::PermissionState;
+use deno_runtime::deno_permissions::add_audit_sink;
+use indexmap::IndexMap;
+
+use crate::args::PermissionLearnFormat;
+use crate::colors;
+
+/// The order the permissions are output in.
+const KINDS: [&str; 8] =
+  ["read", "write", "net", "env", "sys", "run", "ffi", "import"];
+
+/// The number of paths in the same directory that get collapsed into the
+/// directory itself.
+const COLLAPSE_THRESHOLD: usize = 3;
+
+static LEARNER: OnceLock<PermissionLearner> = OnceLock::new();
+
+struct PermissionLearner {
+  format: PermissionLearnFormat,
+  base_dir: PathBuf,
+  home_dir: Option<PathBuf>,
+  learned: Mutex<LearnedPermissions>,
+}
+
+struct LearnSink(&'static PermissionLearner);
+
+impl PermissionAuditSink for LearnSink {
+  fn record(&self, record: &PermissionAuditRecord) {
+    if !matches!(
+      record.operation,
+      PermissionAuditOperation::Check | PermissionAuditOperation::Request
+    ) || record.decision != PermissionState::Granted
+    {
+      return;
+    }
+    self.0.learned.lock().add(record);
+  }
+}
+
+/// Starts collecting the permissions that are used and prints them on exit.
+///
+/// Paths are output relative to the base directory when they're within it.
+/// Neither the base directory nor the home directory are output in place of
+/// the paths within them.
+pub fn init(
+  format: PermissionLearnFormat,
+  base_dir: PathBuf,
+  home_dir: Option<PathBuf>,
+) {
+  let learner = PermissionLearner {
+    format,
+    base_dir,
+    home_dir,
+    learned: Default::default(),
+  };
+  if LEARNER.set(learner).is_ok() {
+    add_audit_sink(Box::new(LearnSink(LEARNER.get().unwrap())));
+    deno_signals::before_exit(print_learned_permissions);
+  }
+}
+
+#[allow(clippy::print_stderr)]
+fn print_learned_permissions() {
+  let Some(learner) = LEARNER.get() else {
+    return;
+  };
+  let learned = learner.learned.lock();
+  if learned.is_empty() {
+    eprintln!("{} No permissions were used.", colors::green("Learned"));
+    return;
+  }
+  let home_dir = learner.home_dir.as_deref();
+  let output = match learner.format {
+    PermissionLearnFormat::Flags => {
+      learned.to_flags(&learner.base_dir, home_dir).join(" ")
+    }
+    PermissionLearnFormat::Config => {
+      learned.to_config_snippet(&learner.base_dir, home_dir)
+    }
+  };
+  eprintln!("{} permissions:\n{}", colors::green("Learned"), output);
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct LearnedPermission {
+  /// Whether the entire permission was used.
+  all: bool,
+  values: BTreeSet<String>,
+  paths: BTreeSet<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+struct LearnedPermissions(HashMap<&'static str, LearnedPermission>);
+
+impl LearnedPermissions {
+  fn add(&mut self, record: &PermissionAuditRecord) {
+    let permission = self.0.entry(record.kind).or_default();
+    match (&record.path, &record.descriptor) {
+      (Some(path), _) => {
+        permission.paths.insert(path.clone());
+      }
+      (None, Some(descriptor)) => {
+        permission.values.insert(descriptor.clone());
+      }
+      (None, None) => {
+        permission.all = true;
+      }
+    }
+  }
+
+  fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Gets the allow list of each used permission in output order, where an
+  /// empty list means the entire permission.
+  fn allow_lists(
+    &self,
+    base_dir: &Path,
+    home_dir: Option<&Path>,
+  ) -> Vec<(&'static str, Vec<String>)> {
+    let protected_dirs = [Some(base_dir), home_dir]
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>();
+    KINDS
+      .iter()
+      .filter_map(|kind| {
+        let permission = self.0.get(kind)?;
+        if permission.all {
+          return Some((*kind, Vec::new()));
+        }
+        let mut values = permission.values.iter().cloned().collect::<Vec<_>>();
+        values.extend(
+          collapse_paths(&permission.paths, &protected_dirs)
+            .iter()
+            .map(|path| display_path(path, base_dir)),
+        );
+        Some((*kind, values))
+      })
+      .collect()
+  }
+
+  fn to_flags(&self, base_dir: &Path, home_dir: Option<&Path>) -> Vec<String> {
+    self
+      .allow_lists(base_dir, home_dir)
+      .into_iter()
+      .map(|(kind, values)| {
+        if values.is_empty() {
+          return format!("--allow-{kind}");
+        }
+        let values = values
+          .iter()
+          .map(|value| value.replace(',', ",,"))
+          .collect::<Vec<_>>()
+          .join(",");
+        if values.contains(char::is_whitespace) {
+          format!("--allow-{kind}=\"{values}\"")
+        } else {
+          format!("--allow-{kind}={values}")
+        }
+      })
+      .collect()
+  }
+
+  fn to_config_snippet(
+    &self,
+    base_dir: &Path,
+    home_dir: Option<&Path>,
+  ) -> String {
+    let set = self
+      .allow_lists(base_dir, home_dir)
+      .into_iter()
+      .map(|(kind, values)| {
+        let value = if values.is_empty() {
+          json!(true)
+        } else {
+          json!(values)
+        };
+        (kind, value)
+      })
+      .collect::<IndexMap<_, _>>();
+    // serialize the maps directly to keep the order of the permissions
+    let config =
+      IndexMap::from([("permissions", IndexMap::from([("default", set)]))]);
+    serde_json::to_string_pretty(&config).unwrap()
+  }
+}
+
+/// Removes the paths that are within other paths and replaces paths that
+/// share a directory with the directory once there are enough of them.
+///
+/// Paths are only collapsed by a single level so that a few directories with
+/// enough paths don't turn into one of their ancestors, and never into one
+/// of the protected directories or a directory that contains them.
+fn collapse_paths(
+  paths: &BTreeSet<PathBuf>,
+  protected_dirs: &[&Path],
+) -> BTreeSet<PathBuf> {
+  let mut paths = remove_nested_paths(paths.clone());
+  let mut children_by_dir: BTreeMap<&Path, usize> = BTreeMap::new();
+  for path in &paths {
+    if let Some(parent) = path.parent() {
+      *children_by_dir.entry(parent).or_default() += 1;
+    }
+  }
+  let dirs = children_by_dir
+    .into_iter()
+    .filter(|(dir, count)| {
+      *count >= COLLAPSE_THRESHOLD
+        // never collapse into the root of the file system
+        && dir.parent().is_some()
+        && !protected_dirs.iter().any(|protected| protected.starts_with(dir))
+    })
+    .map(|(dir, _)| dir.to_path_buf())
+    .collect::<Vec<_>>();
+  paths.extend(dirs);
+  remove_nested_paths(paths)
+}
+
+fn remove_nested_paths(paths: BTreeSet<PathBuf>) -> BTreeSet<PathBuf> {
+  // paths are ordered by component, so a path always comes directly before
+  // the paths that are within it
+  let mut result: BTreeSet<PathBuf> = BTreeSet::new();
+  for path in paths {
+    if result.last().is_some_and(|last| path.starts_with(last)) {
+      continue;
+    }
+    result.insert(path);
+  }
+  result
+}
+
+fn display_path(path: &Path, base_dir: &Path) -> String {
+  match path.strip_prefix(base_dir) {
+    Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+    // forward slashes work in flags and config files on every platform
+    Ok(relative) => {
+      format!("./{}", relative.to_string_lossy().replace('\\', "/"))
+    }
+    Err(_) => path.to_string_lossy().to_string(),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn record(
+    kind: &'static str,
+    descriptor: Option<&str>,
+    path: Option<&str>,
+  ) -> PermissionAuditRecord {
+    PermissionAuditRecord {
+      operation: PermissionAuditOperation::Check,
+      kind,
+      descriptor: descriptor.map(|d| d.to_string()),
+      path: path.map(PathBuf::from),
+      decision: PermissionState::Granted,
+      prompted: false,
+      api_name: None,
+      stack: None,
+    }
+  }
+
+  #[test]
+  fn collapses_paths() {
+    let paths = [
+      "/app/data/a.txt",
+      "/app/data/b.txt",
+      "/app/data/c/d.txt",
+      "/app/data/e.txt",
+      "/app/config.json",
+      "/etc/hosts",
+      "/etc",
+      "/etc/resolv.conf",
+      "/a",
+      "/b",
+      "/c",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+    assert_eq!(
+      collapse_paths(&paths, &[]).into_iter().collect::<Vec<_>>(),
+      ["/a", "/app/config.json", "/app/data", "/b", "/c", "/etc"]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn collapses_paths_one_level() {
+    let paths = [
+      "/usr/lib/a/1.so",
+      "/usr/lib/a/2.so",
+      "/usr/lib/a/3.so",
+      "/usr/lib/b/1.so",
+      "/usr/lib/b/2.so",
+      "/usr/lib/b/3.so",
+      "/usr/lib/c/1.so",
+      "/usr/lib/c/2.so",
+      "/usr/lib/c/3.so",
+      "/app/a.txt",
+      "/app/b.txt",
+      "/app/c.txt",
+      "/home/user/.a",
+      "/home/user/.b",
+      "/home/user/.c",
+      "/home/user/docs/a.txt",
+      "/home/user/docs/b.txt",
+      "/home/user/docs/c.txt",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+    assert_eq!(
+      collapse_paths(&paths, &[Path::new("/app"), Path::new("/home/user")])
+        .into_iter()
+        .collect::<Vec<_>>(),
+      [
+        "/app/a.txt",
+        "/app/b.txt",
+        "/app/c.txt",
+        "/home/user/.a",
+        "/home/user/.b",
+        "/home/user/.c",
+        "/home/user/docs",
+        "/usr/lib/a",
+        "/usr/lib/b",
+        "/usr/lib/c",
+      ]
+      .into_iter()
+      .map(PathBuf::from)
+      .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn outputs_flags_and_config() {
+    let base_dir = Path::new("/app");
+    let mut learned = LearnedPermissions::default();
+    learned.add(&record("net", Some("example.com:443"), None));
+    learned.add(&record("read", Some("./a.txt"), Some("/app/a.txt")));
+    learned.add(&record("read", Some("/etc/hosts"), Some("/etc/hosts")));
+    learned.add(&record("env", Some("HOME"), None));
+    learned.add(&record("env", None, None));
+    learned.add(&record("run", Some("git"), None));
+    learned.add(&record("net", Some("example.com:443"), None));
+    assert_eq!(
+      learned.to_flags(base_dir, None),
+      vec![
+        "--allow-read=./a.txt,/etc/hosts",
+        "--allow-net=example.com:443",
+        "--allow-env",
+        "--allow-run=git",
+      ]
+    );
+    assert_eq!(
+      learned.to_config_snippet(base_dir, None),
+      r#"{
+  "permissions": {
+    "default": {
+      "read": [
+        "./a.txt",
+        "/etc/hosts"
+      ],
+      "net": [
+        "example.com:443"
+      ],
+      "env": true,
+      "run": [
+        "git"
+      ]
+    }
+  }
+}"#
+    );
+  }
+}
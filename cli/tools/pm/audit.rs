@@ -0,0 +1,723 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use deno_core::anyhow::Context;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_npm::NpmPackageId;
+use deno_npm::npm_rc::ResolvedNpmRc;
+use deno_npm::resolution::NpmResolutionSnapshot;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+use deno_semver::package::PackageNv;
+use deno_terminal::colors;
+use serde::Deserialize;
+
+use crate::args::AuditFlags;
+use crate::args::AuditSeverity;
+use crate::args::Flags;
+use crate::factory::CliFactory;
+use crate::http_util;
+use crate::http_util::HttpClient;
+use crate::npm::NpmFetchResolver;
+
+/// The number of dependency paths to show for a vulnerable package at most.
+const MAX_PATHS: usize = 10;
+
+pub async fn audit(
+  flags: Arc<Flags>,
+  audit_flags: AuditFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags);
+  let cli_options = factory.cli_options()?;
+  let npm_resolver = factory.npm_resolver().await?;
+  let Some(managed_npm_resolver) = npm_resolver.as_managed() else {
+    bail!(
+      "deno audit is not supported when the node_modules directory is managed by another package manager."
+    );
+  };
+  let snapshot = managed_npm_resolver.resolution().snapshot();
+
+  // the same version can be in the snapshot more than once with different
+  // peer dependencies, but advisories only care about the version
+  let mut ids_by_nv: BTreeMap<PackageNv, Vec<NpmPackageId>> = BTreeMap::new();
+  for package in snapshot.all_packages_for_every_system() {
+    ids_by_nv
+      .entry(package.id.nv.clone())
+      .or_default()
+      .push(package.id.clone());
+  }
+  if ids_by_nv.is_empty() {
+    log::info!("No npm packages to audit.");
+    return Ok(());
+  }
+
+  let advisories = match &audit_flags.advisories {
+    Some(dir) => {
+      let dir = cli_options.initial_cwd().join(dir);
+      load_osv_advisories(&dir).with_context(|| {
+        format!("Failed reading advisories in '{}'", dir.display())
+      })?
+    }
+    None => {
+      let http_client = factory.http_client_provider().get_or_create()?;
+      fetch_registry_advisories(&http_client, factory.npmrc()?, &ids_by_nv)
+        .await?
+    }
+  };
+
+  let mut findings = Vec::new();
+  for (nv, ids) in &ids_by_nv {
+    let Some(advisories) = advisories.get(nv.name.as_str()) else {
+      continue;
+    };
+    for advisory in advisories {
+      if advisory.affected.matches(&nv.version) {
+        findings.push(Finding { nv, ids, advisory });
+      }
+    }
+  }
+  findings.sort_by(|a, b| {
+    b.advisory
+      .severity
+      .cmp(&a.advisory.severity)
+      .then_with(|| a.nv.cmp(b.nv))
+      .then_with(|| a.advisory.id.cmp(&b.advisory.id))
+  });
+
+  let npm_fetch_resolver = if audit_flags.advisories.is_none() {
+    Some(NpmFetchResolver::new(
+      factory.file_fetcher()?.clone(),
+      factory.npmrc()?.clone(),
+      factory.minimum_release_age()?.cloned(),
+    ))
+  } else {
+    None
+  };
+  let dependents = Dependents::new(&snapshot);
+  for finding in &findings {
+    let fixed_in = match &finding.advisory.affected {
+      AffectedVersions::Osv(osv) => osv.fixed_in(&finding.nv.version).cloned(),
+      AffectedVersions::Npm(version_req) => match &npm_fetch_resolver {
+        Some(resolver) => resolver
+          .package_info(&finding.nv.name)
+          .await
+          .and_then(|info| {
+            info
+              .versions
+              .keys()
+              .filter(|v| {
+                v.pre.is_empty()
+                  && **v > finding.nv.version
+                  && !version_req.matches(v)
+              })
+              .min()
+              .cloned()
+          }),
+        None => None,
+      },
+    };
+    let paths = dependents.paths(finding.ids);
+
+    log::info!(
+      "{} {}",
+      severity_label(finding.advisory.severity),
+      colors::bold(&finding.advisory.title)
+    );
+    log::info!("  Package:   {}", finding.nv);
+    log::info!(
+      "  Fixed in:  {}",
+      match fixed_in {
+        Some(version) => colors::green(version.to_string()).to_string(),
+        None => colors::gray("no fix available").to_string(),
+      }
+    );
+    for (i, path) in paths.iter().enumerate() {
+      log::info!(
+        "  {}{}",
+        if i == 0 { "Path:      " } else { "           " },
+        path
+          .iter()
+          .map(|id| id.nv.to_string())
+          .collect::<Vec<_>>()
+          .join(&colors::gray(" > ").to_string())
+      );
+    }
+    if paths.len() >= MAX_PATHS {
+      log::info!(
+        "             {}",
+        colors::gray(format!("only the first {} paths are shown", MAX_PATHS))
+      );
+    }
+    log::info!("  More info: {}", colors::cyan(&finding.advisory.url));
+    log::info!("");
+  }
+
+  if findings.is_empty() {
+    log::info!(
+      "{} in {} packages",
+      colors::green("No known vulnerabilities found"),
+      ids_by_nv.len(),
+    );
+    return Ok(());
+  }
+
+  let mut counts: BTreeMap<AuditSeverity, usize> = BTreeMap::new();
+  for finding in &findings {
+    *counts.entry(finding.advisory.severity).or_default() += 1;
+  }
+  log::info!(
+    "Found {} {} in {} packages ({})",
+    findings.len(),
+    if findings.len() == 1 {
+      "vulnerability"
+    } else {
+      "vulnerabilities"
+    },
+    ids_by_nv.len(),
+    counts
+      .iter()
+      .rev()
+      .map(|(severity, count)| format!("{} {}", count, severity.as_str()))
+      .collect::<Vec<_>>()
+      .join(", ")
+  );
+
+  if findings
+    .iter()
+    .any(|finding| finding.advisory.severity >= audit_flags.level)
+  {
+    deno_runtime::exit(1);
+  }
+
+  Ok(())
+}
+
+fn severity_label(severity: AuditSeverity) -> String {
+  let text = format!("{:<8}", severity.as_str());
+  match severity {
+    AuditSeverity::Low => colors::gray(text).to_string(),
+    AuditSeverity::Moderate => colors::yellow(text).to_string(),
+    AuditSeverity::High => colors::red(text).to_string(),
+    AuditSeverity::Critical => colors::red_bold(text).to_string(),
+  }
+}
+
+fn parse_severity(text: &str) -> AuditSeverity {
+  match text.to_ascii_lowercase().as_str() {
+    "critical" => AuditSeverity::Critical,
+    "high" => AuditSeverity::High,
+    "moderate" | "medium" => AuditSeverity::Moderate,
+    _ => AuditSeverity::Low,
+  }
+}
+
+struct Finding<'a> {
+  nv: &'a PackageNv,
+  ids: &'a [NpmPackageId],
+  advisory: &'a Advisory,
+}
+
+#[derive(Debug)]
+struct Advisory {
+  id: String,
+  title: String,
+  url: String,
+  severity: AuditSeverity,
+  affected: AffectedVersions,
+}
+
+#[derive(Debug)]
+enum AffectedVersions {
+  /// An npm version range from the registry, such as `<2.6.9`.
+  Npm(VersionReq),
+  Osv(OsvAffectedVersions),
+}
+
+impl AffectedVersions {
+  fn matches(&self, version: &Version) -> bool {
+    match self {
+      AffectedVersions::Npm(version_req) => version_req.matches(version),
+      AffectedVersions::Osv(osv) => osv.matches(version),
+    }
+  }
+}
+
+/// Advisories of the packages in the resolution keyed by package name.
+type Advisories = HashMap<String, Vec<Advisory>>;
+
+#[derive(Deserialize)]
+struct NpmBulkAdvisory {
+  id: u64,
+  url: String,
+  title: String,
+  severity: String,
+  vulnerable_versions: String,
+}
+
+/// Queries the bulk advisory endpoint of the registries that the packages
+/// are resolved from.
+async fn fetch_registry_advisories(
+  http_client: &HttpClient,
+  npmrc: &ResolvedNpmRc,
+  ids_by_nv: &BTreeMap<PackageNv, Vec<NpmPackageId>>,
+) -> Result<Advisories, AnyError> {
+  let mut versions_by_registry: HashMap<&Url, BTreeMap<&str, Vec<String>>> =
+    HashMap::new();
+  for nv in ids_by_nv.keys() {
+    versions_by_registry
+      .entry(npmrc.get_registry_url(&nv.name))
+      .or_default()
+      .entry(nv.name.as_str())
+      .or_default()
+      .push(nv.version.to_string());
+  }
+
+  let mut advisories = Advisories::new();
+  for (registry_url, versions) in versions_by_registry {
+    let url = registry_url.join("-/npm/v1/security/advisories/bulk")?;
+    let mut request = http_client.post_json(url.clone(), &versions)?;
+    // all packages of a registry share the registry config
+    let first_package = versions.keys().next().unwrap();
+    let registry_config = npmrc.get_registry_config(first_package);
+    if let Some(auth) =
+      deno_npm_cache::maybe_auth_header_value_for_npm_registry(registry_config)?
+    {
+      request = request.header(http::header::AUTHORIZATION, auth.parse()?);
+    }
+    let response = request
+      .send()
+      .await
+      .with_context(|| format!("Failed fetching advisories from {}", url))?;
+    let status = response.status();
+    if !status.is_success() {
+      let text = http_util::body_to_string(response)
+        .await
+        .unwrap_or_default();
+      bail!(
+        "Failed fetching advisories from {} ({}): {}",
+        url,
+        status,
+        text
+      );
+    }
+    let response: HashMap<String, Vec<NpmBulkAdvisory>> =
+      http_util::body_to_json(response)
+        .await
+        .with_context(|| format!("Failed parsing advisories from {}", url))?;
+    for (name, entries) in response {
+      for entry in entries {
+        let Ok(version_req) =
+          VersionReq::parse_from_npm(&entry.vulnerable_versions)
+        else {
+          log::debug!(
+            "Skipping advisory {} with invalid range '{}'",
+            entry.id,
+            entry.vulnerable_versions
+          );
+          continue;
+        };
+        advisories.entry(name.clone()).or_default().push(Advisory {
+          id: entry.id.to_string(),
+          title: entry.title,
+          url: entry.url,
+          severity: parse_severity(&entry.severity),
+          affected: AffectedVersions::Npm(version_req),
+        });
+      }
+    }
+  }
+  Ok(advisories)
+}
+
+#[derive(Deserialize)]
+struct OsvEntry {
+  id: String,
+  #[serde(default)]
+  summary: Option<String>,
+  #[serde(default)]
+  affected: Vec<OsvAffected>,
+  #[serde(default)]
+  references: Vec<OsvReference>,
+  #[serde(default)]
+  database_specific: Option<OsvDatabaseSpecific>,
+}
+
+#[derive(Deserialize)]
+struct OsvAffected {
+  package: Option<OsvPackage>,
+  #[serde(default)]
+  ranges: Vec<OsvRange>,
+  #[serde(default)]
+  versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvPackage {
+  ecosystem: String,
+  name: String,
+}
+
+#[derive(Deserialize)]
+struct OsvRange {
+  #[serde(rename = "type", default)]
+  kind: String,
+  #[serde(default)]
+  events: Vec<OsvEvent>,
+}
+
+#[derive(Deserialize)]
+struct OsvEvent {
+  introduced: Option<String>,
+  fixed: Option<String>,
+  last_affected: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvReference {
+  #[serde(rename = "type")]
+  kind: String,
+  url: String,
+}
+
+#[derive(Deserialize)]
+struct OsvDatabaseSpecific {
+  severity: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct OsvAffectedVersions {
+  ranges: Vec<VersionRange>,
+  versions: HashSet<Version>,
+}
+
+#[derive(Debug)]
+struct VersionRange {
+  /// `None` when every version up to the end is affected.
+  introduced: Option<Version>,
+  end: Option<RangeEnd>,
+}
+
+#[derive(Debug)]
+enum RangeEnd {
+  Fixed(Version),
+  LastAffected(Version),
+}
+
+impl VersionRange {
+  fn matches(&self, version: &Version) -> bool {
+    if let Some(introduced) = &self.introduced {
+      if version < introduced {
+        return false;
+      }
+    }
+    match &self.end {
+      Some(RangeEnd::Fixed(fixed)) => version < fixed,
+      Some(RangeEnd::LastAffected(last)) => version <= last,
+      None => true,
+    }
+  }
+}
+
+impl OsvAffectedVersions {
+  fn from_affected(affected: &OsvAffected) -> Self {
+    fn parse_version(text: &str) -> Option<Version> {
+      Version::parse_from_npm(text).ok()
+    }
+
+    let mut ranges = Vec::new();
+    for range in &affected.ranges {
+      // other kinds of ranges, like git commits, can't be compared with
+      // npm versions
+      if !matches!(range.kind.as_str(), "SEMVER" | "ECOSYSTEM") {
+        continue;
+      }
+      let mut current: Option<Option<Version>> = None;
+      for event in &range.events {
+        if let Some(introduced) = &event.introduced {
+          // "0" means every version since the beginning
+          let introduced = match introduced.as_str() {
+            "0" => None,
+            text => match parse_version(text) {
+              Some(version) => Some(version),
+              // skip it rather than treating every version as affected
+              None => continue,
+            },
+          };
+          if let Some(introduced) = current.replace(introduced) {
+            ranges.push(VersionRange {
+              introduced,
+              end: None,
+            });
+          }
+        } else if let Some(end) = (event.fixed.as_deref())
+          .and_then(parse_version)
+          .map(RangeEnd::Fixed)
+          .or_else(|| {
+            (event.last_affected.as_deref())
+              .and_then(parse_version)
+              .map(RangeEnd::LastAffected)
+          })
+        {
+          if let Some(introduced) = current.take() {
+            ranges.push(VersionRange {
+              introduced,
+              end: Some(end),
+            });
+          }
+        }
+      }
+      if let Some(introduced) = current {
+        ranges.push(VersionRange {
+          introduced,
+          end: None,
+        });
+      }
+    }
+    Self {
+      ranges,
+      versions: affected
+        .versions
+        .iter()
+        .filter_map(|v| parse_version(v))
+        .collect(),
+    }
+  }
+
+  fn matches(&self, version: &Version) -> bool {
+    self.versions.contains(version)
+      || self.ranges.iter().any(|range| range.matches(version))
+  }
+
+  /// The lowest version that fixes a range the version is in.
+  fn fixed_in(&self, version: &Version) -> Option<&Version> {
+    self
+      .ranges
+      .iter()
+      .filter(|range| range.matches(version))
+      .filter_map(|range| match &range.end {
+        Some(RangeEnd::Fixed(fixed)) => Some(fixed),
+        _ => None,
+      })
+      .min()
+  }
+}
+
+/// Reads the npm advisories from a directory of OSV JSON files, such as
+/// an extracted copy of https://osv-vulnerabilities.storage.googleapis.com/npm/all.zip
+fn load_osv_advisories(dir: &Path) -> Result<Advisories, AnyError> {
+  let mut advisories = Advisories::new();
+  let mut pending = vec![dir.to_path_buf()];
+  while let Some(dir) = pending.pop() {
+    for entry in std::fs::read_dir(&dir)? {
+      let entry = entry?;
+      let path = entry.path();
+      if entry.file_type()?.is_dir() {
+        pending.push(path);
+        continue;
+      }
+      if path.extension().and_then(|e| e.to_str()) != Some("json") {
+        continue;
+      }
+      let text = std::fs::read_to_string(&path)?;
+      let osv_entry = serde_json::from_str::<OsvEntry>(&text)
+        .with_context(|| format!("Failed parsing '{}'", path.display()))?;
+      add_osv_entry(&mut advisories, osv_entry);
+    }
+  }
+  Ok(advisories)
+}
+
+fn add_osv_entry(advisories: &mut Advisories, entry: OsvEntry) {
+  // advisories without a severity are reported with the lowest one
+  let severity = (entry.database_specific.as_ref())
+    .and_then(|d| d.severity.as_deref())
+    .map(parse_severity)
+    .unwrap_or_default();
+  let url = entry
+    .references
+    .iter()
+    .find(|r| r.kind == "ADVISORY")
+    .map(|r| r.url.clone())
+    .unwrap_or_else(|| format!("https://osv.dev/vulnerability/{}", entry.id));
+  for affected in &entry.affected {
+    let Some(package) = &affected.package else {
+      continue;
+    };
+    if package.ecosystem != "npm" {
+      continue;
+    }
+    advisories
+      .entry(package.name.clone())
+      .or_default()
+      .push(Advisory {
+        id: entry.id.clone(),
+        title: entry.summary.clone().unwrap_or_else(|| entry.id.clone()),
+        url: url.clone(),
+        severity,
+        affected: AffectedVersions::Osv(OsvAffectedVersions::from_affected(
+          affected,
+        )),
+      });
+  }
+}
+
+/// The reverse of the dependencies in the npm resolution, used to find the
+/// paths from the top level packages to a package.
+struct Dependents<'a> {
+  top_level: HashSet<&'a NpmPackageId>,
+  dependents: HashMap<&'a NpmPackageId, Vec<&'a NpmPackageId>>,
+}
+
+impl<'a> Dependents<'a> {
+  fn new(snapshot: &'a NpmResolutionSnapshot) -> Self {
+    let mut dependents: HashMap<_, Vec<_>> = HashMap::new();
+    for package in snapshot.all_packages_for_every_system() {
+      for dep_id in package.dependencies.values() {
+        dependents.entry(dep_id).or_default().push(&package.id);
+      }
+    }
+    Self {
+      top_level: snapshot.top_level_packages().collect(),
+      dependents,
+    }
+  }
+
+  fn paths(&self, ids: &'a [NpmPackageId]) -> Vec<Vec<&'a NpmPackageId>> {
+    let mut paths = Vec::new();
+    for id in ids {
+      self.collect_paths(&mut vec![id], &mut paths);
+    }
+    paths.sort();
+    paths
+  }
+
+  fn collect_paths(
+    &self,
+    path: &mut Vec<&'a NpmPackageId>,
+    paths: &mut Vec<Vec<&'a NpmPackageId>>,
+  ) {
+    if paths.len() >= MAX_PATHS {
+      return;
+    }
+    let id = *path.last().unwrap();
+    if self.top_level.contains(id) {
+      paths.push(path.iter().rev().copied().collect());
+    }
+    for dependent in self.dependents.get(id).into_iter().flatten() {
+      // skip circular dependencies
+      if !path.contains(dependent) {
+        path.push(dependent);
+        self.collect_paths(path, paths);
+        path.pop();
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn version(text: &str) -> Version {
+    Version::parse_from_npm(text).unwrap()
+  }
+
+  #[test]
+  fn osv_affected_versions() {
+    let entry: OsvEntry = serde_json::from_value(serde_json::json!({
+      "id": "GHSA-0000-0000-0000",
+      "summary": "Prototype pollution",
+      "affected": [{
+        "package": { "ecosystem": "npm", "name": "example" },
+        "ranges": [{
+          "type": "SEMVER",
+          "events": [
+            { "introduced": "0" },
+            { "fixed": "1.2.3" },
+            { "introduced": "2.0.0" },
+            { "last_affected": "2.1.0" },
+            { "introduced": "3.0.0" },
+          ],
+        }],
+        "versions": ["2.5.0"],
+      }, {
+        "package": { "ecosystem": "PyPI", "name": "example" },
+      }],
+      "database_specific": { "severity": "HIGH" },
+    }))
+    .unwrap();
+    let mut advisories = Advisories::new();
+    add_osv_entry(&mut advisories, entry);
+    assert_eq!(advisories.len(), 1);
+    let advisory = &advisories["example"][0];
+    assert_eq!(advisory.severity, AuditSeverity::High);
+    assert_eq!(advisory.title, "Prototype pollution");
+    assert_eq!(
+      advisory.url,
+      "https://osv.dev/vulnerability/GHSA-0000-0000-0000"
+    );
+    let AffectedVersions::Osv(affected) = &advisory.affected else {
+      unreachable!();
+    };
+
+    assert!(affected.matches(&version("0.1.0")));
+    assert!(affected.matches(&version("1.2.2")));
+    assert!(!affected.matches(&version("1.2.3")));
+    assert!(affected.matches(&version("2.1.0")));
+    assert!(!affected.matches(&version("2.2.0")));
+    assert!(affected.matches(&version("2.5.0")));
+    assert!(affected.matches(&version("3.4.0")));
+
+    assert_eq!(
+      affected.fixed_in(&version("1.0.0")),
+      Some(&version("1.2.3"))
+    );
+    assert_eq!(affected.fixed_in(&version("2.0.0")), None);
+    assert_eq!(affected.fixed_in(&version("3.0.0")), None);
+  }
+
+  #[test]
+  fn osv_affected_versions_skips_unsupported() {
+    let affected: OsvAffected = serde_json::from_value(serde_json::json!({
+      "package": { "ecosystem": "npm", "name": "example" },
+      "ranges": [{
+        "type": "GIT",
+        "events": [
+          { "introduced": "0" },
+          { "fixed": "0123456789abcdef" },
+        ],
+      }, {
+        "type": "ECOSYSTEM",
+        "events": [
+          { "introduced": "not-a-version" },
+          { "fixed": "1.0.0" },
+          { "introduced": "2.0.0" },
+          { "fixed": "2.0.1" },
+        ],
+      }],
+    }))
+    .unwrap();
+    let affected = OsvAffectedVersions::from_affected(&affected);
+    assert!(!affected.matches(&version("0.1.0")));
+    assert!(!affected.matches(&version("1.5.0")));
+    assert!(affected.matches(&version("2.0.0")));
+    assert!(!affected.matches(&version("2.0.1")));
+  }
+
+  #[test]
+  fn severity() {
+    assert_eq!(parse_severity("CRITICAL"), AuditSeverity::Critical);
+    assert_eq!(parse_severity("high"), AuditSeverity::High);
+    assert_eq!(parse_severity("MODERATE"), AuditSeverity::Moderate);
+    assert_eq!(parse_severity("medium"), AuditSeverity::Moderate);
+    assert_eq!(parse_severity("info"), AuditSeverity::Low);
+  }
+}
@@ -42,6 +42,7 @@ pub async fn cache_top_level_deps(
     } else {
       Arc::new(crate::jsr::JsrFetchResolver::new(
         factory.file_fetcher()?.clone(),
+        factory.minimum_release_age()?.cloned(),
       ))
     };
     let mut graph_permit = factory
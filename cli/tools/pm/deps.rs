@@ -37,6 +37,7 @@ use tokio::sync::Semaphore;
 
 use super::ConfigUpdater;
 use crate::args::CliLockfile;
+use crate::factory::CliFactory;
 use crate::graph_container::MainModuleGraphContainer;
 use crate::graph_container::ModuleGraphContainer;
 use crate::graph_container::ModuleGraphUpdatePermit;
@@ -75,6 +76,13 @@ impl DepLocation {
       DepLocation::PackageJson(arc, _) => Cow::Borrowed(arc.path.as_ref()),
     }
   }
+
+  pub fn key_path(&self) -> &KeyPath {
+    match self {
+      DepLocation::DenoJson(_, key_path, _) => key_path,
+      DepLocation::PackageJson(_, key_path) => key_path,
+    }
+  }
   fn config_kind(&self) -> super::ConfigKind {
     match self {
       DepLocation::DenoJson(_, _, _) => super::ConfigKind::DenoJson,
@@ -479,6 +487,26 @@ pub struct DepManagerArgs {
   pub lockfile: Option<Arc<CliLockfile>>,
 }
 
+pub async fn dep_manager_args(
+  factory: &CliFactory,
+  npm_fetch_resolver: Arc<NpmFetchResolver>,
+  jsr_fetch_resolver: Arc<JsrFetchResolver>,
+) -> Result<DepManagerArgs, AnyError> {
+  Ok(DepManagerArgs {
+    module_load_preparer: factory.module_load_preparer().await?.clone(),
+    jsr_fetch_resolver,
+    npm_fetch_resolver,
+    npm_resolver: factory.npm_resolver().await?.clone(),
+    npm_installer: factory.npm_installer().await?.clone(),
+    permissions_container: factory.root_permissions_container()?.clone(),
+    main_module_graph_container: factory
+      .main_module_graph_container()
+      .await?
+      .clone(),
+    lockfile: factory.maybe_lockfile().await?.cloned(),
+  })
+}
+
 impl DepManager {
   pub fn reloaded_after_modification(self, args: DepManagerArgs) -> Self {
     let mut new = Self::with_deps_args(self.deps, args);
@@ -657,6 +685,16 @@ impl DepManager {
     self.resolved_versions[id.0].as_ref()
   }
 
+  pub fn deps_with_resolved_versions(
+    &self,
+  ) -> impl Iterator<Item = (&Dep, Option<&PackageNv>)> {
+    self
+      .deps
+      .iter()
+      .zip(self.resolved_versions.iter())
+      .map(|(dep, resolved)| (dep, resolved.as_ref()))
+  }
+
   pub async fn resolve_current_versions(&mut self) -> Result<(), AnyError> {
     self.run_dependency_resolution().await?;
 
@@ -35,12 +35,18 @@ use crate::file_fetcher::create_cli_file_fetcher;
 use crate::jsr::JsrFetchResolver;
 use crate::npm::NpmFetchResolver;
 
+mod audit;
 mod cache_deps;
 pub(crate) mod deps;
 mod outdated;
+mod sbom;
+mod why;
 
+pub use audit::audit;
 pub use cache_deps::cache_top_level_deps;
 pub use outdated::outdated;
+pub use sbom::sbom;
+pub use why::why;
 
 #[derive(Debug, Copy, Clone, Hash)]
 enum ConfigKind {
@@ -437,9 +443,16 @@ pub async fn add(
   let npmrc = cli_factory.npmrc()?;
 
   let deps_file_fetcher = Arc::new(deps_file_fetcher);
-  let jsr_resolver = Arc::new(JsrFetchResolver::new(deps_file_fetcher.clone()));
-  let npm_resolver =
-    Arc::new(NpmFetchResolver::new(deps_file_fetcher, npmrc.clone()));
+  let minimum_release_age = cli_factory.minimum_release_age()?.cloned();
+  let jsr_resolver = Arc::new(JsrFetchResolver::new(
+    deps_file_fetcher.clone(),
+    minimum_release_age.clone(),
+  ));
+  let npm_resolver = Arc::new(NpmFetchResolver::new(
+    deps_file_fetcher,
+    npmrc.clone(),
+    minimum_release_age,
+  ));
 
   let mut selected_packages = Vec::with_capacity(add_flags.packages.len());
   let mut package_reqs = Vec::with_capacity(add_flags.packages.len());
@@ -19,8 +19,8 @@ use super::deps::Dep;
 use super::deps::DepId;
 use super::deps::DepKind;
 use super::deps::DepManager;
-use super::deps::DepManagerArgs;
 use super::deps::PackageLatestVersion;
+use super::deps::dep_manager_args;
 use crate::args::Flags;
 use crate::args::OutdatedFlags;
 use crate::factory::CliFactory;
@@ -201,9 +201,12 @@ pub async fn outdated(
   let npm_fetch_resolver = Arc::new(NpmFetchResolver::new(
     file_fetcher.clone(),
     factory.npmrc()?.clone(),
+    factory.minimum_release_age()?.cloned(),
+  ));
+  let jsr_fetch_resolver = Arc::new(JsrFetchResolver::new(
+    file_fetcher.clone(),
+    factory.minimum_release_age()?.cloned(),
   ));
-  let jsr_fetch_resolver =
-    Arc::new(JsrFetchResolver::new(file_fetcher.clone()));
 
   if !cli_options.start_dir.has_deno_json()
     && !cli_options.start_dir.has_pkg_json()
@@ -503,26 +506,6 @@ async fn update(
   Ok(())
 }
 
-async fn dep_manager_args(
-  factory: &CliFactory,
-  npm_fetch_resolver: Arc<NpmFetchResolver>,
-  jsr_fetch_resolver: Arc<JsrFetchResolver>,
-) -> Result<DepManagerArgs, AnyError> {
-  Ok(DepManagerArgs {
-    module_load_preparer: factory.module_load_preparer().await?.clone(),
-    jsr_fetch_resolver,
-    npm_fetch_resolver,
-    npm_resolver: factory.npm_resolver().await?.clone(),
-    npm_installer: factory.npm_installer().await?.clone(),
-    permissions_container: factory.root_permissions_container()?.clone(),
-    main_module_graph_container: factory
-      .main_module_graph_container()
-      .await?
-      .clone(),
-    lockfile: factory.maybe_lockfile().await?.cloned(),
-  })
-}
-
 mod filter {
   use deno_core::anyhow::Context;
   use deno_core::anyhow::anyhow;
@@ -0,0 +1,797 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use base64::Engine as _;
+use base64::prelude::BASE64_STANDARD;
+use deno_ast::ModuleSpecifier;
+use deno_cache_dir::HttpCache;
+use deno_core::anyhow::Context;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::serde_json::json;
+use deno_graph::GraphKind;
+use deno_graph::ModuleGraph;
+use deno_lib::version::DENO_VERSION_INFO;
+use deno_npm::registry::NpmPackageVersionDistInfo;
+use deno_npm::registry::NpmPackageVersionDistInfoIntegrity;
+use deno_npm::resolution::NpmResolutionSnapshot;
+use deno_npm_installer::graph::NpmCachingStrategy;
+use deno_path_util::resolve_url_or_path;
+use deno_semver::Version;
+use deno_semver::jsr::JsrDepPackageReq;
+use deno_semver::package::PackageKind;
+use deno_semver::package::PackageNv;
+use jsonc_parser::ParseOptions;
+
+use crate::args::Flags;
+use crate::args::SbomFlags;
+use crate::args::SbomFormat;
+use crate::args::jsr_url;
+use crate::factory::CliFactory;
+use crate::util::display;
+
+pub async fn sbom(
+  flags: Arc<Flags>,
+  sbom_flags: SbomFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags);
+  let cli_options = factory.cli_options()?;
+  let npm_resolver = factory.npm_resolver().await?;
+  let Some(managed_npm_resolver) = npm_resolver.as_managed() else {
+    bail!(
+      "deno sbom is not supported when the node_modules directory is managed by another package manager."
+    );
+  };
+
+  let entrypoints = sbom_flags
+    .entrypoints
+    .iter()
+    .map(|entrypoint| {
+      resolve_url_or_path(entrypoint, cli_options.initial_cwd())
+        .with_context(|| format!("Failed resolving '{}'", entrypoint))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  let graph = if entrypoints.is_empty() {
+    None
+  } else {
+    let graph = factory
+      .module_graph_creator()
+      .await?
+      .create_graph(GraphKind::All, entrypoints, NpmCachingStrategy::Eager)
+      .await?;
+    Some(graph)
+  };
+  let maybe_lockfile = factory.maybe_lockfile().await?;
+  if maybe_lockfile.is_none() && graph.is_none() {
+    bail!(
+      "deno sbom requires a lockfile. Remove --no-lock or provide the entrypoints to include."
+    );
+  }
+  // take the snapshot after building the graph so that it has everything
+  let snapshot = managed_npm_resolver.resolution().snapshot();
+  let http_cache = factory.http_cache()?;
+
+  let deno_json = cli_options.start_dir.maybe_deno_json();
+  let pkg_json = cli_options.start_dir.maybe_pkg_json();
+  let name = deno_json
+    .and_then(|c| c.json.name.clone())
+    .or_else(|| pkg_json.and_then(|p| p.name.clone()))
+    .or_else(|| {
+      let dir_path = cli_options.start_dir.dir_path();
+      Some(dir_path.file_name()?.to_string_lossy().into_owned())
+    })
+    .unwrap_or_else(|| "unknown".to_string());
+  let version = deno_json
+    .and_then(|c| c.json.version.clone())
+    .or_else(|| pkg_json.and_then(|p| p.version.clone()));
+  let mut sbom = Sbom {
+    name,
+    version,
+    components: Default::default(),
+    dependencies: Default::default(),
+  };
+
+  if let Some(lockfile) = maybe_lockfile {
+    let lockfile = lockfile.lock();
+    let jsr_versions = (lockfile.content.packages.specifiers.iter())
+      .filter(|(req, _)| req.kind == PackageKind::Jsr)
+      .filter_map(|(req, version)| {
+        let version = Version::parse_standard(version).ok()?;
+        Some((&req.req, version))
+      })
+      .collect::<HashMap<_, _>>();
+    for (nv, info) in &lockfile.content.packages.jsr {
+      sbom.add_component(ComponentId::Jsr(nv.clone()), jsr_component(nv));
+      for dep in &info.dependencies {
+        let dep_id = match dep.kind {
+          PackageKind::Jsr => jsr_versions.get(&dep.req).map(|version| {
+            ComponentId::Jsr(PackageNv {
+              name: dep.req.name.clone(),
+              version: version.clone(),
+            })
+          }),
+          PackageKind::Npm => resolve_npm_dep(&snapshot, dep),
+        };
+        if let Some(dep_id) = dep_id {
+          sbom.add_dependency(ComponentId::Jsr(nv.clone()), dep_id);
+        }
+      }
+    }
+    for (url, checksum) in lockfile.remote() {
+      let Ok(url) = ModuleSpecifier::parse(url) else {
+        continue;
+      };
+      sbom.add_component(
+        ComponentId::Remote(url.clone()),
+        remote_component(&url, Some(checksum.as_str())),
+      );
+    }
+  }
+  if let Some(graph) = &graph {
+    add_graph(&mut sbom, graph, &snapshot);
+  }
+  for package in snapshot.all_packages_for_every_system() {
+    let id = ComponentId::Npm(package.id.nv.clone());
+    let license = managed_npm_resolver
+      .resolve_pkg_folder_from_pkg_id(&package.id)
+      .ok()
+      .and_then(|folder| {
+        let text = std::fs::read_to_string(folder.join("package.json")).ok()?;
+        license_from_json(&serde_json::from_str(&text).ok()?)
+      });
+    sbom.add_component(
+      id.clone(),
+      Component {
+        download_url: package.dist.as_ref().map(|dist| dist.tarball.clone()),
+        hashes: package
+          .dist
+          .as_ref()
+          .and_then(npm_hash)
+          .into_iter()
+          .collect(),
+        license,
+      },
+    );
+    for dep_id in package.dependencies.values() {
+      sbom.add_dependency(id.clone(), ComponentId::Npm(dep_id.nv.clone()));
+    }
+  }
+  // jsr packages declare their license in their config file, which is only
+  // in the cache when something imported it
+  for (id, component) in &mut sbom.components {
+    let ComponentId::Jsr(nv) = id else {
+      continue;
+    };
+    component.license = ["jsr.json", "jsr.jsonc", "deno.json", "deno.jsonc"]
+      .iter()
+      .find_map(|file| {
+        let url = jsr_url()
+          .join(&format!("{}/{}/{}", nv.name, nv.version, file))
+          .ok()?;
+        let cache_key = http_cache.cache_item_key(&url).ok()?;
+        let entry = http_cache.get(&cache_key, None).ok()??;
+        let text = String::from_utf8_lossy(&entry.content);
+        let value =
+          jsonc_parser::parse_to_serde_value(&text, &ParseOptions::default())
+            .ok()??;
+        license_from_json(&value)
+      });
+  }
+
+  let meta = SbomMeta {
+    timestamp: chrono::Utc::now()
+      .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    serial_number: uuid::Uuid::new_v4(),
+  };
+  let output = match sbom_flags.format {
+    SbomFormat::CycloneDx => sbom.to_cyclonedx(&meta),
+    SbomFormat::Spdx => sbom.to_spdx(&meta),
+  };
+  match &sbom_flags.output {
+    Some(output_path) => {
+      let output_path = cli_options.initial_cwd().join(output_path);
+      std::fs::write(&output_path, serde_json::to_string_pretty(&output)?)
+        .with_context(|| {
+          format!("Failed writing '{}'", output_path.display())
+        })?;
+      log::info!(
+        "Wrote a bill of materials with {} components to {}",
+        sbom.components.len(),
+        output_path.display()
+      );
+    }
+    None => display::write_json_to_stdout(&output)?,
+  }
+
+  Ok(())
+}
+
+fn add_graph(
+  sbom: &mut Sbom,
+  graph: &ModuleGraph,
+  snapshot: &NpmResolutionSnapshot,
+) {
+  for (nv, deps) in graph.packages.packages_with_deps() {
+    let id = ComponentId::Jsr(nv.clone());
+    sbom.add_component(id.clone(), jsr_component(nv));
+    for dep in deps {
+      let dep_id = match dep.kind {
+        PackageKind::Jsr => (graph.packages.mappings().get(&dep.req))
+          .map(|nv| ComponentId::Jsr(nv.clone())),
+        PackageKind::Npm => resolve_npm_dep(snapshot, dep),
+      };
+      if let Some(dep_id) = dep_id {
+        sbom.add_dependency(id.clone(), dep_id);
+      }
+    }
+  }
+  for module in graph.modules() {
+    let specifier = module.specifier();
+    if matches!(specifier.scheme(), "http" | "https")
+      && !specifier.as_str().starts_with(jsr_url().as_str())
+    {
+      sbom.add_component(
+        ComponentId::Remote(specifier.clone()),
+        remote_component(specifier, None),
+      );
+    }
+  }
+}
+
+fn resolve_npm_dep(
+  snapshot: &NpmResolutionSnapshot,
+  dep: &JsrDepPackageReq,
+) -> Option<ComponentId> {
+  let package = snapshot.resolve_pkg_from_pkg_req(&dep.req).ok()?;
+  Some(ComponentId::Npm(package.id.nv.clone()))
+}
+
+fn jsr_component(nv: &PackageNv) -> Component {
+  Component {
+    download_url: jsr_url()
+      .join(&format!("{}/{}/", nv.name, nv.version))
+      .ok()
+      .map(|url| url.to_string()),
+    // the integrity in the lockfile is the checksum of the version's
+    // meta.json rather than of the package's contents
+    hashes: Vec::new(),
+    license: None,
+  }
+}
+
+fn remote_component(
+  specifier: &ModuleSpecifier,
+  checksum: Option<&str>,
+) -> Component {
+  Component {
+    download_url: Some(specifier.to_string()),
+    hashes: checksum
+      .map(|hex| Hash {
+        algorithm: HashAlgorithm::Sha256,
+        hex: hex.to_string(),
+      })
+      .into_iter()
+      .collect(),
+    license: None,
+  }
+}
+
+fn npm_hash(dist: &NpmPackageVersionDistInfo) -> Option<Hash> {
+  match dist.integrity() {
+    NpmPackageVersionDistInfoIntegrity::Integrity {
+      algorithm,
+      base64_hash,
+    } => {
+      let algorithm = match algorithm {
+        "sha512" => HashAlgorithm::Sha512,
+        "sha256" => HashAlgorithm::Sha256,
+        "sha1" => HashAlgorithm::Sha1,
+        _ => return None,
+      };
+      let bytes = BASE64_STANDARD.decode(base64_hash).ok()?;
+      Some(Hash {
+        algorithm,
+        hex: faster_hex::hex_string(&bytes),
+      })
+    }
+    NpmPackageVersionDistInfoIntegrity::LegacySha1Hex(hex) => Some(Hash {
+      algorithm: HashAlgorithm::Sha1,
+      hex: hex.to_string(),
+    }),
+    NpmPackageVersionDistInfoIntegrity::UnknownIntegrity(_)
+    | NpmPackageVersionDistInfoIntegrity::None => None,
+  }
+}
+
+/// Reads the `license` of a package.json or jsr config file, which can
+/// also be an object with a `type` in old package.json files.
+fn license_from_json(value: &Value) -> Option<String> {
+  match value.get("license")? {
+    Value::String(license) => Some(license.clone()),
+    Value::Object(license) => {
+      license.get("type")?.as_str().map(|s| s.to_string())
+    }
+    _ => None,
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum ComponentId {
+  Jsr(PackageNv),
+  Npm(PackageNv),
+  Remote(ModuleSpecifier),
+}
+
+impl ComponentId {
+  /// The package url, see https://github.com/package-url/purl-spec
+  fn purl(&self) -> Option<String> {
+    fn encode_name(name: &str) -> String {
+      match name.strip_prefix('@') {
+        Some(name) => format!("%40{}", name),
+        None => name.to_string(),
+      }
+    }
+
+    match self {
+      ComponentId::Jsr(nv) => {
+        Some(format!("pkg:jsr/{}@{}", encode_name(&nv.name), nv.version))
+      }
+      ComponentId::Npm(nv) => {
+        Some(format!("pkg:npm/{}@{}", encode_name(&nv.name), nv.version))
+      }
+      ComponentId::Remote(_) => None,
+    }
+  }
+
+  fn bom_ref(&self) -> String {
+    match self {
+      ComponentId::Jsr(_) | ComponentId::Npm(_) => self.purl().unwrap(),
+      ComponentId::Remote(specifier) => specifier.to_string(),
+    }
+  }
+
+  fn name(&self) -> String {
+    match self {
+      ComponentId::Jsr(nv) | ComponentId::Npm(nv) => nv.name.to_string(),
+      ComponentId::Remote(specifier) => specifier.to_string(),
+    }
+  }
+
+  fn version(&self) -> Option<String> {
+    match self {
+      ComponentId::Jsr(nv) | ComponentId::Npm(nv) => {
+        Some(nv.version.to_string())
+      }
+      ComponentId::Remote(_) => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum HashAlgorithm {
+  Sha1,
+  Sha256,
+  Sha512,
+}
+
+#[derive(Debug, Clone)]
+struct Hash {
+  algorithm: HashAlgorithm,
+  hex: String,
+}
+
+#[derive(Debug, Default)]
+struct Component {
+  download_url: Option<String>,
+  hashes: Vec<Hash>,
+  license: Option<String>,
+}
+
+struct SbomMeta {
+  timestamp: String,
+  serial_number: uuid::Uuid,
+}
+
+struct Sbom {
+  name: String,
+  version: Option<String>,
+  components: BTreeMap<ComponentId, Component>,
+  dependencies: BTreeMap<ComponentId, BTreeSet<ComponentId>>,
+}
+
+impl Sbom {
+  fn add_component(&mut self, id: ComponentId, component: Component) {
+    let existing = self.components.entry(id).or_default();
+    if existing.download_url.is_none() {
+      existing.download_url = component.download_url;
+    }
+    if existing.hashes.is_empty() {
+      existing.hashes = component.hashes;
+    }
+    if existing.license.is_none() {
+      existing.license = component.license;
+    }
+  }
+
+  fn add_dependency(&mut self, from: ComponentId, to: ComponentId) {
+    self.dependencies.entry(from).or_default().insert(to);
+  }
+
+  /// The dependencies of the component, skipping those that aren't
+  /// components of the SBOM.
+  fn dependencies_of<'a>(
+    &'a self,
+    id: &ComponentId,
+  ) -> impl Iterator<Item = &'a ComponentId> {
+    self
+      .dependencies
+      .get(id)
+      .into_iter()
+      .flatten()
+      .filter(|id| self.components.contains_key(id))
+  }
+
+  /// The components that no other component depends on, which are the
+  /// direct dependencies of the workspace.
+  fn top_level(&self) -> Vec<&ComponentId> {
+    let dependencies = (self.components.keys())
+      .flat_map(|id| self.dependencies_of(id))
+      .collect::<HashSet<_>>();
+    self
+      .components
+      .keys()
+      .filter(|id| !dependencies.contains(id))
+      .collect()
+  }
+
+  fn to_cyclonedx(&self, meta: &SbomMeta) -> Value {
+    const ROOT_REF: &str = "root";
+
+    let components = self
+      .components
+      .iter()
+      .map(|(id, component)| {
+        let mut value = match id {
+          ComponentId::Jsr(nv) | ComponentId::Npm(nv) => {
+            let (group, name) = match nv.name.split_once('/') {
+              Some((scope, name)) if nv.name.starts_with('@') => {
+                (Some(scope), name)
+              }
+              _ => (None, nv.name.as_str()),
+            };
+            let mut value = json!({
+              "type": "library",
+              "bom-ref": id.bom_ref(),
+              "name": name,
+              "version": nv.version.to_string(),
+              "purl": id.purl(),
+            });
+            if let Some(group) = group {
+              value["group"] = json!(group);
+            }
+            value
+          }
+          ComponentId::Remote(specifier) => json!({
+            "type": "file",
+            "bom-ref": id.bom_ref(),
+            "name": specifier.to_string(),
+          }),
+        };
+        if !component.hashes.is_empty() {
+          value["hashes"] = component
+            .hashes
+            .iter()
+            .map(|hash| {
+              json!({
+                "alg": match hash.algorithm {
+                  HashAlgorithm::Sha1 => "SHA-1",
+                  HashAlgorithm::Sha256 => "SHA-256",
+                  HashAlgorithm::Sha512 => "SHA-512",
+                },
+                "content": hash.hex,
+              })
+            })
+            .collect();
+        }
+        if let Some(license) = &component.license {
+          value["licenses"] = json!([{ "expression": license }]);
+        }
+        if let Some(url) = &component.download_url {
+          value["externalReferences"] =
+            json!([{ "type": "distribution", "url": url }]);
+        }
+        value
+      })
+      .collect::<Vec<_>>();
+
+    let mut dependencies = vec![json!({
+      "ref": ROOT_REF,
+      "dependsOn": self.top_level().iter().map(|id| id.bom_ref()).collect::<Vec<_>>(),
+    })];
+    for id in self.components.keys() {
+      dependencies.push(json!({
+        "ref": id.bom_ref(),
+        "dependsOn": self
+          .dependencies_of(id)
+          .map(|id| id.bom_ref())
+          .collect::<Vec<_>>(),
+      }));
+    }
+
+    let mut root = json!({
+      "type": "application",
+      "bom-ref": ROOT_REF,
+      "name": self.name,
+    });
+    if let Some(version) = &self.version {
+      root["version"] = json!(version);
+    }
+    json!({
+      "bomFormat": "CycloneDX",
+      "specVersion": "1.5",
+      "serialNumber": format!("urn:uuid:{}", meta.serial_number),
+      "version": 1,
+      "metadata": {
+        "timestamp": meta.timestamp,
+        "tools": {
+          "components": [{
+            "type": "application",
+            "name": "deno",
+            "version": DENO_VERSION_INFO.deno,
+          }],
+        },
+        "component": root,
+      },
+      "components": components,
+      "dependencies": dependencies,
+    })
+  }
+
+  fn to_spdx(&self, meta: &SbomMeta) -> Value {
+    const ROOT_ID: &str = "SPDXRef-Root";
+
+    let spdx_ids = self
+      .components
+      .keys()
+      .enumerate()
+      .map(|(i, id)| (id, format!("SPDXRef-Package-{}", i + 1)))
+      .collect::<BTreeMap<_, _>>();
+
+    let mut root = json!({
+      "name": self.name,
+      "SPDXID": ROOT_ID,
+      "downloadLocation": "NOASSERTION",
+      "filesAnalyzed": false,
+      "licenseConcluded": "NOASSERTION",
+      "licenseDeclared": "NOASSERTION",
+    });
+    if let Some(version) = &self.version {
+      root["versionInfo"] = json!(version);
+    }
+    let mut packages = vec![root];
+    for (id, component) in &self.components {
+      let mut value = json!({
+        "name": id.name(),
+        "SPDXID": spdx_ids[id],
+        "downloadLocation": component.download_url.as_deref().unwrap_or("NOASSERTION"),
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": component.license.as_deref().unwrap_or("NOASSERTION"),
+      });
+      if let Some(version) = id.version() {
+        value["versionInfo"] = json!(version);
+      }
+      if !component.hashes.is_empty() {
+        value["checksums"] = component
+          .hashes
+          .iter()
+          .map(|hash| {
+            json!({
+              "algorithm": match hash.algorithm {
+                HashAlgorithm::Sha1 => "SHA1",
+                HashAlgorithm::Sha256 => "SHA256",
+                HashAlgorithm::Sha512 => "SHA512",
+              },
+              "checksumValue": hash.hex,
+            })
+          })
+          .collect();
+      }
+      if let Some(purl) = id.purl() {
+        value["externalRefs"] = json!([{
+          "referenceCategory": "PACKAGE-MANAGER",
+          "referenceType": "purl",
+          "referenceLocator": purl,
+        }]);
+      }
+      packages.push(value);
+    }
+
+    let mut relationships = vec![json!({
+      "spdxElementId": "SPDXRef-DOCUMENT",
+      "relationshipType": "DESCRIBES",
+      "relatedSpdxElement": ROOT_ID,
+    })];
+    for id in self.top_level() {
+      relationships.push(json!({
+        "spdxElementId": ROOT_ID,
+        "relationshipType": "DEPENDS_ON",
+        "relatedSpdxElement": spdx_ids[id],
+      }));
+    }
+    for (from, from_spdx_id) in &spdx_ids {
+      for to in self.dependencies_of(from) {
+        relationships.push(json!({
+          "spdxElementId": from_spdx_id,
+          "relationshipType": "DEPENDS_ON",
+          "relatedSpdxElement": spdx_ids[to],
+        }));
+      }
+    }
+
+    json!({
+      "spdxVersion": "SPDX-2.3",
+      "dataLicense": "CC0-1.0",
+      "SPDXID": "SPDXRef-DOCUMENT",
+      "name": self.name,
+      "documentNamespace": format!("https://spdx.org/spdxdocs/{}-{}", self.name.replace(['@', '/'], ""), meta.serial_number),
+      "creationInfo": {
+        "created": meta.timestamp,
+        "creators": [format!("Tool: deno-{}", DENO_VERSION_INFO.deno)],
+      },
+      "packages": packages,
+      "relationships": relationships,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn test_sbom() -> (Sbom, SbomMeta) {
+    let mut sbom = Sbom {
+      name: "app".to_string(),
+      version: Some("1.0.0".to_string()),
+      components: Default::default(),
+      dependencies: Default::default(),
+    };
+    let jsr = ComponentId::Jsr(PackageNv::from_str("@std/path@1.0.8").unwrap());
+    let npm = ComponentId::Npm(PackageNv::from_str("chalk@5.3.0").unwrap());
+    sbom.add_component(
+      jsr.clone(),
+      Component {
+        download_url: Some("https://jsr.io/@std/path/1.0.8/".to_string()),
+        hashes: vec![Hash {
+          algorithm: HashAlgorithm::Sha256,
+          hex: "abcd".to_string(),
+        }],
+        license: Some("MIT".to_string()),
+      },
+    );
+    sbom.add_component(npm.clone(), Component::default());
+    sbom.add_dependency(jsr, npm);
+    let meta = SbomMeta {
+      timestamp: "2025-01-01T00:00:00Z".to_string(),
+      serial_number: uuid::Uuid::nil(),
+    };
+    (sbom, meta)
+  }
+
+  #[test]
+  fn cyclonedx() {
+    let (sbom, meta) = test_sbom();
+    let value = sbom.to_cyclonedx(&meta);
+    assert_eq!(
+      value["serialNumber"],
+      "urn:uuid:00000000-0000-0000-0000-000000000000"
+    );
+    assert_eq!(
+      value["components"],
+      json!([{
+        "type": "library",
+        "bom-ref": "pkg:jsr/%40std/path@1.0.8",
+        "group": "@std",
+        "name": "path",
+        "version": "1.0.8",
+        "purl": "pkg:jsr/%40std/path@1.0.8",
+        "hashes": [{ "alg": "SHA-256", "content": "abcd" }],
+        "licenses": [{ "expression": "MIT" }],
+        "externalReferences": [{
+          "type": "distribution",
+          "url": "https://jsr.io/@std/path/1.0.8/",
+        }],
+      }, {
+        "type": "library",
+        "bom-ref": "pkg:npm/chalk@5.3.0",
+        "name": "chalk",
+        "version": "5.3.0",
+        "purl": "pkg:npm/chalk@5.3.0",
+      }])
+    );
+    assert_eq!(
+      value["dependencies"],
+      json!([
+        { "ref": "root", "dependsOn": ["pkg:jsr/%40std/path@1.0.8"] },
+        {
+          "ref": "pkg:jsr/%40std/path@1.0.8",
+          "dependsOn": ["pkg:npm/chalk@5.3.0"],
+        },
+        { "ref": "pkg:npm/chalk@5.3.0", "dependsOn": [] },
+      ])
+    );
+  }
+
+  #[test]
+  fn spdx() {
+    let (sbom, meta) = test_sbom();
+    let value = sbom.to_spdx(&meta);
+    assert_eq!(
+      value["documentNamespace"],
+      "https://spdx.org/spdxdocs/app-00000000-0000-0000-0000-000000000000"
+    );
+    assert_eq!(value["packages"][1]["licenseDeclared"], "MIT");
+    assert_eq!(
+      value["packages"][1]["checksums"],
+      json!([{ "algorithm": "SHA256", "checksumValue": "abcd" }])
+    );
+    assert_eq!(value["packages"][2]["licenseDeclared"], "NOASSERTION");
+    assert_eq!(
+      value["relationships"],
+      json!([{
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": "SPDXRef-Root",
+      }, {
+        "spdxElementId": "SPDXRef-Root",
+        "relationshipType": "DEPENDS_ON",
+        "relatedSpdxElement": "SPDXRef-Package-1",
+      }, {
+        "spdxElementId": "SPDXRef-Package-1",
+        "relationshipType": "DEPENDS_ON",
+        "relatedSpdxElement": "SPDXRef-Package-2",
+      }])
+    );
+  }
+
+  #[test]
+  fn skips_dependencies_on_missing_components() {
+    let (mut sbom, meta) = test_sbom();
+    let jsr = ComponentId::Jsr(PackageNv::from_str("@std/path@1.0.8").unwrap());
+    let missing =
+      ComponentId::Npm(PackageNv::from_str("missing@1.0.0").unwrap());
+    sbom.add_dependency(jsr.clone(), missing.clone());
+    sbom.add_dependency(missing, jsr);
+    let value = sbom.to_spdx(&meta);
+    assert_eq!(value["relationships"].as_array().unwrap().len(), 3);
+    let value = sbom.to_cyclonedx(&meta);
+    assert_eq!(
+      value["dependencies"][0],
+      json!({ "ref": "root", "dependsOn": ["pkg:jsr/%40std/path@1.0.8"] })
+    );
+    assert_eq!(
+      value["dependencies"][1]["dependsOn"],
+      json!(["pkg:npm/chalk@5.3.0"])
+    );
+  }
+
+  #[test]
+  fn license() {
+    assert_eq!(
+      license_from_json(&json!({ "license": "MIT" })),
+      Some("MIT".to_string())
+    );
+    assert_eq!(
+      license_from_json(&json!({ "license": { "type": "ISC" } })),
+      Some("ISC".to_string())
+    );
+    assert_eq!(license_from_json(&json!({ "name": "a" })), None);
+  }
+}
@@ -0,0 +1,670 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::collections::hash_map::Entry;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::Context;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_graph::GraphKind;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_npm::NpmPackageId;
+use deno_npm::resolution::NpmResolutionSnapshot;
+use deno_npm_installer::graph::NpmCachingStrategy;
+use deno_path_util::resolve_url_or_path;
+use deno_semver::jsr::JsrDepPackageReq;
+use deno_semver::jsr::JsrPackageReqReference;
+use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::package::PackageKind;
+use deno_semver::package::PackageNv;
+use deno_semver::package::PackageReq;
+use deno_terminal::colors;
+use serde::Serialize;
+
+use super::deps::DepKind;
+use super::deps::DepManager;
+use super::deps::dep_manager_args;
+use crate::args::Flags;
+use crate::args::WhyFlags;
+use crate::factory::CliFactory;
+use crate::graph_container::ModuleGraphContainer;
+use crate::jsr::JsrFetchResolver;
+use crate::npm::NpmFetchResolver;
+use crate::util::display;
+
+const JSON_SCHEMA_VERSION: u8 = 1;
+
+/// The number of chains to show for a package at most, because the number
+/// of chains can grow exponentially with the size of the graph.
+const MAX_CHAINS: usize = 1000;
+/// The number of partial chains to search for a package at most.
+const MAX_PATHS: usize = MAX_CHAINS * 100;
+
+pub async fn why(
+  flags: Arc<Flags>,
+  why_flags: WhyFlags,
+) -> Result<(), AnyError> {
+  let query = PackageQuery::parse(&why_flags.package)?;
+  let factory = CliFactory::from_flags(flags);
+  let cli_options = factory.cli_options()?;
+  if why_flags.entrypoints.is_empty()
+    && !cli_options.start_dir.has_deno_json()
+    && !cli_options.start_dir.has_pkg_json()
+  {
+    bail!(
+      "No deno.json or package.json in \"{}\". Provide the entrypoints to search instead.",
+      cli_options.initial_cwd().display(),
+    );
+  }
+  let npm_resolver = factory.npm_resolver().await?;
+  let Some(managed_npm_resolver) = npm_resolver.as_managed() else {
+    bail!(
+      "deno why is not supported when the node_modules directory is managed by another package manager."
+    );
+  };
+
+  let file_fetcher = factory.file_fetcher()?;
+  let npm_fetch_resolver = Arc::new(NpmFetchResolver::new(
+    file_fetcher.clone(),
+    factory.npmrc()?.clone(),
+    factory.minimum_release_age()?.cloned(),
+  ));
+  let jsr_fetch_resolver = Arc::new(JsrFetchResolver::new(
+    file_fetcher.clone(),
+    factory.minimum_release_age()?.cloned(),
+  ));
+  let args =
+    dep_manager_args(&factory, npm_fetch_resolver, jsr_fetch_resolver).await?;
+  let mut deps = DepManager::from_workspace(
+    cli_options.workspace(),
+    |_: Option<&str>, _: &PackageReq, _: DepKind| true,
+    args,
+  )?;
+  deps.resolve_current_versions().await?;
+
+  let entrypoints = why_flags
+    .entrypoints
+    .iter()
+    .map(|entrypoint| {
+      resolve_url_or_path(entrypoint, cli_options.initial_cwd())
+        .with_context(|| format!("Failed resolving '{}'", entrypoint))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  let entrypoints_graph = if entrypoints.is_empty() {
+    None
+  } else {
+    let graph = factory
+      .module_graph_creator()
+      .await?
+      .create_graph(
+        GraphKind::All,
+        entrypoints.clone(),
+        NpmCachingStrategy::Manual,
+      )
+      .await?;
+    Some(graph)
+  };
+  let workspace_graph = factory.main_module_graph_container().await?.graph();
+  // take the snapshot after building the graphs so that it has everything
+  let snapshot = managed_npm_resolver.resolution().snapshot();
+
+  let mut graph = PackageGraph::default();
+  graph.add_npm_packages(&snapshot);
+  graph.add_jsr_packages(&workspace_graph, &snapshot);
+  for (dep, nv) in deps.deps_with_resolved_versions() {
+    let Some(nv) = nv else {
+      continue;
+    };
+    let package = match dep.kind {
+      DepKind::Jsr => Some(Package::Jsr(nv.clone())),
+      DepKind::Npm => snapshot
+        .resolve_package_from_deno_module(nv)
+        .ok()
+        .map(|package| Package::Npm(package.id.clone())),
+    };
+    if let Some(package) = package {
+      let root = Root::Config {
+        path: dep.location.file_path().into_owned(),
+        key: (dep.location.key_path().parts.iter())
+          .map(|part| part.as_str())
+          .collect::<Vec<_>>()
+          .join("."),
+        specifier: format!("{}:{}", dep.kind.scheme(), dep.req),
+      };
+      graph.add_root(root, package);
+    }
+  }
+  if let Some(entrypoints_graph) = &entrypoints_graph {
+    graph.add_jsr_packages(entrypoints_graph, &snapshot);
+    for entrypoint in &entrypoints {
+      graph.add_entrypoint(entrypoints_graph, &snapshot, entrypoint);
+    }
+  }
+
+  let packages = graph
+    .packages
+    .iter()
+    .filter(|package| query.matches(package))
+    .map(|package| (package, graph.chains(package)))
+    .collect::<Vec<_>>();
+  if packages.is_empty() {
+    bail!(
+      "{} was not found in the dependency graph.",
+      why_flags.package
+    );
+  }
+
+  if why_flags.json {
+    let output = JsonOutput {
+      version: JSON_SCHEMA_VERSION,
+      packages: packages
+        .iter()
+        .map(|(package, chains)| JsonPackage {
+          package: package.to_string(),
+          chains: chains
+            .iter()
+            .map(|chain| JsonChain {
+              root: chain.root,
+              packages: chain.packages.iter().map(|p| p.to_string()).collect(),
+            })
+            .collect(),
+        })
+        .collect(),
+    };
+    display::write_json_to_stdout(&output)?;
+  } else {
+    let cwd = cli_options.initial_cwd();
+    for (i, (package, chains)) in packages.iter().enumerate() {
+      if i > 0 {
+        log::info!("");
+      }
+      log::info!("{}", colors::bold(package.to_string()));
+      if chains.is_empty() {
+        log::info!("  {}", colors::gray("not depended on by the workspace"));
+      }
+      for chain in chains {
+        let root = match chain.root {
+          Root::Config { path, key, .. } => format!(
+            "{} {}",
+            path.strip_prefix(cwd).unwrap_or(path).display(),
+            colors::gray(format!("({})", key))
+          ),
+          Root::Entrypoint { specifier } => {
+            match ModuleSpecifier::parse(specifier)
+              .ok()
+              .and_then(|s| deno_path_util::url_to_file_path(&s).ok())
+            {
+              Some(path) => path
+                .strip_prefix(cwd)
+                .unwrap_or(&path)
+                .display()
+                .to_string(),
+              None => specifier.clone(),
+            }
+          }
+        };
+        let packages = chain
+          .packages
+          .iter()
+          .map(|p| {
+            if p == package {
+              colors::green(p.to_string()).to_string()
+            } else {
+              p.to_string()
+            }
+          })
+          .collect::<Vec<_>>();
+        log::info!(
+          "  {} {} {}",
+          root,
+          colors::gray(">"),
+          packages.join(&colors::gray(" > ").to_string())
+        );
+      }
+      if chains.len() >= MAX_CHAINS {
+        log::info!(
+          "  {}",
+          colors::gray(format!(
+            "only the first {} chains are shown",
+            MAX_CHAINS
+          ))
+        );
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// The package to explain, such as `npm:debug@^4` or `@std/path`.
+#[derive(Debug, PartialEq, Eq)]
+struct PackageQuery {
+  kind: Option<DepKind>,
+  req: PackageReq,
+}
+
+impl PackageQuery {
+  fn parse(text: &str) -> Result<Self, AnyError> {
+    let (kind, req) = if let Some(req) = text.strip_prefix("npm:") {
+      (Some(DepKind::Npm), req)
+    } else if let Some(req) = text.strip_prefix("jsr:") {
+      (Some(DepKind::Jsr), req)
+    } else {
+      (None, text)
+    };
+    let req = PackageReq::from_str(req)
+      .with_context(|| format!("Invalid package '{}'", text))?;
+    Ok(Self { kind, req })
+  }
+
+  fn matches(&self, package: &Package) -> bool {
+    if self.kind.is_some_and(|kind| kind != package.kind()) {
+      return false;
+    }
+    let nv = package.nv();
+    nv.name == self.req.name
+      && (self.req.version_req == *deno_semver::WILDCARD_VERSION_REQ
+        || self.req.version_req.matches(&nv.version))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Package {
+  Jsr(PackageNv),
+  Npm(NpmPackageId),
+}
+
+impl Package {
+  fn kind(&self) -> DepKind {
+    match self {
+      Package::Jsr(_) => DepKind::Jsr,
+      Package::Npm(_) => DepKind::Npm,
+    }
+  }
+
+  fn nv(&self) -> &PackageNv {
+    match self {
+      Package::Jsr(nv) => nv,
+      Package::Npm(id) => &id.nv,
+    }
+  }
+}
+
+impl std::fmt::Display for Package {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}:{}", self.kind().scheme(), self.nv())
+  }
+}
+
+/// Where a chain of dependencies starts.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum Root {
+  /// A dependency in a deno.json or package.json.
+  Config {
+    path: PathBuf,
+    key: String,
+    specifier: String,
+  },
+  /// A dependency of an entrypoint or the local and remote modules it
+  /// imports.
+  Entrypoint { specifier: String },
+}
+
+struct Chain<'a> {
+  root: &'a Root,
+  /// The packages from the root to the explained package.
+  packages: Vec<&'a Package>,
+}
+
+/// The dependencies between the jsr and npm packages.
+#[derive(Default)]
+struct PackageGraph {
+  packages: BTreeSet<Package>,
+  roots: HashMap<Package, BTreeSet<Root>>,
+  dependents: HashMap<Package, BTreeSet<Package>>,
+}
+
+impl PackageGraph {
+  fn add_root(&mut self, root: Root, package: Package) {
+    self.packages.insert(package.clone());
+    self.roots.entry(package).or_default().insert(root);
+  }
+
+  fn add_dep(&mut self, from: Package, to: Package) {
+    self.packages.insert(from.clone());
+    self.packages.insert(to.clone());
+    self.dependents.entry(to).or_default().insert(from);
+  }
+
+  fn add_npm_packages(&mut self, snapshot: &NpmResolutionSnapshot) {
+    for package in snapshot.all_packages_for_every_system() {
+      self.packages.insert(Package::Npm(package.id.clone()));
+      for dep_id in package.dependencies.values() {
+        self.add_dep(
+          Package::Npm(package.id.clone()),
+          Package::Npm(dep_id.clone()),
+        );
+      }
+    }
+  }
+
+  fn add_jsr_packages(
+    &mut self,
+    graph: &ModuleGraph,
+    snapshot: &NpmResolutionSnapshot,
+  ) {
+    for (nv, deps) in graph.packages.packages_with_deps() {
+      self.packages.insert(Package::Jsr(nv.clone()));
+      for dep in deps {
+        if let Some(package) = resolve_dep_req(graph, snapshot, dep) {
+          self.add_dep(Package::Jsr(nv.clone()), package);
+        }
+      }
+    }
+  }
+
+  /// Adds the packages imported by the entrypoint and the modules it
+  /// imports that aren't in a package.
+  fn add_entrypoint(
+    &mut self,
+    graph: &ModuleGraph,
+    snapshot: &NpmResolutionSnapshot,
+    entrypoint: &ModuleSpecifier,
+  ) {
+    let root = Root::Entrypoint {
+      specifier: entrypoint.to_string(),
+    };
+    let mut pending = VecDeque::from([entrypoint]);
+    let mut seen = HashSet::from([entrypoint]);
+    while let Some(specifier) = pending.pop_front() {
+      if let Some(dep) = dep_req_from_specifier(specifier) {
+        if let Some(package) = resolve_dep_req(graph, snapshot, &dep) {
+          self.add_root(root.clone(), package);
+        }
+        continue;
+      }
+      let Some(Module::Js(module)) = graph.get(specifier) else {
+        continue;
+      };
+      for dep in module.dependencies.values() {
+        for specifier in [dep.get_code(), dep.get_type()].into_iter().flatten()
+        {
+          if seen.insert(specifier) {
+            pending.push_back(specifier);
+          }
+        }
+      }
+    }
+  }
+
+  /// Finds the chains of dependencies from the roots to the package,
+  /// keeping the shortest ones when there are too many.
+  fn chains<'a>(&'a self, package: &'a Package) -> Vec<Chain<'a>> {
+    let root_distances = self.root_distances();
+    let Some(distance) = root_distances.get(package) else {
+      return Vec::new();
+    };
+    // Each path is stored as its last package, the index of the path that it
+    // extends and its length. Paths are searched in order of the length of
+    // the shortest chain they could be part of, so shorter chains are found
+    // first, and only through packages that lead to a root.
+    let mut paths = vec![(package, None, 0)];
+    let mut pending = BinaryHeap::from([Reverse((*distance, 0))]);
+    let mut chains = Vec::new();
+    while let Some(Reverse((_, index))) = pending.pop() {
+      if chains.len() >= MAX_CHAINS || paths.len() >= MAX_PATHS {
+        break;
+      }
+      let (current, _, len) = paths[index];
+      let path_packages = || {
+        std::iter::successors(Some(index), |index| paths[*index].1)
+          .map(|index| paths[index].0)
+      };
+      for root in self.roots.get(current).into_iter().flatten() {
+        chains.push(Chain {
+          root,
+          packages: path_packages().collect(),
+        });
+      }
+      let dependents = self
+        .dependents
+        .get(current)
+        .into_iter()
+        .flatten()
+        .filter_map(|dependent| {
+          let distance = root_distances.get(dependent)?;
+          // skip circular dependencies
+          if path_packages().any(|package| package == dependent) {
+            return None;
+          }
+          Some((dependent, len + 1 + distance))
+        })
+        .collect::<Vec<_>>();
+      for (dependent, chain_len) in dependents {
+        pending.push(Reverse((chain_len, paths.len())));
+        paths.push((dependent, Some(index), len + 1));
+      }
+    }
+    chains.sort_by_key(|chain| chain.packages.len());
+    chains.truncate(MAX_CHAINS);
+    chains.sort_by(|a, b| {
+      a.root.cmp(b.root).then_with(|| a.packages.cmp(&b.packages))
+    });
+    chains
+  }
+
+  /// Gets the fewest dependents between each package and a root, for the
+  /// packages that are depended on by a root.
+  fn root_distances(&self) -> HashMap<&Package, usize> {
+    let mut dependencies = HashMap::<&Package, Vec<&Package>>::new();
+    for (package, dependents) in &self.dependents {
+      for dependent in dependents {
+        dependencies.entry(dependent).or_default().push(package);
+      }
+    }
+    let mut distances = self
+      .roots
+      .keys()
+      .map(|package| (package, 0))
+      .collect::<HashMap<_, _>>();
+    let mut pending = self.roots.keys().collect::<VecDeque<_>>();
+    while let Some(package) = pending.pop_front() {
+      let distance = distances[package] + 1;
+      for dependency in dependencies.get(package).into_iter().flatten() {
+        if let Entry::Vacant(entry) = distances.entry(*dependency) {
+          entry.insert(distance);
+          pending.push_back(*dependency);
+        }
+      }
+    }
+    distances
+  }
+}
+
+fn dep_req_from_specifier(
+  specifier: &ModuleSpecifier,
+) -> Option<JsrDepPackageReq> {
+  if let Ok(req_ref) = JsrPackageReqReference::from_specifier(specifier) {
+    Some(JsrDepPackageReq::jsr(req_ref.into_inner().req))
+  } else if let Ok(req_ref) = NpmPackageReqReference::from_specifier(specifier)
+  {
+    Some(JsrDepPackageReq::npm(req_ref.into_inner().req))
+  } else {
+    None
+  }
+}
+
+fn resolve_dep_req(
+  graph: &ModuleGraph,
+  snapshot: &NpmResolutionSnapshot,
+  dep: &JsrDepPackageReq,
+) -> Option<Package> {
+  match dep.kind {
+    PackageKind::Jsr => {
+      let nv = graph.packages.mappings().get(&dep.req)?;
+      Some(Package::Jsr(nv.clone()))
+    }
+    PackageKind::Npm => {
+      let package = snapshot.resolve_pkg_from_pkg_req(&dep.req).ok()?;
+      Some(Package::Npm(package.id.clone()))
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+  version: u8,
+  packages: Vec<JsonPackage<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonPackage<'a> {
+  package: String,
+  chains: Vec<JsonChain<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonChain<'a> {
+  root: &'a Root,
+  packages: Vec<String>,
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn jsr(nv: &str) -> Package {
+    Package::Jsr(PackageNv::from_str(nv).unwrap())
+  }
+
+  fn npm(id: &str) -> Package {
+    Package::Npm(NpmPackageId::from_serialized(id).unwrap())
+  }
+
+  fn config_root(key: &str) -> Root {
+    Root::Config {
+      path: PathBuf::from("/project/deno.json"),
+      key: key.to_string(),
+      specifier: String::new(),
+    }
+  }
+
+  #[test]
+  fn parses_and_matches_query() {
+    let query = PackageQuery::parse("npm:debug@^2").unwrap();
+    assert!(query.matches(&npm("debug@2.6.9")));
+    assert!(!query.matches(&npm("debug@4.3.4")));
+    assert!(!query.matches(&jsr("debug@2.6.9")));
+
+    let query = PackageQuery::parse("@std/path").unwrap();
+    assert!(query.matches(&jsr("@std/path@1.0.8")));
+    assert!(query.matches(&npm("@std/path@0.1.0-beta")));
+    assert!(!query.matches(&jsr("@std/fs@1.0.8")));
+
+    assert!(PackageQuery::parse("npm:").is_err());
+  }
+
+  #[test]
+  fn finds_chains() {
+    let mut graph = PackageGraph::default();
+    graph.add_root(config_root("imports.express"), npm("express@4.21.2"));
+    graph.add_root(config_root("imports.@std/http"), jsr("@std/http@1.0.0"));
+    graph.add_dep(npm("express@4.21.2"), npm("debug@2.6.9"));
+    graph.add_dep(npm("express@4.21.2"), npm("send@0.19.0"));
+    graph.add_dep(npm("send@0.19.0"), npm("debug@2.6.9"));
+    graph.add_dep(jsr("@std/http@1.0.0"), npm("debug@2.6.9"));
+    // circular
+    graph.add_dep(npm("debug@2.6.9"), npm("send@0.19.0"));
+
+    let debug = npm("debug@2.6.9");
+    let chains = graph
+      .chains(&debug)
+      .into_iter()
+      .map(|chain| {
+        (
+          chain.root.clone(),
+          chain
+            .packages
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>(),
+        )
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      chains,
+      vec![
+        (
+          config_root("imports.@std/http"),
+          vec![
+            "jsr:@std/http@1.0.0".to_string(),
+            "npm:debug@2.6.9".to_string()
+          ],
+        ),
+        (
+          config_root("imports.express"),
+          vec![
+            "npm:express@4.21.2".to_string(),
+            "npm:debug@2.6.9".to_string()
+          ],
+        ),
+        (
+          config_root("imports.express"),
+          vec![
+            "npm:express@4.21.2".to_string(),
+            "npm:send@0.19.0".to_string(),
+            "npm:debug@2.6.9".to_string()
+          ],
+        ),
+      ]
+    );
+
+    // not depended on by anything
+    let unused = npm("unused@1.0.0");
+    graph.packages.insert(unused.clone());
+    assert!(graph.chains(&unused).is_empty());
+  }
+
+  #[test]
+  fn keeps_shortest_chains() {
+    let mut graph = PackageGraph::default();
+    let target = npm("target@1.0.0");
+    // two packages per layer that both depend on the layer below, which
+    // results in more long chains than are kept
+    let layer =
+      |i: usize| [npm(&format!("a{i}-x@1.0.0")), npm(&format!("a{i}-y@1.0.0"))];
+    for package in layer(0) {
+      graph.add_dep(package, target.clone());
+    }
+    for i in 1..11 {
+      for from in layer(i) {
+        for to in layer(i - 1) {
+          graph.add_dep(from.clone(), to);
+        }
+      }
+    }
+    for package in layer(10) {
+      graph.add_root(config_root("imports.a"), package);
+    }
+    graph.add_root(config_root("imports.z"), npm("z@1.0.0"));
+    graph.add_dep(npm("z@1.0.0"), target.clone());
+
+    let chains = graph.chains(&target);
+    assert_eq!(chains.len(), MAX_CHAINS);
+    assert!(
+      chains
+        .iter()
+        .any(|chain| *chain.root == config_root("imports.z"))
+    );
+  }
+}
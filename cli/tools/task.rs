@@ -214,6 +214,7 @@ pub async fn execute_script(
             command: Some(task_flags.task.as_ref().unwrap().to_string()),
             dependencies: vec![],
             description: None,
+            permissions: None,
           },
           kill_signal,
           cli_options.argv(),
@@ -457,10 +458,18 @@ impl<'a> TaskRunner<'a> {
       }
     };
 
-    let custom_commands = task_runner::resolve_custom_commands(
+    let mut custom_commands = task_runner::resolve_custom_commands(
       self.npm_resolver,
       self.node_resolver,
     )?;
+    if let Some(permission_set) = &definition.permissions {
+      custom_commands.insert(
+        "deno".to_string(),
+        Rc::new(task_runner::PermissionSetDenoCommand(
+          permission_set.clone(),
+        )),
+      );
+    }
 
     self
       .run_single(RunSingleOptions {
@@ -853,6 +862,7 @@ fn print_available_tasks(
             command: Some(script.to_string()),
             dependencies: vec![],
             description: None,
+            permissions: None,
           },
         });
       }
@@ -0,0 +1,600 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::Context;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::url::Url;
+use deno_lib::standalone::binary::DataSection;
+use deno_lib::standalone::binary::Metadata;
+use deno_lib::standalone::binary::NodeModules;
+use deno_lib::standalone::virtual_fs::OffsetWithLength;
+use deno_lib::standalone::virtual_fs::VfsEntry;
+use deno_lib::standalone::virtual_fs::VirtualDirectoryEntries;
+use deno_lib::standalone::virtual_fs::VirtualFile;
+use deno_lib::util::checksum;
+use deno_runtime::deno_permissions::PermissionsOptions;
+use deno_terminal::colors;
+
+use crate::args::UnpackFlags;
+use crate::util::display;
+
+const JSON_SCHEMA_VERSION: u8 = 1;
+
+pub fn unpack(unpack_flags: UnpackFlags) -> Result<(), AnyError> {
+  let binary_path = PathBuf::from(&unpack_flags.binary);
+  let bytes = std::fs::read(&binary_path)
+    .with_context(|| format!("Failed reading '{}'", binary_path.display()))?;
+  let data = DataSection::find_in_executable(&bytes).with_context(|| {
+    format!(
+      "'{}' is not an executable produced by deno compile",
+      binary_path.display()
+    )
+  })?;
+  let contents = Contents::new(&data);
+
+  if let Some(output) = &unpack_flags.output {
+    let output = PathBuf::from(output);
+    extract(&data, &contents, &output)?;
+    std::fs::write(
+      output.join("metadata.json"),
+      serde_json::to_vec_pretty(&contents.to_json(&data.metadata))?,
+    )?;
+    log::info!(
+      "{} {} files and {} remote modules to {}",
+      colors::green("Extracted"),
+      contents.files.len(),
+      contents.remote_modules.len(),
+      output.display()
+    );
+  }
+
+  if unpack_flags.json {
+    display::write_json_to_stdout(&contents.to_json(&data.metadata))
+  } else if unpack_flags.output.is_none() {
+    display::write_to_stdout_ignore_sigpipe(
+      contents.display(&data.metadata).as_bytes(),
+    )
+    .map_err(AnyError::from)
+  } else {
+    Ok(())
+  }
+}
+
+struct Contents<'a> {
+  files: Vec<FileEntry<'a>>,
+  remote_modules: Vec<RemoteModule<'a>>,
+  redirects: Vec<(&'a str, &'a str)>,
+}
+
+struct FileEntry<'a> {
+  /// Path relative to the root directory of the vfs, using forward slashes.
+  path: String,
+  kind: FileEntryKind<'a>,
+}
+
+enum FileEntryKind<'a> {
+  File(&'a VirtualFile),
+  Symlink(String),
+}
+
+struct RemoteModule<'a> {
+  specifier: &'a str,
+  media_type: String,
+  data: &'a [u8],
+  maybe_source_map: Option<&'a [u8]>,
+}
+
+impl<'a> Contents<'a> {
+  fn new(data: &'a DataSection<'a>) -> Self {
+    fn collect_files<'a>(
+      entries: &'a VirtualDirectoryEntries,
+      parent: &str,
+      files: &mut Vec<FileEntry<'a>>,
+    ) {
+      for entry in entries.iter() {
+        let path = if parent.is_empty() {
+          entry.name().to_string()
+        } else {
+          format!("{}/{}", parent, entry.name())
+        };
+        match entry {
+          VfsEntry::Dir(dir) => collect_files(&dir.entries, &path, files),
+          VfsEntry::File(file) => files.push(FileEntry {
+            path,
+            kind: FileEntryKind::File(file),
+          }),
+          VfsEntry::Symlink(symlink) => files.push(FileEntry {
+            path,
+            kind: FileEntryKind::Symlink(symlink.dest_parts.display()),
+          }),
+        }
+      }
+    }
+
+    let specifiers = data
+      .specifiers
+      .iter()
+      .map(|(specifier, id)| (*id, specifier.as_ref()))
+      .collect::<HashMap<_, _>>();
+    let mut files = Vec::new();
+    collect_files(&data.vfs_root_entries, "", &mut files);
+    let remote_modules = data
+      .remote_modules
+      .iter()
+      .filter_map(|(id, entry)| {
+        Some(RemoteModule {
+          specifier: *specifiers.get(&id)?,
+          media_type: entry.media_type.to_string(),
+          data: entry.data.as_ref(),
+          maybe_source_map: entry.maybe_source_map.as_deref(),
+        })
+      })
+      .collect();
+    let redirects = data
+      .redirects
+      .iter()
+      .filter_map(|(from, to)| {
+        Some((*specifiers.get(&from)?, *specifiers.get(to)?))
+      })
+      .collect();
+    Self {
+      files,
+      remote_modules,
+      redirects,
+    }
+  }
+
+  fn to_json(&self, metadata: &Metadata) -> serde_json::Value {
+    json!({
+      "version": JSON_SCHEMA_VERSION,
+      "metadata": metadata,
+      "files": self.files.iter().map(|entry| match &entry.kind {
+        FileEntryKind::File(file) => json!({
+          "path": entry.path,
+          "size": file.offset.len,
+          "compressedSize": file.offset.compressed_len,
+          "hasSourceMap": file.source_map_offset.is_some(),
+        }),
+        FileEntryKind::Symlink(target) => json!({
+          "path": entry.path,
+          "symlink": target,
+        }),
+      }).collect::<Vec<_>>(),
+      "remoteModules": self.remote_modules.iter().map(|module| json!({
+        "specifier": module.specifier,
+        "mediaType": module.media_type,
+        "size": module.data.len(),
+        "hasSourceMap": module.maybe_source_map.is_some(),
+      })).collect::<Vec<_>>(),
+      "redirects": self.redirects.iter().map(|(from, to)| json!({
+        "from": from,
+        "to": to,
+      })).collect::<Vec<_>>(),
+    })
+  }
+
+  fn display(&self, metadata: &Metadata) -> String {
+    let mut text = String::new();
+    let mut line = |name: &str, value: &str| {
+      text.push_str(&format!(
+        "{} {}\n",
+        colors::bold(format!("{name}:")),
+        value
+      ));
+    };
+    line("Entrypoint", &metadata.entrypoint_key);
+    if !metadata.argv.is_empty() {
+      line("Arguments", &metadata.argv.join(" "));
+    }
+    let permission_flags = permission_flags(&metadata.permissions);
+    line(
+      "Permissions",
+      &if permission_flags.is_empty() {
+        colors::gray("none").to_string()
+      } else {
+        permission_flags.join(" ")
+      },
+    );
+    let unstable = &metadata.unstable_config;
+    let unstable_flags = [
+      (unstable.legacy_flag_enabled, "unstable"),
+      (unstable.bare_node_builtins, "bare-node-builtins"),
+      (unstable.detect_cjs, "detect-cjs"),
+      (unstable.lazy_dynamic_imports, "lazy-dynamic-imports"),
+      (unstable.raw_imports, "raw-imports"),
+      (unstable.sloppy_imports, "sloppy-imports"),
+      (unstable.npm_lazy_caching, "npm-lazy-caching"),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, name)| name)
+    .chain(metadata.unstable_config.features.iter().map(|f| f.as_str()))
+    .map(|name| match name {
+      "unstable" => "--unstable".to_string(),
+      name => format!("--unstable-{}", name),
+    })
+    .collect::<Vec<_>>();
+    if !unstable_flags.is_empty() {
+      line("Unstable", &unstable_flags.join(" "));
+    }
+    if let Some(location) = &metadata.location {
+      line("Location", location.as_str());
+    }
+    if let Some(seed) = metadata.seed {
+      line("Seed", &seed.to_string());
+    }
+    if !metadata.v8_flags.is_empty() {
+      line("V8 flags", &metadata.v8_flags.join(" "));
+    }
+    if let Some(hosts) = &metadata.unsafely_ignore_certificate_errors {
+      line(
+        "Ignored certificate errors",
+        &if hosts.is_empty() {
+          "all hosts".to_string()
+        } else {
+          hosts.join(", ")
+        },
+      );
+    }
+    match &metadata.node_modules {
+      Some(NodeModules::Managed {
+        node_modules_dir: Some(dir),
+      }) => line("Node modules", &format!("managed ({})", dir)),
+      Some(NodeModules::Managed {
+        node_modules_dir: None,
+      }) => line("Node modules", "managed (global cache)"),
+      Some(NodeModules::Byonm { .. }) => line("Node modules", "byonm"),
+      None => {}
+    }
+
+    if !metadata.env_vars_from_env_file.is_empty() {
+      text.push_str(&format!(
+        "\n{}\n",
+        colors::bold("Environment variables (from env file)")
+      ));
+      for (key, value) in &metadata.env_vars_from_env_file {
+        text.push_str(&format!("  {}={}\n", key, value));
+      }
+    }
+
+    if !self.remote_modules.is_empty() {
+      text.push_str(&format!(
+        "\n{} ({})\n",
+        colors::bold("Remote modules"),
+        self.remote_modules.len()
+      ));
+      for module in &self.remote_modules {
+        text.push_str(&format!(
+          "  {} {}\n",
+          module.specifier,
+          colors::gray(format!(
+            "({}, {})",
+            module.media_type,
+            display::human_size(module.data.len() as f64)
+          ))
+        ));
+      }
+    }
+
+    if !self.redirects.is_empty() {
+      text.push_str(&format!("\n{}\n", colors::bold("Redirects")));
+      for (from, to) in &self.redirects {
+        text.push_str(&format!("  {} --> {}\n", from, to));
+      }
+    }
+
+    text.push_str(&format!(
+      "\n{} ({})\n",
+      colors::bold("Embedded files"),
+      self.files.len()
+    ));
+    for entry in &self.files {
+      match &entry.kind {
+        FileEntryKind::File(file) => {
+          text.push_str(&format!(
+            "  {} {}\n",
+            entry.path,
+            colors::gray(format!(
+              "({})",
+              display::human_size(file.offset.len as f64)
+            ))
+          ));
+        }
+        FileEntryKind::Symlink(target) => {
+          text.push_str(&format!("  {} --> {}\n", entry.path, target));
+        }
+      }
+    }
+    text
+  }
+}
+
+fn permission_flags(permissions: &PermissionsOptions) -> Vec<String> {
+  if permissions.allow_all {
+    return vec!["--allow-all".to_string()];
+  }
+  let lists = [
+    ("allow-read", &permissions.allow_read),
+    ("deny-read", &permissions.deny_read),
+    ("allow-write", &permissions.allow_write),
+    ("deny-write", &permissions.deny_write),
+    ("allow-net", &permissions.allow_net),
+    ("deny-net", &permissions.deny_net),
+    ("allow-env", &permissions.allow_env),
+    ("deny-env", &permissions.deny_env),
+    ("allow-run", &permissions.allow_run),
+    ("deny-run", &permissions.deny_run),
+    ("allow-sys", &permissions.allow_sys),
+    ("deny-sys", &permissions.deny_sys),
+    ("allow-ffi", &permissions.allow_ffi),
+    ("deny-ffi", &permissions.deny_ffi),
+    ("allow-import", &permissions.allow_import),
+    ("deny-import", &permissions.deny_import),
+  ];
+  lists
+    .into_iter()
+    .filter_map(|(name, list)| {
+      let list = list.as_ref()?;
+      Some(if list.is_empty() {
+        format!("--{}", name)
+      } else {
+        format!("--{}={}", name, list.join(","))
+      })
+    })
+    .collect()
+}
+
+/// Writes the embedded files to `<output>/files` and the remote modules
+/// to `<output>/remote`, along with any source maps.
+fn extract(
+  data: &DataSection,
+  contents: &Contents,
+  output: &Path,
+) -> Result<(), AnyError> {
+  let files_dir = output.join("files");
+  let mut symlinks = Vec::new();
+  for entry in &contents.files {
+    let path = join_untrusted_path(&files_dir, entry.path.split('/'))?;
+    match &entry.kind {
+      FileEntryKind::File(file) => {
+        write_file(&path, &read_data(data.vfs_files_data, file.offset)?)?;
+        if let Some(offset) = file.source_map_offset {
+          write_file(
+            &append_extension(&path, "map"),
+            &read_data(data.vfs_files_data, offset)?,
+          )?;
+        }
+      }
+      FileEntryKind::Symlink(target) => {
+        let target = join_untrusted_path(&files_dir, target.split('/'))?;
+        symlinks.push((path, target));
+      }
+    }
+  }
+  // create the symlinks last so that no files are written through them
+  for (path, target) in symlinks {
+    create_symlink(&path, &target)?;
+  }
+
+  let remote_dir = output.join("remote");
+  for module in &contents.remote_modules {
+    let path = remote_module_path(&remote_dir, module.specifier)?;
+    write_file(&path, module.data)?;
+    if let Some(source_map) = module.maybe_source_map {
+      write_file(&append_extension(&path, "map"), source_map)?;
+    }
+  }
+  Ok(())
+}
+
+fn read_data(
+  files_data: &[u8],
+  offset: OffsetWithLength,
+) -> Result<Cow<[u8]>, AnyError> {
+  let Some(data) = usize::try_from(offset.offset).ok().and_then(|start| {
+    let end = start.checked_add(usize::try_from(offset.stored_len()).ok()?)?;
+    files_data.get(start..end)
+  }) else {
+    bail!("Invalid file offset.");
+  };
+  match offset.compressed_len {
+    Some(_) => {
+      // don't trust the stored length for allocating and instead read up to
+      // one byte more than it in order to detect a mismatch
+      let decoder = zstd::stream::read::Decoder::with_buffer(data)
+        .context("Failed decompressing file data.")?;
+      let mut decompressed = Vec::new();
+      decoder
+        .take(offset.len.saturating_add(1))
+        .read_to_end(&mut decompressed)
+        .context("Failed decompressing file data.")?;
+      if decompressed.len() as u64 != offset.len {
+        bail!("Invalid file length.");
+      }
+      Ok(Cow::Owned(decompressed))
+    }
+    None => Ok(Cow::Borrowed(data)),
+  }
+}
+
+/// Maps a remote specifier to a path in the output directory, such as
+/// `https://deno.land/x/mod.ts` to `remote/https/deno.land/x/mod.ts`.
+fn remote_module_path(
+  remote_dir: &Path,
+  specifier: &str,
+) -> Result<PathBuf, AnyError> {
+  let url = Url::parse(specifier)
+    .with_context(|| format!("Invalid remote specifier '{}'", specifier))?;
+  let scheme_dir = remote_dir.join(url.scheme());
+  let Some(host) = url.host_str() else {
+    // ex. data urls
+    return Ok(scheme_dir.join(checksum::r#gen(&[specifier.as_bytes()])));
+  };
+  let host = match url.port() {
+    Some(port) => format!("{}_{}", host, port),
+    None => host.to_string(),
+  };
+  let mut segments = url
+    .path_segments()
+    .map(|segments| segments.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+    .unwrap_or_default();
+  if segments.is_empty() {
+    segments.push("index");
+  }
+  join_untrusted_path(
+    &scheme_dir,
+    std::iter::once(host.as_str()).chain(segments),
+  )
+}
+
+/// Joins path components read from the binary, erroring for any component
+/// that could escape the output directory.
+fn join_untrusted_path<'a>(
+  base: &Path,
+  components: impl IntoIterator<Item = &'a str>,
+) -> Result<PathBuf, AnyError> {
+  let mut path = base.to_path_buf();
+  for component in components {
+    if component.is_empty()
+      || component == "."
+      || component == ".."
+      || component.contains(['/', '\\'])
+    {
+      bail!("Invalid path component '{}' in binary.", component);
+    }
+    path.push(component);
+  }
+  Ok(path)
+}
+
+fn append_extension(path: &Path, ext: &str) -> PathBuf {
+  let mut path = path.as_os_str().to_owned();
+  path.push(".");
+  path.push(ext);
+  PathBuf::from(path)
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), AnyError> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  std::fs::write(path, data)
+    .with_context(|| format!("Failed writing '{}'", path.display()))
+}
+
+#[cfg(unix)]
+fn create_symlink(path: &Path, target: &Path) -> Result<(), AnyError> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  std::os::unix::fs::symlink(target, path)
+    .with_context(|| format!("Failed creating symlink '{}'", path.display()))
+}
+
+#[cfg(not(unix))]
+fn create_symlink(path: &Path, target: &Path) -> Result<(), AnyError> {
+  log::warn!(
+    "{} Skipped creating symlink '{}' to '{}'.",
+    colors::yellow("Warning"),
+    path.display(),
+    target.display()
+  );
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_remote_module_path() {
+    let dir = Path::new("/out");
+    assert_eq!(
+      remote_module_path(dir, "https://deno.land/x/mod.ts").unwrap(),
+      PathBuf::from("/out/https/deno.land/x/mod.ts")
+    );
+    assert_eq!(
+      remote_module_path(dir, "http://localhost:4545/").unwrap(),
+      PathBuf::from("/out/http/localhost_4545/index")
+    );
+    assert!(
+      remote_module_path(dir, "data:text/plain,hi")
+        .unwrap()
+        .starts_with("/out/data")
+    );
+    assert!(
+      remote_module_path(dir, "https://deno.land/x/%2e%2e%2fmod.ts").is_ok()
+    );
+  }
+
+  #[test]
+  fn test_join_untrusted_path() {
+    let dir = Path::new("/out");
+    assert_eq!(
+      join_untrusted_path(dir, ["a", "b.txt"]).unwrap(),
+      PathBuf::from("/out/a/b.txt")
+    );
+    assert!(join_untrusted_path(dir, ["a", ".."]).is_err());
+    assert!(join_untrusted_path(dir, ["a/../.."]).is_err());
+    assert!(join_untrusted_path(dir, ["a\\..\\.."]).is_err());
+    assert!(join_untrusted_path(dir, [""]).is_err());
+  }
+
+  #[test]
+  fn test_read_data() {
+    let compressed = zstd::bulk::compress(b"hello", 3).unwrap();
+    let files_data = [b"hi".as_slice(), &compressed].concat();
+    let read = |offset, len, compressed_len| {
+      read_data(
+        &files_data,
+        OffsetWithLength {
+          offset,
+          len,
+          compressed_len,
+        },
+      )
+      .map(|data| data.into_owned())
+    };
+    let compressed_len = compressed.len() as u64;
+    assert_eq!(read(0, 2, None).unwrap(), b"hi");
+    assert_eq!(read(2, 5, Some(compressed_len)).unwrap(), b"hello");
+    assert!(read(2, 1000, None).is_err());
+    assert!(read(u64::MAX, 2, None).is_err());
+    assert!(read(2, u64::MAX, None).is_err());
+    assert!(read(2, 4, Some(compressed_len)).is_err());
+    assert!(read(2, u64::MAX, Some(compressed_len)).is_err());
+  }
+
+  #[test]
+  fn test_permission_flags() {
+    let permissions = PermissionsOptions {
+      allow_read: Some(vec!["data.txt".to_string()]),
+      allow_net: Some(vec![]),
+      deny_env: Some(vec!["SECRET".to_string(), "TOKEN".to_string()]),
+      ..Default::default()
+    };
+    assert_eq!(
+      permission_flags(&permissions),
+      vec![
+        "--allow-read=data.txt",
+        "--allow-net",
+        "--deny-env=SECRET,TOKEN"
+      ]
+    );
+    assert_eq!(
+      permission_flags(&PermissionsOptions {
+        allow_all: true,
+        ..Default::default()
+      }),
+      vec!["--allow-all"]
+    );
+  }
+}
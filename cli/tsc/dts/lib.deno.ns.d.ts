@@ -6225,6 +6225,128 @@ declare namespace Deno {
     allowHost?: boolean;
     /** Sets the local address where the socket will connect from. */
     localAddress?: string;
+    /** Enables a private HTTP cache (RFC 9111) for requests made with this
+     * client. Stored responses are reused while they are fresh and
+     * revalidated with `ETag` or `Last-Modified` once they are stale,
+     * following the `cache` mode of each request.
+     *
+     * Requires read and write permission for the cache directory. */
+    cache?: HttpCacheOptions;
+    /** Enables a cookie jar for this client. Cookies set by responses are
+     * stored following RFC 6265 and sent with later requests that match
+     * their domain, path and `Secure` attribute. Pass `{}` to keep the
+     * cookies in memory only.
+     *
+     * Requires read and write permission for the cookie file, if any. */
+    cookieJar?: CookieJarOptions;
+    /** The timeout for establishing a connection, in milliseconds. */
+    connectTimeout?: number;
+    /** The timeout for a whole request, in milliseconds. It covers
+     * establishing the connection, all retries, and receiving the response
+     * body. Requests that time out are rejected with a
+     * {@linkcode Deno.errors.TimedOut} error. */
+    timeout?: number;
+    /** Automatically retries requests with idempotent methods (`GET`,
+     * `HEAD`, `OPTIONS`, `TRACE`, `PUT` and `DELETE`) that fail with a
+     * connection error or receive one of the configured status codes, with
+     * exponential backoff between attempts. Requests with a streaming body
+     * are never retried. */
+    retry?: RetryOptions;
+    /** Configures how the client resolves host names. By default the system
+     * resolver is used.
+     *
+     * Requires net permission for the configured name servers. */
+    dns?: DnsOptions;
+  }
+
+  /**
+   * The options for the HTTP cache of a {@linkcode Deno.HttpClient}.
+   *
+   * @category Fetch
+   */
+  export interface HttpCacheOptions {
+    /** The directory the cached responses are stored in. */
+    path: string;
+    /** The maximum size of a response body to store, in bytes.
+     *
+     * @default {16777216}
+     */
+    maxEntrySize?: number;
+  }
+
+  /**
+   * The options for the cookie jar of a {@linkcode Deno.HttpClient}.
+   *
+   * @category Fetch
+   */
+  export interface CookieJarOptions {
+    /** The file persistent cookies are loaded from and saved to. Session
+     * cookies, which have no `Expires` or `Max-Age` attribute, are never
+     * saved. */
+    path?: string;
+  }
+
+  /**
+   * The DNS configuration of a {@linkcode Deno.HttpClient}.
+   *
+   * ```ts
+   * // Send requests for `api.example.com` to a local mock server.
+   * const client = Deno.createHttpClient({
+   *   dns: { staticHosts: { "api.example.com": ["127.0.0.1"] } },
+   * });
+   * ```
+   *
+   * @category Fetch
+   */
+  export interface DnsOptions {
+    /** Name servers to query instead of the system ones, in the
+     * `IP[:PORT][#SERVERNAME]` form, for example `"1.1.1.1"`,
+     * `"[::1]:5353"` or `"1.1.1.1#cloudflare-dns.com"`. The server name is
+     * used to verify the certificate of DNS-over-HTTPS servers and defaults
+     * to the IP address. */
+    nameservers?: string[];
+    /** The protocol used to query `nameservers`.
+     *
+     * @default {"udp"}
+     */
+    protocol?: "udp" | "tcp" | "https";
+    /** The upper bound for how long answers are cached, in seconds. */
+    cacheTtl?: number;
+    /** Host names that resolve to fixed IP addresses without querying DNS.
+     *
+     * Requires `allow-net` permission for each of the addresses. */
+    staticHosts?: Record<string, string[]>;
+  }
+
+  /**
+   * The retry policy of a {@linkcode Deno.HttpClient}.
+   *
+   * @category Fetch
+   */
+  export interface RetryOptions {
+    /** The maximum number of retries after the initial attempt.
+     *
+     * @default {3}
+     */
+    maxRetries?: number;
+    /** The delay before the first retry in milliseconds. It is doubled for
+     * every following retry.
+     *
+     * @default {100}
+     */
+    initialBackoff?: number;
+    /** The upper bound for the delay between retries in milliseconds. A
+     * `Retry-After` response header is honored up to this limit.
+     *
+     * @default {10000}
+     */
+    maxBackoff?: number;
+    /** Response status codes that are retried, in addition to connection
+     * errors.
+     *
+     * @default {[]}
+     */
+    statusCodes?: number[];
   }
 
   /**
@@ -301,7 +301,14 @@ declare namespace Deno {
    * to wait before retrying the execution. For example, `[1000, 5000, 10000]`
    * means that a failed execution will be retried at most 3 times, with 1
    * second, 5 seconds, and 10 seconds delay between each retry. There is a
-   * limit of 5 retries and a maximum interval of 1 hour (3600000 milliseconds).
+   * limit of 5 retries and a maximum interval of 1 hour (3600000 milliseconds)
+   * by default, which can be changed with the `cron` option of `deno.json`.
+   *
+   * `timezone` option can be used to interpret the schedule in an IANA time
+   * zone, such as `"America/New_York"`, instead of UTC. Times that occur
+   * twice when clocks are set back run once, at their first occurrence, and
+   * times that are skipped when clocks are set forward run at the end of the
+   * skipped period.
    *
    * @category Cloud
    * @experimental
@@ -309,7 +316,11 @@ declare namespace Deno {
   export function cron(
     name: string,
     schedule: string | CronSchedule,
-    options: { backoffSchedule?: number[]; signal?: AbortSignal },
+    options: {
+      backoffSchedule?: number[];
+      signal?: AbortSignal;
+      timezone?: string;
+    },
     handler: () => Promise<void> | void,
   ): Promise<void>;
 
@@ -996,6 +1007,47 @@ declare namespace Deno {
       options?: { raw?: boolean },
     ): ReadableStream<{ [K in keyof T]: KvEntryMaybe<T[K]> }>;
 
+    /**
+     * Export the entries of the database as newline-delimited JSON, with one
+     * entry per line in key order. The returned stream can be written to a
+     * file, or passed to {@linkcode Deno.Kv.import} of another database.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * using file = await Deno.create("backup.ndjson");
+     * await db.export().pipeTo(file.writable);
+     * ```
+     *
+     * The `prefix` option can be used to only export the entries with keys
+     * starting with the given key.
+     *
+     * Entries are read in batches, so entries that are written to the
+     * database during the export may or may not be included. Entries set with
+     * `expireIn` include their expiration time, except for in-memory and
+     * remote databases.
+     */
+    export(options?: { prefix?: KvKey }): ReadableStream<Uint8Array>;
+
+    /**
+     * Import the entries of an export created with
+     * {@linkcode Deno.Kv.export} or `deno kv export`, overwriting the entries
+     * with the same keys. Resolves to the number of imported entries.
+     *
+     * ```ts
+     * const db = await Deno.openKv(":memory:");
+     * using file = await Deno.open("backup.ndjson");
+     * await db.import(file.readable);
+     * ```
+     *
+     * The imported entries get new versionstamps and keep their expiration
+     * times, and entries that have already expired are skipped. Entries are
+     * committed in batches, so if the import fails, some of the entries may
+     * have been written already.
+     */
+    import(
+      source: ReadableStream<Uint8Array> | AsyncIterable<Uint8Array>,
+    ): Promise<number>;
+
     /**
      * Close the database connection. This will prevent any further operations
      * from being performed on the database, and interrupt any in-flight
@@ -101,7 +101,11 @@ function cron(
   schedule: string | Deno.CronSchedule,
   handlerOrOptions1:
     | (() => Promise<void> | void)
-    | ({ backoffSchedule?: number[]; signal?: AbortSignal }),
+    | ({
+      backoffSchedule?: number[];
+      signal?: AbortSignal;
+      timezone?: string;
+    }),
   handler2?: () => Promise<void> | void,
 ) {
   if (name === undefined) {
@@ -119,7 +123,7 @@ function cron(
 
   let handler: () => Promise<void> | void;
   let options:
-    | { backoffSchedule?: number[]; signal?: AbortSignal }
+    | { backoffSchedule?: number[]; signal?: AbortSignal; timezone?: string }
     | undefined = undefined;
 
   if (typeof handlerOrOptions1 === "function") {
@@ -140,6 +144,7 @@ function cron(
     name,
     schedule,
     options?.backoffSchedule,
+    options?.timezone,
   );
 
   if (options?.signal) {
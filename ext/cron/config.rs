@@ -0,0 +1,133 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The limits of crons, which can only be created with [`CronConfigBuilder`]
+/// so that they're always valid.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(try_from = "CronConfigBuilder")]
+pub struct CronConfig {
+  pub(crate) max_crons: usize,
+  pub(crate) dispatch_concurrency_limit: usize,
+  pub(crate) max_backoff_ms: u32,
+  pub(crate) max_backoff_count: usize,
+}
+
+impl CronConfig {
+  pub fn builder() -> CronConfigBuilder {
+    CronConfigBuilder::default()
+  }
+}
+
+impl Default for CronConfig {
+  fn default() -> Self {
+    CronConfig {
+      max_crons: MAX_CRONS,
+      dispatch_concurrency_limit: DISPATCH_CONCURRENCY_LIMIT,
+      max_backoff_ms: MAX_BACKOFF_MS,
+      max_backoff_count: MAX_BACKOFF_COUNT,
+    }
+  }
+}
+
+const MAX_CRONS: usize = 100;
+const DISPATCH_CONCURRENCY_LIMIT: usize = 50;
+const MAX_BACKOFF_MS: u32 = 60 * 60 * 1_000; // 1 hour
+const MAX_BACKOFF_COUNT: usize = 5;
+
+#[derive(Debug, thiserror::Error, deno_error::JsError)]
+#[class(type)]
+#[error("Invalid cron config: {0} must be at least 1")]
+pub struct CronConfigError(&'static str);
+
+#[derive(Default, Deserialize)]
+pub struct CronConfigBuilder {
+  max_crons: Option<usize>,
+  dispatch_concurrency_limit: Option<usize>,
+  max_backoff_ms: Option<u32>,
+  max_backoff_count: Option<usize>,
+}
+
+impl CronConfigBuilder {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn max_crons(&mut self, max_crons: usize) -> &mut Self {
+    self.max_crons = Some(max_crons);
+    self
+  }
+
+  pub fn dispatch_concurrency_limit(
+    &mut self,
+    dispatch_concurrency_limit: usize,
+  ) -> &mut Self {
+    self.dispatch_concurrency_limit = Some(dispatch_concurrency_limit);
+    self
+  }
+
+  pub fn max_backoff_ms(&mut self, max_backoff_ms: u32) -> &mut Self {
+    self.max_backoff_ms = Some(max_backoff_ms);
+    self
+  }
+
+  pub fn max_backoff_count(&mut self, max_backoff_count: usize) -> &mut Self {
+    self.max_backoff_count = Some(max_backoff_count);
+    self
+  }
+
+  pub fn build(&self) -> Result<CronConfig, CronConfigError> {
+    let config = CronConfig {
+      max_crons: self.max_crons.unwrap_or(MAX_CRONS),
+      dispatch_concurrency_limit: self
+        .dispatch_concurrency_limit
+        .unwrap_or(DISPATCH_CONCURRENCY_LIMIT),
+      max_backoff_ms: self.max_backoff_ms.unwrap_or(MAX_BACKOFF_MS),
+      max_backoff_count: self.max_backoff_count.unwrap_or(MAX_BACKOFF_COUNT),
+    };
+    // A dispatch concurrency limit of 0 would never run any cron. Backoffs
+    // can be disabled with a limit of 0, so those aren't checked.
+    for (name, value) in [
+      ("maxCrons", config.max_crons),
+      (
+        "dispatchConcurrencyLimit",
+        config.dispatch_concurrency_limit,
+      ),
+    ] {
+      if value == 0 {
+        return Err(CronConfigError(name));
+      }
+    }
+    Ok(config)
+  }
+}
+
+impl TryFrom<CronConfigBuilder> for CronConfig {
+  type Error = CronConfigError;
+
+  fn try_from(builder: CronConfigBuilder) -> Result<Self, Self::Error> {
+    builder.build()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_build_rejects_zero_limits() {
+    assert!(CronConfig::builder().build().is_ok());
+    let err = CronConfig::builder()
+      .dispatch_concurrency_limit(0)
+      .build()
+      .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid cron config: dispatchConcurrencyLimit must be at least 1"
+    );
+    assert!(CronConfig::builder().max_crons(0).build().is_err());
+    assert!(CronConfig::builder().max_backoff_ms(0).build().is_ok());
+    assert!(CronConfig::builder().max_backoff_count(0).build().is_ok());
+  }
+}
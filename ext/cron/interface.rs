@@ -21,4 +21,6 @@ pub struct CronSpec {
   pub name: String,
   pub cron_schedule: String,
   pub backoff_schedule: Option<Vec<u32>>,
+  /// The IANA timezone the schedule is interpreted in, UTC by default.
+  pub timezone: Option<String>,
 }
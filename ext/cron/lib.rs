@@ -1,7 +1,9 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
 
+mod config;
 mod interface;
 pub mod local;
+pub mod sqlite;
 
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -16,6 +18,7 @@ use deno_error::JsErrorBox;
 use deno_error::JsErrorClass;
 use deno_features::FeatureChecker;
 
+pub use crate::config::*;
 pub use crate::interface::*;
 
 pub const UNSTABLE_FEATURE_NAME: &str = "cron";
@@ -75,9 +78,15 @@ pub enum CronError {
   #[class(type)]
   #[error("Invalid backoff schedule")]
   InvalidBackoff,
+  #[class(type)]
+  #[error("Invalid timezone: {0}")]
+  InvalidTimezone(String),
   #[class(generic)]
   #[error(transparent)]
   AcquireError(#[from] tokio::sync::AcquireError),
+  #[class(generic)]
+  #[error(transparent)]
+  Sqlite(#[from] rusqlite::Error),
   #[class(inherit)]
   #[error(transparent)]
   Other(JsErrorBox),
@@ -90,6 +99,7 @@ fn op_cron_create<C>(
   #[string] name: String,
   #[string] cron_schedule: String,
   #[serde] backoff_schedule: Option<Vec<u32>>,
+  #[string] timezone: Option<String>,
 ) -> Result<ResourceId, CronError>
 where
   C: CronHandler + 'static,
@@ -108,6 +118,7 @@ where
     name,
     cron_schedule,
     backoff_schedule,
+    timezone,
   })?;
 
   let handle_rid = {
@@ -5,32 +5,66 @@ use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::env;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::rc::Weak;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::LocalResult;
+use chrono::TimeZone;
+use chrono::Utc;
+use chrono_tz::Tz;
 use deno_core::futures;
 use deno_core::futures::FutureExt;
 use deno_core::unsync::JoinHandle;
 use deno_core::unsync::spawn;
+use deno_core::unsync::spawn_blocking;
+use deno_error::JsErrorBox;
 use tokio::sync::OwnedSemaphorePermit;
 use tokio::sync::Semaphore;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::WeakSender;
 
+use crate::CronConfig;
 use crate::CronError;
 use crate::CronHandle;
 use crate::CronHandler;
 use crate::CronSpec;
+use crate::sqlite::SqliteCronStore;
 
-const MAX_CRONS: usize = 100;
-const DISPATCH_CONCURRENCY_LIMIT: usize = 50;
-const MAX_BACKOFF_MS: u32 = 60 * 60 * 1_000; // 1 hour
-const MAX_BACKOFF_COUNT: usize = 5;
 const DEFAULT_BACKOFF_SCHEDULE: [u32; 5] = [100, 1_000, 5_000, 30_000, 60_000];
 
+/// Durable storage for the schedule state of a [`LocalCronHandler`].
+///
+/// A store lets a run that was missed while no process was running a cron be
+/// caught up on restart, and makes sure that each run is executed by a
+/// single process when several processes register the same cron. The store
+/// is called on a blocking thread since it may do file I/O.
+pub trait CronStore: Send + Sync {
+  /// Registers a cron, returning the scheduled time of a run that was missed
+  /// while no process was running it, if any.
+  fn register(
+    &self,
+    name: &str,
+    cron_schedule: &str,
+    now: u64,
+  ) -> Result<Option<u64>, CronError>;
+
+  /// Records the scheduled time of the next run of a cron.
+  fn set_next_run(&self, name: &str, next_run: u64) -> Result<(), CronError>;
+
+  /// Claims the execution of the run scheduled at `run`. Returns `false` if
+  /// another process executes it.
+  fn claim(&self, name: &str, run: u64, now: u64) -> Result<bool, CronError>;
+
+  /// Records that the run scheduled at `run` succeeded.
+  fn record_success(&self, name: &str, run: u64) -> Result<(), CronError>;
+}
+
 pub struct LocalCronHandler {
+  config: CronConfig,
   cron_schedule_tx: OnceCell<mpsc::Sender<(String, bool)>>,
   concurrency_limiter: Arc<Semaphore>,
   cron_loop_join_handle: OnceCell<JoinHandle<()>>,
@@ -40,12 +74,24 @@ pub struct LocalCronHandler {
 struct RuntimeState {
   crons: HashMap<String, Cron>,
   scheduled_deadlines: BTreeMap<u64, Vec<String>>,
+  store: Option<Arc<dyn CronStore>>,
 }
 
 struct Cron {
   spec: CronSpec,
+  timezone: Option<Tz>,
   next_tx: mpsc::WeakSender<()>,
   current_execution_retries: u32,
+  /// The scheduled time of the run that is due next or being executed.
+  current_run: u64,
+  /// A run that was missed while no process was running the cron, to be
+  /// executed right away.
+  missed_run: Option<u64>,
+  /// Whether `current_run` has been dispatched to the cron's handler.
+  dispatched: bool,
+  /// The schedule to register in the store before the cron is scheduled for
+  /// the first time.
+  registration: Option<String>,
 }
 
 impl Cron {
@@ -66,21 +112,43 @@ impl Default for LocalCronHandler {
 
 impl LocalCronHandler {
   pub fn new() -> Self {
+    Self::with_config(CronConfig::default(), None)
+  }
+
+  /// Creates a handler with the limits of `config`, which persists the
+  /// schedule state of crons in `store`, if any.
+  pub fn with_config(
+    config: CronConfig,
+    store: Option<Arc<dyn CronStore>>,
+  ) -> Self {
     Self {
+      config,
       cron_schedule_tx: OnceCell::new(),
-      concurrency_limiter: Arc::new(Semaphore::new(DISPATCH_CONCURRENCY_LIMIT)),
+      concurrency_limiter: Arc::new(Semaphore::new(
+        config.dispatch_concurrency_limit,
+      )),
       cron_loop_join_handle: OnceCell::new(),
       runtime_state: Rc::new(RefCell::new(RuntimeState {
         crons: HashMap::new(),
         scheduled_deadlines: BTreeMap::new(),
+        store,
       })),
     }
   }
 
+  /// Creates a handler that persists the schedule state of crons in the
+  /// SQLite database at `db_path`, if any.
+  pub fn with_db_path(config: CronConfig, db_path: Option<PathBuf>) -> Self {
+    let store = db_path
+      .map(|path| Arc::new(SqliteCronStore::new(path)) as Arc<dyn CronStore>);
+    Self::with_config(config, store)
+  }
+
   async fn cron_loop(
     runtime_state: Rc<RefCell<RuntimeState>>,
     mut cron_schedule_rx: mpsc::Receiver<(String, bool)>,
   ) -> Result<(), CronError> {
+    let store = runtime_state.borrow().store.clone();
     loop {
       let earliest_deadline = runtime_state
         .borrow()
@@ -112,35 +180,55 @@ impl LocalCronHandler {
 
       // Schedule next execution of the cron if needed.
       if let Some((name, prev_success)) = cron_to_schedule {
-        let mut runtime_state = runtime_state.borrow_mut();
-        if let Some(cron) = runtime_state.crons.get_mut(&name) {
-          let backoff_schedule = cron.backoff_schedule();
-          let next_deadline = if !prev_success
-            && cron.current_execution_retries < backoff_schedule.len() as u32
-          {
-            let backoff_ms =
-              backoff_schedule[cron.current_execution_retries as usize];
-            let now = chrono::Utc::now().timestamp_millis() as u64;
-            cron.current_execution_retries += 1;
-            now + backoff_ms as u64
-          } else {
-            let next_ts = compute_next_deadline(&cron.spec.cron_schedule)?;
-            cron.current_execution_retries = 0;
-            next_ts
-          };
-          runtime_state
-            .scheduled_deadlines
-            .entry(next_deadline)
-            .or_default()
-            .push(name.to_string());
+        if let Some(store) = &store {
+          Self::update_store(&runtime_state, store, &name, prev_success).await;
+        }
+
+        let next_run_to_store = {
+          let mut runtime_state = runtime_state.borrow_mut();
+          let runtime_state = &mut *runtime_state;
+          let mut next_run_to_store = None;
+          if let Some(cron) = runtime_state.crons.get_mut(&name) {
+            let missed_run = cron.missed_run.take();
+            let backoff_schedule = cron.backoff_schedule();
+            let next_deadline = if let Some(missed_run) = missed_run {
+              cron.current_run = missed_run;
+              chrono::Utc::now().timestamp_millis() as u64
+            } else if !prev_success
+              && cron.current_execution_retries < backoff_schedule.len() as u32
+            {
+              let backoff_ms =
+                backoff_schedule[cron.current_execution_retries as usize];
+              let now = chrono::Utc::now().timestamp_millis() as u64;
+              cron.current_execution_retries += 1;
+              now + backoff_ms as u64
+            } else {
+              let next_ts =
+                compute_next_deadline(&cron.spec.cron_schedule, cron.timezone)?;
+              cron.current_execution_retries = 0;
+              cron.current_run = next_ts;
+              next_run_to_store = Some(next_ts);
+              next_ts
+            };
+            runtime_state
+              .scheduled_deadlines
+              .entry(next_deadline)
+              .or_default()
+              .push(name.to_string());
+          }
+          next_run_to_store
+        };
+
+        if let (Some(store), Some(next_run)) = (&store, next_run_to_store) {
+          let _ =
+            call_store(store, move |store| store.set_next_run(&name, next_run))
+              .await;
         }
       }
 
       // Dispatch ready to execute crons.
-      let crons_to_execute = {
-        let mut runtime_state = runtime_state.borrow_mut();
-        runtime_state.get_ready_crons()?
-      };
+      let crons_to_execute =
+        Self::take_ready_crons(&runtime_state, store.as_ref()).await?;
       for (_, tx) in crons_to_execute {
         if let Some(tx) = tx.upgrade() {
           let _ = tx.send(()).await;
@@ -148,44 +236,128 @@ impl LocalCronHandler {
       }
     }
   }
-}
 
-impl RuntimeState {
-  fn get_ready_crons(
-    &mut self,
+  /// Registers a new cron in the store, or records that the run of the cron
+  /// that was dispatched succeeded.
+  async fn update_store(
+    runtime_state: &RefCell<RuntimeState>,
+    store: &Arc<dyn CronStore>,
+    name: &str,
+    prev_success: bool,
+  ) {
+    let (registration, succeeded_run) = {
+      let mut runtime_state = runtime_state.borrow_mut();
+      let Some(cron) = runtime_state.crons.get_mut(name) else {
+        return;
+      };
+      let dispatched = std::mem::take(&mut cron.dispatched);
+      (
+        cron.registration.take(),
+        (prev_success && dispatched).then_some(cron.current_run),
+      )
+    };
+    if let Some(run) = succeeded_run {
+      let name = name.to_string();
+      // A failure to record the run only means it may be caught up again
+      // after a restart.
+      let _ =
+        call_store(store, move |store| store.record_success(&name, run)).await;
+    }
+    if let Some(cron_schedule) = registration {
+      let now = chrono::Utc::now().timestamp_millis() as u64;
+      let name_ = name.to_string();
+      // A failure to register the cron only means that a missed run isn't
+      // caught up.
+      let missed_run = call_store(store, move |store| {
+        store.register(&name_, &cron_schedule, now)
+      })
+      .await
+      .ok()
+      .flatten();
+      if let Some(cron) = runtime_state.borrow_mut().crons.get_mut(name) {
+        cron.missed_run = missed_run;
+      }
+    }
+  }
+
+  /// Removes the crons that are due from the schedule, returning the ones
+  /// that this process executes.
+  async fn take_ready_crons(
+    runtime_state: &RefCell<RuntimeState>,
+    store: Option<&Arc<dyn CronStore>>,
   ) -> Result<Vec<(String, WeakSender<()>)>, CronError> {
     let now = chrono::Utc::now().timestamp_millis() as u64;
 
-    let ready = {
-      let to_remove = self
+    let due = {
+      let mut runtime_state = runtime_state.borrow_mut();
+      let to_remove = runtime_state
         .scheduled_deadlines
         .range(..=now)
         .map(|(ts, _)| *ts)
         .collect::<Vec<_>>();
-      to_remove
-        .iter()
-        .flat_map(|ts| {
-          self
-            .scheduled_deadlines
-            .remove(ts)
-            .unwrap()
-            .iter()
-            .map(move |name| (*ts, name.clone()))
-            .collect::<Vec<_>>()
-        })
-        .filter_map(|(_, name)| {
-          self
-            .crons
-            .get(&name)
-            .map(|c| (name.clone(), c.next_tx.clone()))
-        })
-        .collect::<Vec<_>>()
+      let mut due = Vec::new();
+      for ts in to_remove {
+        due.extend(runtime_state.scheduled_deadlines.remove(&ts).unwrap());
+      }
+      due
     };
+    let mut ready = Vec::new();
+    for name in due {
+      let Some(run) = runtime_state
+        .borrow()
+        .crons
+        .get(&name)
+        .map(|cron| cron.current_run)
+      else {
+        continue;
+      };
+      let claimed = match store {
+        // If the store is unavailable, run the cron rather than skip it.
+        Some(store) => {
+          let name = name.clone();
+          call_store(store, move |store| store.claim(&name, run, now))
+            .await
+            .unwrap_or(true)
+        }
+        None => true,
+      };
+      let mut runtime_state = runtime_state.borrow_mut();
+      let runtime_state = &mut *runtime_state;
+      let Some(cron) = runtime_state.crons.get_mut(&name) else {
+        continue;
+      };
+      if claimed {
+        cron.dispatched = true;
+        ready.push((name, cron.next_tx.clone()));
+      } else {
+        // Another process executes this run, wait for the next one.
+        let next_ts =
+          compute_next_deadline(&cron.spec.cron_schedule, cron.timezone)?;
+        cron.current_execution_retries = 0;
+        cron.current_run = next_ts;
+        runtime_state
+          .scheduled_deadlines
+          .entry(next_ts)
+          .or_default()
+          .push(name);
+      }
+    }
 
     Ok(ready)
   }
 }
 
+/// Calls the store on a blocking thread, since it may do file I/O.
+async fn call_store<T: Send + 'static>(
+  store: &Arc<dyn CronStore>,
+  f: impl FnOnce(&dyn CronStore) -> Result<T, CronError> + Send + 'static,
+) -> Result<T, CronError> {
+  let store = store.clone();
+  spawn_blocking(move || f(&*store))
+    .await
+    .map_err(|err| CronError::Other(JsErrorBox::generic(err.to_string())))?
+}
+
 #[async_trait(?Send)]
 impl CronHandler for LocalCronHandler {
   type EH = CronExecutionHandle;
@@ -206,7 +378,7 @@ impl CronHandler for LocalCronHandler {
 
     let mut runtime_state = self.runtime_state.borrow_mut();
 
-    if runtime_state.crons.len() > MAX_CRONS {
+    if runtime_state.crons.len() > self.config.max_crons {
       return Err(CronError::TooManyCrons);
     }
     if runtime_state.crons.contains_key(&spec.name) {
@@ -221,14 +393,36 @@ impl CronHandler for LocalCronHandler {
 
     // Validate backoff_schedule.
     if let Some(backoff_schedule) = &spec.backoff_schedule {
-      validate_backoff_schedule(backoff_schedule)?;
+      validate_backoff_schedule(backoff_schedule, &self.config)?;
     }
 
+    let timezone = spec
+      .timezone
+      .as_deref()
+      .map(|timezone| {
+        timezone
+          .parse::<Tz>()
+          .map_err(|_| CronError::InvalidTimezone(timezone.to_string()))
+      })
+      .transpose()?;
+
+    // The cron is registered in the store by the cron loop. Runs of the
+    // schedule in another timezone are not caught up.
+    let registration = runtime_state.store.is_some().then(|| match timezone {
+      Some(timezone) => format!("{} {}", spec.cron_schedule, timezone),
+      None => spec.cron_schedule.clone(),
+    });
+
     let (next_tx, next_rx) = mpsc::channel::<()>(1);
     let cron = Cron {
       spec: spec.clone(),
+      timezone,
       next_tx: next_tx.downgrade(),
       current_execution_retries: 0,
+      current_run: 0,
+      missed_run: None,
+      dispatched: false,
+      registration,
     };
     runtime_state.crons.insert(spec.name.clone(), cron);
 
@@ -299,7 +493,10 @@ impl CronHandle for CronExecutionHandle {
   }
 }
 
-fn compute_next_deadline(cron_expression: &str) -> Result<u64, CronError> {
+fn compute_next_deadline(
+  cron_expression: &str,
+  timezone: Option<Tz>,
+) -> Result<u64, CronError> {
   let now = chrono::Utc::now();
 
   if let Ok(test_schedule) = env::var("DENO_CRON_TEST_SCHEDULE_OFFSET") {
@@ -311,19 +508,62 @@ fn compute_next_deadline(cron_expression: &str) -> Result<u64, CronError> {
   let cron = cron_expression
     .parse::<saffron::Cron>()
     .map_err(|_| CronError::InvalidCron)?;
-  let Some(next_deadline) = cron.next_after(now) else {
+  let Some(next_deadline) = next_after(&cron, timezone, now) else {
     return Err(CronError::InvalidCron);
   };
   Ok(next_deadline.timestamp_millis() as u64)
 }
 
+/// Returns the first time after `now` that matches `cron` on the wall clock
+/// of `timezone`, or of UTC if there is none.
+///
+/// Wall-clock times that occur twice when clocks are set back match only
+/// their first occurrence. Wall-clock times that are skipped when clocks are
+/// set forward match the end of the gap.
+fn next_after(
+  cron: &saffron::Cron,
+  timezone: Option<Tz>,
+  now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+  let Some(timezone) = timezone else {
+    return cron.next_after(now);
+  };
+
+  // saffron only handles UTC, so match the wall-clock time as if it was UTC
+  // and map the match back to the timezone.
+  let mut wall_clock = now.with_timezone(&timezone).naive_local();
+  // When clocks are set back, the matches in the repeated period precede
+  // `now` and are skipped, one minute at a time at most.
+  for _ in 0..=24 * 60 {
+    let next_wall_clock = cron.next_after(wall_clock.and_utc())?.naive_utc();
+    let next = match timezone.from_local_datetime(&next_wall_clock) {
+      LocalResult::Single(next) => next,
+      LocalResult::Ambiguous(earliest, _) => earliest,
+      LocalResult::None => (1..=24 * 60).find_map(|minutes| {
+        timezone
+          .from_local_datetime(
+            &(next_wall_clock + chrono::Duration::minutes(minutes)),
+          )
+          .earliest()
+      })?,
+    };
+    let next = next.with_timezone(&Utc);
+    if next > now {
+      return Some(next);
+    }
+    wall_clock = next_wall_clock;
+  }
+  None
+}
+
 fn validate_backoff_schedule(
   backoff_schedule: &[u32],
+  config: &CronConfig,
 ) -> Result<(), CronError> {
-  if backoff_schedule.len() > MAX_BACKOFF_COUNT {
+  if backoff_schedule.len() > config.max_backoff_count {
     return Err(CronError::InvalidBackoff);
   }
-  if backoff_schedule.iter().any(|s| *s > MAX_BACKOFF_MS) {
+  if backoff_schedule.iter().any(|s| *s > config.max_backoff_ms) {
     return Err(CronError::InvalidBackoff);
   }
   Ok(())
@@ -336,10 +576,109 @@ mod tests {
   #[test]
   fn test_compute_next_deadline() {
     let now = chrono::Utc::now().timestamp_millis() as u64;
-    assert!(compute_next_deadline("*/1 * * * *").unwrap() > now);
-    assert!(compute_next_deadline("* * * * *").unwrap() > now);
-    assert!(compute_next_deadline("bogus").is_err());
-    assert!(compute_next_deadline("* * * * * *").is_err());
-    assert!(compute_next_deadline("* * *").is_err());
+    assert!(compute_next_deadline("*/1 * * * *", None).unwrap() > now);
+    assert!(compute_next_deadline("* * * * *", None).unwrap() > now);
+    assert!(
+      compute_next_deadline("* * * * *", Some(chrono_tz::Asia::Tokyo)).unwrap()
+        > now
+    );
+    assert!(compute_next_deadline("bogus", None).is_err());
+    assert!(compute_next_deadline("* * * * * *", None).is_err());
+    assert!(compute_next_deadline("* * *", None).is_err());
+  }
+
+  fn next_after_str(
+    cron_expression: &str,
+    timezone: Tz,
+    now: &str,
+  ) -> DateTime<Utc> {
+    let cron = cron_expression.parse::<saffron::Cron>().unwrap();
+    let now = now.parse::<DateTime<Utc>>().unwrap();
+    next_after(&cron, Some(timezone), now).unwrap()
+  }
+
+  fn utc(date: &str) -> DateTime<Utc> {
+    date.parse().unwrap()
+  }
+
+  #[test]
+  fn test_next_after_timezone() {
+    use chrono_tz::America::New_York;
+    use chrono_tz::Asia::Tokyo;
+
+    // 09:00 in Tokyo is 00:00 UTC.
+    assert_eq!(
+      next_after_str("0 9 * * *", Tokyo, "2024-01-01T12:00:00Z"),
+      utc("2024-01-02T00:00:00Z")
+    );
+    // 09:00 in New York is 14:00 UTC in winter and 13:00 UTC in summer.
+    assert_eq!(
+      next_after_str("0 9 * * *", New_York, "2024-01-01T12:00:00Z"),
+      utc("2024-01-01T14:00:00Z")
+    );
+    assert_eq!(
+      next_after_str("0 9 * * *", New_York, "2024-07-01T12:00:00Z"),
+      utc("2024-07-01T13:00:00Z")
+    );
+  }
+
+  #[test]
+  fn test_next_after_dst_transitions() {
+    use chrono_tz::America::New_York;
+
+    // Clocks are set forward from 02:00 to 03:00 on 2024-03-10, so 02:30 is
+    // skipped and runs at 03:00 EDT.
+    assert_eq!(
+      next_after_str("30 2 * * *", New_York, "2024-03-10T06:00:30Z"),
+      utc("2024-03-10T07:00:00Z")
+    );
+    // A schedule in the gap runs once for it.
+    assert_eq!(
+      next_after_str("*/15 * * * *", New_York, "2024-03-10T06:50:00Z"),
+      utc("2024-03-10T07:00:00Z")
+    );
+    assert_eq!(
+      next_after_str("*/15 * * * *", New_York, "2024-03-10T07:00:00Z"),
+      utc("2024-03-10T07:15:00Z")
+    );
+
+    // Clocks are set back from 02:00 to 01:00 on 2024-11-03, so 01:30
+    // occurs twice and runs at its first occurrence, 01:30 EDT.
+    assert_eq!(
+      next_after_str("30 1 * * *", New_York, "2024-11-03T04:00:30Z"),
+      utc("2024-11-03T05:30:00Z")
+    );
+    // It doesn't run again at 01:30 EST.
+    assert_eq!(
+      next_after_str("30 1 * * *", New_York, "2024-11-03T05:45:00Z"),
+      utc("2024-11-04T06:30:00Z")
+    );
+    assert_eq!(
+      next_after_str("30 1 * * *", New_York, "2024-11-03T06:10:00Z"),
+      utc("2024-11-04T06:30:00Z")
+    );
+    // Schedules outside of the repeated period are unaffected.
+    assert_eq!(
+      next_after_str("0 3 * * *", New_York, "2024-11-03T05:45:00Z"),
+      utc("2024-11-03T08:00:00Z")
+    );
+  }
+
+  #[test]
+  fn test_validate_backoff_schedule() {
+    let config = CronConfig::default();
+    assert!(validate_backoff_schedule(&[1_000, 5_000], &config).is_ok());
+    assert!(validate_backoff_schedule(&[1; 6], &config).is_err());
+    assert!(
+      validate_backoff_schedule(&[2 * 60 * 60 * 1_000], &config).is_err()
+    );
+
+    let config = CronConfig::builder()
+      .max_backoff_count(10)
+      .max_backoff_ms(24 * 60 * 60 * 1_000)
+      .build()
+      .unwrap();
+    assert!(validate_backoff_schedule(&[1; 6], &config).is_ok());
+    assert!(validate_backoff_schedule(&[2 * 60 * 60 * 1_000], &config).is_ok());
   }
 }
@@ -0,0 +1,270 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+// NOTE to all: use **cached** prepared statements when interfacing with SQLite.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use rusqlite::TransactionBehavior;
+use rusqlite::params;
+
+use crate::CronError;
+use crate::local::CronStore;
+
+/// How long a claimed run is reserved for the process executing it. If the
+/// process dies without recording a successful run, the run can be caught up
+/// by another process once the lease expires.
+const LEASE_DURATION_MS: u64 = 10 * 60 * 1_000; // 10 minutes
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A [`CronStore`] backed by a SQLite database, which may be shared by several
+/// processes on the same host.
+pub struct SqliteCronStore {
+  path: PathBuf,
+  /// Identifies the claims of this store, unique among the live processes
+  /// sharing the database.
+  owner: String,
+  conn: Mutex<Option<Connection>>,
+}
+
+impl SqliteCronStore {
+  /// Creates a store for the database at `path`. The database is opened on
+  /// first use.
+  pub fn new(path: PathBuf) -> Self {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    Self {
+      path,
+      owner: format!(
+        "{}-{}",
+        std::process::id(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+      ),
+      conn: Mutex::new(None),
+    }
+  }
+
+  fn with_conn<T>(
+    &self,
+    f: impl FnOnce(&mut Connection) -> Result<T, rusqlite::Error>,
+  ) -> Result<T, CronError> {
+    let mut conn = self.conn.lock().unwrap();
+    if conn.is_none() {
+      *conn = Some(self.open()?);
+    }
+    Ok(f(conn.as_mut().unwrap())?)
+  }
+
+  fn open(&self) -> Result<Connection, rusqlite::Error> {
+    let conn = Connection::open(&self.path)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.execute_batch(
+      "
+      PRAGMA journal_mode=WAL;
+      PRAGMA synchronous=NORMAL;
+      CREATE TABLE IF NOT EXISTS cron_state (
+        name TEXT PRIMARY KEY,
+        cron_schedule TEXT NOT NULL,
+        next_run_at INTEGER,
+        last_success_at INTEGER,
+        claimed_run_at INTEGER,
+        lease_owner TEXT,
+        lease_expires_at INTEGER
+      );
+      ",
+    )?;
+    Ok(conn)
+  }
+}
+
+impl CronStore for SqliteCronStore {
+  fn register(
+    &self,
+    name: &str,
+    cron_schedule: &str,
+    now: u64,
+  ) -> Result<Option<u64>, CronError> {
+    self.with_conn(|conn| {
+      let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+      let row = tx
+        .prepare_cached(
+          "SELECT cron_schedule, next_run_at, last_success_at, claimed_run_at, lease_expires_at FROM cron_state WHERE name = ?",
+        )?
+        .query_row(params![name], |row| {
+          Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<i64>>(1)?,
+            row.get::<_, Option<i64>>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+            row.get::<_, Option<i64>>(4)?,
+          ))
+        })
+        .optional()?;
+
+      let missed_run = match row {
+        None => {
+          tx.prepare_cached(
+            "INSERT INTO cron_state (name, cron_schedule) VALUES (?, ?)",
+          )?
+          .execute(params![name, cron_schedule])?;
+          None
+        }
+        Some((prev_schedule, ..)) if prev_schedule != cron_schedule => {
+          // Runs of a previous schedule are not caught up.
+          tx.prepare_cached(
+            "UPDATE cron_state SET cron_schedule = ?, next_run_at = NULL, claimed_run_at = NULL, lease_owner = NULL, lease_expires_at = NULL WHERE name = ?",
+          )?
+          .execute(params![cron_schedule, name])?;
+          None
+        }
+        Some((_, next_run, last_success, claimed_run, lease_expires)) => {
+          let now = now as i64;
+          next_run
+            .filter(|next_run| {
+              let due = *next_run <= now;
+              let succeeded =
+                last_success.is_some_and(|last| last >= *next_run);
+              let executing = claimed_run == Some(*next_run)
+                && lease_expires.is_some_and(|expires| expires > now);
+              due && !succeeded && !executing
+            })
+            .map(|next_run| next_run as u64)
+        }
+      };
+      tx.commit()?;
+      Ok(missed_run)
+    })
+  }
+
+  fn set_next_run(&self, name: &str, next_run: u64) -> Result<(), CronError> {
+    self.with_conn(|conn| {
+      conn
+        .prepare_cached("UPDATE cron_state SET next_run_at = ? WHERE name = ?")?
+        .execute(params![next_run as i64, name])?;
+      Ok(())
+    })
+  }
+
+  fn claim(&self, name: &str, run: u64, now: u64) -> Result<bool, CronError> {
+    self.with_conn(|conn| {
+      // A run can be claimed if no process has claimed it yet, if this
+      // process already owns it (when retrying a failed execution), or if
+      // the process that claimed it didn't finish it within its lease.
+      let changed = conn
+        .prepare_cached(
+          "UPDATE cron_state SET claimed_run_at = :run, lease_owner = :owner, lease_expires_at = :expires
+          WHERE name = :name AND (
+            claimed_run_at IS NULL
+            OR claimed_run_at < :run
+            OR (claimed_run_at = :run AND lease_owner = :owner)
+            OR (
+              claimed_run_at = :run
+              AND lease_expires_at <= :now
+              AND (last_success_at IS NULL OR last_success_at < :run)
+            )
+          )",
+        )?
+        .execute(rusqlite::named_params! {
+          ":name": name,
+          ":run": run as i64,
+          ":owner": self.owner,
+          ":now": now as i64,
+          ":expires": (now + LEASE_DURATION_MS) as i64,
+        })?;
+      Ok(changed == 1)
+    })
+  }
+
+  fn record_success(&self, name: &str, run: u64) -> Result<(), CronError> {
+    self.with_conn(|conn| {
+      conn
+        .prepare_cached(
+          "UPDATE cron_state SET last_success_at = MAX(COALESCE(last_success_at, 0), ?), lease_expires_at = NULL WHERE name = ?",
+        )?
+        .execute(params![run as i64, name])?;
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn claims_run_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cron.db");
+    let a = SqliteCronStore::new(path.clone());
+    let b = SqliteCronStore::new(path);
+
+    assert_eq!(a.register("job", "* * * * *", 0).unwrap(), None);
+    assert_eq!(b.register("job", "* * * * *", 0).unwrap(), None);
+    a.set_next_run("job", 60_000).unwrap();
+
+    assert!(a.claim("job", 60_000, 60_000).unwrap());
+    assert!(!b.claim("job", 60_000, 60_001).unwrap());
+    // The owner can claim the run again to retry it.
+    assert!(a.claim("job", 60_000, 61_000).unwrap());
+    a.record_success("job", 60_000).unwrap();
+
+    // The next run is claimed by whichever process gets there first.
+    assert!(b.claim("job", 120_000, 120_000).unwrap());
+    assert!(!a.claim("job", 120_000, 120_000).unwrap());
+  }
+
+  #[test]
+  fn catches_up_missed_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cron.db");
+
+    let store = SqliteCronStore::new(path.clone());
+    store.register("job", "* * * * *", 0).unwrap();
+    store.set_next_run("job", 60_000).unwrap();
+    drop(store);
+
+    // The process was down at the scheduled time.
+    let store = SqliteCronStore::new(path.clone());
+    assert_eq!(
+      store.register("job", "* * * * *", 90_000).unwrap(),
+      Some(60_000)
+    );
+    assert!(store.claim("job", 60_000, 90_000).unwrap());
+    store.record_success("job", 60_000).unwrap();
+    drop(store);
+
+    // The missed run has been executed.
+    let store = SqliteCronStore::new(path.clone());
+    assert_eq!(store.register("job", "* * * * *", 100_000).unwrap(), None);
+
+    // Runs of a previous schedule are not caught up.
+    store.set_next_run("job", 120_000).unwrap();
+    assert_eq!(store.register("job", "0 * * * *", 150_000).unwrap(), None);
+  }
+
+  #[test]
+  fn expired_lease() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cron.db");
+
+    // The process executing the run dies before it completes.
+    let a = SqliteCronStore::new(path.clone());
+    a.register("job", "* * * * *", 0).unwrap();
+    a.set_next_run("job", 60_000).unwrap();
+    assert!(a.claim("job", 60_000, 60_000).unwrap());
+    drop(a);
+
+    let b = SqliteCronStore::new(path);
+    assert_eq!(b.register("job", "* * * * *", 61_000).unwrap(), None);
+    let expired = 60_000 + LEASE_DURATION_MS;
+    assert_eq!(
+      b.register("job", "* * * * *", expired).unwrap(),
+      Some(60_000)
+    );
+    assert!(b.claim("job", 60_000, expired).unwrap());
+  }
+}
@@ -83,6 +83,7 @@ function processUrlList(urlList, urlListProcessed) {
  * @property {() => [string, string][]} headerList
  * @property {null | typeof __window.bootstrap.fetchBody.InnerBody} body
  * @property {"follow" | "error" | "manual"} redirectMode
+ * @property {RequestCache} cacheMode
  * @property {number} redirectCount
  * @property {(() => string)[]} urlList
  * @property {string[]} urlListProcessed
@@ -131,6 +132,7 @@ function newInnerRequest(method, url, headerList, body, maybeBlob) {
     },
     body,
     redirectMode: "follow",
+    cacheMode: "default",
     redirectCount: 0,
     urlList: [typeof url === "string" ? () => url : url],
     urlListProcessed: [],
@@ -182,6 +184,7 @@ function cloneInnerRequest(request, skipBody = false) {
     headerList,
     body,
     redirectMode: request.redirectMode,
+    cacheMode: request.cacheMode,
     redirectCount: request.redirectCount,
     urlList: [() => request.url()],
     urlListProcessed: [request.url()],
@@ -361,6 +364,11 @@ class Request {
       request.redirectMode = init.redirect;
     }
 
+    // 23.
+    if (init.cache !== undefined) {
+      request.cacheMode = init.cache;
+    }
+
     // 25.
     if (init.method !== undefined) {
       const method = init.method;
@@ -489,6 +497,11 @@ class Request {
     return this[_request].redirectMode;
   }
 
+  get cache() {
+    webidl.assertBranded(this, RequestPrototype);
+    return this[_request].cacheMode;
+  }
+
   get signal() {
     webidl.assertBranded(this, RequestPrototype);
     return this[_signal];
@@ -563,6 +576,17 @@ webidl.converters["RequestRedirect"] = webidl.createEnumConverter(
     "manual",
   ],
 );
+webidl.converters["RequestCache"] = webidl.createEnumConverter(
+  "RequestCache",
+  [
+    "default",
+    "no-store",
+    "reload",
+    "no-cache",
+    "force-cache",
+    "only-if-cached",
+  ],
+);
 webidl.converters["RequestInit"] = webidl.createDictionaryConverter(
   "RequestInit",
   [
@@ -574,6 +598,7 @@ webidl.converters["RequestInit"] = webidl.createDictionaryConverter(
         webidl.converters["BodyInit_DOMString"],
       ),
     },
+    { key: "cache", converter: webidl.converters["RequestCache"] },
     { key: "redirect", converter: webidl.converters["RequestRedirect"] },
     {
       key: "signal",
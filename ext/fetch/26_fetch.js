@@ -176,6 +176,7 @@ async function mainFetch(req, recursive, terminator) {
     req.currentUrl(),
     req.headerList,
     req.clientRid,
+    req.cacheMode,
     reqBody !== null || reqRid !== null,
     reqBody,
     reqRid,
@@ -0,0 +1,562 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! A cookie jar for `Deno.HttpClient`, implementing the storage model of
+//! RFC 6265, section 5.
+//!
+//! Cookies from `Set-Cookie` response headers are stored per client and sent
+//! back in the `Cookie` header of later requests that match their domain,
+//! path and `Secure` attribute. Persistent cookies can optionally be saved to
+//! a JSON file, so that they survive restarts.
+
+use std::net::IpAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Uri;
+use http::header::SET_COOKIE;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieJarOptions {
+  /// The file persistent cookies are loaded from and saved to. Without a
+  /// path, all cookies are kept in memory only.
+  pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cookie {
+  name: String,
+  value: String,
+  domain: String,
+  path: String,
+  /// Whether the cookie is only sent to the host that set it, because it had
+  /// no `Domain` attribute.
+  host_only: bool,
+  secure: bool,
+  http_only: bool,
+  /// The expiry time in seconds since the epoch, or `None` for a session
+  /// cookie.
+  expires: Option<i64>,
+  /// Orders cookies with paths of the same length by creation time.
+  creation_index: u64,
+}
+
+impl Cookie {
+  fn is_expired(&self, now: i64) -> bool {
+    self.expires.is_some_and(|expires| expires <= now)
+  }
+
+  fn matches(&self, request: &RequestUrl) -> bool {
+    let domain_matches = if self.host_only {
+      request.host == self.domain
+    } else {
+      domain_match(&request.host, &self.domain)
+    };
+    domain_matches
+      && path_match(&request.path, &self.path)
+      && (!self.secure || request.secure)
+  }
+}
+
+/// The parts of a request URL that cookies are matched against.
+struct RequestUrl {
+  host: String,
+  path: String,
+  secure: bool,
+}
+
+impl RequestUrl {
+  fn from_uri(uri: &Uri) -> Option<Self> {
+    Some(Self {
+      host: uri.host()?.to_ascii_lowercase(),
+      path: uri.path().to_string(),
+      secure: matches!(uri.scheme_str(), Some("https" | "wss")),
+    })
+  }
+}
+
+#[derive(Debug)]
+pub struct CookieJar {
+  cookies: Mutex<Vec<Cookie>>,
+  path: Option<PathBuf>,
+}
+
+impl CookieJar {
+  /// Creates a cookie jar, loading the persisted cookies from the file at
+  /// `options.path` if it exists.
+  pub fn new(options: CookieJarOptions) -> std::io::Result<Self> {
+    let mut cookies = match &options.path {
+      Some(path) => match std::fs::read(path) {
+        Ok(data) => serde_json::from_slice::<Vec<Cookie>>(&data)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => vec![],
+        Err(err) => return Err(err),
+      },
+      None => vec![],
+    };
+    let now = now();
+    cookies.retain(|cookie| !cookie.is_expired(now));
+    Ok(Self {
+      cookies: Mutex::new(cookies),
+      path: options.path,
+    })
+  }
+
+  /// Returns the value of the `Cookie` header for a request to `uri`, if any
+  /// stored cookies apply to it (RFC 6265, section 5.4).
+  pub fn cookie_header(&self, uri: &Uri) -> Option<HeaderValue> {
+    let request = RequestUrl::from_uri(uri)?;
+    let now = now();
+    let mut cookies = self.cookies.lock().unwrap();
+    cookies.retain(|cookie| !cookie.is_expired(now));
+    let mut matching = cookies
+      .iter()
+      .filter(|cookie| cookie.matches(&request))
+      .collect::<Vec<_>>();
+    if matching.is_empty() {
+      return None;
+    }
+    matching.sort_by(|a, b| {
+      b.path
+        .len()
+        .cmp(&a.path.len())
+        .then(a.creation_index.cmp(&b.creation_index))
+    });
+    let header = matching
+      .iter()
+      .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+      .collect::<Vec<_>>()
+      .join("; ");
+    HeaderValue::from_str(&header).ok()
+  }
+
+  /// Stores the cookies from the `Set-Cookie` headers of a response to a
+  /// request to `uri` (RFC 6265, section 5.3).
+  pub fn store_response_cookies(&self, uri: &Uri, headers: &HeaderMap) {
+    let Some(request) = RequestUrl::from_uri(uri) else {
+      return;
+    };
+    let now = now();
+    let mut cookies = self.cookies.lock().unwrap();
+    let mut persistent_changed = false;
+    for value in headers.get_all(SET_COOKIE) {
+      let Ok(value) = std::str::from_utf8(value.as_bytes()) else {
+        continue;
+      };
+      let Some(mut cookie) = parse_set_cookie(value, &request, now) else {
+        continue;
+      };
+      let existing = cookies.iter().position(|c| {
+        c.name == cookie.name
+          && c.domain == cookie.domain
+          && c.path == cookie.path
+      });
+      cookie.creation_index = match existing {
+        Some(index) => {
+          let existing = cookies.remove(index);
+          persistent_changed |= existing.expires.is_some();
+          existing.creation_index
+        }
+        None => cookies
+          .iter()
+          .map(|c| c.creation_index + 1)
+          .max()
+          .unwrap_or(0),
+      };
+      if !cookie.is_expired(now) {
+        persistent_changed |= cookie.expires.is_some();
+        cookies.push(cookie);
+      }
+    }
+    if persistent_changed {
+      if let Some(path) = &self.path {
+        // Failing to persist the cookies shouldn't fail the request, they
+        // are still available in memory.
+        let _ = save(path, &cookies, now);
+      }
+    }
+  }
+}
+
+/// Saves the persistent cookies to `path`, replacing the file atomically.
+fn save(path: &Path, cookies: &[Cookie], now: i64) -> std::io::Result<()> {
+  let persistent = cookies
+    .iter()
+    .filter(|cookie| cookie.expires.is_some() && !cookie.is_expired(now))
+    .collect::<Vec<_>>();
+  let data = serde_json::to_vec(&persistent)?;
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+  std::fs::write(&temp_path, data)?;
+  std::fs::rename(&temp_path, path).inspect_err(|_| {
+    let _ = std::fs::remove_file(&temp_path);
+  })
+}
+
+/// Parses a `Set-Cookie` header value received for `request` into a cookie,
+/// or returns `None` if the cookie must be ignored (RFC 6265, section 5.2).
+fn parse_set_cookie(
+  value: &str,
+  request: &RequestUrl,
+  now: i64,
+) -> Option<Cookie> {
+  let mut parts = value.split(';');
+  let (name, value) = parts.next()?.split_once('=')?;
+  let name = name.trim();
+  if name.is_empty() {
+    return None;
+  }
+
+  let mut domain = None;
+  let mut path = None;
+  let mut secure = false;
+  let mut http_only = false;
+  let mut max_age = None;
+  let mut expires = None;
+  for attribute in parts {
+    let (key, value) = match attribute.split_once('=') {
+      Some((key, value)) => (key.trim(), value.trim()),
+      None => (attribute.trim(), ""),
+    };
+    match key.to_ascii_lowercase().as_str() {
+      "expires" => {
+        if let Some(date) = parse_cookie_date(value) {
+          expires = Some(date);
+        }
+      }
+      "max-age" => {
+        let digits = value.strip_prefix('-').unwrap_or(value);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+          // An overflowing delta is clamped rather than ignored.
+          max_age = Some(match value.parse::<i64>() {
+            Ok(delta) if delta <= 0 => i64::MIN,
+            Ok(delta) => now.saturating_add(delta),
+            Err(_) if value.starts_with('-') => i64::MIN,
+            Err(_) => i64::MAX,
+          });
+        }
+      }
+      "domain" => {
+        let value = value.strip_prefix('.').unwrap_or(value);
+        if !value.is_empty() {
+          domain = Some(value.to_ascii_lowercase());
+        }
+      }
+      "path" => {
+        path = value.starts_with('/').then(|| value.to_string());
+      }
+      "secure" => secure = true,
+      "httponly" => http_only = true,
+      _ => {}
+    }
+  }
+
+  let (domain, host_only) = match domain {
+    Some(domain) if domain != request.host => {
+      // Without a public suffix list, at least reject top-level domains.
+      if !domain_match(&request.host, &domain) || !domain.contains('.') {
+        return None;
+      }
+      (domain, false)
+    }
+    Some(domain) => (domain, false),
+    None => (request.host.clone(), true),
+  };
+  // Insecure origins can't set secure cookies (RFC 6265bis, section 5.7).
+  if secure && !request.secure {
+    return None;
+  }
+
+  Some(Cookie {
+    name: name.to_string(),
+    value: value.trim().to_string(),
+    domain,
+    path: path.unwrap_or_else(|| default_path(&request.path)),
+    host_only,
+    secure,
+    http_only,
+    // Max-Age takes precedence over Expires.
+    expires: max_age.or(expires),
+    creation_index: 0,
+  })
+}
+
+/// https://www.rfc-editor.org/rfc/rfc6265#section-5.1.3
+fn domain_match(host: &str, domain: &str) -> bool {
+  if host == domain {
+    return true;
+  }
+  let is_ip = host
+    .trim_start_matches('[')
+    .trim_end_matches(']')
+    .parse::<IpAddr>()
+    .is_ok();
+  !is_ip
+    && host.len() > domain.len()
+    && host.ends_with(domain)
+    && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+}
+
+/// https://www.rfc-editor.org/rfc/rfc6265#section-5.1.4
+fn default_path(request_path: &str) -> String {
+  match request_path.rfind('/') {
+    Some(0) | None => "/".to_string(),
+    Some(index) => request_path[..index].to_string(),
+  }
+}
+
+/// https://www.rfc-editor.org/rfc/rfc6265#section-5.1.4
+fn path_match(request_path: &str, cookie_path: &str) -> bool {
+  let request_path = if request_path.is_empty() {
+    "/"
+  } else {
+    request_path
+  };
+  request_path == cookie_path
+    || (request_path.starts_with(cookie_path)
+      && (cookie_path.ends_with('/')
+        || request_path.as_bytes()[cookie_path.len()] == b'/'))
+}
+
+/// Parses a date in the lenient format accepted for the `Expires` attribute,
+/// returning seconds since the epoch (RFC 6265, section 5.1.1).
+fn parse_cookie_date(input: &str) -> Option<i64> {
+  const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct",
+    "nov", "dec",
+  ];
+
+  fn is_delimiter(c: char) -> bool {
+    matches!(c, '\x09' | '\x20'..='\x2f' | '\x3b'..='\x40' | '\x5b'..='\x60' | '\x7b'..='\x7e')
+  }
+
+  /// Parses `min..=max` leading digits, which may only be followed by a
+  /// non-digit.
+  fn leading_digits(token: &str, min: usize, max: usize) -> Option<u32> {
+    let len = token.bytes().take_while(|b| b.is_ascii_digit()).count();
+    if len < min || len > max {
+      return None;
+    }
+    token[..len].parse().ok()
+  }
+
+  let mut time = None;
+  let mut day = None;
+  let mut month = None;
+  let mut year = None;
+  for token in input.split(is_delimiter).filter(|t| !t.is_empty()) {
+    if time.is_none() {
+      let mut parts = token.splitn(3, ':');
+      if let (Some(hour), Some(minute), Some(second)) =
+        (parts.next(), parts.next(), parts.next())
+      {
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        let hour = leading_digits(hour, 1, 2).filter(|_| all_digits(hour));
+        let minute =
+          leading_digits(minute, 1, 2).filter(|_| all_digits(minute));
+        if let (Some(hour), Some(minute), Some(second)) =
+          (hour, minute, leading_digits(second, 1, 2))
+        {
+          time = Some((hour, minute, second));
+          continue;
+        }
+      }
+    }
+    if day.is_none() {
+      if let Some(value) = leading_digits(token, 1, 2) {
+        day = Some(value);
+        continue;
+      }
+    }
+    if month.is_none() && token.len() >= 3 {
+      let prefix = token[..3].to_ascii_lowercase();
+      if let Some(index) = MONTHS.iter().position(|m| *m == prefix) {
+        month = Some(index as u32 + 1);
+        continue;
+      }
+    }
+    if year.is_none() {
+      if let Some(value) = leading_digits(token, 2, 4) {
+        year = Some(value);
+        continue;
+      }
+    }
+  }
+
+  let (hour, minute, second) = time?;
+  let (day, month, mut year) = (day?, month?, year?);
+  if (70..=99).contains(&year) {
+    year += 1900;
+  } else if year <= 69 {
+    year += 2000;
+  }
+  if !(1..=31).contains(&day)
+    || year < 1601
+    || hour > 23
+    || minute > 59
+    || second > 59
+  {
+    return None;
+  }
+  let days = days_from_civil(year as i64, month, day)?;
+  Some(days * 86400 + hour as i64 * 3600 + minute as i64 * 60 + second as i64)
+}
+
+/// Returns the number of days since the epoch for a date in the proleptic
+/// Gregorian calendar, or `None` if the day doesn't exist in the month.
+fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+  let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+  let days_in_month = match month {
+    2 if leap => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  };
+  if day > days_in_month {
+    return None;
+  }
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let month = month as i64;
+  let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2)
+    / 5
+    + day as i64
+    - 1;
+  let day_of_era =
+    year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  Some(era * 146097 + day_of_era - 719468)
+}
+
+fn now() -> i64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs() as i64)
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn jar_with(uri: &str, set_cookies: &[&'static str]) -> CookieJar {
+    let jar = CookieJar::new(CookieJarOptions::default()).unwrap();
+    store(&jar, uri, set_cookies);
+    jar
+  }
+
+  fn store(jar: &CookieJar, uri: &str, set_cookies: &[&'static str]) {
+    let mut headers = HeaderMap::new();
+    for value in set_cookies {
+      headers.append(SET_COOKIE, HeaderValue::from_static(value));
+    }
+    jar.store_response_cookies(&uri.parse().unwrap(), &headers);
+  }
+
+  fn cookies(jar: &CookieJar, uri: &str) -> Option<String> {
+    jar
+      .cookie_header(&uri.parse().unwrap())
+      .map(|value| value.to_str().unwrap().to_string())
+  }
+
+  #[test]
+  fn domain_and_path() {
+    let jar = jar_with(
+      "https://www.example.com/a/b",
+      &[
+        "host=1",
+        "domain=2; Domain=.Example.com",
+        "root=3; Path=/",
+        "other=4; Domain=example.org",
+        "tld=5; Domain=com",
+      ],
+    );
+    assert_eq!(
+      cookies(&jar, "https://www.example.com/a/c").as_deref(),
+      Some("host=1; domain=2; root=3")
+    );
+    assert_eq!(
+      cookies(&jar, "https://api.example.com/a").as_deref(),
+      Some("domain=2; root=3")
+    );
+    assert_eq!(
+      cookies(&jar, "https://www.example.com/").as_deref(),
+      Some("root=3")
+    );
+    assert_eq!(cookies(&jar, "https://example.org/"), None);
+  }
+
+  #[test]
+  fn secure_and_expiry() {
+    let jar = jar_with(
+      "https://example.com/",
+      &[
+        "secure=1; Secure",
+        "expired=2; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+        "max_age=3; Max-Age=60; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+      ],
+    );
+    assert_eq!(
+      cookies(&jar, "https://example.com/").as_deref(),
+      Some("secure=1; max_age=3")
+    );
+    assert_eq!(
+      cookies(&jar, "http://example.com/").as_deref(),
+      Some("max_age=3")
+    );
+
+    // A cookie is removed by setting it again with an expiry in the past.
+    store(&jar, "https://example.com/", &["max_age=; Max-Age=0"]);
+    assert_eq!(cookies(&jar, "http://example.com/"), None,);
+
+    // Insecure origins can't set secure cookies.
+    store(&jar, "http://example.com/", &["insecure=1; Secure"]);
+    assert_eq!(cookies(&jar, "http://example.com/"), None);
+  }
+
+  #[test]
+  fn persistence() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = CookieJarOptions {
+      path: Some(dir.path().join("cookies.json")),
+    };
+    let jar = CookieJar::new(options.clone()).unwrap();
+    store(
+      &jar,
+      "https://example.com/",
+      &["session=1", "persistent=2; Max-Age=3600"],
+    );
+    let jar = CookieJar::new(options).unwrap();
+    assert_eq!(
+      cookies(&jar, "https://example.com/").as_deref(),
+      Some("persistent=2")
+    );
+  }
+
+  #[test]
+  fn cookie_dates() {
+    assert_eq!(
+      parse_cookie_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+      Some(784111777)
+    );
+    assert_eq!(
+      parse_cookie_date("Sunday, 06-Nov-94 08:49:37 GMT"),
+      Some(784111777)
+    );
+    assert_eq!(
+      parse_cookie_date("Sun Nov  6 08:49:37 1994"),
+      Some(784111777)
+    );
+    assert_eq!(parse_cookie_date("Wed, 31 Feb 2024 00:00:00 GMT"), None);
+    assert_eq!(parse_cookie_date("not a date"), None);
+  }
+}
@@ -1,16 +1,25 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
+use std::collections::HashMap;
 use std::future::Future;
 use std::io;
+use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Poll;
 use std::task::{self};
+use std::time::Duration;
 use std::vec;
 
+use hickory_resolver::config::NameServerConfig;
+use hickory_resolver::config::NameServerConfigGroup;
+use hickory_resolver::config::ResolverConfig;
+use hickory_resolver::config::ResolverOpts;
 use hickory_resolver::name_server::TokioConnectionProvider;
+use hickory_resolver::proto::xfer::Protocol;
 use hyper_util::client::legacy::connect::dns::GaiResolver;
 use hyper_util::client::legacy::connect::dns::Name;
+use serde::Deserialize;
 use tokio::task::JoinHandle;
 use tower::Service;
 
@@ -64,6 +73,173 @@ impl Resolver {
   ) -> Self {
     Self::Hickory(resolver)
   }
+
+  /// Create a resolver from the `dns` option of `Deno.createHttpClient()`.
+  ///
+  /// Name servers and the cache TTL are applied to a hickory resolver; without
+  /// them, the system resolver is used. Static hosts take precedence over
+  /// either.
+  pub fn from_options(options: DnsOptions) -> Result<Self, DnsOptionsError> {
+    let resolver =
+      if options.nameservers.is_empty() && options.cache_ttl.is_none() {
+        Self::default()
+      } else {
+        let (config, mut opts) = if options.nameservers.is_empty() {
+          hickory_resolver::system_conf::read_system_conf()?
+        } else {
+          let mut group = NameServerConfigGroup::new();
+          for name_server in options.name_servers()? {
+            match options.protocol {
+              DnsProtocol::Udp => group
+                .push(NameServerConfig::new(name_server.addr, Protocol::Udp)),
+              DnsProtocol::Tcp => group
+                .push(NameServerConfig::new(name_server.addr, Protocol::Tcp)),
+              DnsProtocol::Https => {
+                group.merge(NameServerConfigGroup::from_ips_https(
+                  &[name_server.addr.ip()],
+                  name_server.addr.port(),
+                  name_server
+                    .tls_name
+                    .unwrap_or_else(|| name_server.addr.ip().to_string()),
+                  true,
+                ))
+              }
+            }
+          }
+          (
+            ResolverConfig::from_parts(None, vec![], group),
+            ResolverOpts::default(),
+          )
+        };
+        if let Some(cache_ttl) = options.cache_ttl {
+          opts.positive_max_ttl = Some(Duration::from_secs(cache_ttl));
+          opts.negative_max_ttl = Some(Duration::from_secs(cache_ttl));
+        }
+        Self::Hickory(hickory_resolver::Resolver::tokio(config, opts))
+      };
+
+    if options.static_hosts.is_empty() {
+      return Ok(resolver);
+    }
+    let hosts = options
+      .static_hosts
+      .into_iter()
+      .map(|(host, addrs)| (normalize_host(&host), addrs))
+      .collect();
+    Ok(Self::Custom(Arc::new(StaticHostsResolver {
+      hosts,
+      fallback: resolver,
+    })))
+  }
+}
+
+/// The protocol used to query custom name servers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsProtocol {
+  #[default]
+  Udp,
+  Tcp,
+  /// DNS-over-HTTPS (RFC 8484).
+  Https,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DnsOptions {
+  /// Name servers to query instead of the system ones, in the
+  /// `IP[:PORT][#SERVERNAME]` form. The server name is the TLS name used to
+  /// verify DNS-over-HTTPS servers, and defaults to the IP address.
+  pub nameservers: Vec<String>,
+  pub protocol: DnsProtocol,
+  /// The upper bound for how long answers are cached, in seconds.
+  pub cache_ttl: Option<u64>,
+  /// Host names that resolve to fixed addresses without querying DNS.
+  pub static_hosts: HashMap<String, Vec<IpAddr>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameServer {
+  pub addr: SocketAddr,
+  pub tls_name: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error, deno_error::JsError)]
+pub enum DnsOptionsError {
+  #[class(type)]
+  #[error("Invalid name server: {0}")]
+  InvalidNameServer(String),
+  #[class(type)]
+  #[error(
+    "Invalid name server: {0}, a server name can only be set with the 'https' protocol"
+  )]
+  UnexpectedServerName(String),
+  #[class(generic)]
+  #[error(transparent)]
+  Resolve(#[from] hickory_resolver::ResolveError),
+}
+
+impl DnsOptions {
+  /// Parses the configured name servers, with the default port of the
+  /// protocol if none is given.
+  pub fn name_servers(&self) -> Result<Vec<NameServer>, DnsOptionsError> {
+    let default_port = match self.protocol {
+      DnsProtocol::Udp | DnsProtocol::Tcp => 53,
+      DnsProtocol::Https => 443,
+    };
+    self
+      .nameservers
+      .iter()
+      .map(|name_server| {
+        let (addr, tls_name) = match name_server.split_once('#') {
+          Some((addr, tls_name)) => (addr, Some(tls_name.to_string())),
+          None => (name_server.as_str(), None),
+        };
+        if tls_name.is_some() && self.protocol != DnsProtocol::Https {
+          return Err(DnsOptionsError::UnexpectedServerName(
+            name_server.clone(),
+          ));
+        }
+        let addr = addr
+          .parse::<SocketAddr>()
+          .or_else(|_| {
+            addr
+              .trim_start_matches('[')
+              .trim_end_matches(']')
+              .parse::<IpAddr>()
+              .map(|ip| SocketAddr::new(ip, default_port))
+          })
+          .map_err(|_| {
+            DnsOptionsError::InvalidNameServer(name_server.clone())
+          })?;
+        Ok(NameServer { addr, tls_name })
+      })
+      .collect()
+  }
+}
+
+fn normalize_host(host: &str) -> String {
+  host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Resolves the static hosts of [`DnsOptions`], and everything else with
+/// the fallback resolver.
+#[derive(Debug)]
+struct StaticHostsResolver {
+  hosts: HashMap<String, Vec<IpAddr>>,
+  fallback: Resolver,
+}
+
+impl Resolve for StaticHostsResolver {
+  fn resolve(&self, name: Name) -> Resolving {
+    if let Some(addrs) = self.hosts.get(&normalize_host(name.as_str())) {
+      let addrs: Vec<_> =
+        addrs.iter().map(|ip| SocketAddr::new(*ip, 0)).collect();
+      return Box::pin(async move { Ok(addrs.into_iter()) });
+    }
+    let mut fallback = self.fallback.clone();
+    Box::pin(async move { fallback.call(name).await })
+  }
 }
 
 type SocketAddrs = vec::IntoIter<SocketAddr>;
@@ -166,4 +342,87 @@ mod tests {
     let addr = addr.next().unwrap();
     assert_eq!(addr, "127.0.0.1:8080".parse().unwrap());
   }
+
+  #[tokio::test]
+  async fn static_hosts() {
+    let mut resolver = Resolver::from_options(DnsOptions {
+      static_hosts: HashMap::from([(
+        "Example.test.".to_string(),
+        vec!["127.0.0.1".parse().unwrap(), "::1".parse().unwrap()],
+      )]),
+      ..Default::default()
+    })
+    .unwrap();
+    let addrs = resolver
+      .call(Name::from_str("example.test").unwrap())
+      .await
+      .unwrap()
+      .collect::<Vec<_>>();
+    assert_eq!(
+      addrs,
+      vec![
+        "127.0.0.1:0".parse::<SocketAddr>().unwrap(),
+        "[::1]:0".parse().unwrap()
+      ]
+    );
+  }
+
+  #[test]
+  fn name_servers() {
+    let options = DnsOptions {
+      nameservers: vec![
+        "1.1.1.1".to_string(),
+        "8.8.8.8:5353".to_string(),
+        "2606:4700:4700::1111".to_string(),
+        "[::1]:5353".to_string(),
+      ],
+      ..Default::default()
+    };
+    let addrs = options
+      .name_servers()
+      .unwrap()
+      .into_iter()
+      .map(|name_server| name_server.addr.to_string())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      addrs,
+      vec![
+        "1.1.1.1:53",
+        "8.8.8.8:5353",
+        "[2606:4700:4700::1111]:53",
+        "[::1]:5353"
+      ]
+    );
+
+    let options = DnsOptions {
+      nameservers: vec!["1.1.1.1#cloudflare-dns.com".to_string()],
+      protocol: DnsProtocol::Https,
+      ..Default::default()
+    };
+    assert_eq!(
+      options.name_servers().unwrap(),
+      vec![NameServer {
+        addr: "1.1.1.1:443".parse().unwrap(),
+        tls_name: Some("cloudflare-dns.com".to_string()),
+      }]
+    );
+
+    let options = DnsOptions {
+      nameservers: vec!["1.1.1.1#cloudflare-dns.com".to_string()],
+      ..Default::default()
+    };
+    assert!(matches!(
+      options.name_servers(),
+      Err(DnsOptionsError::UnexpectedServerName(_))
+    ));
+
+    let options = DnsOptions {
+      nameservers: vec!["dns.example".to_string()],
+      ..Default::default()
+    };
+    assert!(matches!(
+      options.name_servers(),
+      Err(DnsOptionsError::InvalidNameServer(_))
+    ));
+  }
 }
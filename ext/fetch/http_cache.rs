@@ -0,0 +1,873 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! A private HTTP cache for `fetch()`, as specified by RFC 9111 and the
+//! "HTTP-network-or-cache fetch" steps of the Fetch standard.
+//!
+//! The cache is opt-in per `Deno.HttpClient` and stores one response per URL
+//! on disk. Each entry is a single file named after the SHA-256 hash of the
+//! URL, containing a line of JSON metadata followed by the response body.
+
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use bytes::Bytes;
+use deno_core::url::Url;
+use deno_error::JsErrorBox;
+use http::HeaderMap;
+use http::Method;
+use http::StatusCode;
+use http::Uri;
+use http::header::AGE;
+use http::header::CACHE_CONTROL;
+use http::header::CONNECTION;
+use http::header::CONTENT_ENCODING;
+use http::header::CONTENT_LENGTH;
+use http::header::CONTENT_LOCATION;
+use http::header::CONTENT_RANGE;
+use http::header::DATE;
+use http::header::ETAG;
+use http::header::EXPIRES;
+use http::header::HeaderName;
+use http::header::HeaderValue;
+use http::header::IF_MATCH;
+use http::header::IF_MODIFIED_SINCE;
+use http::header::IF_NONE_MATCH;
+use http::header::IF_RANGE;
+use http::header::IF_UNMODIFIED_SINCE;
+use http::header::LAST_MODIFIED;
+use http::header::LOCATION;
+use http::header::PRAGMA;
+use http::header::RANGE;
+use http::header::TE;
+use http::header::TRANSFER_ENCODING;
+use http::header::UPGRADE;
+use http::header::VARY;
+use http_body_util::BodyExt;
+use hyper::body::Body;
+use hyper::body::Frame;
+use hyper::body::SizeHint;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+
+use crate::Client;
+use crate::FetchError;
+use crate::ReqBody;
+use crate::ResBody;
+
+/// Responses with a larger body are passed through without being stored.
+const DEFAULT_MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The upper bound for heuristic freshness, which is otherwise a tenth of the
+/// time since the response was last modified (RFC 9111, section 4.2.2).
+const MAX_HEURISTIC_FRESHNESS: u64 = 24 * 60 * 60;
+
+/// Status codes that are cacheable by default (RFC 9110, section 15.1).
+const HEURISTICALLY_CACHEABLE: &[u16] =
+  &[200, 203, 204, 300, 301, 308, 404, 405, 410, 414, 501];
+
+static CONDITIONAL_HEADERS: &[HeaderName] = &[
+  IF_MODIFIED_SINCE,
+  IF_NONE_MATCH,
+  IF_UNMODIFIED_SINCE,
+  IF_MATCH,
+  IF_RANGE,
+];
+
+/// Hop-by-hop headers, which describe the connection rather than the
+/// response and are therefore never stored.
+static UNSTORED_HEADERS: &[HeaderName] =
+  &[CONNECTION, TE, TRANSFER_ENCODING, UPGRADE];
+
+/// Headers that aren't updated by a `304 Not Modified` response, since they
+/// describe the stored body (RFC 9111, section 3.2).
+static UNUPDATED_HEADERS: &[HeaderName] =
+  &[CONTENT_LENGTH, CONTENT_ENCODING, CONTENT_RANGE];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpCacheOptions {
+  /// The directory the cache entries are stored in. It's created on the first
+  /// write if it doesn't exist.
+  pub path: PathBuf,
+  /// The maximum size of a response body to store, in bytes.
+  pub max_entry_size: Option<u64>,
+}
+
+/// The cache mode of a request, as set by the `cache` option of `fetch()`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RequestCacheMode {
+  #[default]
+  Default,
+  NoStore,
+  Reload,
+  NoCache,
+  ForceCache,
+  OnlyIfCached,
+}
+
+#[derive(Debug)]
+pub struct HttpCache {
+  dir: PathBuf,
+  max_entry_size: u64,
+}
+
+impl HttpCache {
+  pub fn new(options: HttpCacheOptions) -> Self {
+    Self {
+      dir: options.path,
+      max_entry_size: options.max_entry_size.unwrap_or(DEFAULT_MAX_ENTRY_SIZE),
+    }
+  }
+
+  fn entry_path(&self, url: &str) -> PathBuf {
+    let hash = sha2::Sha256::digest(url.as_bytes());
+    self.dir.join(faster_hex::hex_string(&hash))
+  }
+
+  /// Returns the stored response for `url`, if any, and if it was selected by
+  /// the same values of the headers nominated by its `Vary` header.
+  async fn get(
+    &self,
+    url: &str,
+    headers: &HeaderMap,
+  ) -> Option<CachedResponse> {
+    let data = tokio::fs::read(self.entry_path(url)).await.ok()?;
+    let newline = data.iter().position(|b| *b == b'\n')?;
+    let metadata =
+      serde_json::from_slice::<CachedMetadata>(&data[..newline]).ok()?;
+    if metadata.url != url
+      || metadata
+        .vary
+        .iter()
+        .any(|(name, value)| header_value(headers, name) != *value)
+    {
+      return None;
+    }
+    let body = Bytes::from(data).slice(newline + 1..);
+    CachedResponse::from_metadata(metadata, body)
+  }
+
+  fn put(self: &Arc<Self>, entry: CachedResponse) {
+    let cache = self.clone();
+    tokio::task::spawn_blocking(move || {
+      // The cache is best effort, a failed write only means a cache miss later.
+      let _ = cache.write(&entry);
+    });
+  }
+
+  fn write(&self, entry: &CachedResponse) -> std::io::Result<()> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    std::fs::create_dir_all(&self.dir)?;
+    let mut data = serde_json::to_vec(&entry.to_metadata())?;
+    data.push(b'\n');
+    data.extend_from_slice(&entry.body);
+    // Write to a temporary file first, so that concurrent readers never see a
+    // partially written entry.
+    let path = self.entry_path(&entry.url);
+    let temp_path = path.with_extension(format!(
+      "{}.{}.tmp",
+      std::process::id(),
+      COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&temp_path, data)?;
+    std::fs::rename(&temp_path, &path).inspect_err(|_| {
+      let _ = std::fs::remove_file(&temp_path);
+    })
+  }
+
+  async fn remove(&self, url: &str) {
+    let _ = tokio::fs::remove_file(self.entry_path(url)).await;
+  }
+
+  /// Invalidates the stored responses after a successful request with an
+  /// unsafe method (RFC 9111, section 4.4).
+  async fn invalidate(&self, url: &str, response_headers: &HeaderMap) {
+    self.remove(url).await;
+    let Ok(base) = Url::parse(url) else {
+      return;
+    };
+    for name in [LOCATION, CONTENT_LOCATION] {
+      let Some(location) = response_headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| base.join(value).ok())
+      else {
+        continue;
+      };
+      if location.origin() == base.origin() {
+        self.remove(&cache_key(&location)).await;
+      }
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedMetadata {
+  url: String,
+  status: u16,
+  headers: Vec<(String, String)>,
+  vary: Vec<(String, Option<String>)>,
+  request_time: u64,
+  response_time: u64,
+}
+
+struct CachedResponse {
+  url: String,
+  status: StatusCode,
+  headers: HeaderMap,
+  /// The request headers nominated by the `Vary` response header, with the
+  /// values they had in the request that the response was stored for.
+  vary: Vec<(String, Option<String>)>,
+  /// The times, in seconds since the epoch, at which the request that
+  /// produced the response was sent and at which the response was received.
+  request_time: u64,
+  response_time: u64,
+  body: Bytes,
+}
+
+impl CachedResponse {
+  fn from_metadata(metadata: CachedMetadata, body: Bytes) -> Option<Self> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in metadata.headers {
+      headers.append(
+        HeaderName::from_bytes(name.as_bytes()).ok()?,
+        HeaderValue::from_str(&value).ok()?,
+      );
+    }
+    Some(Self {
+      url: metadata.url,
+      status: StatusCode::from_u16(metadata.status).ok()?,
+      headers,
+      vary: metadata.vary,
+      request_time: metadata.request_time,
+      response_time: metadata.response_time,
+      body,
+    })
+  }
+
+  fn to_metadata(&self) -> CachedMetadata {
+    CachedMetadata {
+      url: self.url.clone(),
+      status: self.status.as_u16(),
+      headers: self
+        .headers
+        .iter()
+        .filter_map(|(name, value)| {
+          Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect(),
+      vary: self.vary.clone(),
+      request_time: self.request_time,
+      response_time: self.response_time,
+    }
+  }
+
+  /// The freshness lifetime in seconds (RFC 9111, section 4.2.1).
+  fn freshness_lifetime(&self, cache_control: &CacheControl) -> u64 {
+    if let Some(max_age) = cache_control.max_age {
+      return max_age;
+    }
+    let date = header_date(&self.headers, DATE).unwrap_or(self.response_time);
+    if let Some(expires) = self.headers.get(EXPIRES) {
+      // An invalid date, like "0", means that the response already expired.
+      return parse_date(expires)
+        .map(|expires| expires.saturating_sub(date))
+        .unwrap_or(0);
+    }
+    match header_date(&self.headers, LAST_MODIFIED) {
+      Some(last_modified)
+        if HEURISTICALLY_CACHEABLE.contains(&self.status.as_u16()) =>
+      {
+        (date.saturating_sub(last_modified) / 10).min(MAX_HEURISTIC_FRESHNESS)
+      }
+      _ => 0,
+    }
+  }
+
+  /// The current age in seconds (RFC 9111, section 4.2.3).
+  fn current_age(&self, now: u64) -> u64 {
+    let age_value = self
+      .headers
+      .get(AGE)
+      .and_then(|value| value.to_str().ok()?.trim().parse::<u64>().ok())
+      .unwrap_or(0);
+    let date = header_date(&self.headers, DATE).unwrap_or(self.response_time);
+    let apparent_age = self.response_time.saturating_sub(date);
+    let response_delay = self.response_time.saturating_sub(self.request_time);
+    let corrected_initial_age = apparent_age.max(age_value + response_delay);
+    let resident_time = now.saturating_sub(self.response_time);
+    corrected_initial_age + resident_time
+  }
+
+  /// Whether the response can be used for a request with the given
+  /// `Cache-Control` directives without revalidating it first.
+  fn is_fresh(&self, request_cache_control: &CacheControl, now: u64) -> bool {
+    let cache_control = CacheControl::from_headers(&self.headers);
+    if cache_control.no_cache {
+      return false;
+    }
+    let lifetime = self.freshness_lifetime(&cache_control);
+    let age = self.current_age(now);
+    if request_cache_control
+      .max_age
+      .is_some_and(|max_age| age > max_age)
+    {
+      return false;
+    }
+    let age = age + request_cache_control.min_fresh.unwrap_or(0);
+    if age < lifetime {
+      return true;
+    }
+    match request_cache_control.max_stale {
+      Some(_) if cache_control.must_revalidate => false,
+      Some(None) => true,
+      Some(Some(max_stale)) => age < lifetime + max_stale,
+      None => false,
+    }
+  }
+
+  /// Updates the stored headers with the ones of a `304 Not Modified`
+  /// response (RFC 9111, section 4.3.4).
+  fn freshen(
+    &mut self,
+    headers: &HeaderMap,
+    request_time: u64,
+    response_time: u64,
+  ) {
+    for name in headers.keys() {
+      if UNSTORED_HEADERS.contains(name) || UNUPDATED_HEADERS.contains(name) {
+        continue;
+      }
+      self.headers.remove(name);
+      for value in headers.get_all(name) {
+        self.headers.append(name.clone(), value.clone());
+      }
+    }
+    self.request_time = request_time;
+    self.response_time = response_time;
+  }
+
+  fn to_response(&self, now: u64) -> http::Response<ResBody> {
+    let body = http_body_util::Full::new(self.body.clone())
+      .map_err(|never| match never {})
+      .boxed();
+    let mut response = http::Response::new(body);
+    *response.status_mut() = self.status;
+    *response.headers_mut() = self.headers.clone();
+    response
+      .headers_mut()
+      .insert(AGE, HeaderValue::from(self.current_age(now)));
+    response
+  }
+}
+
+/// The `Cache-Control` directives that affect a private cache.
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+  no_store: bool,
+  no_cache: bool,
+  must_revalidate: bool,
+  public: bool,
+  private: bool,
+  only_if_cached: bool,
+  max_age: Option<u64>,
+  max_stale: Option<Option<u64>>,
+  min_fresh: Option<u64>,
+}
+
+impl CacheControl {
+  fn from_headers(headers: &HeaderMap) -> Self {
+    let mut cache_control = Self::default();
+    for value in headers.get_all(CACHE_CONTROL) {
+      let Ok(value) = value.to_str() else {
+        continue;
+      };
+      for directive in value.split(',') {
+        let (name, argument) = match directive.split_once('=') {
+          Some((name, argument)) => {
+            (name.trim(), Some(argument.trim().trim_matches('"')))
+          }
+          None => (directive.trim(), None),
+        };
+        // An invalid delta-seconds value is treated as zero, which is the
+        // safe choice for all directives that take one.
+        let seconds =
+          || argument.and_then(|a| a.parse::<u64>().ok()).unwrap_or(0);
+        match name.to_ascii_lowercase().as_str() {
+          "no-store" => cache_control.no_store = true,
+          "no-cache" => cache_control.no_cache = true,
+          "must-revalidate" => cache_control.must_revalidate = true,
+          "public" => cache_control.public = true,
+          "private" => cache_control.private = true,
+          "only-if-cached" => cache_control.only_if_cached = true,
+          "max-age" => cache_control.max_age = Some(seconds()),
+          "max-stale" => {
+            cache_control.max_stale = Some(argument.map(|_| seconds()))
+          }
+          "min-fresh" => cache_control.min_fresh = Some(seconds()),
+          _ => {}
+        }
+      }
+    }
+    cache_control
+  }
+}
+
+/// Sends `request` with `client`, going through the HTTP cache of the client
+/// if it has one.
+pub async fn send(
+  client: Client,
+  mut request: http::Request<ReqBody>,
+  mut mode: RequestCacheMode,
+) -> Result<http::Response<ResBody>, FetchError> {
+  // https://fetch.spec.whatwg.org/#http-network-or-cache-fetch
+  if mode == RequestCacheMode::Default
+    && CONDITIONAL_HEADERS
+      .iter()
+      .any(|name| request.headers().contains_key(name))
+  {
+    mode = RequestCacheMode::NoStore;
+  }
+  let headers = request.headers_mut();
+  match mode {
+    RequestCacheMode::NoCache if !headers.contains_key(CACHE_CONTROL) => {
+      headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=0"));
+    }
+    RequestCacheMode::NoStore | RequestCacheMode::Reload => {
+      if !headers.contains_key(PRAGMA) {
+        headers.insert(PRAGMA, HeaderValue::from_static("no-cache"));
+      }
+      if !headers.contains_key(CACHE_CONTROL) {
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+      }
+    }
+    _ => {}
+  }
+
+  let request_cache_control = CacheControl::from_headers(request.headers());
+  if request_cache_control.only_if_cached {
+    mode = RequestCacheMode::OnlyIfCached;
+  }
+
+  let Some(cache) = client.cache.clone() else {
+    if mode == RequestCacheMode::OnlyIfCached {
+      return Err(FetchError::NotInCache);
+    }
+    return Ok(client.send(request).await?);
+  };
+
+  let url = cache_key_for_uri(request.uri());
+  if !request.method().is_safe() {
+    let response = client.send(request).await?;
+    let status = response.status();
+    if status.is_success() || status.is_redirection() {
+      cache.invalidate(&url, response.headers()).await;
+    }
+    return Ok(response);
+  }
+
+  // Only full `GET` responses are stored.
+  if request.method() != Method::GET
+    || mode == RequestCacheMode::NoStore
+    || request_cache_control.no_store
+    || request.headers().contains_key(RANGE)
+  {
+    if mode == RequestCacheMode::OnlyIfCached {
+      return Err(FetchError::NotInCache);
+    }
+    return Ok(client.send(request).await?);
+  }
+
+  let stored = match mode {
+    RequestCacheMode::Reload => None,
+    _ => cache.get(&url, request.headers()).await,
+  };
+  let request_headers = request.headers().clone();
+  let request_time = now();
+
+  let Some(mut stored) = stored else {
+    if mode == RequestCacheMode::OnlyIfCached {
+      return Err(FetchError::NotInCache);
+    }
+    let response = client.send(request).await?;
+    return Ok(store_response(
+      &cache,
+      url,
+      &request_headers,
+      response,
+      request_time,
+    ));
+  };
+
+  let use_stored = match mode {
+    RequestCacheMode::ForceCache | RequestCacheMode::OnlyIfCached => true,
+    RequestCacheMode::Default => {
+      !request_cache_control.no_cache
+        && stored.is_fresh(&request_cache_control, request_time)
+    }
+    _ => false,
+  };
+  if use_stored {
+    return Ok(stored.to_response(request_time));
+  }
+
+  // Revalidate the stored response (RFC 9111, section 4.3.1).
+  let headers = request.headers_mut();
+  if let Some(etag) = stored.headers.get(ETAG) {
+    headers.insert(IF_NONE_MATCH, etag.clone());
+  }
+  if let Some(last_modified) = stored.headers.get(LAST_MODIFIED) {
+    headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+  }
+  let response = client.send(request).await?;
+  if response.status() != StatusCode::NOT_MODIFIED {
+    return Ok(store_response(
+      &cache,
+      url,
+      &request_headers,
+      response,
+      request_time,
+    ));
+  }
+  let response_time = now();
+  stored.freshen(response.headers(), request_time, response_time);
+  let response = stored.to_response(response_time);
+  if !CacheControl::from_headers(&stored.headers).no_store {
+    cache.put(stored);
+  }
+  Ok(response)
+}
+
+/// Wraps the body of `response` so that the response gets stored once the
+/// body has been read to the end, if it's storable.
+fn store_response(
+  cache: &Arc<HttpCache>,
+  url: String,
+  request_headers: &HeaderMap,
+  response: http::Response<ResBody>,
+  request_time: u64,
+) -> http::Response<ResBody> {
+  let (parts, body) = response.into_parts();
+  let Some(vary) = storable_vary(request_headers, parts.status, &parts.headers)
+  else {
+    return http::Response::from_parts(parts, body);
+  };
+  if body
+    .size_hint()
+    .exact()
+    .is_some_and(|size| size > cache.max_entry_size)
+  {
+    return http::Response::from_parts(parts, body);
+  }
+
+  let mut headers = parts.headers.clone();
+  for name in UNSTORED_HEADERS {
+    headers.remove(name);
+  }
+  let entry = CachedResponse {
+    url,
+    status: parts.status,
+    headers,
+    vary,
+    request_time,
+    response_time: now(),
+    body: Bytes::new(),
+  };
+  let mut body = CachingBody {
+    inner: body,
+    buffer: Vec::new(),
+    pending: Some((cache.clone(), entry)),
+  };
+  // An empty body might never be polled, so it's stored right away.
+  if body.inner.is_end_stream() {
+    body.finish();
+  }
+  http::Response::from_parts(parts, body.boxed())
+}
+
+/// Returns the request headers nominated by the `Vary` header of the
+/// response if the response may be stored (RFC 9111, section 3), or `None`
+/// if it may not.
+fn storable_vary(
+  request_headers: &HeaderMap,
+  status: StatusCode,
+  headers: &HeaderMap,
+) -> Option<Vec<(String, Option<String>)>> {
+  let cache_control = CacheControl::from_headers(headers);
+  if cache_control.no_store
+    || CacheControl::from_headers(request_headers).no_store
+    || status.is_informational()
+    || status == StatusCode::PARTIAL_CONTENT
+    || status == StatusCode::NOT_MODIFIED
+  {
+    return None;
+  }
+  let has_explicit_freshness = cache_control.max_age.is_some()
+    || cache_control.public
+    || cache_control.private
+    || headers.contains_key(EXPIRES);
+  if !has_explicit_freshness
+    && !HEURISTICALLY_CACHEABLE.contains(&status.as_u16())
+  {
+    return None;
+  }
+
+  let mut vary = Vec::new();
+  for value in headers.get_all(VARY) {
+    for name in value.to_str().ok()?.split(',') {
+      let name = name.trim().to_ascii_lowercase();
+      if name == "*" {
+        return None;
+      }
+      if !name.is_empty() {
+        let value = header_value(request_headers, &name);
+        vary.push((name, value));
+      }
+    }
+  }
+  Some(vary)
+}
+
+/// A response body that buffers the data it yields, and stores the response
+/// in the cache once the end of the body is reached.
+struct CachingBody {
+  inner: ResBody,
+  buffer: Vec<u8>,
+  pending: Option<(Arc<HttpCache>, CachedResponse)>,
+}
+
+impl CachingBody {
+  fn finish(&mut self) {
+    if let Some((cache, mut entry)) = self.pending.take() {
+      entry.body = std::mem::take(&mut self.buffer).into();
+      cache.put(entry);
+    }
+  }
+}
+
+impl Body for CachingBody {
+  type Data = Bytes;
+  type Error = JsErrorBox;
+
+  fn poll_frame(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Result<Frame<Bytes>, JsErrorBox>>> {
+    let this = &mut *self;
+    let result = std::task::ready!(Pin::new(&mut this.inner).poll_frame(cx));
+    match &result {
+      Some(Ok(frame)) => {
+        if let (Some(data), Some((cache, _))) =
+          (frame.data_ref(), &this.pending)
+        {
+          if (this.buffer.len() + data.len()) as u64 > cache.max_entry_size {
+            this.pending = None;
+            this.buffer = Vec::new();
+          } else {
+            this.buffer.extend_from_slice(data);
+          }
+        }
+        if this.inner.is_end_stream() {
+          this.finish();
+        }
+      }
+      Some(Err(_)) => {
+        this.pending = None;
+        this.buffer = Vec::new();
+      }
+      None => this.finish(),
+    }
+    Poll::Ready(result)
+  }
+
+  fn is_end_stream(&self) -> bool {
+    self.inner.is_end_stream()
+  }
+
+  fn size_hint(&self) -> SizeHint {
+    self.inner.size_hint()
+  }
+}
+
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or(Duration::ZERO)
+    .as_secs()
+}
+
+fn cache_key(url: &Url) -> String {
+  let mut url = url.clone();
+  url.set_fragment(None);
+  url.into()
+}
+
+fn cache_key_for_uri(uri: &Uri) -> String {
+  match Url::parse(&uri.to_string()) {
+    Ok(url) => cache_key(&url),
+    Err(_) => uri.to_string(),
+  }
+}
+
+/// Returns the combined value of the request header `name`, for comparison
+/// with the value stored for a `Vary` header.
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+  let mut values = headers.get_all(name).iter().peekable();
+  values.peek()?;
+  Some(
+    values
+      .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+      .collect::<Vec<_>>()
+      .join(", "),
+  )
+}
+
+fn parse_date(value: &HeaderValue) -> Option<u64> {
+  let date = httpdate::parse_http_date(value.to_str().ok()?).ok()?;
+  Some(date.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+fn header_date(headers: &HeaderMap, name: HeaderName) -> Option<u64> {
+  parse_date(headers.get(name)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn headers(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+    headers
+      .iter()
+      .map(|(name, value)| {
+        (
+          HeaderName::from_static(name),
+          HeaderValue::from_static(value),
+        )
+      })
+      .collect()
+  }
+
+  fn cached_response(
+    response_headers: &[(&'static str, &'static str)],
+  ) -> CachedResponse {
+    CachedResponse {
+      url: "https://example.com/".to_string(),
+      status: StatusCode::OK,
+      headers: headers(response_headers),
+      vary: vec![],
+      request_time: 1_000_000,
+      response_time: 1_000_002,
+      body: Bytes::new(),
+    }
+  }
+
+  #[test]
+  fn parses_cache_control() {
+    let cache_control = CacheControl::from_headers(&headers(&[
+      ("cache-control", "Max-Age=\"60\", no-cache=\"set-cookie\""),
+      ("cache-control", "max-stale, must-revalidate, s-maxage=10"),
+    ]));
+    assert_eq!(
+      cache_control,
+      CacheControl {
+        no_cache: true,
+        must_revalidate: true,
+        max_age: Some(60),
+        max_stale: Some(None),
+        ..Default::default()
+      }
+    );
+    let cache_control =
+      CacheControl::from_headers(&headers(&[("cache-control", "max-age=x")]));
+    assert_eq!(cache_control.max_age, Some(0));
+  }
+
+  #[test]
+  fn freshness() {
+    // Date: 1_000_000, Expires: 1_000_060
+    let response = cached_response(&[
+      ("date", "Mon, 12 Jan 1970 13:46:40 GMT"),
+      ("expires", "Mon, 12 Jan 1970 13:47:40 GMT"),
+    ]);
+    let cache_control = CacheControl::from_headers(&response.headers);
+    assert_eq!(response.freshness_lifetime(&cache_control), 60);
+    assert_eq!(response.current_age(1_000_010), 10);
+    let no_directives = CacheControl::default();
+    assert!(response.is_fresh(&no_directives, 1_000_010));
+    assert!(!response.is_fresh(&no_directives, 1_000_060));
+    let max_age = CacheControl {
+      max_age: Some(5),
+      ..Default::default()
+    };
+    assert!(!response.is_fresh(&max_age, 1_000_010));
+    let max_stale = CacheControl {
+      max_stale: Some(Some(30)),
+      ..Default::default()
+    };
+    assert!(response.is_fresh(&max_stale, 1_000_080));
+
+    // The `Age` header and the response delay add to the age.
+    let response =
+      cached_response(&[("age", "30"), ("cache-control", "max-age=60")]);
+    assert_eq!(response.current_age(1_000_002), 32);
+
+    // Heuristic freshness is a tenth of the time since the last modification.
+    let response = cached_response(&[
+      ("date", "Mon, 12 Jan 1970 13:46:40 GMT"),
+      ("last-modified", "Mon, 12 Jan 1970 13:30:00 GMT"),
+    ]);
+    assert_eq!(response.freshness_lifetime(&CacheControl::default()), 100);
+  }
+
+  #[test]
+  fn storable() {
+    let request = headers(&[("accept-language", "en")]);
+    assert_eq!(
+      storable_vary(
+        &request,
+        StatusCode::OK,
+        &headers(&[("vary", "Accept-Language, Accept-Encoding")]),
+      ),
+      Some(vec![
+        ("accept-language".to_string(), Some("en".to_string())),
+        ("accept-encoding".to_string(), None),
+      ])
+    );
+    assert_eq!(
+      storable_vary(&request, StatusCode::OK, &headers(&[("vary", "*")])),
+      None
+    );
+    assert_eq!(
+      storable_vary(
+        &request,
+        StatusCode::OK,
+        &headers(&[("cache-control", "no-store")]),
+      ),
+      None
+    );
+    assert_eq!(
+      storable_vary(&request, StatusCode::CREATED, &HeaderMap::new()),
+      None
+    );
+    assert_eq!(
+      storable_vary(
+        &request,
+        StatusCode::CREATED,
+        &headers(&[("cache-control", "max-age=60")]),
+      ),
+      Some(vec![])
+    );
+  }
+}
@@ -1,7 +1,10 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
 
+mod cookie_jar;
 pub mod dns;
 mod fs_fetch_handler;
+mod http_cache;
+mod policy;
 mod proxy;
 #[cfg(test)]
 mod tests;
@@ -24,6 +27,8 @@ use std::task::Poll;
 
 use bytes::Bytes;
 // Re-export data_url
+use cookie_jar::CookieJar;
+pub use cookie_jar::CookieJarOptions;
 pub use data_url;
 use data_url::DataUrl;
 use deno_core::AsyncRefCell;
@@ -42,7 +47,6 @@ use deno_core::ResourceId;
 use deno_core::futures::FutureExt;
 use deno_core::futures::Stream;
 use deno_core::futures::StreamExt;
-use deno_core::futures::TryFutureExt;
 use deno_core::futures::stream::Peekable;
 use deno_core::op2;
 use deno_core::url;
@@ -68,6 +72,7 @@ use http::header::ACCEPT;
 use http::header::ACCEPT_ENCODING;
 use http::header::AUTHORIZATION;
 use http::header::CONTENT_LENGTH;
+use http::header::COOKIE;
 use http::header::HOST;
 use http::header::HeaderName;
 use http::header::HeaderValue;
@@ -76,12 +81,16 @@ use http::header::RANGE;
 use http::header::USER_AGENT;
 use http_body_util::BodyExt;
 use http_body_util::combinators::BoxBody;
+use http_cache::HttpCache;
+pub use http_cache::HttpCacheOptions;
+pub use http_cache::RequestCacheMode;
 use hyper::body::Frame;
 use hyper_util::client::legacy::Builder as HyperClientBuilder;
 use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::client::legacy::connect::HttpInfo;
 use hyper_util::rt::TokioExecutor;
 use hyper_util::rt::TokioTimer;
+pub use policy::RetryOptions;
 pub use proxy::basic_auth;
 use serde::Deserialize;
 use serde::Serialize;
@@ -207,6 +216,14 @@ pub enum FetchError {
   #[class(type)]
   #[error("Request was cancelled")]
   RequestCanceled,
+  #[class("TimedOut")]
+  #[error("Request timed out after {}ms", .0.as_millis())]
+  Timeout(std::time::Duration),
+  #[class(type)]
+  #[error(
+    "The request's cache mode is 'only-if-cached' but the response is not in the cache"
+  )]
+  NotInCache,
   #[class(generic)]
   #[error(transparent)]
   Http(#[from] http::Error),
@@ -468,6 +485,7 @@ pub fn op_fetch<FP>(
   #[string] url: String,
   #[serde] headers: Vec<(ByteString, ByteString)>,
   #[smi] client_rid: Option<u32>,
+  #[serde] cache_mode: RequestCacheMode,
   has_body: bool,
   #[buffer] data: Option<JsBuffer>,
   #[smi] resource: Option<ResourceId>,
@@ -587,11 +605,13 @@ where
       let cancel_handle_ = cancel_handle.clone();
 
       let fut = async move {
-        client
-          .send(request)
-          .map_err(Into::into)
-          .or_cancel(cancel_handle_)
-          .await
+        let timeout = client.timeout;
+        policy::with_timeout(
+          timeout,
+          http_cache::send(client, request, cache_mode),
+        )
+        .or_cancel(cancel_handle_)
+        .await
       };
 
       let request_rid = state.resource_table.add(FetchRequestResource {
@@ -902,6 +922,12 @@ pub struct CreateHttpClientArgs {
   #[serde(default)]
   allow_host: bool,
   local_address: Option<String>,
+  cache: Option<HttpCacheOptions>,
+  cookie_jar: Option<CookieJarOptions>,
+  connect_timeout: Option<u64>,
+  timeout: Option<u64>,
+  retry: Option<RetryOptions>,
+  dns: Option<dns::DnsOptions>,
 }
 
 fn default_true() -> bool {
@@ -948,6 +974,55 @@ where
     }
   }
 
+  if let Some(cache) = &mut args.cache {
+    let permissions = state.borrow_mut::<FP>();
+    cache.path = permissions
+      .check_open(
+        Cow::Borrowed(&cache.path),
+        OpenAccessKind::ReadWrite,
+        "Deno.createHttpClient()",
+      )?
+      .into_owned_path();
+  }
+
+  if let Some(CookieJarOptions { path: Some(path) }) = &mut args.cookie_jar {
+    let permissions = state.borrow_mut::<FP>();
+    *path = permissions
+      .check_open(
+        Cow::Borrowed(path),
+        OpenAccessKind::ReadWrite,
+        "Deno.createHttpClient()",
+      )?
+      .into_owned_path();
+  }
+
+  if let Some(dns) = &args.dns {
+    let permissions = state.borrow_mut::<FP>();
+    let name_servers =
+      dns.name_servers().map_err(HttpClientCreateError::Dns)?;
+    for name_server in name_servers {
+      let url = Url::parse(&format!("dns://{}", name_server.addr))?;
+      permissions.check_net_url(&url, "Deno.createHttpClient()")?;
+    }
+    // requests to a static host connect to its addresses instead, so they
+    // must be allowed on any port
+    for addr in dns.static_hosts.values().flatten() {
+      let host = match addr {
+        IpAddr::V4(addr) => addr.to_string(),
+        IpAddr::V6(addr) => format!("[{addr}]"),
+      };
+      let url = Url::parse(&format!("dns://{host}"))?;
+      permissions.check_net_url(&url, "Deno.createHttpClient()")?;
+    }
+  }
+
+  let dns_resolver = match args.dns {
+    Some(dns) => {
+      dns::Resolver::from_options(dns).map_err(HttpClientCreateError::Dns)?
+    }
+    None => dns::Resolver::default(),
+  };
+
   let options = state.borrow::<Options>();
   let ca_certs = args
     .ca_certs
@@ -963,7 +1038,7 @@ where
         .map_err(HttpClientCreateError::RootCertStore)?,
       ca_certs,
       proxy: args.proxy,
-      dns_resolver: dns::Resolver::default(),
+      dns_resolver,
       unsafely_ignore_certificate_errors: options
         .unsafely_ignore_certificate_errors
         .clone(),
@@ -983,6 +1058,11 @@ where
       http2: args.http2,
       local_address: args.local_address,
       client_builder_hook: options.client_builder_hook,
+      cache: args.cache,
+      cookie_jar: args.cookie_jar,
+      connect_timeout: args.connect_timeout,
+      timeout: args.timeout,
+      retry: args.retry,
     },
   )?;
 
@@ -1006,6 +1086,18 @@ pub struct CreateHttpClientOptions {
   pub http2: bool,
   pub local_address: Option<String>,
   pub client_builder_hook: Option<fn(HyperClientBuilder) -> HyperClientBuilder>,
+  /// Enables a private HTTP cache for `fetch()` requests made with the client.
+  pub cache: Option<HttpCacheOptions>,
+  /// Enables a cookie jar that stores cookies from responses and sends them
+  /// with later requests made with the client.
+  pub cookie_jar: Option<CookieJarOptions>,
+  /// The timeout for establishing a connection, in milliseconds.
+  pub connect_timeout: Option<u64>,
+  /// The timeout for a whole request, from sending it until its response
+  /// body is received, in milliseconds.
+  pub timeout: Option<u64>,
+  /// Retries failed requests with idempotent methods.
+  pub retry: Option<RetryOptions>,
 }
 
 impl Default for CreateHttpClientOptions {
@@ -1023,6 +1115,11 @@ impl Default for CreateHttpClientOptions {
       http2: true,
       local_address: None,
       client_builder_hook: None,
+      cache: None,
+      cookie_jar: None,
+      connect_timeout: None,
+      timeout: None,
+      retry: None,
     }
   }
 }
@@ -1049,6 +1146,12 @@ pub enum HttpClientCreateError {
   UnixProxyNotSupportedOnWindows,
   #[error("Vsock proxy is not supported on this platform")]
   VsockProxyNotSupported,
+  #[class(inherit)]
+  #[error("Failed to load the cookie jar: {0}")]
+  CookieJar(std::io::Error),
+  #[class(inherit)]
+  #[error(transparent)]
+  Dns(dns::DnsOptionsError),
 }
 
 /// Create new instance of async Client. This client supports
@@ -1089,6 +1192,11 @@ pub fn create_http_client(
       .map_err(|_| HttpClientCreateError::InvalidAddress(local_address))?;
     http_connector.set_local_address(Some(local_addr));
   }
+  if let Some(connect_timeout) = options.connect_timeout {
+    http_connector.set_connect_timeout(Some(std::time::Duration::from_millis(
+      connect_timeout,
+    )));
+  }
 
   let user_agent = user_agent.parse::<HeaderValue>().map_err(|_| {
     HttpClientCreateError::InvalidUserAgent(user_agent.to_string())
@@ -1165,6 +1273,13 @@ pub fn create_http_client(
     }
   }
 
+  let cookie_jar = options
+    .cookie_jar
+    .map(CookieJar::new)
+    .transpose()
+    .map_err(HttpClientCreateError::CookieJar)?
+    .map(Arc::new);
+
   let pooled_client = builder.build(connector);
   let retry_client = retry::Retry::new(FetchRetry, pooled_client);
   let decompress = Decompression::new(retry_client).gzip(true).br(true);
@@ -1173,6 +1288,12 @@ pub fn create_http_client(
     inner: decompress,
     proxies,
     user_agent,
+    cache: options
+      .cache
+      .map(|options| Arc::new(HttpCache::new(options))),
+    cookie_jar,
+    retry: options.retry,
+    timeout: options.timeout.map(std::time::Duration::from_millis),
   })
 }
 
@@ -1193,6 +1314,10 @@ pub struct Client {
   // Used to check whether to include a proxy-authorization header
   proxies: Arc<proxy::Proxies>,
   user_agent: HeaderValue,
+  cache: Option<Arc<HttpCache>>,
+  cookie_jar: Option<Arc<CookieJar>>,
+  retry: Option<RetryOptions>,
+  timeout: Option<std::time::Duration>,
 }
 
 type Connector = proxy::ProxyConnector<HttpConnector<dns::Resolver>>;
@@ -1270,15 +1395,86 @@ impl Client {
       req.headers_mut().insert(PROXY_AUTHORIZATION, auth.clone());
     }
 
+    if let Some(cookie) = self
+      .cookie_jar
+      .as_ref()
+      .and_then(|cookie_jar| cookie_jar.cookie_header(req.uri()))
+    {
+      append_cookie_header(req.headers_mut(), cookie);
+    }
+
+    let uri = req.uri().clone();
+
+    let resp = match &self.retry {
+      Some(retry) => self.send_with_retries(req, retry).await?,
+      None => self.send_once(req).await?,
+    };
+    if let Some(cookie_jar) = &self.cookie_jar {
+      cookie_jar.store_response_cookies(&uri, resp.headers());
+    }
+    Ok(resp)
+  }
+
+  async fn send_once(
+    &self,
+    req: http::Request<ReqBody>,
+  ) -> Result<http::Response<ResBody>, ClientSendError> {
     let uri = req.uri().clone();
 
     let resp = self
       .inner
+      .clone()
       .oneshot(req)
       .await
       .map_err(|e| ClientSendError { uri, source: e })?;
     Ok(resp.map(|b| b.map_err(|e| JsErrorBox::generic(e.to_string())).boxed()))
   }
+
+  /// Sends `req`, retrying it with exponential backoff on connection errors
+  /// and on the configured status codes if its method is idempotent.
+  async fn send_with_retries(
+    &self,
+    mut req: http::Request<ReqBody>,
+    retry: &RetryOptions,
+  ) -> Result<http::Response<ResBody>, ClientSendError> {
+    let mut attempt = 0;
+    loop {
+      let next_req = (attempt < retry.max_retries
+        && RetryOptions::is_retryable_method(req.method()))
+      .then(|| clone_request(&req))
+      .flatten();
+      let result = self.send_once(req).await;
+      let Some(next_req) = next_req else {
+        return result;
+      };
+      let delay = match &result {
+        Err(err) if err.is_connect_error() => retry.backoff(attempt),
+        Ok(resp) if retry.is_retryable_response(resp) => retry
+          .retry_after(resp)
+          .unwrap_or_else(|| retry.backoff(attempt)),
+        _ => return result,
+      };
+      drop(result);
+      tokio::time::sleep(delay).await;
+      req = next_req;
+      attempt += 1;
+    }
+  }
+}
+
+/// Adds the cookies from the cookie jar to the `Cookie` header of a request,
+/// after any cookies that were set explicitly.
+fn append_cookie_header(headers: &mut http::HeaderMap, cookie: HeaderValue) {
+  let value = match headers.get(COOKIE) {
+    Some(existing) => {
+      let mut value = existing.as_bytes().to_vec();
+      value.extend_from_slice(b"; ");
+      value.extend_from_slice(cookie.as_bytes());
+      HeaderValue::from_bytes(&value).unwrap_or(cookie)
+    }
+    None => cookie,
+  };
+  headers.insert(COOKIE, value);
 }
 
 // This is a custom enum to allow the retry policy to clone the variants that could be retried.
@@ -1429,21 +1625,28 @@ where
     &mut self,
     req: &http::Request<ReqBody>,
   ) -> Option<http::Request<ReqBody>> {
-    let body = match req.body() {
-      ReqBody::Full(b) => ReqBody::Full(b.clone()),
-      ReqBody::Empty(b) => ReqBody::Empty(*b),
-      ReqBody::Streaming(..) => return None,
-    };
-
-    let mut clone = http::Request::new(body);
-    *clone.method_mut() = req.method().clone();
-    *clone.uri_mut() = req.uri().clone();
-    *clone.headers_mut() = req.headers().clone();
-    *clone.extensions_mut() = req.extensions().clone();
-    Some(clone)
+    clone_request(req)
   }
 }
 
+/// Clones a request for a retry, unless it has a streaming body.
+fn clone_request(
+  req: &http::Request<ReqBody>,
+) -> Option<http::Request<ReqBody>> {
+  let body = match req.body() {
+    ReqBody::Full(b) => ReqBody::Full(b.clone()),
+    ReqBody::Empty(b) => ReqBody::Empty(*b),
+    ReqBody::Streaming(..) => return None,
+  };
+
+  let mut clone = http::Request::new(body);
+  *clone.method_mut() = req.method().clone();
+  *clone.uri_mut() = req.uri().clone();
+  *clone.headers_mut() = req.headers().clone();
+  *clone.extensions_mut() = req.extensions().clone();
+  Some(clone)
+}
+
 fn is_error_retryable(err: &(dyn std::error::Error + 'static)) -> bool {
   // Note: hyper doesn't promise it will always be this h2 version. Keep up to date.
   if let Some(err) = find_source::<h2::Error>(err) {
@@ -0,0 +1,211 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! Client-level retry and timeout policies for `Deno.HttpClient`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use deno_error::JsErrorBox;
+use http::Method;
+use http::header::RETRY_AFTER;
+use http_body_util::BodyExt;
+use hyper::body::Body;
+use hyper::body::Frame;
+use hyper::body::SizeHint;
+use serde::Deserialize;
+use tokio::time::Instant;
+use tokio::time::Sleep;
+
+use crate::FetchError;
+use crate::ResBody;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetryOptions {
+  /// The maximum number of retries after the initial attempt.
+  pub max_retries: u32,
+  /// The delay before the first retry in milliseconds. It's doubled for
+  /// every following retry.
+  pub initial_backoff: u64,
+  /// The upper bound for the delay between retries in milliseconds.
+  pub max_backoff: u64,
+  /// Response status codes that are retried, in addition to connection
+  /// errors.
+  pub status_codes: Vec<u16>,
+}
+
+impl Default for RetryOptions {
+  fn default() -> Self {
+    Self {
+      max_retries: 3,
+      initial_backoff: 100,
+      max_backoff: 10_000,
+      status_codes: vec![],
+    }
+  }
+}
+
+impl RetryOptions {
+  /// Whether a request with `method` may be sent again after a failure,
+  /// because sending it more than once has the same effect as sending it once
+  /// (RFC 9110, section 9.2.2).
+  pub fn is_retryable_method(method: &Method) -> bool {
+    matches!(
+      *method,
+      Method::GET
+        | Method::HEAD
+        | Method::OPTIONS
+        | Method::TRACE
+        | Method::PUT
+        | Method::DELETE
+    )
+  }
+
+  pub fn is_retryable_response(
+    &self,
+    response: &http::Response<ResBody>,
+  ) -> bool {
+    self.status_codes.contains(&response.status().as_u16())
+  }
+
+  /// The delay before the retry following the `attempt`th one, which is zero
+  /// for the initial request.
+  pub fn backoff(&self, attempt: u32) -> Duration {
+    let delay = self
+      .initial_backoff
+      .saturating_mul(2u64.saturating_pow(attempt));
+    Duration::from_millis(delay.min(self.max_backoff))
+  }
+
+  /// The delay requested by the `Retry-After` header of a response, capped at
+  /// the maximum backoff.
+  pub fn retry_after(
+    &self,
+    response: &http::Response<ResBody>,
+  ) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    let delay = match value.parse::<u64>() {
+      Ok(seconds) => Duration::from_secs(seconds),
+      Err(_) => httpdate::parse_http_date(value)
+        .ok()?
+        .duration_since(SystemTime::now())
+        .unwrap_or(Duration::ZERO),
+    };
+    Some(delay.min(Duration::from_millis(self.max_backoff)))
+  }
+}
+
+/// Fails `fut` with a timeout error if no response is received within
+/// `timeout`, and fails the body of the response if it isn't received in full
+/// before the remaining time runs out.
+pub async fn with_timeout(
+  timeout: Option<Duration>,
+  fut: impl Future<Output = Result<http::Response<ResBody>, FetchError>>,
+) -> Result<http::Response<ResBody>, FetchError> {
+  let Some(timeout) = timeout else {
+    return fut.await;
+  };
+  let deadline = Instant::now() + timeout;
+  let response = tokio::time::timeout_at(deadline, fut)
+    .await
+    .map_err(|_| FetchError::Timeout(timeout))??;
+  Ok(response.map(|body| {
+    DeadlineBody {
+      inner: body,
+      sleep: Box::pin(tokio::time::sleep_until(deadline)),
+      timeout,
+    }
+    .boxed()
+  }))
+}
+
+struct DeadlineBody {
+  inner: ResBody,
+  sleep: Pin<Box<Sleep>>,
+  timeout: Duration,
+}
+
+impl Body for DeadlineBody {
+  type Data = Bytes;
+  type Error = JsErrorBox;
+
+  fn poll_frame(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Result<Frame<Bytes>, JsErrorBox>>> {
+    if let Poll::Ready(frame) = Pin::new(&mut self.inner).poll_frame(cx) {
+      return Poll::Ready(frame);
+    }
+    match self.sleep.as_mut().poll(cx) {
+      Poll::Ready(()) => Poll::Ready(Some(Err(JsErrorBox::from_err(
+        FetchError::Timeout(self.timeout),
+      )))),
+      Poll::Pending => Poll::Pending,
+    }
+  }
+
+  fn is_end_stream(&self) -> bool {
+    self.inner.is_end_stream()
+  }
+
+  fn size_hint(&self) -> SizeHint {
+    self.inner.size_hint()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn response(retry_after: &'static str) -> http::Response<ResBody> {
+    let mut response = http::Response::new(
+      http_body_util::Empty::new()
+        .map_err(|never| match never {})
+        .boxed(),
+    );
+    response
+      .headers_mut()
+      .insert(RETRY_AFTER, http::HeaderValue::from_static(retry_after));
+    response
+  }
+
+  #[test]
+  fn backoff() {
+    let options = RetryOptions {
+      initial_backoff: 100,
+      max_backoff: 1_000,
+      ..Default::default()
+    };
+    assert_eq!(options.backoff(0), Duration::from_millis(100));
+    assert_eq!(options.backoff(1), Duration::from_millis(200));
+    assert_eq!(options.backoff(3), Duration::from_millis(800));
+    assert_eq!(options.backoff(4), Duration::from_millis(1_000));
+    assert_eq!(options.backoff(100), Duration::from_millis(1_000));
+  }
+
+  #[test]
+  fn retry_after() {
+    let options = RetryOptions {
+      max_backoff: 5_000,
+      ..Default::default()
+    };
+    assert_eq!(
+      options.retry_after(&response("2")),
+      Some(Duration::from_secs(2))
+    );
+    assert_eq!(
+      options.retry_after(&response("120")),
+      Some(Duration::from_secs(5))
+    );
+    assert_eq!(
+      options.retry_after(&response("Thu, 01 Jan 1970 00:00:00 GMT")),
+      Some(Duration::ZERO)
+    );
+    assert_eq!(options.retry_after(&response("soon")), None);
+  }
+}
@@ -67,6 +67,27 @@ fn test_userspace_resolver() {
   });
 }
 
+#[tokio::test]
+async fn test_static_hosts_resolver() {
+  let src_addr = create_https_server(true).await;
+  let resolver = dns::Resolver::from_options(dns::DnsOptions {
+    static_hosts: std::collections::HashMap::from([(
+      "example.test".to_string(),
+      vec![src_addr.ip()],
+    )]),
+    ..Default::default()
+  })
+  .unwrap();
+  rust_test_client_with_resolver(
+    None,
+    format!("example.test:{}", src_addr.port()),
+    "https",
+    http::Version::HTTP_2,
+    resolver,
+  )
+  .await;
+}
+
 #[tokio::test]
 async fn test_https_proxy_http11() {
   let src_addr = create_https_server(false).await;
@@ -127,6 +148,11 @@ async fn rust_test_client_with_resolver(
       http2: true,
       local_address: None,
       client_builder_hook: None,
+      cache: None,
+      cookie_jar: None,
+      connect_timeout: None,
+      timeout: None,
+      retry: None,
     },
   )
   .unwrap();
@@ -296,3 +322,332 @@ async fn create_socks_proxy(src_addr: SocketAddr) -> SocketAddr {
 
   prx_addr
 }
+
+#[tokio::test]
+async fn test_http_cache() {
+  use crate::RequestCacheMode;
+
+  let cache_dir = tempfile::tempdir().unwrap();
+  let (src_addr, hits) = create_caching_http_server().await;
+  let client = create_http_client(
+    "fetch/test",
+    CreateHttpClientOptions {
+      cache: Some(crate::HttpCacheOptions {
+        path: cache_dir.path().to_path_buf(),
+        max_entry_size: None,
+      }),
+      ..Default::default()
+    },
+  )
+  .unwrap();
+  let fetch = |path: &str, mode: RequestCacheMode, language: &'static str| {
+    cached_fetch(&client, format!("http://{src_addr}{path}"), mode, language)
+  };
+
+  // A fresh response is served from the cache.
+  let (_, body) = fetch("/fresh", RequestCacheMode::Default, "en")
+    .await
+    .unwrap();
+  assert_eq!(body, "fresh");
+  wait_for_cache_entries(cache_dir.path(), 1).await;
+  let (_, body) = fetch("/fresh", RequestCacheMode::Default, "en")
+    .await
+    .unwrap();
+  assert_eq!(body, "fresh");
+  assert_eq!(hits.load(SeqCst), 1);
+
+  // "no-store" always goes to the network.
+  fetch("/fresh", RequestCacheMode::NoStore, "en")
+    .await
+    .unwrap();
+  assert_eq!(hits.load(SeqCst), 2);
+
+  // A response that requires validation is revalidated with its ETag, and the
+  // stored body is used for the `304 Not Modified` response.
+  let (_, body) = fetch("/etag", RequestCacheMode::Default, "en")
+    .await
+    .unwrap();
+  assert_eq!(body, "etag");
+  wait_for_cache_entries(cache_dir.path(), 2).await;
+  let (status, body) = fetch("/etag", RequestCacheMode::Default, "en")
+    .await
+    .unwrap();
+  assert_eq!(status, http::StatusCode::OK);
+  assert_eq!(body, "etag");
+  assert_eq!(hits.load(SeqCst), 4);
+
+  // "force-cache" uses the stale response without revalidating it.
+  let (_, body) = fetch("/etag", RequestCacheMode::ForceCache, "en")
+    .await
+    .unwrap();
+  assert_eq!(body, "etag");
+  assert_eq!(hits.load(SeqCst), 4);
+
+  // A different value for a header nominated by `Vary` is a cache miss.
+  let (_, body) = fetch("/vary", RequestCacheMode::Default, "en")
+    .await
+    .unwrap();
+  assert_eq!(body, "en");
+  wait_for_cache_entries(cache_dir.path(), 3).await;
+  let (_, body) = fetch("/vary", RequestCacheMode::Default, "de")
+    .await
+    .unwrap();
+  assert_eq!(body, "de");
+  assert_eq!(hits.load(SeqCst), 6);
+
+  // "only-if-cached" fails if there is no stored response.
+  assert!(matches!(
+    fetch("/missing", RequestCacheMode::OnlyIfCached, "en").await,
+    Err(crate::FetchError::NotInCache)
+  ));
+  assert_eq!(hits.load(SeqCst), 6);
+}
+
+async fn cached_fetch(
+  client: &crate::Client,
+  url: String,
+  mode: crate::RequestCacheMode,
+  language: &'static str,
+) -> Result<(http::StatusCode, Bytes), crate::FetchError> {
+  let req = http::Request::builder()
+    .uri(url)
+    .header(http::header::ACCEPT_LANGUAGE, language)
+    .body(crate::ReqBody::empty())
+    .unwrap();
+  let resp = crate::http_cache::send(client.clone(), req, mode).await?;
+  let status = resp.status();
+  let body = resp.collect().await.unwrap().to_bytes();
+  Ok((status, body))
+}
+
+/// Cache entries are written in the background, so wait for them to appear
+/// before making requests that expect them.
+async fn wait_for_cache_entries(dir: &std::path::Path, count: usize) {
+  for _ in 0..100 {
+    let entries = std::fs::read_dir(dir)
+      .map(|entries| {
+        entries
+          .filter(|entry| {
+            entry
+              .as_ref()
+              .is_ok_and(|entry| entry.path().extension().is_none())
+          })
+          .count()
+      })
+      .unwrap_or(0);
+    if entries == count {
+      return;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+  }
+  panic!("expected {count} cache entries in {}", dir.display());
+}
+
+async fn create_caching_http_server() -> (SocketAddr, Arc<AtomicUsize>) {
+  let src_tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let src_addr = src_tcp.local_addr().unwrap();
+  let hits = Arc::new(AtomicUsize::new(0));
+
+  let hits_ = hits.clone();
+  tokio::spawn(async move {
+    while let Ok((sock, _)) = src_tcp.accept().await {
+      let hits = hits_.clone();
+      let fut = hyper::server::conn::http1::Builder::new().serve_connection(
+        hyper_util::rt::TokioIo::new(sock),
+        hyper::service::service_fn(
+          move |req: http::Request<hyper::body::Incoming>| {
+            hits.fetch_add(1, SeqCst);
+            let response = http::Response::builder();
+            let response = match req.uri().path() {
+              "/fresh" => response
+                .header("cache-control", "max-age=60")
+                .body(Bytes::from("fresh")),
+              "/etag"
+                if req
+                  .headers()
+                  .get("if-none-match")
+                  .is_some_and(|etag| etag == "\"v1\"") =>
+              {
+                response
+                  .status(http::StatusCode::NOT_MODIFIED)
+                  .header("etag", "\"v1\"")
+                  .body(Bytes::new())
+              }
+              "/etag" => response
+                .header("cache-control", "no-cache")
+                .header("etag", "\"v1\"")
+                .body(Bytes::from("etag")),
+              "/vary" => response
+                .header("cache-control", "max-age=60")
+                .header("vary", "Accept-Language")
+                .body(Bytes::copy_from_slice(
+                  req.headers()["accept-language"].as_bytes(),
+                )),
+              _ => response
+                .status(http::StatusCode::NOT_FOUND)
+                .body(Bytes::new()),
+            };
+            async move {
+              Ok::<_, std::convert::Infallible>(
+                response.unwrap().map(http_body_util::Full::new),
+              )
+            }
+          },
+        ),
+      );
+      tokio::spawn(fut);
+    }
+  });
+
+  (src_addr, hits)
+}
+
+#[tokio::test]
+async fn test_cookie_jar() {
+  let src_tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let src_addr = src_tcp.local_addr().unwrap();
+  tokio::spawn(async move {
+    while let Ok((sock, _)) = src_tcp.accept().await {
+      let fut = hyper::server::conn::http1::Builder::new().serve_connection(
+        hyper_util::rt::TokioIo::new(sock),
+        hyper::service::service_fn(
+          |req: http::Request<hyper::body::Incoming>| async move {
+            // Echo the received cookies, and set a new one on login.
+            let cookie = req
+              .headers()
+              .get(http::header::COOKIE)
+              .map(|value| Bytes::copy_from_slice(value.as_bytes()))
+              .unwrap_or_default();
+            let mut response = http::Response::builder();
+            if req.uri().path() == "/login" {
+              response = response
+                .header(http::header::SET_COOKIE, "session=abc; Path=/");
+            }
+            Ok::<_, std::convert::Infallible>(
+              response.body(http_body_util::Full::new(cookie)).unwrap(),
+            )
+          },
+        ),
+      );
+      tokio::spawn(fut);
+    }
+  });
+
+  let client = create_http_client(
+    "fetch/test",
+    CreateHttpClientOptions {
+      cookie_jar: Some(Default::default()),
+      ..Default::default()
+    },
+  )
+  .unwrap();
+  let send = |path: &str, cookie: Option<&'static str>| {
+    let mut req =
+      http::Request::builder().uri(format!("http://{src_addr}{path}"));
+    if let Some(cookie) = cookie {
+      req = req.header(http::header::COOKIE, cookie);
+    }
+    let req = req.body(crate::ReqBody::empty()).unwrap();
+    let client = client.clone();
+    async move {
+      let resp = client.send(req).await.unwrap();
+      resp.collect().await.unwrap().to_bytes()
+    }
+  };
+
+  assert_eq!(send("/login", None).await, "");
+  assert_eq!(send("/account", None).await, "session=abc");
+  assert_eq!(
+    send("/account", Some("theme=dark")).await,
+    "theme=dark; session=abc"
+  );
+}
+
+#[tokio::test]
+async fn test_retry_and_timeout() {
+  let src_tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let src_addr = src_tcp.local_addr().unwrap();
+  let hits = Arc::new(AtomicUsize::new(0));
+  let hits_ = hits.clone();
+  tokio::spawn(async move {
+    while let Ok((sock, _)) = src_tcp.accept().await {
+      let hits = hits_.clone();
+      let fut = hyper::server::conn::http1::Builder::new().serve_connection(
+        hyper_util::rt::TokioIo::new(sock),
+        hyper::service::service_fn(
+          move |req: http::Request<hyper::body::Incoming>| {
+            let hit = hits.fetch_add(1, SeqCst);
+            async move {
+              let status = match req.uri().path() {
+                // Fails the first two attempts.
+                "/flaky" if hit < 2 => http::StatusCode::SERVICE_UNAVAILABLE,
+                "/slow" => {
+                  tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                  http::StatusCode::OK
+                }
+                _ => http::StatusCode::OK,
+              };
+              Ok::<_, std::convert::Infallible>(
+                http::Response::builder()
+                  .status(status)
+                  .body(http_body_util::Full::new(Bytes::new()))
+                  .unwrap(),
+              )
+            }
+          },
+        ),
+      );
+      tokio::spawn(fut);
+    }
+  });
+
+  let client = create_http_client(
+    "fetch/test",
+    CreateHttpClientOptions {
+      retry: Some(crate::RetryOptions {
+        initial_backoff: 1,
+        status_codes: vec![503],
+        ..Default::default()
+      }),
+      ..Default::default()
+    },
+  )
+  .unwrap();
+  let request = |method: http::Method, path: &str| {
+    http::Request::builder()
+      .method(method)
+      .uri(format!("http://{src_addr}{path}"))
+      .body(crate::ReqBody::empty())
+      .unwrap()
+  };
+
+  // Idempotent requests are retried on the configured status codes.
+  let resp = client
+    .clone()
+    .send(request(http::Method::GET, "/flaky"))
+    .await
+    .unwrap();
+  assert_eq!(resp.status(), http::StatusCode::OK);
+  assert_eq!(hits.load(SeqCst), 3);
+
+  // Other requests are not.
+  hits.store(0, SeqCst);
+  let resp = client
+    .clone()
+    .send(request(http::Method::POST, "/flaky"))
+    .await
+    .unwrap();
+  assert_eq!(resp.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+  assert_eq!(hits.load(SeqCst), 1);
+
+  let result = crate::policy::with_timeout(
+    Some(std::time::Duration::from_millis(50)),
+    crate::http_cache::send(
+      client,
+      request(http::Method::GET, "/slow"),
+      Default::default(),
+    ),
+  )
+  .await;
+  assert!(matches!(result, Err(crate::FetchError::Timeout(_))));
+}
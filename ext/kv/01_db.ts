@@ -9,7 +9,9 @@ import {
   op_kv_database_open,
   op_kv_dequeue_next_message,
   op_kv_encode_cursor,
+  op_kv_export_batch,
   op_kv_finish_dequeued_message,
+  op_kv_import_batch,
   op_kv_snapshot_read,
   op_kv_watch,
   op_kv_watch_next,
@@ -33,7 +35,10 @@ const {
   RangeError,
   SafeMap,
   SafeMapIterator,
+  StringPrototypeLastIndexOf,
   StringPrototypeReplace,
+  StringPrototypeSlice,
+  StringPrototypeSplit,
   Symbol,
   SymbolAsyncIterator,
   SymbolFor,
@@ -44,6 +49,7 @@ const {
 
 import { SymbolDispose } from "ext:deno_web/00_infra.js";
 import { ReadableStream } from "ext:deno_web/06_streams.js";
+import { TextDecoder } from "ext:deno_web/08_text_encoding.js";
 
 const encodeCursor: (
   selector: [Deno.KvKey | null, Deno.KvKey | null, Deno.KvKey | null],
@@ -111,6 +117,9 @@ type RawValue = {
   value: bigint;
 };
 
+// The size of the text that is accumulated before importing it.
+const importBatchSize = 1024 * 1024;
+
 const kvSymbol = Symbol("KvRid");
 const commitVersionstampSymbol = Symbol("KvCommitVersionstamp");
 
@@ -414,6 +423,65 @@ class Kv {
     });
   }
 
+  export(
+    options: { prefix?: Deno.KvKey } = { __proto__: null },
+  ): ReadableStream<Uint8Array> {
+    const rid = this.#rid;
+    const prefix = options.prefix ?? [];
+    let cursor: string | null = null;
+    return new ReadableStream({
+      async pull(controller) {
+        let batch;
+        try {
+          batch = await op_kv_export_batch(rid, prefix, cursor);
+        } catch (err) {
+          controller.error(err);
+          return;
+        }
+        if (batch.data !== "") {
+          controller.enqueue(core.encode(batch.data));
+        }
+        if (batch.cursor === null) {
+          controller.close();
+        } else {
+          cursor = batch.cursor;
+        }
+      },
+    });
+  }
+
+  async import(
+    source: ReadableStream<Uint8Array> | AsyncIterable<Uint8Array>,
+  ): Promise<number> {
+    const rid = this.#rid;
+    const decoder = new TextDecoder();
+    let pending = "";
+    let lineOffset = 0;
+    let count = 0;
+    const importText = async (text: string) => {
+      count += await op_kv_import_batch(rid, text, lineOffset);
+      lineOffset += StringPrototypeSplit(text, "\n").length - 1;
+    };
+    for await (const chunk of source) {
+      pending += decoder.decode(chunk, { stream: true });
+      if (pending.length < importBatchSize) {
+        continue;
+      }
+      const end = StringPrototypeLastIndexOf(pending, "\n") + 1;
+      if (end === 0) {
+        continue;
+      }
+      const text = StringPrototypeSlice(pending, 0, end);
+      pending = StringPrototypeSlice(pending, end);
+      await importText(text);
+    }
+    pending += decoder.decode();
+    if (pending !== "") {
+      await importText(pending);
+    }
+    return count;
+  }
+
   close() {
     core.close(this.#rid);
     this.#isClosed = true;
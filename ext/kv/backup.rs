@@ -0,0 +1,499 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! Export and import of databases as newline-delimited JSON, with one entry
+//! per line in key order:
+//!
+//! ```json
+//! {"key":[{"type":"string","value":"users"},{"type":"bigint","value":"1"}],"value":{"type":"v8","value":"/w9vIgRuYW1lIgNib2J7AQ=="},"versionstamp":"00000000000000010000"}
+//! ```
+//!
+//! Numbers and bigints are written as decimal strings so that they round-trip
+//! exactly, and byte arrays as base64. Entries that expire have an `expireAt`
+//! field with their expiration time in milliseconds since the epoch, which is
+//! kept on import. Imported entries are written with fresh versionstamps, as a
+//! database assigns the versionstamps itself.
+
+use std::io::BufRead;
+use std::io::Write;
+use std::num::NonZeroU32;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use chrono::DateTime;
+use chrono::Utc;
+use deno_core::serde_json;
+use deno_error::JsErrorBox;
+use denokv_proto::AtomicWrite;
+use denokv_proto::Consistency;
+use denokv_proto::Database;
+use denokv_proto::Key;
+use denokv_proto::KeyPart;
+use denokv_proto::KvEntry;
+use denokv_proto::KvValue;
+use denokv_proto::Mutation;
+use denokv_proto::MutationKind;
+use denokv_proto::ReadRange;
+use denokv_proto::SnapshotReadOptions;
+use denokv_proto::decode_key;
+use denokv_proto::encode_key;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::ExpirationReader;
+use crate::KvConfig;
+
+/// The number of entries read from the database at a time when exporting.
+pub const EXPORT_BATCH_SIZE: u32 = 500;
+
+#[derive(Debug, thiserror::Error, deno_error::JsError)]
+pub enum KvBackupError {
+  #[class(inherit)]
+  #[error(transparent)]
+  Kv(JsErrorBox),
+  #[class(inherit)]
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  #[class(type)]
+  #[error("Invalid entry on line {line}: {message}")]
+  InvalidEntry { line: usize, message: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupEntry {
+  key: Vec<BackupKeyPart>,
+  value: BackupValue,
+  versionstamp: String,
+  #[serde(
+    rename = "expireAt",
+    default,
+    skip_serializing_if = "Option::is_none"
+  )]
+  expire_at: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+enum BackupKeyPart {
+  Boolean(bool),
+  Number(String),
+  Bigint(String),
+  String(String),
+  Bytes(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+enum BackupValue {
+  V8(String),
+  Bytes(String),
+  U64(String),
+}
+
+impl BackupEntry {
+  fn from_kv_entry(
+    entry: KvEntry,
+    expire_at: Option<u64>,
+  ) -> Result<Self, std::io::Error> {
+    let key = decode_key(&entry.key)?
+      .0
+      .into_iter()
+      .map(|part| match part {
+        KeyPart::False => BackupKeyPart::Boolean(false),
+        KeyPart::True => BackupKeyPart::Boolean(true),
+        KeyPart::Float(n) => BackupKeyPart::Number(n.to_string()),
+        KeyPart::Int(n) => BackupKeyPart::Bigint(n.to_string()),
+        KeyPart::String(s) => BackupKeyPart::String(s),
+        KeyPart::Bytes(b) => BackupKeyPart::Bytes(BASE64_STANDARD.encode(b)),
+      })
+      .collect();
+    let value = match entry.value {
+      KvValue::V8(buf) => BackupValue::V8(BASE64_STANDARD.encode(buf)),
+      KvValue::Bytes(buf) => BackupValue::Bytes(BASE64_STANDARD.encode(buf)),
+      KvValue::U64(n) => BackupValue::U64(n.to_string()),
+    };
+    Ok(Self {
+      key,
+      value,
+      versionstamp: faster_hex::hex_string(&entry.versionstamp),
+      expire_at,
+    })
+  }
+
+  /// Converts the entry to the key, value and expiration time to write on
+  /// import.
+  fn into_key_value(
+    self,
+  ) -> Result<(Vec<u8>, KvValue, Option<DateTime<Utc>>), String> {
+    let parts = self
+      .key
+      .into_iter()
+      .map(|part| {
+        Ok(match part {
+          BackupKeyPart::Boolean(false) => KeyPart::False,
+          BackupKeyPart::Boolean(true) => KeyPart::True,
+          BackupKeyPart::Number(n) => KeyPart::Float(
+            n.parse().map_err(|_| format!("invalid number '{n}'"))?,
+          ),
+          BackupKeyPart::Bigint(n) => KeyPart::Int(
+            n.parse().map_err(|_| format!("invalid bigint '{n}'"))?,
+          ),
+          BackupKeyPart::String(s) => KeyPart::String(s),
+          BackupKeyPart::Bytes(b) => KeyPart::Bytes(decode_base64(&b)?),
+        })
+      })
+      .collect::<Result<Vec<_>, String>>()?;
+    if parts.is_empty() {
+      return Err("key cannot be empty".to_string());
+    }
+    let key = encode_key(&Key(parts)).map_err(|err| err.to_string())?;
+    let value = match self.value {
+      BackupValue::V8(buf) => KvValue::V8(decode_base64(&buf)?),
+      BackupValue::Bytes(buf) => KvValue::Bytes(decode_base64(&buf)?),
+      BackupValue::U64(n) => {
+        KvValue::U64(n.parse().map_err(|_| format!("invalid u64 value '{n}'"))?)
+      }
+    };
+    let expire_at = self
+      .expire_at
+      .map(|ms| {
+        i64::try_from(ms)
+          .ok()
+          .and_then(DateTime::<Utc>::from_timestamp_millis)
+          .ok_or_else(|| format!("invalid expireAt {ms}"))
+      })
+      .transpose()?;
+    Ok((key, value, expire_at))
+  }
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u8>, String> {
+  BASE64_STANDARD
+    .decode(data)
+    .map_err(|_| format!("invalid base64 '{data}'"))
+}
+
+/// Reads up to `limit` entries with keys starting with the encoded `prefix`
+/// and following `cursor`, the key of the last entry of the previous batch,
+/// along with their expiration times.
+pub(crate) async fn export_batch<DB: Database + ExpirationReader>(
+  db: &DB,
+  prefix: &[u8],
+  cursor: Option<&[u8]>,
+  limit: u32,
+) -> Result<Vec<(KvEntry, Option<u64>)>, KvBackupError> {
+  let start = cursor
+    .unwrap_or(prefix)
+    .iter()
+    .copied()
+    .chain(Some(0))
+    .collect();
+  let end = prefix.iter().copied().chain(Some(0xff)).collect();
+  let mut output = db
+    .snapshot_read(
+      vec![ReadRange {
+        start,
+        end,
+        limit: NonZeroU32::new(limit).unwrap_or(NonZeroU32::MIN),
+        reverse: false,
+      }],
+      SnapshotReadOptions {
+        consistency: Consistency::Strong,
+      },
+    )
+    .await
+    .map_err(KvBackupError::Kv)?;
+  let entries = output.pop().map(|range| range.entries).unwrap_or_default();
+  if entries.is_empty() {
+    return Ok(vec![]);
+  }
+  let expirations = db
+    .read_expirations(entries.iter().map(|entry| entry.key.clone()).collect())
+    .await
+    .map_err(KvBackupError::Kv)?;
+  Ok(entries.into_iter().zip(expirations).collect())
+}
+
+/// Writes `entries` with their expiration times to `writer`, one JSON line
+/// each.
+pub(crate) fn write_entries(
+  writer: &mut dyn Write,
+  entries: Vec<(KvEntry, Option<u64>)>,
+) -> Result<(), KvBackupError> {
+  for (entry, expire_at) in entries {
+    let entry = BackupEntry::from_kv_entry(entry, expire_at)?;
+    serde_json::to_writer(&mut *writer, &entry)
+      .map_err(std::io::Error::from)?;
+    writer.write_all(b"\n")?;
+  }
+  Ok(())
+}
+
+/// Writes all entries of `db` to `writer`, returning the number of entries.
+///
+/// The entries are read in batches, so entries that are written to the
+/// database during the export may or may not be included.
+pub async fn export<DB: Database + ExpirationReader>(
+  db: &DB,
+  writer: &mut dyn Write,
+) -> Result<u64, KvBackupError> {
+  let mut cursor = None;
+  let mut count = 0;
+  loop {
+    let entries =
+      export_batch(db, &[], cursor.as_deref(), EXPORT_BATCH_SIZE).await?;
+    let Some((last, _)) = entries.last() else {
+      break;
+    };
+    cursor = Some(last.key.clone());
+    count += entries.len() as u64;
+    let done = entries.len() < EXPORT_BATCH_SIZE as usize;
+    write_entries(writer, entries)?;
+    if done {
+      break;
+    }
+  }
+  writer.flush()?;
+  Ok(count)
+}
+
+/// Writes the entries of the JSON lines in `reader` to `db`, returning the
+/// number of entries.
+///
+/// Entries are committed in batches that stay within the limits of `config`,
+/// so a failed import may leave part of the entries written. Entries that
+/// have already expired are skipped.
+pub async fn import<DB: Database>(
+  db: &DB,
+  reader: &mut dyn BufRead,
+  config: &KvConfig,
+) -> Result<u64, KvBackupError> {
+  let mut mutations = Vec::new();
+  let mut batch_size = 0;
+  let mut count = 0;
+  let mut line = String::new();
+  let mut line_number = 0;
+  let now = Utc::now();
+  loop {
+    line.clear();
+    if reader.read_line(&mut line)? == 0 {
+      break;
+    }
+    line_number += 1;
+    let Some((key, value, expire_at)) = parse_line(&line, line_number)? else {
+      continue;
+    };
+    if key.len() > config.max_write_key_size_bytes {
+      return Err(KvBackupError::InvalidEntry {
+        line: line_number,
+        message: format!(
+          "key too large (max {} bytes)",
+          config.max_write_key_size_bytes
+        ),
+      });
+    }
+    if value_size(&value) > config.max_value_size_bytes {
+      return Err(KvBackupError::InvalidEntry {
+        line: line_number,
+        message: format!(
+          "value too large (max {} bytes)",
+          config.max_value_size_bytes
+        ),
+      });
+    }
+    if expire_at.is_some_and(|expire_at| expire_at <= now) {
+      continue;
+    }
+    let size = key.len() + value_size(&value);
+    if !mutations.is_empty()
+      && (mutations.len() >= config.max_mutations
+        || batch_size + size > config.max_total_mutation_size_bytes)
+    {
+      count += commit(db, std::mem::take(&mut mutations)).await?;
+      batch_size = 0;
+    }
+    batch_size += size;
+    mutations.push(Mutation {
+      key,
+      kind: MutationKind::Set(value),
+      expire_at,
+    });
+  }
+  if !mutations.is_empty() {
+    count += commit(db, mutations).await?;
+  }
+  Ok(count)
+}
+
+/// Parses a line of an export, which is `None` for blank lines.
+fn parse_line(
+  line: &str,
+  line_number: usize,
+) -> Result<Option<(Vec<u8>, KvValue, Option<DateTime<Utc>>)>, KvBackupError> {
+  let line = line.trim();
+  if line.is_empty() {
+    return Ok(None);
+  }
+  let invalid = |message: String| KvBackupError::InvalidEntry {
+    line: line_number,
+    message,
+  };
+  let entry = serde_json::from_str::<BackupEntry>(line)
+    .map_err(|err| invalid(err.to_string()))?;
+  entry.into_key_value().map(Some).map_err(invalid)
+}
+
+/// Commits `mutations` in a single atomic write.
+async fn commit<DB: Database>(
+  db: &DB,
+  mutations: Vec<Mutation>,
+) -> Result<u64, KvBackupError> {
+  let count = mutations.len() as u64;
+  db.atomic_write(AtomicWrite {
+    checks: vec![],
+    mutations,
+    enqueues: vec![],
+  })
+  .await
+  .map_err(KvBackupError::Kv)?;
+  Ok(count)
+}
+
+fn value_size(value: &KvValue) -> usize {
+  match value {
+    KvValue::V8(buf) | KvValue::Bytes(buf) => buf.len(),
+    KvValue::U64(_) => 8,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(parts: Vec<KeyPart>, value: KvValue) -> KvEntry {
+    KvEntry {
+      key: encode_key(&Key(parts)).unwrap(),
+      value,
+      versionstamp: [0, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+    }
+  }
+
+  fn key_parts() -> Vec<KeyPart> {
+    vec![
+      KeyPart::String("users".to_string()),
+      KeyPart::Int(42.into()),
+      KeyPart::Float(-0.1),
+      KeyPart::Float(f64::INFINITY),
+      KeyPart::Bytes(vec![0, 255]),
+      KeyPart::True,
+    ]
+  }
+
+  fn round_trip(value: KvValue) -> KvValue {
+    let kv_entry = entry(key_parts(), value);
+    let key = kv_entry.key.clone();
+    let mut out = Vec::new();
+    write_entries(&mut out, vec![(kv_entry, None)]).unwrap();
+    let line = String::from_utf8(out).unwrap();
+    assert!(line.ends_with('\n'));
+    assert!(line.contains(r#""versionstamp":"00000000000000010000""#));
+    assert!(!line.contains("expireAt"));
+    let (imported_key, imported_value, expire_at) =
+      parse_line(&line, 1).unwrap().unwrap();
+    assert_eq!(imported_key, key);
+    assert_eq!(expire_at, None);
+    imported_value
+  }
+
+  #[test]
+  fn entry_round_trip() {
+    assert!(matches!(
+      round_trip(KvValue::V8(vec![255, 15, 34, 1, 97])),
+      KvValue::V8(buf) if buf == [255, 15, 34, 1, 97]
+    ));
+    assert!(matches!(
+      round_trip(KvValue::Bytes(vec![1, 2, 3])),
+      KvValue::Bytes(buf) if buf == [1, 2, 3]
+    ));
+    assert!(matches!(
+      round_trip(KvValue::U64(u64::MAX)),
+      KvValue::U64(u64::MAX)
+    ));
+  }
+
+  #[test]
+  fn entry_format() {
+    let mut out = Vec::new();
+    write_entries(
+      &mut out,
+      vec![(
+        entry(
+          vec![KeyPart::String("a".to_string()), KeyPart::Float(1.5)],
+          KvValue::Bytes(vec![104, 105]),
+        ),
+        None,
+      )],
+    )
+    .unwrap();
+    assert_eq!(
+      String::from_utf8(out).unwrap(),
+      concat!(
+        r#"{"key":[{"type":"string","value":"a"},{"type":"number","value":"1.5"}],"#,
+        r#""value":{"type":"bytes","value":"aGk="},"versionstamp":"00000000000000010000"}"#,
+        "\n"
+      )
+    );
+  }
+
+  #[test]
+  fn entry_expire_at() {
+    let mut out = Vec::new();
+    write_entries(
+      &mut out,
+      vec![(
+        entry(vec![KeyPart::True], KvValue::U64(1)),
+        Some(1_700_000_000_123),
+      )],
+    )
+    .unwrap();
+    let line = String::from_utf8(out).unwrap();
+    assert!(line.ends_with(concat!(r#""expireAt":1700000000123}"#, "\n")));
+    let (_, _, expire_at) = parse_line(&line, 1).unwrap().unwrap();
+    assert_eq!(expire_at.unwrap().timestamp_millis(), 1_700_000_000_123);
+
+    let err = parse_line(
+      r#"{"key":[{"type":"boolean","value":true}],"value":{"type":"u64","value":"1"},"versionstamp":"","expireAt":18446744073709551615}"#,
+      2,
+    )
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid entry on line 2: invalid expireAt 18446744073709551615"
+    );
+  }
+
+  #[test]
+  fn invalid_lines() {
+    assert!(parse_line("  \n", 1).unwrap().is_none());
+    let err = parse_line("{}", 3).unwrap_err();
+    assert!(matches!(err, KvBackupError::InvalidEntry { line: 3, .. }));
+    let err = parse_line(
+      r#"{"key":[],"value":{"type":"u64","value":"1"},"versionstamp":""}"#,
+      4,
+    )
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid entry on line 4: key cannot be empty"
+    );
+    let err = parse_line(
+      r#"{"key":[{"type":"bigint","value":"x"}],"value":{"type":"u64","value":"1"},"versionstamp":""}"#,
+      5,
+    )
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid entry on line 5: invalid bigint 'x'"
+    );
+  }
+}
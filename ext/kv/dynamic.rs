@@ -13,6 +13,7 @@ use denokv_proto::WatchStream;
 use crate::AtomicWrite;
 use crate::Database;
 use crate::DatabaseHandler;
+use crate::ExpirationReader;
 use crate::QueueMessageHandle;
 use crate::ReadRange;
 use crate::SnapshotReadOptions;
@@ -123,7 +124,7 @@ impl DatabaseHandler for Box<dyn DynamicDbHandler> {
 impl<T, DB> DynamicDbHandler for T
 where
   T: DatabaseHandler<DB = DB>,
-  DB: Database + 'static,
+  DB: Database + ExpirationReader + 'static,
 {
   async fn dyn_open(
     &self,
@@ -154,6 +155,11 @@ pub trait DynamicDb {
   fn dyn_watch(&self, keys: Vec<Vec<u8>>) -> WatchStream;
 
   fn dyn_close(&self);
+
+  async fn dyn_read_expirations(
+    &self,
+    keys: Vec<Vec<u8>>,
+  ) -> Result<Vec<Option<u64>>, JsErrorBox>;
 }
 
 #[derive(Clone)]
@@ -193,10 +199,20 @@ impl Database for RcDynamicDb {
   }
 }
 
+#[async_trait(?Send)]
+impl ExpirationReader for RcDynamicDb {
+  async fn read_expirations(
+    &self,
+    keys: Vec<Vec<u8>>,
+  ) -> Result<Vec<Option<u64>>, JsErrorBox> {
+    (*self.0).dyn_read_expirations(keys).await
+  }
+}
+
 #[async_trait(?Send)]
 impl<T, QMH> DynamicDb for T
 where
-  T: Database<QMH = QMH>,
+  T: Database<QMH = QMH> + ExpirationReader,
   QMH: QueueMessageHandle + 'static,
 {
   async fn dyn_snapshot_read(
@@ -232,4 +248,11 @@ where
   fn dyn_close(&self) {
     self.close()
   }
+
+  async fn dyn_read_expirations(
+    &self,
+    keys: Vec<Vec<u8>>,
+  ) -> Result<Vec<Option<u64>>, JsErrorBox> {
+    self.read_expirations(keys).await
+  }
 }
@@ -10,7 +10,7 @@ use denokv_proto::Database;
 
 #[async_trait(?Send)]
 pub trait DatabaseHandler {
-  type DB: Database + 'static;
+  type DB: Database + ExpirationReader + 'static;
 
   async fn open(
     &self,
@@ -18,3 +18,16 @@ pub trait DatabaseHandler {
     path: Option<String>,
   ) -> Result<Self::DB, JsErrorBox>;
 }
+
+/// Reads when entries expire, which [`Database`] doesn't return along with
+/// the entries, to include it in exports.
+#[async_trait(?Send)]
+pub trait ExpirationReader {
+  /// Returns when each of the entries with `keys` expires, in milliseconds
+  /// since the epoch, or `None` for entries that don't expire or whose
+  /// expiration time isn't known.
+  async fn read_expirations(
+    &self,
+    keys: Vec<Vec<u8>>,
+  ) -> Result<Vec<Option<u64>>, JsErrorBox>;
+}
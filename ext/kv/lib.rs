@@ -1,5 +1,6 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
 
+pub mod backup;
 pub mod config;
 pub mod dynamic;
 mod interface;
@@ -74,6 +75,8 @@ deno_core::extension!(deno_kv,
     op_kv_finish_dequeued_message<DBH>,
     op_kv_watch<DBH>,
     op_kv_watch_next,
+    op_kv_export_batch<DBH>,
+    op_kv_import_batch<DBH>,
   ],
   esm = [ "01_db.ts" ],
   options = {
@@ -207,6 +210,9 @@ pub enum KvErrorKind {
   #[class(type)]
   #[error("Invalid range")]
   InvalidRange,
+  #[class(inherit)]
+  #[error(transparent)]
+  Backup(#[from] backup::KvBackupError),
 }
 
 #[op2(async, stack_trace)]
@@ -1009,6 +1015,111 @@ fn op_kv_encode_cursor(
   Ok(cursor)
 }
 
+#[derive(Serialize)]
+struct ExportBatch {
+  /// The entries of the batch as JSON lines.
+  data: String,
+  /// The cursor to pass to read the next batch, or `None` if there is none.
+  cursor: Option<String>,
+}
+
+#[op2(async)]
+#[serde]
+async fn op_kv_export_batch<DBH>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[serde] prefix: KvKey,
+  #[string] cursor: Option<String>,
+) -> Result<ExportBatch, KvError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let db = {
+    let state = state.borrow();
+    let resource = state
+      .resource_table
+      .get::<DatabaseResource<DBH::DB>>(rid)
+      .map_err(KvErrorKind::Resource)?;
+    resource.db.clone()
+  };
+
+  let prefix = if prefix.is_empty() {
+    vec![]
+  } else {
+    encode_v8_key(prefix)?
+  };
+  let cursor = cursor
+    .map(|cursor| BASE64_URL_SAFE.decode(cursor))
+    .transpose()
+    .map_err(|_| KvErrorKind::InvalidCursor)?;
+  if let Some(cursor) = &cursor {
+    if !cursor.starts_with(&prefix) {
+      return Err(KvErrorKind::CursorOutOfBounds.into_box());
+    }
+  }
+
+  let entries = backup::export_batch(
+    &db,
+    &prefix,
+    cursor.as_deref(),
+    backup::EXPORT_BATCH_SIZE,
+  )
+  .await?;
+  let cursor = if entries.len() < backup::EXPORT_BATCH_SIZE as usize {
+    None
+  } else {
+    entries
+      .last()
+      .map(|(entry, _)| BASE64_URL_SAFE.encode(&entry.key))
+  };
+  let mut data = Vec::new();
+  backup::write_entries(&mut data, entries)?;
+  Ok(ExportBatch {
+    // serde_json only writes valid UTF-8.
+    data: String::from_utf8(data).unwrap(),
+    cursor,
+  })
+}
+
+#[op2(async)]
+#[number]
+async fn op_kv_import_batch<DBH>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] data: String,
+  #[number] line_offset: usize,
+) -> Result<u64, KvError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let db = {
+    let state = state.borrow();
+    let resource = state
+      .resource_table
+      .get::<DatabaseResource<DBH::DB>>(rid)
+      .map_err(KvErrorKind::Resource)?;
+    resource.db.clone()
+  };
+
+  let config = {
+    let state = state.borrow();
+    state.borrow::<Rc<KvConfig>>().clone()
+  };
+
+  let count = backup::import(&db, &mut data.as_bytes(), &config)
+    .await
+    .map_err(|err| match err {
+      backup::KvBackupError::InvalidEntry { line, message } => {
+        backup::KvBackupError::InvalidEntry {
+          line: line + line_offset,
+          message,
+        }
+      }
+      err => err,
+    })?;
+  Ok(count)
+}
+
 fn check_read_key_size(key: &[u8], config: &KvConfig) -> Result<(), KvError> {
   if key.len() > config.max_read_key_size_bytes {
     Err(
@@ -26,6 +26,7 @@ use http_body_util::BodyExt;
 use url::Url;
 
 use crate::DatabaseHandler;
+use crate::ExpirationReader;
 
 #[derive(Clone)]
 pub struct HttpOptions {
@@ -161,6 +162,18 @@ impl RemoteResponse for FetchResponse {
   }
 }
 
+/// The remote protocol doesn't return expiration times, so entries of remote
+/// databases are exported without them.
+#[async_trait(?Send)]
+impl<P, T> ExpirationReader for Remote<P, T> {
+  async fn read_expirations(
+    &self,
+    keys: Vec<Vec<u8>>,
+  ) -> Result<Vec<Option<u64>>, JsErrorBox> {
+    Ok(vec![None; keys.len()])
+  }
+}
+
 #[async_trait(?Send)]
 impl<P: RemoteDbHandlerPermissions + 'static> DatabaseHandler
   for RemoteDbHandler<P>
@@ -229,6 +242,11 @@ impl<P: RemoteDbHandlerPermissions + 'static> DatabaseHandler
         http2: true,
         local_address: None,
         client_builder_hook: None,
+        cache: None,
+        cookie_jar: None,
+        connect_timeout: None,
+        timeout: None,
+        retry: None,
       },
     )
     .map_err(JsErrorBox::from_err)?;
@@ -18,13 +18,22 @@ use deno_error::JsErrorBox;
 use deno_permissions::CheckedPath;
 use deno_permissions::OpenAccessKind;
 use deno_permissions::PermissionCheckError;
+use denokv_proto::AtomicWrite;
+use denokv_proto::CommitResult;
+use denokv_proto::Database;
+use denokv_proto::ReadRange;
+use denokv_proto::ReadRangeOutput;
+use denokv_proto::SnapshotReadOptions;
+use denokv_proto::WatchStream;
 pub use denokv_sqlite::SqliteBackendError;
 use denokv_sqlite::SqliteConfig;
 use denokv_sqlite::SqliteNotifier;
 use rand::SeedableRng;
 use rusqlite::OpenFlags;
+use rusqlite::OptionalExtension;
 
 use crate::DatabaseHandler;
+use crate::ExpirationReader;
 
 static SQLITE_NOTIFIERS_MAP: OnceLock<Mutex<HashMap<PathBuf, SqliteNotifier>>> =
   OnceLock::new();
@@ -89,7 +98,7 @@ enum Mode {
 
 #[async_trait(?Send)]
 impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
-  type DB = denokv_sqlite::Sqlite;
+  type DB = SqliteDb;
 
   async fn open(
     &self,
@@ -136,8 +145,6 @@ impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
 
     let path = validate_path::<P>(&state, path)?;
     let default_storage_dir = self.default_storage_dir.clone();
-    type ConnGen =
-      Arc<dyn Fn() -> rusqlite::Result<rusqlite::Connection> + Send + Sync>;
     let (conn_gen, notifier_key): (ConnGen, _) = spawn_blocking(move || {
       denokv_sqlite::sqlite_retry_loop(move || {
         let mode = match std::env::var("DENO_KV_DB_MODE")
@@ -200,6 +207,8 @@ impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
     .unwrap()
     .map_err(JsErrorBox::from_err)?;
 
+    // Only databases on disk can be shared with another connection.
+    let shared_conn_gen = notifier_key.is_some().then(|| conn_gen.clone());
     let notifier = if let Some(notifier_key) = notifier_key {
       SQLITE_NOTIFIERS_MAP
         .get_or_init(Default::default)
@@ -212,31 +221,135 @@ impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
       SqliteNotifier::default()
     };
 
-    let versionstamp_rng_seed = self.versionstamp_rng_seed;
+    let db = new_sqlite(conn_gen, notifier, self.versionstamp_rng_seed)?;
+    Ok(SqliteDb {
+      db,
+      conn_gen: shared_conn_gen,
+    })
+  }
+}
 
-    let config = SqliteConfig {
-      batch_timeout: None,
-      num_workers: 1,
-    };
+/// A SQLite database, which also reads the expiration times of its entries
+/// for exports.
+#[derive(Clone)]
+pub struct SqliteDb {
+  db: denokv_sqlite::Sqlite,
+  /// Opens another connection to the database, or `None` for in-memory
+  /// databases, which can't be shared between connections. Entries of
+  /// in-memory databases are exported without their expiration times.
+  conn_gen: Option<ConnGen>,
+}
 
-    denokv_sqlite::Sqlite::new(
-      move || {
-        let conn =
-          conn_gen().map_err(|e| JsErrorBox::generic(e.to_string()))?;
-        conn
-          .pragma_update(None, "journal_mode", "wal")
-          .map_err(|e| JsErrorBox::generic(e.to_string()))?;
-        Ok((
-          conn,
-          match versionstamp_rng_seed {
-            Some(seed) => Box::new(rand::rngs::StdRng::seed_from_u64(seed)),
-            None => Box::new(rand::rngs::StdRng::from_entropy()),
-          },
-        ))
-      },
-      notifier,
-      config,
-    )
+#[async_trait(?Send)]
+impl Database for SqliteDb {
+  type QMH = <denokv_sqlite::Sqlite as Database>::QMH;
+
+  async fn snapshot_read(
+    &self,
+    requests: Vec<ReadRange>,
+    options: SnapshotReadOptions,
+  ) -> Result<Vec<ReadRangeOutput>, JsErrorBox> {
+    self.db.snapshot_read(requests, options).await
+  }
+
+  async fn atomic_write(
+    &self,
+    write: AtomicWrite,
+  ) -> Result<Option<CommitResult>, JsErrorBox> {
+    self.db.atomic_write(write).await
+  }
+
+  async fn dequeue_next_message(
+    &self,
+  ) -> Result<Option<Self::QMH>, JsErrorBox> {
+    self.db.dequeue_next_message().await
+  }
+
+  fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream {
+    self.db.watch(keys)
+  }
+
+  fn close(&self) {
+    self.db.close()
+  }
+}
+
+#[async_trait(?Send)]
+impl ExpirationReader for SqliteDb {
+  async fn read_expirations(
+    &self,
+    keys: Vec<Vec<u8>>,
+  ) -> Result<Vec<Option<u64>>, JsErrorBox> {
+    let Some(conn_gen) = self.conn_gen.clone() else {
+      return Ok(vec![None; keys.len()]);
+    };
+    spawn_blocking(move || {
+      let conn = conn_gen()?;
+      let mut stmt =
+        conn.prepare_cached("select expiration_ms from kv where k = ?")?;
+      keys
+        .iter()
+        .map(|key| {
+          let expiration_ms = stmt
+            .query_row([key], |row| row.get::<_, i64>(0))
+            .optional()?;
+          // Entries that don't expire have an expiration time of -1.
+          Ok(
+            expiration_ms
+              .filter(|expiration_ms| *expiration_ms >= 0)
+              .map(|expiration_ms| expiration_ms as u64),
+          )
+        })
+        .collect::<Result<Vec<_>, rusqlite::Error>>()
+    })
+    .await
+    .unwrap()
     .map_err(|e| JsErrorBox::generic(e.to_string()))
   }
 }
+
+type ConnGen =
+  Arc<dyn Fn() -> rusqlite::Result<rusqlite::Connection> + Send + Sync>;
+
+/// Opens the database at `path` outside of a runtime, such as for exporting
+/// and importing it from the command line.
+pub fn open_path(path: PathBuf) -> Result<SqliteDb, JsErrorBox> {
+  let flags = OpenFlags::default().difference(OpenFlags::SQLITE_OPEN_URI);
+  let conn_gen: ConnGen =
+    Arc::new(move || rusqlite::Connection::open_with_flags(&path, flags));
+  let db = new_sqlite(conn_gen.clone(), SqliteNotifier::default(), None)?;
+  Ok(SqliteDb {
+    db,
+    conn_gen: Some(conn_gen),
+  })
+}
+
+fn new_sqlite(
+  conn_gen: ConnGen,
+  notifier: SqliteNotifier,
+  versionstamp_rng_seed: Option<u64>,
+) -> Result<denokv_sqlite::Sqlite, JsErrorBox> {
+  let config = SqliteConfig {
+    batch_timeout: None,
+    num_workers: 1,
+  };
+
+  denokv_sqlite::Sqlite::new(
+    move || {
+      let conn = conn_gen().map_err(|e| JsErrorBox::generic(e.to_string()))?;
+      conn
+        .pragma_update(None, "journal_mode", "wal")
+        .map_err(|e| JsErrorBox::generic(e.to_string()))?;
+      Ok((
+        conn,
+        match versionstamp_rng_seed {
+          Some(seed) => Box::new(rand::rngs::StdRng::seed_from_u64(seed)),
+          None => Box::new(rand::rngs::StdRng::from_entropy()),
+        },
+      ))
+    },
+    notifier,
+    config,
+  )
+  .map_err(|e| JsErrorBox::generic(e.to_string()))
+}
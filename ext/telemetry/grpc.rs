@@ -0,0 +1,561 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! OTLP/gRPC exporters for traces, metrics and logs.
+//!
+//! The exporters in `opentelemetry_otlp` that speak gRPC are built on top of
+//! `tonic`'s own transport, which would bypass the tunnel, vsock and TLS
+//! configuration that `HyperClient` applies. Instead, the OTLP requests are
+//! encoded with `prost` and sent as unary gRPC calls over an HTTP/2-only
+//! `HyperClient`.
+
+use std::env;
+use std::time::Duration;
+
+use deno_core::futures::FutureExt;
+use deno_core::futures::future::BoxFuture;
+use hyper::HeaderMap;
+use hyper::Request;
+use hyper::Uri;
+use hyper::header::HeaderName;
+use hyper::header::HeaderValue;
+use opentelemetry::trace::TraceError;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
+use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::export::logs::LogBatch;
+use opentelemetry_sdk::export::logs::LogExporter;
+use opentelemetry_sdk::export::trace::ExportResult;
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::export::trace::SpanExporter;
+use opentelemetry_sdk::logs::LogError;
+use opentelemetry_sdk::logs::LogResult;
+use opentelemetry_sdk::metrics::MetricError;
+use opentelemetry_sdk::metrics::MetricResult;
+use opentelemetry_sdk::metrics::Temporality;
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+
+use crate::hyper_client::HyperClient;
+
+const DEFAULT_ENDPOINT: &str = "http://localhost:4317";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const TRACE_EXPORT_PATH: &str =
+  "/opentelemetry.proto.collector.trace.v1.TraceService/Export";
+const METRICS_EXPORT_PATH: &str =
+  "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export";
+const LOGS_EXPORT_PATH: &str =
+  "/opentelemetry.proto.collector.logs.v1.LogsService/Export";
+
+#[derive(Debug, thiserror::Error)]
+pub enum GrpcError {
+  #[error("Invalid OTLP gRPC endpoint '{0}'")]
+  InvalidEndpoint(String),
+  #[error("Invalid OTLP header '{0}'")]
+  InvalidHeader(String),
+  #[error("OTLP gRPC request failed: {0}")]
+  Http(Box<dyn std::error::Error + Send + Sync>),
+  #[error("OTLP gRPC request failed with HTTP status {0}")]
+  HttpStatus(hyper::StatusCode),
+  #[error("OTLP gRPC request failed with status {code}: {message}")]
+  Status { code: u32, message: String },
+  #[error("OTLP gRPC response is missing the grpc-status field")]
+  MissingStatus,
+  #[error("OTLP gRPC request timed out")]
+  Timeout,
+}
+
+/// The OTLP signals, used to look up the signal-specific environment
+/// variables (e.g. `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`).
+#[derive(Debug, Clone, Copy)]
+pub enum Signal {
+  Traces,
+  Metrics,
+  Logs,
+}
+
+impl Signal {
+  fn env_name(self) -> &'static str {
+    match self {
+      Self::Traces => "TRACES",
+      Self::Metrics => "METRICS",
+      Self::Logs => "LOGS",
+    }
+  }
+}
+
+/// A unary gRPC channel to an OTLP receiver.
+#[derive(Debug, Clone)]
+pub struct GrpcChannel {
+  client: HyperClient,
+  scheme: http::uri::Scheme,
+  authority: http::uri::Authority,
+  headers: HeaderMap,
+  timeout: Duration,
+}
+
+impl GrpcChannel {
+  /// Creates a channel from the `OTEL_EXPORTER_OTLP_*` environment variables.
+  /// Signal-specific variables take precedence over the generic ones.
+  pub fn from_env(
+    client: HyperClient,
+    signal: Signal,
+  ) -> Result<Self, GrpcError> {
+    let signal_var = |name: &str| {
+      env::var(format!("OTEL_EXPORTER_OTLP_{}_{}", signal.env_name(), name))
+        .or_else(|_| env::var(format!("OTEL_EXPORTER_OTLP_{}", name)))
+        .ok()
+        .filter(|value| !value.is_empty())
+    };
+
+    let endpoint =
+      signal_var("ENDPOINT").unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = env::var("OTEL_EXPORTER_OTLP_HEADERS") {
+      parse_headers(&value, &mut headers)?;
+    }
+    if let Ok(value) =
+      env::var(format!("OTEL_EXPORTER_OTLP_{}_HEADERS", signal.env_name()))
+    {
+      parse_headers(&value, &mut headers)?;
+    }
+
+    let timeout = signal_var("TIMEOUT")
+      .and_then(|value| value.parse().ok())
+      .map(Duration::from_millis)
+      .unwrap_or(DEFAULT_TIMEOUT);
+
+    Self::new(client, &endpoint, headers, timeout)
+  }
+
+  pub fn new(
+    client: HyperClient,
+    endpoint: &str,
+    headers: HeaderMap,
+    timeout: Duration,
+  ) -> Result<Self, GrpcError> {
+    // The path of the endpoint is ignored for gRPC, the service and method
+    // names make up the request path.
+    let uri: Uri = endpoint
+      .parse()
+      .map_err(|_| GrpcError::InvalidEndpoint(endpoint.to_string()))?;
+    let (Some(scheme), Some(authority)) =
+      (uri.scheme().cloned(), uri.authority().cloned())
+    else {
+      return Err(GrpcError::InvalidEndpoint(endpoint.to_string()));
+    };
+    Ok(Self {
+      client,
+      scheme,
+      authority,
+      headers,
+      timeout,
+    })
+  }
+
+  async fn unary(
+    &self,
+    path: &'static str,
+    message: impl prost::Message,
+  ) -> Result<(), GrpcError> {
+    let uri = Uri::builder()
+      .scheme(self.scheme.clone())
+      .authority(self.authority.clone())
+      .path_and_query(path)
+      .build()
+      .map_err(|err| GrpcError::Http(err.into()))?;
+
+    let mut request = Request::post(uri)
+      .header("content-type", "application/grpc")
+      .header("te", "trailers")
+      .header("grpc-timeout", format!("{}m", self.timeout.as_millis()))
+      .body(encode_message(&message))
+      .map_err(|err| GrpcError::Http(err.into()))?;
+    request.headers_mut().extend(self.headers.clone());
+
+    let (response, trailers) = tokio::time::timeout(
+      self.timeout,
+      self.client.send_with_trailers(request),
+    )
+    .await
+    .map_err(|_| GrpcError::Timeout)?
+    .map_err(GrpcError::Http)?;
+
+    if response.status() != hyper::StatusCode::OK {
+      return Err(GrpcError::HttpStatus(response.status()));
+    }
+
+    // A "Trailers-Only" response carries the status in the headers.
+    let status_headers = trailers
+      .as_ref()
+      .filter(|trailers| trailers.contains_key("grpc-status"))
+      .unwrap_or(response.headers());
+    let code = status_headers
+      .get("grpc-status")
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.parse::<u32>().ok())
+      .ok_or(GrpcError::MissingStatus)?;
+    if code != 0 {
+      let message = status_headers
+        .get("grpc-message")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+      return Err(GrpcError::Status { code, message });
+    }
+
+    Ok(())
+  }
+}
+
+/// Parses a `key1=value1,key2=value2` list as used by
+/// `OTEL_EXPORTER_OTLP_HEADERS`.
+fn parse_headers(
+  value: &str,
+  headers: &mut HeaderMap,
+) -> Result<(), GrpcError> {
+  for pair in value.split(',').filter(|pair| !pair.trim().is_empty()) {
+    let Some((name, value)) = pair.split_once('=') else {
+      return Err(GrpcError::InvalidHeader(pair.to_string()));
+    };
+    let name = HeaderName::from_bytes(name.trim().as_bytes())
+      .map_err(|_| GrpcError::InvalidHeader(pair.to_string()))?;
+    let value = HeaderValue::from_str(value.trim())
+      .map_err(|_| GrpcError::InvalidHeader(pair.to_string()))?;
+    headers.insert(name, value);
+  }
+  Ok(())
+}
+
+/// Wraps an encoded message in a gRPC length-prefixed frame.
+fn encode_message(message: &impl prost::Message) -> Vec<u8> {
+  let len = message.encoded_len();
+  let mut buf = Vec::with_capacity(5 + len);
+  // Compressed flag, followed by the big-endian message length.
+  buf.push(0);
+  buf.extend_from_slice(&(len as u32).to_be_bytes());
+  message
+    .encode(&mut buf)
+    .expect("Vec<u8> has enough capacity");
+  buf
+}
+
+#[derive(Debug)]
+pub struct GrpcSpanExporter {
+  channel: GrpcChannel,
+  resource: ResourceAttributesWithSchema,
+}
+
+impl GrpcSpanExporter {
+  pub fn new(channel: GrpcChannel) -> Self {
+    Self {
+      channel,
+      resource: ResourceAttributesWithSchema::default(),
+    }
+  }
+}
+
+impl SpanExporter for GrpcSpanExporter {
+  fn export(
+    &mut self,
+    batch: Vec<SpanData>,
+  ) -> BoxFuture<'static, ExportResult> {
+    let channel = self.channel.clone();
+    let request = ExportTraceServiceRequest {
+      resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+    };
+    async move {
+      channel
+        .unary(TRACE_EXPORT_PATH, request)
+        .await
+        .map_err(|err| TraceError::Other(Box::new(err)))
+    }
+    .boxed()
+  }
+
+  fn set_resource(&mut self, resource: &Resource) {
+    self.resource = resource.into();
+  }
+}
+
+#[derive(Debug)]
+pub struct GrpcLogExporter {
+  channel: GrpcChannel,
+  resource: ResourceAttributesWithSchema,
+}
+
+impl GrpcLogExporter {
+  pub fn new(channel: GrpcChannel) -> Self {
+    Self {
+      channel,
+      resource: ResourceAttributesWithSchema::default(),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl LogExporter for GrpcLogExporter {
+  async fn export(&mut self, batch: LogBatch<'_>) -> LogResult<()> {
+    let request = ExportLogsServiceRequest {
+      resource_logs: group_logs_by_resource_and_scope(batch, &self.resource),
+    };
+    self
+      .channel
+      .unary(LOGS_EXPORT_PATH, request)
+      .await
+      .map_err(|err| LogError::Other(Box::new(err)))
+  }
+
+  fn set_resource(&mut self, resource: &Resource) {
+    self.resource = resource.into();
+  }
+}
+
+#[derive(Debug)]
+pub struct GrpcMetricExporter {
+  channel: GrpcChannel,
+  temporality: Temporality,
+}
+
+impl GrpcMetricExporter {
+  pub fn new(channel: GrpcChannel, temporality: Temporality) -> Self {
+    Self {
+      channel,
+      temporality,
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl PushMetricExporter for GrpcMetricExporter {
+  async fn export(&self, metrics: &mut ResourceMetrics) -> MetricResult<()> {
+    let request = ExportMetricsServiceRequest::from(&*metrics);
+    self
+      .channel
+      .unary(METRICS_EXPORT_PATH, request)
+      .await
+      .map_err(|err| MetricError::Other(err.to_string()))
+  }
+
+  async fn force_flush(&self) -> MetricResult<()> {
+    Ok(())
+  }
+
+  fn shutdown(&self) -> MetricResult<()> {
+    Ok(())
+  }
+
+  fn temporality(&self) -> Temporality {
+    self.temporality
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::borrow::Cow;
+  use std::convert::Infallible;
+  use std::time::SystemTime;
+
+  use bytes::Buf;
+  use http_body_util::BodyExt;
+  use http_body_util::Full;
+  use http_body_util::StreamBody;
+  use hyper::Response;
+  use hyper::body::Frame;
+  use hyper::body::Incoming;
+  use hyper::service::service_fn;
+  use hyper_util::rt::TokioExecutor;
+  use hyper_util::rt::TokioIo;
+  use opentelemetry::KeyValue;
+  use opentelemetry::trace::SpanContext;
+  use opentelemetry::trace::SpanId;
+  use opentelemetry::trace::SpanKind;
+  use opentelemetry::trace::Status;
+  use opentelemetry::trace::TraceFlags;
+  use opentelemetry::trace::TraceId;
+  use opentelemetry::trace::TraceState;
+  use opentelemetry_sdk::trace::SpanEvents;
+  use opentelemetry_sdk::trace::SpanLinks;
+  use prost::Message;
+  use tokio::net::TcpListener;
+  use tokio::sync::mpsc;
+
+  use super::*;
+
+  /// How the in-process receiver reports the gRPC status.
+  #[derive(Clone, Copy)]
+  enum Reply {
+    Trailers(u32),
+    TrailersOnly(u32),
+  }
+
+  /// Starts an in-process OTLP/gRPC receiver over h2c and returns its
+  /// endpoint, together with the path and decoded payload of every request.
+  async fn start_receiver(
+    reply: Reply,
+  ) -> (String, mpsc::UnboundedReceiver<(String, Vec<u8>)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+      loop {
+        let (stream, _) = listener.accept().await.unwrap();
+        let tx = tx.clone();
+        let service = service_fn(move |req: hyper::Request<Incoming>| {
+          let tx = tx.clone();
+          async move {
+            assert_eq!(req.headers()["content-type"], "application/grpc");
+            let path = req.uri().path().to_string();
+            let mut body = req.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(body.get_u8(), 0);
+            let len = body.get_u32() as usize;
+            assert_eq!(body.len(), len);
+            tx.send((path, body.to_vec())).unwrap();
+
+            let response =
+              Response::builder().header("content-type", "application/grpc");
+            let response = match reply {
+              Reply::Trailers(code) => {
+                let mut trailers = HeaderMap::new();
+                trailers.insert("grpc-status", code.into());
+                trailers
+                  .insert("grpc-message", HeaderValue::from_static("nope"));
+                let frames = deno_core::futures::stream::iter([
+                  Ok::<_, Infallible>(Frame::data(bytes::Bytes::new())),
+                  Ok(Frame::trailers(trailers)),
+                ]);
+                response.body(StreamBody::new(frames).boxed()).unwrap()
+              }
+              Reply::TrailersOnly(code) => response
+                .header("grpc-status", code)
+                .body(Full::new(bytes::Bytes::new()).boxed())
+                .unwrap(),
+            };
+            Ok::<_, Infallible>(response)
+          }
+        });
+        tokio::spawn(async move {
+          let _ =
+            hyper::server::conn::http2::Builder::new(TokioExecutor::new())
+              .serve_connection(TokioIo::new(stream), service)
+              .await;
+        });
+      }
+    });
+
+    (format!("http://{addr}"), rx)
+  }
+
+  fn channel(endpoint: &str) -> GrpcChannel {
+    GrpcChannel::new(
+      HyperClient::new_http2_only().unwrap(),
+      endpoint,
+      HeaderMap::new(),
+      Duration::from_secs(5),
+    )
+    .unwrap()
+  }
+
+  fn span_data(name: &'static str) -> SpanData {
+    SpanData {
+      span_context: SpanContext::new(
+        TraceId::from_u128(1),
+        SpanId::from_u64(2),
+        TraceFlags::SAMPLED,
+        false,
+        TraceState::NONE,
+      ),
+      parent_span_id: SpanId::INVALID,
+      span_kind: SpanKind::Internal,
+      name: Cow::Borrowed(name),
+      start_time: SystemTime::UNIX_EPOCH,
+      end_time: SystemTime::UNIX_EPOCH,
+      attributes: vec![KeyValue::new("key", "value")],
+      dropped_attributes_count: 0,
+      status: Status::Unset,
+      events: SpanEvents::default(),
+      links: SpanLinks::default(),
+      instrumentation_scope: Default::default(),
+    }
+  }
+
+  #[tokio::test]
+  async fn exports_spans() {
+    let (endpoint, mut rx) = start_receiver(Reply::Trailers(0)).await;
+    let mut exporter = GrpcSpanExporter::new(channel(&endpoint));
+    exporter
+      .set_resource(&Resource::new([KeyValue::new("service.name", "test")]));
+    exporter.export(vec![span_data("span")]).await.unwrap();
+
+    let (path, body) = rx.recv().await.unwrap();
+    assert_eq!(path, TRACE_EXPORT_PATH);
+    let request = ExportTraceServiceRequest::decode(body.as_slice()).unwrap();
+    let resource_spans = &request.resource_spans[0];
+    let resource = resource_spans.resource.as_ref().unwrap();
+    assert_eq!(resource.attributes[0].key, "service.name");
+    let span = &resource_spans.scope_spans[0].spans[0];
+    assert_eq!(span.name, "span");
+    assert_eq!(span.trace_id, TraceId::from_u128(1).to_bytes());
+  }
+
+  #[tokio::test]
+  async fn exports_metrics() {
+    let (endpoint, mut rx) = start_receiver(Reply::TrailersOnly(0)).await;
+    let exporter =
+      GrpcMetricExporter::new(channel(&endpoint), Temporality::Cumulative);
+    let mut metrics = ResourceMetrics {
+      resource: Resource::new([KeyValue::new("service.name", "test")]),
+      scope_metrics: vec![],
+    };
+    exporter.export(&mut metrics).await.unwrap();
+
+    let (path, body) = rx.recv().await.unwrap();
+    assert_eq!(path, METRICS_EXPORT_PATH);
+    let request = ExportMetricsServiceRequest::decode(body.as_slice()).unwrap();
+    assert_eq!(request.resource_metrics.len(), 1);
+  }
+
+  #[tokio::test]
+  async fn reports_grpc_status() {
+    let (endpoint, _rx) = start_receiver(Reply::Trailers(14)).await;
+    let err = channel(&endpoint)
+      .unary(LOGS_EXPORT_PATH, ExportLogsServiceRequest::default())
+      .await
+      .unwrap_err();
+    assert!(matches!(
+      err,
+      GrpcError::Status { code: 14, ref message } if message == "nope"
+    ));
+
+    let (endpoint, _rx) = start_receiver(Reply::TrailersOnly(3)).await;
+    let err = channel(&endpoint)
+      .unary(LOGS_EXPORT_PATH, ExportLogsServiceRequest::default())
+      .await
+      .unwrap_err();
+    assert!(matches!(err, GrpcError::Status { code: 3, .. }));
+  }
+
+  #[test]
+  fn parses_headers() {
+    let mut headers = HeaderMap::new();
+    parse_headers("api-key=secret, x-tenant = a ,", &mut headers).unwrap();
+    assert_eq!(headers["api-key"], "secret");
+    assert_eq!(headers["x-tenant"], "a");
+    assert!(parse_headers("novalue", &mut headers).is_err());
+  }
+
+  #[test]
+  fn rejects_endpoint_without_authority() {
+    let client = HyperClient::new_http2_only().unwrap();
+    assert!(matches!(
+      GrpcChannel::new(client, "/v1/traces", HeaderMap::new(), DEFAULT_TIMEOUT),
+      Err(GrpcError::InvalidEndpoint(_))
+    ));
+  }
+}
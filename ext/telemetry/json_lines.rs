@@ -0,0 +1,257 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! Exporters that write OTLP-JSON lines to stderr or to a file, for use
+//! without an OTLP receiver (e.g. in air-gapped CI or for local debugging).
+//!
+//! Every export call writes a single line containing an
+//! `Export{Trace,Metrics,Logs}ServiceRequest` in the OTLP/JSON encoding, so
+//! the output can be parsed line by line and all three signals can share one
+//! file.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use deno_core::futures::FutureExt;
+use deno_core::futures::future::BoxFuture;
+use opentelemetry::trace::TraceError;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
+use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::export::logs::LogBatch;
+use opentelemetry_sdk::export::logs::LogExporter;
+use opentelemetry_sdk::export::trace::ExportResult;
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::export::trace::SpanExporter;
+use opentelemetry_sdk::logs::LogError;
+use opentelemetry_sdk::logs::LogResult;
+use opentelemetry_sdk::metrics::MetricError;
+use opentelemetry_sdk::metrics::MetricResult;
+use opentelemetry_sdk::metrics::Temporality;
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use serde::Serialize;
+
+/// The destination of the OTLP-JSON lines.
+#[derive(Debug)]
+pub enum JsonLinesWriter {
+  Stderr,
+  File(Mutex<File>),
+}
+
+impl JsonLinesWriter {
+  /// Opens `path` for appending, creating it if it doesn't exist.
+  pub fn open(path: &Path) -> std::io::Result<Self> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(Self::File(Mutex::new(file)))
+  }
+
+  fn write_line(&self, value: &impl Serialize) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    // The whole line is written at once, so that lines from different
+    // exporters don't interleave.
+    match self {
+      Self::Stderr => {
+        let mut stderr = std::io::stderr().lock();
+        stderr.write_all(&line)?;
+        stderr.flush()
+      }
+      Self::File(file) => {
+        let mut file = file.lock().unwrap();
+        file.write_all(&line)?;
+        file.flush()
+      }
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct JsonLinesSpanExporter {
+  writer: Arc<JsonLinesWriter>,
+  resource: ResourceAttributesWithSchema,
+}
+
+impl JsonLinesSpanExporter {
+  pub fn new(writer: Arc<JsonLinesWriter>) -> Self {
+    Self {
+      writer,
+      resource: ResourceAttributesWithSchema::default(),
+    }
+  }
+}
+
+impl SpanExporter for JsonLinesSpanExporter {
+  fn export(
+    &mut self,
+    batch: Vec<SpanData>,
+  ) -> BoxFuture<'static, ExportResult> {
+    let request = ExportTraceServiceRequest {
+      resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+    };
+    let result = self
+      .writer
+      .write_line(&request)
+      .map_err(|err| TraceError::Other(Box::new(err)));
+    async move { result }.boxed()
+  }
+
+  fn set_resource(&mut self, resource: &Resource) {
+    self.resource = resource.into();
+  }
+}
+
+#[derive(Debug)]
+pub struct JsonLinesLogExporter {
+  writer: Arc<JsonLinesWriter>,
+  resource: ResourceAttributesWithSchema,
+}
+
+impl JsonLinesLogExporter {
+  pub fn new(writer: Arc<JsonLinesWriter>) -> Self {
+    Self {
+      writer,
+      resource: ResourceAttributesWithSchema::default(),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl LogExporter for JsonLinesLogExporter {
+  async fn export(&mut self, batch: LogBatch<'_>) -> LogResult<()> {
+    let request = ExportLogsServiceRequest {
+      resource_logs: group_logs_by_resource_and_scope(batch, &self.resource),
+    };
+    self
+      .writer
+      .write_line(&request)
+      .map_err(|err| LogError::Other(Box::new(err)))
+  }
+
+  fn set_resource(&mut self, resource: &Resource) {
+    self.resource = resource.into();
+  }
+}
+
+#[derive(Debug)]
+pub struct JsonLinesMetricExporter {
+  writer: Arc<JsonLinesWriter>,
+  temporality: Temporality,
+}
+
+impl JsonLinesMetricExporter {
+  pub fn new(writer: Arc<JsonLinesWriter>, temporality: Temporality) -> Self {
+    Self {
+      writer,
+      temporality,
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl PushMetricExporter for JsonLinesMetricExporter {
+  async fn export(&self, metrics: &mut ResourceMetrics) -> MetricResult<()> {
+    let request = ExportMetricsServiceRequest::from(&*metrics);
+    self
+      .writer
+      .write_line(&request)
+      .map_err(|err| MetricError::Other(err.to_string()))
+  }
+
+  async fn force_flush(&self) -> MetricResult<()> {
+    Ok(())
+  }
+
+  fn shutdown(&self) -> MetricResult<()> {
+    Ok(())
+  }
+
+  fn temporality(&self) -> Temporality {
+    self.temporality
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::borrow::Cow;
+  use std::time::SystemTime;
+
+  use opentelemetry::KeyValue;
+  use opentelemetry::trace::SpanContext;
+  use opentelemetry::trace::SpanId;
+  use opentelemetry::trace::SpanKind;
+  use opentelemetry::trace::Status;
+  use opentelemetry::trace::TraceFlags;
+  use opentelemetry::trace::TraceId;
+  use opentelemetry::trace::TraceState;
+  use opentelemetry_sdk::trace::SpanEvents;
+  use opentelemetry_sdk::trace::SpanLinks;
+
+  use super::*;
+
+  fn span_data(name: &'static str) -> SpanData {
+    SpanData {
+      span_context: SpanContext::new(
+        TraceId::from_u128(1),
+        SpanId::from_u64(2),
+        TraceFlags::SAMPLED,
+        false,
+        TraceState::NONE,
+      ),
+      parent_span_id: SpanId::INVALID,
+      span_kind: SpanKind::Internal,
+      name: Cow::Borrowed(name),
+      start_time: SystemTime::UNIX_EPOCH,
+      end_time: SystemTime::UNIX_EPOCH,
+      attributes: vec![],
+      dropped_attributes_count: 0,
+      status: Status::Unset,
+      events: SpanEvents::default(),
+      links: SpanLinks::default(),
+      instrumentation_scope: Default::default(),
+    }
+  }
+
+  #[tokio::test]
+  async fn writes_one_line_per_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("otel.jsonl");
+    let writer = Arc::new(JsonLinesWriter::open(&path).unwrap());
+
+    let mut span_exporter = JsonLinesSpanExporter::new(writer.clone());
+    span_exporter
+      .set_resource(&Resource::new([KeyValue::new("service.name", "test")]));
+    span_exporter.export(vec![span_data("a")]).await.unwrap();
+    span_exporter.export(vec![span_data("b")]).await.unwrap();
+
+    let metric_exporter =
+      JsonLinesMetricExporter::new(writer, Temporality::Cumulative);
+    metric_exporter
+      .export(&mut ResourceMetrics {
+        resource: Resource::empty(),
+        scope_metrics: vec![],
+      })
+      .await
+      .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines = contents
+      .lines()
+      .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    let span = &lines[0]["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+    assert_eq!(span["name"], "a");
+    assert_eq!(span["traceId"], "00000000000000000000000000000001");
+    let span = &lines[1]["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+    assert_eq!(span["name"], "b");
+    assert!(lines[2]["resourceMetrics"].is_array());
+  }
+}
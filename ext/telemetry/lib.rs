@@ -9,6 +9,7 @@ use std::collections::HashMap;
 use std::env;
 use std::ffi::c_void;
 use std::fmt::Debug;
+use std::path::Path;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -94,6 +95,12 @@ use thiserror::Error;
 use tokio::sync::oneshot;
 use tokio::task::JoinSet;
 
+mod grpc;
+mod json_lines;
+mod sampler;
+
+pub use sampler::OtelSampler;
+
 deno_core::extension!(
   deno_telemetry,
   ops = [
@@ -135,6 +142,7 @@ pub struct OtelConfig {
   pub console: OtelConsoleConfig,
   pub deterministic_prefix: Option<u8>,
   pub propagators: std::collections::HashSet<OtelPropagators>,
+  pub sampler: OtelSampler,
 }
 
 impl OtelConfig {
@@ -176,6 +184,46 @@ impl Default for OtelConsoleConfig {
   }
 }
 
+/// The transport used to talk to the OTLP endpoint, as selected by
+/// `OTEL_EXPORTER_OTLP_PROTOCOL`.
+#[derive(Debug, Clone, Copy)]
+enum OtlpProtocol {
+  Http(Protocol),
+  Grpc,
+}
+
+/// The exporter used for a signal, as selected by `OTEL_TRACES_EXPORTER`,
+/// `OTEL_METRICS_EXPORTER` and `OTEL_LOGS_EXPORTER`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignalExporter {
+  /// Export to an OTLP endpoint using `OTEL_EXPORTER_OTLP_PROTOCOL`.
+  Otlp,
+  /// Write OTLP-JSON lines to stderr.
+  Console,
+  /// Write OTLP-JSON lines to the file at `OTEL_DENO_EXPORTER_FILE`.
+  File,
+}
+
+impl SignalExporter {
+  fn from_env(name: &str) -> deno_core::anyhow::Result<Self> {
+    match env::var(name).as_deref() {
+      Ok("otlp") | Ok("") | Err(env::VarError::NotPresent) => Ok(Self::Otlp),
+      Ok("console") => Ok(Self::Console),
+      Ok("file") => Ok(Self::File),
+      Ok(exporter) => Err(deno_core::anyhow::anyhow!(
+        "Env var {} specifies an unsupported exporter: {}",
+        name,
+        exporter
+      )),
+      Err(err) => Err(deno_core::anyhow::anyhow!(
+        "Failed to read env var {}: {}",
+        name,
+        err
+      )),
+    }
+  }
+}
+
 static OTEL_SHARED_RUNTIME_SPAWN_TASK_TX: Lazy<
   UnboundedSender<BoxFuture<'static, ()>>,
 > = Lazy::new(otel_create_shared_runtime);
@@ -376,7 +424,7 @@ const METRIC_EXPORT_INTERVAL_NAME: &str = "OTEL_METRIC_EXPORT_INTERVAL";
 const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
 
 impl DenoPeriodicReader {
-  fn new(exporter: opentelemetry_otlp::MetricExporter) -> Self {
+  fn new(exporter: impl PushMetricExporter) -> Self {
     let interval = env::var(METRIC_EXPORT_INTERVAL_NAME)
       .ok()
       .and_then(|v| v.parse().map(Duration::from_millis).ok())
@@ -511,6 +559,7 @@ mod hyper_client {
   use deno_tls::load_private_keys;
   use http_body_util::BodyExt;
   use http_body_util::Full;
+  use hyper::HeaderMap;
   use hyper::Uri;
   use hyper_rustls::HttpsConnector;
   use hyper_rustls::MaybeHttpsStream;
@@ -695,6 +744,17 @@ mod hyper_client {
 
   impl HyperClient {
     pub fn new() -> deno_core::anyhow::Result<Self> {
+      Self::with_http2_only(false)
+    }
+
+    /// Creates a client that only speaks HTTP/2, as required by OTLP/gRPC.
+    /// Plaintext endpoints use HTTP/2 with prior knowledge (h2c), TLS
+    /// endpoints negotiate `h2` through ALPN.
+    pub fn new_http2_only() -> deno_core::anyhow::Result<Self> {
+      Self::with_http2_only(true)
+    }
+
+    fn with_http2_only(http2_only: bool) -> deno_core::anyhow::Result<Self> {
       let connector = if let Some(tunnel) = get_tunnel() {
         Connector::Tunnel(tunnel.clone())
       } else if let Ok(addr) = std::env::var("OTEL_DENO_VSOCK") {
@@ -736,8 +796,13 @@ mod hyper_client {
           _ => TlsKeys::Null,
         };
 
+        let socket_use = if http2_only {
+          SocketUse::Http2Only
+        } else {
+          SocketUse::Http
+        };
         let tls_config =
-          create_client_config(None, ca_certs, None, keys, SocketUse::Http)?;
+          create_client_config(None, ca_certs, None, keys, socket_use)?;
         let mut http_connector = HttpConnector::new();
         http_connector.enforce_http(false);
         let connector = HttpsConnector::from((http_connector, tls_config));
@@ -745,9 +810,29 @@ mod hyper_client {
       };
 
       Ok(Self {
-        inner: Client::builder(OtelSharedRuntime).build(connector),
+        inner: Client::builder(OtelSharedRuntime)
+          .http2_only(http2_only)
+          .build(connector),
       })
     }
+
+    /// Sends a request and returns the response together with the trailers
+    /// that followed the body, if any. Unlike `HttpClient::send` this does not
+    /// turn non-2xx statuses into errors, because gRPC reports its own status
+    /// in the trailers.
+    pub async fn send_with_trailers(
+      &self,
+      request: Request<Vec<u8>>,
+    ) -> Result<(Response<Bytes>, Option<HeaderMap>), HttpError> {
+      let (parts, body) = request.into_parts();
+      let request = Request::from_parts(parts, Full::from(body));
+      let response = self.inner.request(request).await?;
+      let (parts, body) = response.into_parts();
+      let collected = body.collect().await?;
+      let trailers = collected.trailers().cloned();
+      let response = Response::from_parts(parts, collected.to_bytes());
+      Ok((response, trailers))
+    }
   }
 
   #[async_trait::async_trait]
@@ -802,11 +887,13 @@ pub fn init(
 
   // Parse the `OTEL_EXPORTER_OTLP_PROTOCOL` variable. The opentelemetry_*
   // crates don't do this automatically.
-  // TODO(piscisaureus): enable GRPC support.
   let protocol = match env::var("OTEL_EXPORTER_OTLP_PROTOCOL").as_deref() {
-    Ok("http/protobuf") => Protocol::HttpBinary,
-    Ok("http/json") => Protocol::HttpJson,
-    Ok("") | Err(env::VarError::NotPresent) => Protocol::HttpBinary,
+    Ok("http/protobuf") => OtlpProtocol::Http(Protocol::HttpBinary),
+    Ok("http/json") => OtlpProtocol::Http(Protocol::HttpJson),
+    Ok("grpc") => OtlpProtocol::Grpc,
+    Ok("") | Err(env::VarError::NotPresent) => {
+      OtlpProtocol::Http(Protocol::HttpBinary)
+    }
     Ok(protocol) => {
       return Err(deno_core::anyhow::anyhow!(
         "Env var OTEL_EXPORTER_OTLP_PROTOCOL specifies an unsupported protocol: {}",
@@ -862,14 +949,58 @@ pub fn init(
   // `OTEL_EXPORTER_OTLP_ENDPOINT` environment variable. Additional headers can
   // be specified using `OTEL_EXPORTER_OTLP_HEADERS`.
 
-  let client = hyper_client::HyperClient::new()?;
+  let client = match protocol {
+    OtlpProtocol::Http(_) => hyper_client::HyperClient::new()?,
+    OtlpProtocol::Grpc => hyper_client::HyperClient::new_http2_only()?,
+  };
 
-  let span_exporter = HttpExporterBuilder::default()
-    .with_http_client(client.clone())
-    .with_protocol(protocol)
-    .build_span_exporter()?;
-  let mut span_processor =
-    BatchSpanProcessor::builder(span_exporter, OtelSharedRuntime).build();
+  // The console and file exporters write OTLP-JSON lines instead of talking
+  // to an OTLP endpoint. All signals that use the file exporter share a
+  // single file.
+  let traces_exporter = SignalExporter::from_env("OTEL_TRACES_EXPORTER")?;
+  let metrics_exporter = SignalExporter::from_env("OTEL_METRICS_EXPORTER")?;
+  let logs_exporter = SignalExporter::from_env("OTEL_LOGS_EXPORTER")?;
+  let mut file_writer = None;
+  let mut json_lines_writer =
+    |exporter: SignalExporter| -> deno_core::anyhow::Result<_> {
+      if exporter != SignalExporter::File {
+        return Ok(Arc::new(json_lines::JsonLinesWriter::Stderr));
+      }
+      if let Some(writer) = &file_writer {
+        return Ok(Arc::clone(writer));
+      }
+      let path = env::var("OTEL_DENO_EXPORTER_FILE").map_err(|_| {
+        deno_core::anyhow::anyhow!(
+          "Env var OTEL_DENO_EXPORTER_FILE must be set to use the file exporter"
+        )
+      })?;
+      let writer =
+        Arc::new(json_lines::JsonLinesWriter::open(Path::new(&path))?);
+      file_writer = Some(Arc::clone(&writer));
+      Ok(writer)
+    };
+
+  let mut span_processor = match (traces_exporter, protocol) {
+    (SignalExporter::Console | SignalExporter::File, _) => {
+      let span_exporter = json_lines::JsonLinesSpanExporter::new(
+        json_lines_writer(traces_exporter)?,
+      );
+      BatchSpanProcessor::builder(span_exporter, OtelSharedRuntime).build()
+    }
+    (SignalExporter::Otlp, OtlpProtocol::Http(protocol)) => {
+      let span_exporter = HttpExporterBuilder::default()
+        .with_http_client(client.clone())
+        .with_protocol(protocol)
+        .build_span_exporter()?;
+      BatchSpanProcessor::builder(span_exporter, OtelSharedRuntime).build()
+    }
+    (SignalExporter::Otlp, OtlpProtocol::Grpc) => {
+      let channel =
+        grpc::GrpcChannel::from_env(client.clone(), grpc::Signal::Traces)?;
+      let span_exporter = grpc::GrpcSpanExporter::new(channel);
+      BatchSpanProcessor::builder(span_exporter, OtelSharedRuntime).build()
+    }
+  };
   span_processor.set_resource(&resource);
 
   let temporality_preference =
@@ -887,22 +1018,55 @@ pub fn init(
       ));
     }
   };
-  let metric_exporter = HttpExporterBuilder::default()
-    .with_http_client(client.clone())
-    .with_protocol(protocol)
-    .build_metrics_exporter(temporality)?;
-  let metric_reader = DenoPeriodicReader::new(metric_exporter);
+  let metric_reader = match (metrics_exporter, protocol) {
+    (SignalExporter::Console | SignalExporter::File, _) => {
+      DenoPeriodicReader::new(json_lines::JsonLinesMetricExporter::new(
+        json_lines_writer(metrics_exporter)?,
+        temporality,
+      ))
+    }
+    (SignalExporter::Otlp, OtlpProtocol::Http(protocol)) => {
+      let metric_exporter = HttpExporterBuilder::default()
+        .with_http_client(client.clone())
+        .with_protocol(protocol)
+        .build_metrics_exporter(temporality)?;
+      DenoPeriodicReader::new(metric_exporter)
+    }
+    (SignalExporter::Otlp, OtlpProtocol::Grpc) => {
+      let channel =
+        grpc::GrpcChannel::from_env(client.clone(), grpc::Signal::Metrics)?;
+      DenoPeriodicReader::new(grpc::GrpcMetricExporter::new(
+        channel,
+        temporality,
+      ))
+    }
+  };
   let meter_provider = SdkMeterProvider::builder()
     .with_reader(metric_reader)
     .with_resource(resource.clone())
     .build();
 
-  let log_exporter = HttpExporterBuilder::default()
-    .with_http_client(client)
-    .with_protocol(protocol)
-    .build_log_exporter()?;
-  let log_processor =
-    BatchLogProcessor::builder(log_exporter, OtelSharedRuntime).build();
+  let log_processor = match (logs_exporter, protocol) {
+    (SignalExporter::Console | SignalExporter::File, _) => {
+      let log_exporter = json_lines::JsonLinesLogExporter::new(
+        json_lines_writer(logs_exporter)?,
+      );
+      BatchLogProcessor::builder(log_exporter, OtelSharedRuntime).build()
+    }
+    (SignalExporter::Otlp, OtlpProtocol::Http(protocol)) => {
+      let log_exporter = HttpExporterBuilder::default()
+        .with_http_client(client.clone())
+        .with_protocol(protocol)
+        .build_log_exporter()?;
+      BatchLogProcessor::builder(log_exporter, OtelSharedRuntime).build()
+    }
+    (SignalExporter::Otlp, OtlpProtocol::Grpc) => {
+      let channel =
+        grpc::GrpcChannel::from_env(client.clone(), grpc::Signal::Logs)?;
+      let log_exporter = grpc::GrpcLogExporter::new(channel);
+      BatchLogProcessor::builder(log_exporter, OtelSharedRuntime).build()
+    }
+  };
   log_processor.set_resource(&resource);
 
   let builtin_instrumentation_scope =
@@ -1381,7 +1545,11 @@ impl OtelTracer {
     start_time: Option<f64>,
     #[smi] attribute_count: usize,
   ) -> Result<OtelSpan, JsErrorBox> {
-    let OtelGlobals { id_generator, .. } = OTEL_GLOBALS.get().unwrap();
+    let OtelGlobals {
+      id_generator,
+      config,
+      ..
+    } = OTEL_GLOBALS.get().unwrap();
     let span_context;
     let parent_span_id;
     match parent {
@@ -1391,20 +1559,22 @@ impl OtelTracer {
           OtelSpanState::Recording(span) => &span.span_context,
           OtelSpanState::Done(span_context) => span_context,
         };
+        let trace_id = parent_span_context.trace_id();
         span_context = SpanContext::new(
-          parent_span_context.trace_id(),
+          trace_id,
           id_generator.new_span_id(),
-          TraceFlags::SAMPLED,
+          config.sampler.sample(Some(parent_span_context), trace_id),
           false,
           parent_span_context.trace_state().clone(),
         );
         parent_span_id = parent_span_context.span_id();
       }
       None => {
+        let trace_id = id_generator.new_trace_id();
         span_context = SpanContext::new(
-          id_generator.new_trace_id(),
+          trace_id,
           id_generator.new_span_id(),
-          TraceFlags::SAMPLED,
+          config.sampler.sample(None, trace_id),
           false,
           TraceState::NONE,
         );
@@ -1432,6 +1602,13 @@ impl OtelTracer {
           .ok_or_else(|| JsErrorBox::generic("invalid start time"))
       })
       .unwrap_or_else(|| Ok(SystemTime::now()))?;
+    if !span_context.is_sampled() {
+      // Unsampled spans are never recorded, but keep their context so that
+      // the sampling decision propagates to children and outgoing requests.
+      return Ok(OtelSpan(RefCell::new(Box::new(OtelSpanState::Done(
+        span_context,
+      )))));
+    }
     let span_data = SpanData {
       span_context,
       parent_span_id,
@@ -1457,6 +1634,7 @@ impl OtelTracer {
     scope: &mut v8::HandleScope<'s>,
     parent_trace_id: v8::Local<'s, v8::Value>,
     parent_span_id: v8::Local<'s, v8::Value>,
+    #[smi] parent_trace_flags: u8,
     name: v8::Local<'s, v8::Value>,
     #[smi] span_kind: u8,
     start_time: Option<f64>,
@@ -1470,11 +1648,24 @@ impl OtelTracer {
     if parent_span_id == SpanId::INVALID {
       return Err(JsErrorBox::generic("invalid span id"));
     };
-    let OtelGlobals { id_generator, .. } = OTEL_GLOBALS.get().unwrap();
+    let OtelGlobals {
+      id_generator,
+      config,
+      ..
+    } = OTEL_GLOBALS.get().unwrap();
+    let parent_span_context = SpanContext::new(
+      parent_trace_id,
+      parent_span_id,
+      TraceFlags::new(parent_trace_flags),
+      true,
+      TraceState::NONE,
+    );
     let span_context = SpanContext::new(
       parent_trace_id,
       id_generator.new_span_id(),
-      TraceFlags::SAMPLED,
+      config
+        .sampler
+        .sample(Some(&parent_span_context), parent_trace_id),
       false,
       TraceState::NONE,
     );
@@ -1499,6 +1690,13 @@ impl OtelTracer {
           .ok_or_else(|| JsErrorBox::generic("invalid start time"))
       })
       .unwrap_or_else(|| Ok(SystemTime::now()))?;
+    if !span_context.is_sampled() {
+      // Unsampled spans are never recorded, but keep their context so that
+      // the sampling decision propagates to children and outgoing requests.
+      return Ok(OtelSpan(RefCell::new(Box::new(OtelSpanState::Done(
+        span_context,
+      )))));
+    }
     let span_data = SpanData {
       span_context,
       parent_span_id,
@@ -1562,6 +1760,11 @@ impl OtelSpan {
     Err(OtelSpanCannotBeConstructedError)
   }
 
+  #[fast]
+  fn is_recording(&self) -> bool {
+    matches!(&**self.0.borrow(), OtelSpanState::Recording(_))
+  }
+
   #[serde]
   fn span_context(&self) -> JsSpanContext {
     let state = self.0.borrow();
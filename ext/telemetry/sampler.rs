@@ -0,0 +1,181 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use opentelemetry::trace::SpanContext;
+use opentelemetry::trace::TraceFlags;
+use opentelemetry::trace::TraceId;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The sampler that decides whether a new span is recorded and exported, as
+/// configured by `OTEL_TRACES_SAMPLER` and `OTEL_TRACES_SAMPLER_ARG`.
+///
+/// Unsampled spans still get a span context (with the sampled flag cleared),
+/// so that the decision propagates to child spans and to outgoing requests.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OtelSampler {
+  AlwaysOn,
+  AlwaysOff,
+  TraceIdRatio(f64),
+  #[default]
+  ParentBasedAlwaysOn,
+  ParentBasedAlwaysOff,
+  ParentBasedTraceIdRatio(f64),
+}
+
+impl OtelSampler {
+  /// Parses the value of `OTEL_TRACES_SAMPLER`, with the optional value of
+  /// `OTEL_TRACES_SAMPLER_ARG`. The ratio samplers default to a ratio of 1.0
+  /// if no argument is given.
+  pub fn parse(sampler: &str, arg: Option<&str>) -> Result<Self, String> {
+    let ratio = || match arg {
+      None | Some("") => Ok(1.0),
+      Some(arg) => match arg.trim().parse::<f64>() {
+        Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+        _ => Err(format!(
+          "'OTEL_TRACES_SAMPLER_ARG' must be a number between 0 and 1, got '{arg}'"
+        )),
+      },
+    };
+    Ok(match sampler.trim() {
+      "always_on" => Self::AlwaysOn,
+      "always_off" => Self::AlwaysOff,
+      "traceidratio" => Self::TraceIdRatio(ratio()?),
+      "parentbased_always_on" => Self::ParentBasedAlwaysOn,
+      "parentbased_always_off" => Self::ParentBasedAlwaysOff,
+      "parentbased_traceidratio" => Self::ParentBasedTraceIdRatio(ratio()?),
+      other => {
+        return Err(format!(
+          "'OTEL_TRACES_SAMPLER' value '{other}' is not supported"
+        ));
+      }
+    })
+  }
+
+  /// Returns the trace flags for a new span in the trace `trace_id`, given the
+  /// context of its parent span, if any.
+  pub fn sample(
+    &self,
+    parent: Option<&SpanContext>,
+    trace_id: TraceId,
+  ) -> TraceFlags {
+    let sampled = match (self, parent) {
+      (Self::AlwaysOn, _) => true,
+      (Self::AlwaysOff, _) => false,
+      (Self::TraceIdRatio(ratio), _) => sample_ratio(*ratio, trace_id),
+      (
+        Self::ParentBasedAlwaysOn
+        | Self::ParentBasedAlwaysOff
+        | Self::ParentBasedTraceIdRatio(_),
+        Some(parent),
+      ) => parent.is_sampled(),
+      (Self::ParentBasedAlwaysOn, None) => true,
+      (Self::ParentBasedAlwaysOff, None) => false,
+      (Self::ParentBasedTraceIdRatio(ratio), None) => {
+        sample_ratio(*ratio, trace_id)
+      }
+    };
+    if sampled {
+      TraceFlags::SAMPLED
+    } else {
+      TraceFlags::default()
+    }
+  }
+}
+
+/// Deterministically samples `ratio` of all traces based on the lower 63 bits
+/// of the trace id, matching the algorithm of the other OpenTelemetry SDKs so
+/// that decisions are consistent across services.
+fn sample_ratio(ratio: f64, trace_id: TraceId) -> bool {
+  if ratio >= 1.0 {
+    return true;
+  }
+  let upper_bound = (ratio.max(0.0) * (1u64 << 63) as f64) as u64;
+  let bytes = trace_id.to_bytes();
+  let low = u64::from_be_bytes(bytes[8..].try_into().unwrap());
+  (low >> 1) < upper_bound
+}
+
+#[cfg(test)]
+mod tests {
+  use opentelemetry::trace::SpanId;
+  use opentelemetry::trace::TraceState;
+
+  use super::*;
+
+  fn parent(sampled: bool) -> SpanContext {
+    SpanContext::new(
+      TraceId::from_u128(1),
+      SpanId::from_u64(1),
+      if sampled {
+        TraceFlags::SAMPLED
+      } else {
+        TraceFlags::default()
+      },
+      true,
+      TraceState::NONE,
+    )
+  }
+
+  #[test]
+  fn parse() {
+    assert_eq!(
+      OtelSampler::parse("always_off", None),
+      Ok(OtelSampler::AlwaysOff)
+    );
+    assert_eq!(
+      OtelSampler::parse("traceidratio", Some("0.25")),
+      Ok(OtelSampler::TraceIdRatio(0.25))
+    );
+    assert_eq!(
+      OtelSampler::parse("parentbased_traceidratio", None),
+      Ok(OtelSampler::ParentBasedTraceIdRatio(1.0))
+    );
+    assert!(OtelSampler::parse("traceidratio", Some("2")).is_err());
+    assert!(OtelSampler::parse("jaeger_remote", None).is_err());
+  }
+
+  #[test]
+  fn parent_based() {
+    let trace_id = TraceId::from_u128(1);
+    for sampler in [
+      OtelSampler::ParentBasedAlwaysOn,
+      OtelSampler::ParentBasedAlwaysOff,
+      OtelSampler::ParentBasedTraceIdRatio(0.0),
+    ] {
+      assert!(sampler.sample(Some(&parent(true)), trace_id).is_sampled());
+      assert!(!sampler.sample(Some(&parent(false)), trace_id).is_sampled());
+    }
+    assert!(
+      OtelSampler::ParentBasedAlwaysOn
+        .sample(None, trace_id)
+        .is_sampled()
+    );
+    assert!(
+      !OtelSampler::ParentBasedAlwaysOff
+        .sample(None, trace_id)
+        .is_sampled()
+    );
+    assert!(
+      !OtelSampler::AlwaysOff
+        .sample(Some(&parent(true)), trace_id)
+        .is_sampled()
+    );
+  }
+
+  #[test]
+  fn trace_id_ratio() {
+    let sampler = OtelSampler::TraceIdRatio(0.5);
+    assert!(sampler.sample(None, TraceId::from_u128(0)).is_sampled());
+    assert!(
+      !sampler
+        .sample(None, TraceId::from_u128(u64::MAX as u128))
+        .is_sampled()
+    );
+
+    let sampled = (0..10_000u128)
+      .map(|i| TraceId::from_u128(i.wrapping_mul(0x9e3779b97f4a7c15)))
+      .filter(|trace_id| sampler.sample(None, *trace_id).is_sampled())
+      .count();
+    assert!((4_500..5_500).contains(&sampled), "{sampled}");
+  }
+}
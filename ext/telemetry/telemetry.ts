@@ -203,7 +203,9 @@ interface AsyncContextSnapshot {
 }
 
 export function enterSpan(span: Span): AsyncContextSnapshot | undefined {
-  if (!span.isRecording()) return undefined;
+  // Spans that were not sampled are entered too, so that their children
+  // inherit the sampling decision.
+  if (!getOtelSpan(span)) return undefined;
   const context = (CURRENT.get() ?? ROOT_CONTEXT).setValue(SPAN_KEY, span);
   return CURRENT.enter(context);
 }
@@ -232,6 +234,7 @@ interface OtelTracer {
   startSpanForeign(
     parentTraceId: string,
     parentSpanId: string,
+    parentTraceFlags: number,
     name: string,
     spanKind: SpanKind,
     startTime: number | undefined,
@@ -242,6 +245,7 @@ interface OtelTracer {
 interface OtelSpan {
   __key: "span";
 
+  isRecording(): boolean;
   spanContext(): SpanContext;
   setStatus(status: SpanStatusCode, errorDescription: string): void;
   addEvent(
@@ -408,6 +412,7 @@ class Tracer {
       otelSpan = this.#tracer.startSpanForeign(
         spanContext.traceId,
         spanContext.spanId,
+        spanContext.traceFlags ?? 0,
         name,
         options?.kind ?? 0,
         startTime,
@@ -514,7 +519,7 @@ class Span {
   }
 
   isRecording(): boolean {
-    return this.#otelSpan !== undefined;
+    return this.#otelSpan?.isRecording() ?? false;
   }
 
   recordException(exception: string | Exception, time?: TimeInput): void {
@@ -36,8 +36,15 @@ use crate::import_map::value_to_dep_req;
 use crate::import_map::values_to_set;
 use crate::util::is_skippable_io_error;
 
+mod permissions;
 mod ts;
 
+pub use permissions::AllowDenyPermissionConfig;
+pub use permissions::PackagePermissionsObject;
+pub use permissions::PermissionConfigValue;
+pub use permissions::PermissionNameOrObject;
+pub use permissions::PermissionsConfig;
+pub use permissions::PermissionsObject;
 pub use ts::CompilerOptions;
 pub use ts::EmitConfigOptions;
 pub use ts::RawJsxCompilerOptions;
@@ -666,6 +673,9 @@ pub struct TaskDefinition {
   pub dependencies: Vec<String>,
   #[serde(default)]
   pub description: Option<String>,
+  /// Name of the permission set to run the task's `deno` commands with.
+  #[serde(default)]
+  pub permissions: Option<String>,
 }
 
 #[cfg(test)]
@@ -675,6 +685,7 @@ impl From<&str> for TaskDefinition {
       command: Some(value.to_string()),
       dependencies: vec![],
       description: None,
+      permissions: None,
     }
   }
 }
@@ -714,6 +725,7 @@ impl TaskDefinition {
               command: Some(command),
               dependencies: Vec::new(),
               description: None,
+              permissions: None,
             },
             serde_json::Value::Object(_) => {
               serde_json::from_value(value).map_err(serde::de::Error::custom)?
@@ -872,6 +884,28 @@ pub struct DeployConfig {
   pub app: String,
 }
 
+/// Limits of `Deno.cron()`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct CronConfig {
+  pub max_crons: Option<usize>,
+  pub dispatch_concurrency_limit: Option<usize>,
+  pub max_backoff_ms: Option<u32>,
+  pub max_backoff_count: Option<usize>,
+}
+
+/// Versions of npm and jsr packages published fewer than `days` ago are
+/// ignored during version resolution.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct MinimumReleaseAgeConfig {
+  pub days: u32,
+  /// Package names (ex. `chalk`, `@std/path` or `@types/*`) that are
+  /// always allowed to resolve to their newest versions.
+  #[serde(default)]
+  pub exclude: Vec<String>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
@@ -884,6 +918,8 @@ pub struct ConfigFileJson {
   pub tasks: Option<Value>,
   pub test: Option<Value>,
   pub bench: Option<Value>,
+  pub compile: Option<Value>,
+  pub permissions: Option<Value>,
   pub lock: Option<Value>,
   pub exclude: Option<Value>,
   pub node_modules_dir: Option<Value>,
@@ -891,6 +927,8 @@ pub struct ConfigFileJson {
   pub license: Option<Value>,
   pub publish: Option<Value>,
   pub deploy: Option<Value>,
+  pub cron: Option<Value>,
+  pub minimum_release_age: Option<Value>,
 
   pub name: Option<String>,
   pub version: Option<String>,
@@ -1550,7 +1588,11 @@ impl ConfigFile {
 
   pub fn to_bench_config(&self) -> Result<BenchConfig, ToInvalidConfigError> {
     match self.json.bench.clone() {
-      Some(config) => {
+      Some(mut config) => {
+        // resolved separately by `to_bench_permissions_config`
+        if let Value::Object(config) = &mut config {
+          config.remove("permissions");
+        }
         let mut exclude_patterns = self.resolve_exclude_patterns()?;
         let mut serialized: SerializedBenchConfig =
           serde_json::from_value(config).map_err(|error| {
@@ -1633,7 +1675,11 @@ impl ConfigFile {
 
   pub fn to_test_config(&self) -> Result<TestConfig, ToInvalidConfigError> {
     match self.json.test.clone() {
-      Some(config) => {
+      Some(mut config) => {
+        // resolved separately by `to_test_permissions_config`
+        if let Value::Object(config) = &mut config {
+          config.remove("permissions");
+        }
         let mut exclude_patterns = self.resolve_exclude_patterns()?;
         let mut serialized: SerializedTestConfig =
           serde_json::from_value(config).map_err(|error| {
@@ -1792,6 +1838,97 @@ impl ConfigFile {
     }
   }
 
+  pub fn to_cron_config(
+    &self,
+  ) -> Result<Option<CronConfig>, ToInvalidConfigError> {
+    match &self.json.cron {
+      Some(config) => {
+        Ok(Some(serde_json::from_value(config.clone()).map_err(
+          |error| ToInvalidConfigError::Parse {
+            config: "cron",
+            source: error,
+          },
+        )?))
+      }
+      None => Ok(None),
+    }
+  }
+
+  pub fn to_minimum_release_age_config(
+    &self,
+  ) -> Result<Option<MinimumReleaseAgeConfig>, ToInvalidConfigError> {
+    match &self.json.minimum_release_age {
+      Some(config) => {
+        Ok(Some(serde_json::from_value(config.clone()).map_err(
+          |error| ToInvalidConfigError::Parse {
+            config: "minimumReleaseAge",
+            source: error,
+          },
+        )?))
+      }
+      None => Ok(None),
+    }
+  }
+
+  pub fn to_permissions_config(
+    &self,
+  ) -> Result<PermissionsConfig, ToInvalidConfigError> {
+    let Some(config) = &self.json.permissions else {
+      return Ok(PermissionsConfig::default());
+    };
+    let mut sets: IndexMap<String, PermissionsObject> =
+      serde_json::from_value(config.clone()).map_err(|error| {
+        ToInvalidConfigError::Parse {
+          config: "permissions",
+          source: error,
+        }
+      })?;
+    let dir_path = self.dir_path();
+    for set in sets.values_mut() {
+      set.resolve_paths(&dir_path);
+    }
+    Ok(PermissionsConfig { sets })
+  }
+
+  pub fn to_test_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    self.to_nested_permissions_config(self.json.test.as_ref(), "test")
+  }
+
+  pub fn to_bench_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    self.to_nested_permissions_config(self.json.bench.as_ref(), "bench")
+  }
+
+  pub fn to_compile_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    self.to_nested_permissions_config(self.json.compile.as_ref(), "compile")
+  }
+
+  fn to_nested_permissions_config(
+    &self,
+    config: Option<&Value>,
+    config_name: &'static str,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    let Some(value) = config.and_then(|c| c.get("permissions")) else {
+      return Ok(None);
+    };
+    let mut permissions: PermissionNameOrObject =
+      serde_json::from_value(value.clone()).map_err(|error| {
+        ToInvalidConfigError::Parse {
+          config: config_name,
+          source: error,
+        }
+      })?;
+    if let PermissionNameOrObject::Object(set) = &mut permissions {
+      set.resolve_paths(&self.dir_path());
+    }
+    Ok(Some(permissions))
+  }
+
   pub fn resolve_tasks_config(
     &self,
   ) -> Result<IndexMap<String, TaskDefinition>, ResolveTaskConfigError> {
@@ -2087,7 +2224,8 @@ mod tests {
       TaskDefinition {
         description: Some("Build client project".to_string()),
         command: Some("deno run -A client.js".to_string()),
-        dependencies: vec!["build".to_string()]
+        dependencies: vec!["build".to_string()],
+        permissions: None,
       }
     );
 
@@ -2235,6 +2373,192 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_parse_config_cron() {
+    let config_text = r#"{
+      "cron": {
+        "maxCrons": 200,
+        "maxBackoffCount": 10
+      }
+    }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    assert_eq!(
+      config_file.to_cron_config().unwrap(),
+      Some(CronConfig {
+        max_crons: Some(200),
+        max_backoff_count: Some(10),
+        ..Default::default()
+      })
+    );
+
+    let config_text = r#"{ "cron": { "maxCronz": 200 } }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    assert!(config_file.to_cron_config().is_err());
+  }
+
+  #[test]
+  fn test_parse_config_minimum_release_age() {
+    let config_text = r#"{
+      "minimumReleaseAge": {
+        "days": 3,
+        "exclude": ["@std/*", "chalk"]
+      }
+    }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    assert_eq!(
+      config_file.to_minimum_release_age_config().unwrap(),
+      Some(MinimumReleaseAgeConfig {
+        days: 3,
+        exclude: vec!["@std/*".to_string(), "chalk".to_string()],
+      })
+    );
+
+    let config_text = r#"{ "minimumReleaseAge": { "dayz": 3 } }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    assert!(config_file.to_minimum_release_age_config().is_err());
+  }
+
+  #[test]
+  fn test_parse_config_permissions() {
+    let config_text = r#"{
+      "permissions": {
+        "default": {
+          "read": ["./data", "../shared"],
+          "net": { "allow": ["deno.land"], "deny": ["evil.com"] },
+          "run": ["deno", "./bin/tool"],
+          "env": true
+        },
+        "all": { "all": true }
+      },
+      "test": {
+        "include": ["tests/"],
+        "permissions": "default"
+      },
+      "bench": {
+        "permissions": { "write": false }
+      }
+    }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    let dir_path = config_file.dir_path();
+    let permissions = config_file.to_permissions_config().unwrap();
+    assert_eq!(permissions.sets.len(), 2);
+    let default_set = permissions.get("default").unwrap();
+    assert_eq!(
+      default_set.read.allow,
+      Some(PermissionConfigValue::Some(vec![
+        dir_path.join("data").to_string_lossy().into_owned(),
+        dir_path
+          .parent()
+          .unwrap()
+          .join("shared")
+          .to_string_lossy()
+          .into_owned(),
+      ]))
+    );
+    assert_eq!(
+      default_set.net,
+      AllowDenyPermissionConfig {
+        allow: Some(PermissionConfigValue::Some(vec!["deno.land".to_string()])),
+        deny: Some(PermissionConfigValue::Some(vec!["evil.com".to_string()])),
+      }
+    );
+    assert_eq!(
+      default_set.run.allow,
+      Some(PermissionConfigValue::Some(vec![
+        "deno".to_string(),
+        dir_path
+          .join("bin")
+          .join("tool")
+          .to_string_lossy()
+          .into_owned(),
+      ]))
+    );
+    assert_eq!(
+      default_set.env.allow.as_ref().unwrap().to_flag_value(),
+      Some(vec![])
+    );
+    assert_eq!(permissions.get("all").unwrap().all, Some(true));
+
+    assert_eq!(
+      config_file.to_test_permissions_config().unwrap(),
+      Some(PermissionNameOrObject::Name("default".to_string()))
+    );
+    // still parses the rest of the test config
+    assert!(config_file.to_test_config().is_ok());
+    assert_eq!(
+      config_file.to_bench_permissions_config().unwrap(),
+      Some(PermissionNameOrObject::Object(Box::new(
+        PermissionsObject {
+          write: AllowDenyPermissionConfig {
+            allow: Some(PermissionConfigValue::All(false)),
+            deny: None,
+          },
+          ..Default::default()
+        }
+      )))
+    );
+    assert_eq!(config_file.to_compile_permissions_config().unwrap(), None);
+
+    let config_text = r#"{ "permissions": { "default": { "reed": true } } }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    assert!(config_file.to_permissions_config().is_err());
+  }
+
+  #[test]
+  fn test_parse_config_permission_packages() {
+    let config_text = r#"{
+      "permissions": {
+        "default": {
+          "all": true,
+          "packages": {
+            "npm:*": { "run": false, "env": false },
+            "npm:esbuild": { "run": true, "read": ["./node_modules"] }
+          }
+        }
+      }
+    }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    let dir_path = config_file.dir_path();
+    let permissions = config_file.to_permissions_config().unwrap();
+    let packages = &permissions.get("default").unwrap().packages;
+    assert_eq!(
+      packages.keys().collect::<Vec<_>>(),
+      vec!["npm:*", "npm:esbuild"]
+    );
+    assert_eq!(
+      packages["npm:*"],
+      PackagePermissionsObject {
+        run: Some(PermissionConfigValue::All(false)),
+        env: Some(PermissionConfigValue::All(false)),
+        ..Default::default()
+      }
+    );
+    assert_eq!(
+      packages["npm:esbuild"],
+      PackagePermissionsObject {
+        run: Some(PermissionConfigValue::All(true)),
+        read: Some(PermissionConfigValue::Some(vec![
+          dir_path.join("node_modules").to_string_lossy().into_owned(),
+        ])),
+        ..Default::default()
+      }
+    );
+
+    let config_text = r#"{
+      "permissions": { "default": { "packages": { "npm:*": { "import": true } } } }
+    }"#;
+    let config_specifier = Url::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, config_specifier).unwrap();
+    assert!(config_file.to_permissions_config().is_err());
+  }
+
   #[test]
   fn test_parse_config_with_global_files_only() {
     let config_text = r#"{
@@ -0,0 +1,208 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use deno_path_util::normalize_path;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+/// The value of an allow or deny list in a permission set.
+///
+/// `true` applies to everything, `false` to nothing and a list to the
+/// provided values.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PermissionConfigValue {
+  All(bool),
+  Some(Vec<String>),
+}
+
+impl PermissionConfigValue {
+  /// Converts this to the representation used by the permission flags where
+  /// `Some(vec![])` means everything.
+  pub fn to_flag_value(&self) -> Option<Vec<String>> {
+    match self {
+      PermissionConfigValue::All(true) => Some(Vec::new()),
+      PermissionConfigValue::All(false) => None,
+      PermissionConfigValue::Some(values) if values.is_empty() => None,
+      PermissionConfigValue::Some(values) => Some(values.clone()),
+    }
+  }
+
+  fn resolve_paths(
+    &mut self,
+    base: &Path,
+    should_resolve: impl Fn(&str) -> bool,
+  ) {
+    if let PermissionConfigValue::Some(values) = self {
+      for value in values.iter_mut() {
+        if should_resolve(value) {
+          *value = resolve_path(base, value);
+        }
+      }
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllowDenyPermissionConfig {
+  pub allow: Option<PermissionConfigValue>,
+  pub deny: Option<PermissionConfigValue>,
+}
+
+impl<'de> Deserialize<'de> for AllowDenyPermissionConfig {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct AllowDenyObject {
+      #[serde(default)]
+      allow: Option<PermissionConfigValue>,
+      #[serde(default)]
+      deny: Option<PermissionConfigValue>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AllowDenyValue {
+      Value(PermissionConfigValue),
+      Object(AllowDenyObject),
+    }
+
+    Ok(match AllowDenyValue::deserialize(deserializer)? {
+      // a plain value is shorthand for the allow list
+      AllowDenyValue::Value(value) => AllowDenyPermissionConfig {
+        allow: Some(value),
+        deny: None,
+      },
+      AllowDenyValue::Object(object) => AllowDenyPermissionConfig {
+        allow: object.allow,
+        deny: object.deny,
+      },
+    })
+  }
+}
+
+impl AllowDenyPermissionConfig {
+  fn resolve_paths(
+    &mut self,
+    base: &Path,
+    should_resolve: impl Fn(&str) -> bool,
+  ) {
+    if let Some(allow) = &mut self.allow {
+      allow.resolve_paths(base, &should_resolve);
+    }
+    if let Some(deny) = &mut self.deny {
+      deny.resolve_paths(base, &should_resolve);
+    }
+  }
+}
+
+/// A set of permissions declared in a config file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PermissionsObject {
+  pub all: Option<bool>,
+  pub read: AllowDenyPermissionConfig,
+  pub write: AllowDenyPermissionConfig,
+  pub net: AllowDenyPermissionConfig,
+  pub env: AllowDenyPermissionConfig,
+  pub run: AllowDenyPermissionConfig,
+  pub ffi: AllowDenyPermissionConfig,
+  pub sys: AllowDenyPermissionConfig,
+  pub import: AllowDenyPermissionConfig,
+  /// Restrictions on what npm and jsr packages may access keyed by a
+  /// package like `npm:chalk` or a pattern like `npm:*`.
+  pub packages: IndexMap<String, PackagePermissionsObject>,
+}
+
+impl PermissionsObject {
+  /// Resolves the relative paths in the set against the directory of the
+  /// config file that declared it.
+  pub(crate) fn resolve_paths(&mut self, base: &Path) {
+    self.read.resolve_paths(base, |_| true);
+    self.write.resolve_paths(base, |_| true);
+    self.ffi.resolve_paths(base, |_| true);
+    // only resolve values that look like paths so commands on the PATH
+    // can still be referenced by name
+    self
+      .run
+      .resolve_paths(base, |value| value.contains('/') || value.contains('\\'));
+    for package in self.packages.values_mut() {
+      package.resolve_paths(base);
+    }
+  }
+}
+
+/// What the packages matching a pattern may access. `true` allows whatever
+/// the permission set allows, `false` or an empty list nothing and a list
+/// only the provided values.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackagePermissionsObject {
+  pub read: Option<PermissionConfigValue>,
+  pub write: Option<PermissionConfigValue>,
+  pub net: Option<PermissionConfigValue>,
+  pub env: Option<PermissionConfigValue>,
+  pub run: Option<PermissionConfigValue>,
+  pub ffi: Option<PermissionConfigValue>,
+  pub sys: Option<PermissionConfigValue>,
+}
+
+impl PackagePermissionsObject {
+  fn resolve_paths(&mut self, base: &Path) {
+    for value in [&mut self.read, &mut self.write, &mut self.ffi]
+      .into_iter()
+      .flatten()
+    {
+      value.resolve_paths(base, |_| true);
+    }
+    if let Some(run) = &mut self.run {
+      run.resolve_paths(base, |value| {
+        value.contains('/') || value.contains('\\')
+      });
+    }
+  }
+}
+
+/// Either the name of a permission set or an inline permission set.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PermissionNameOrObject {
+  Name(String),
+  Object(Box<PermissionsObject>),
+}
+
+/// The named permission sets declared in the `permissions` property.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PermissionsConfig {
+  pub sets: IndexMap<String, PermissionsObject>,
+}
+
+impl PermissionsConfig {
+  pub fn get(&self, name: &str) -> Option<&PermissionsObject> {
+    self.sets.get(name)
+  }
+
+  /// Combines the sets of a workspace root with those of a member, where
+  /// the member's sets take precedence over sets with the same name.
+  pub fn merge(mut self, member: PermissionsConfig) -> PermissionsConfig {
+    for (name, set) in member.sets {
+      self.sets.insert(name, set);
+    }
+    self
+  }
+}
+
+fn resolve_path(base: &Path, value: &str) -> String {
+  let path = Path::new(value);
+  if path.is_absolute() {
+    return value.to_string();
+  }
+  normalize_path(Cow::Owned(base.join(path)))
+    .to_string_lossy()
+    .into_owned()
+}
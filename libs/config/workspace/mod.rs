@@ -44,13 +44,17 @@ use crate::deno_json::ConfigFile;
 use crate::deno_json::ConfigFileError;
 use crate::deno_json::ConfigFileRc;
 use crate::deno_json::ConfigFileReadError;
+use crate::deno_json::CronConfig;
 use crate::deno_json::DeployConfig;
 use crate::deno_json::FmtConfig;
 use crate::deno_json::FmtOptionsConfig;
 use crate::deno_json::LinkConfigParseError;
 use crate::deno_json::LintRulesConfig;
+use crate::deno_json::MinimumReleaseAgeConfig;
 use crate::deno_json::NodeModulesDirMode;
 use crate::deno_json::NodeModulesDirParseError;
+use crate::deno_json::PermissionNameOrObject;
+use crate::deno_json::PermissionsConfig;
 use crate::deno_json::PublishConfig;
 pub use crate::deno_json::TaskDefinition;
 use crate::deno_json::TestConfig;
@@ -910,6 +914,12 @@ impl Workspace {
           kind: WorkspaceDiagnosticKind::RootOnlyOption("lock"),
         });
       }
+      if member_config.json.minimum_release_age.is_some() {
+        diagnostics.push(WorkspaceDiagnostic {
+          config_url: member_config.specifier.clone(),
+          kind: WorkspaceDiagnosticKind::RootOnlyOption("minimumReleaseAge"),
+        });
+      }
       if member_config.json.node_modules_dir.is_some() {
         diagnostics.push(WorkspaceDiagnostic {
           config_url: member_config.specifier.clone(),
@@ -1299,6 +1309,15 @@ impl Workspace {
     self.root_deno_json().map(|c| with_root(c))
   }
 
+  pub fn minimum_release_age(
+    &self,
+  ) -> Result<Option<MinimumReleaseAgeConfig>, ToInvalidConfigError> {
+    match self.root_deno_json() {
+      Some(deno_json) => deno_json.to_minimum_release_age_config(),
+      None => Ok(None),
+    }
+  }
+
   pub fn node_modules_dir(
     &self,
   ) -> Result<Option<NodeModulesDirMode>, deno_json::NodeModulesDirParseError>
@@ -2068,6 +2087,84 @@ impl WorkspaceDirectory {
     Ok(config)
   }
 
+  pub fn to_cron_config(
+    &self,
+  ) -> Result<Option<CronConfig>, ToInvalidConfigError> {
+    let config = if let Some(deno_json) = self.deno_json.as_ref() {
+      if let Some(config) = deno_json.member.to_cron_config()? {
+        Some(config)
+      } else {
+        match &deno_json.root {
+          Some(root) => root.to_cron_config()?,
+          None => None,
+        }
+      }
+    } else {
+      None
+    };
+
+    Ok(config)
+  }
+
+  /// Gets the named permission sets, where sets declared in a workspace
+  /// member override the root's sets of the same name.
+  pub fn to_permissions_config(
+    &self,
+  ) -> Result<PermissionsConfig, ToInvalidConfigError> {
+    let Some(deno_json) = self.deno_json.as_ref() else {
+      return Ok(PermissionsConfig::default());
+    };
+    let member_config = deno_json.member.to_permissions_config()?;
+    let root_config = match &deno_json.root {
+      Some(root) => root.to_permissions_config()?,
+      None => return Ok(member_config),
+    };
+    Ok(root_config.merge(member_config))
+  }
+
+  pub fn to_test_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    self
+      .to_member_or_root_permissions_config(|c| c.to_test_permissions_config())
+  }
+
+  pub fn to_bench_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    self
+      .to_member_or_root_permissions_config(|c| c.to_bench_permissions_config())
+  }
+
+  pub fn to_compile_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    self.to_member_or_root_permissions_config(|c| {
+      c.to_compile_permissions_config()
+    })
+  }
+
+  fn to_member_or_root_permissions_config(
+    &self,
+    get_config: impl Fn(
+      &ConfigFile,
+    ) -> Result<
+      Option<PermissionNameOrObject>,
+      ToInvalidConfigError,
+    >,
+  ) -> Result<Option<PermissionNameOrObject>, ToInvalidConfigError> {
+    let Some(deno_json) = self.deno_json.as_ref() else {
+      return Ok(None);
+    };
+    if let Some(config) = get_config(&deno_json.member)? {
+      return Ok(Some(config));
+    }
+    match &deno_json.root {
+      Some(root) => get_config(root),
+      None => Ok(None),
+    }
+  }
+
   /// Removes any "include" patterns from the root files that have
   /// a base in another workspace member.
   fn exclude_includes_with_member_for_base_for_root(
@@ -32,6 +32,7 @@ use url::Url;
 
 mod fs_util;
 mod registry_info;
+mod release_age;
 mod remote;
 mod rt;
 mod tarball;
@@ -44,6 +45,7 @@ pub use fs_util::hard_link_file;
 pub use registry_info::RegistryInfoProvider;
 pub use registry_info::SerializedCachedPackageInfo;
 pub use registry_info::get_package_url;
+pub use release_age::MinimumReleaseAge;
 pub use remote::maybe_auth_header_value_for_npm_registry;
 pub use tarball::EnsurePackageError;
 pub use tarball::TarballCache;
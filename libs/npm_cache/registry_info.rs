@@ -23,6 +23,7 @@ use crate::NpmCacheHttpClient;
 use crate::NpmCacheHttpClientResponse;
 use crate::NpmCacheSetting;
 use crate::NpmCacheSys;
+use crate::release_age::MinimumReleaseAge;
 use crate::remote::maybe_auth_header_value_for_npm_registry;
 use crate::rt::MultiRuntimeAsyncValueCreator;
 use crate::rt::spawn_blocking;
@@ -41,6 +42,19 @@ pub struct SerializedCachedPackageInfo {
     rename = "_deno.etag"
   )]
   pub etag: Option<String>,
+  /// Publish dates of each version, which are used to enforce
+  /// the minimum release age.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub time: HashMap<String, serde_json::Value>,
+  /// Custom property that's set when the packument was cached with the
+  /// publish times, which tells a registry that doesn't provide them apart
+  /// from a packument that was cached before they were stored.
+  #[serde(
+    default,
+    skip_serializing_if = "std::ops::Not::not",
+    rename = "_deno.timeStored"
+  )]
+  pub time_stored: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -140,6 +154,7 @@ struct RegistryInfoProviderInner<
   cache: Arc<NpmCache<TSys>>,
   http_client: Arc<THttpClient>,
   npmrc: Arc<ResolvedNpmRc>,
+  minimum_release_age: Option<Arc<MinimumReleaseAge>>,
   force_reload_flag: AtomicFlag,
   memory_cache: Mutex<MemoryCache>,
   previously_loaded_packages: Mutex<HashSet<String>>,
@@ -250,14 +265,18 @@ impl<THttpClient: NpmCacheHttpClient, TSys: NpmCacheSys>
         || downloader.previously_loaded_packages.lock().contains(&name)
       {
         // attempt to load from the file cache
-        match downloader.cache.load_package_info(&name).await.map_err(JsErrorBox::from_err)? { Some(cached_info) => {
-          return Ok(FutureResult::SavedFsCache(Arc::new(cached_info.info)));
+        match downloader.cache.load_package_info(&name).await.map_err(JsErrorBox::from_err)? { Some(cached_info) if !downloader.is_missing_release_times(&name, &cached_info) => {
+          return Ok(FutureResult::SavedFsCache(downloader.to_package_info(cached_info)));
         } _ => {
           None
         }}
       } else {
         downloader.cache.load_package_info(&name).await.ok().flatten()
       };
+      // packuments cached without publish times can't be filtered, so
+      // download them again without an etag
+      let maybe_file_cached = maybe_file_cached
+        .filter(|cached_info| !downloader.is_missing_release_times(&name, cached_info));
 
       if *downloader.cache.cache_setting() == NpmCacheSetting::Only {
         return Err(JsErrorBox::new(
@@ -271,7 +290,7 @@ impl<THttpClient: NpmCacheHttpClient, TSys: NpmCacheSys>
       downloader.previously_loaded_packages.lock().insert(name.to_string());
 
       let (maybe_etag, maybe_cached_info) = match maybe_file_cached {
-        Some(cached_info) => (cached_info.etag, Some(cached_info.info)),
+        Some(cached_info) => (cached_info.etag.clone(), Some(cached_info)),
         None => (None, None)
       };
 
@@ -286,7 +305,7 @@ impl<THttpClient: NpmCacheHttpClient, TSys: NpmCacheSys>
       match response {
         NpmCacheHttpClientResponse::NotModified => {
           log::debug!("Respected etag for packument '{0}'", name); // used in the tests
-          Ok(FutureResult::SavedFsCache(Arc::new(maybe_cached_info.unwrap())))
+          Ok(FutureResult::SavedFsCache(downloader.to_package_info(maybe_cached_info.unwrap())))
         },
         NpmCacheHttpClientResponse::NotFound => Ok(FutureResult::PackageNotExists),
         NpmCacheHttpClientResponse::Bytes(response) => {
@@ -294,9 +313,10 @@ impl<THttpClient: NpmCacheHttpClient, TSys: NpmCacheSys>
             move || -> Result<FutureResult, JsErrorBox> {
               let mut package_info: SerializedCachedPackageInfo = serde_json::from_slice(&response.bytes).map_err(JsErrorBox::from_err)?;
               package_info.etag = response.etag;
+              package_info.time_stored = true;
               match downloader.cache.save_package_info(&name, &package_info) {
                 Ok(()) => {
-                  Ok(FutureResult::SavedFsCache(Arc::new(package_info.info)))
+                  Ok(FutureResult::SavedFsCache(downloader.to_package_info(package_info)))
                 }
                 Err(err) => {
                   log::debug!(
@@ -304,7 +324,7 @@ impl<THttpClient: NpmCacheHttpClient, TSys: NpmCacheSys>
                     name,
                     err
                   );
-                  Ok(FutureResult::ErroredFsCache(Arc::new(package_info.info)))
+                  Ok(FutureResult::ErroredFsCache(downloader.to_package_info(package_info)))
                 }
               }
             },
@@ -319,6 +339,32 @@ impl<THttpClient: NpmCacheHttpClient, TSys: NpmCacheSys>
     .boxed_local()
   }
 
+  /// Gets if the cached packument predates storing the publish times
+  /// while they're necessary for filtering out recent versions.
+  fn is_missing_release_times(
+    &self,
+    name: &str,
+    cached_info: &SerializedCachedPackageInfo,
+  ) -> bool {
+    !cached_info.time_stored
+      && *self.cache.cache_setting() != NpmCacheSetting::Only
+      && self
+        .minimum_release_age
+        .as_ref()
+        .is_some_and(|m| m.applies_to(name))
+  }
+
+  fn to_package_info(
+    &self,
+    cached_info: SerializedCachedPackageInfo,
+  ) -> Arc<NpmPackageInfo> {
+    let mut info = cached_info.info;
+    if let Some(minimum_release_age) = &self.minimum_release_age {
+      minimum_release_age.filter_npm_package_info(&mut info, &cached_info.time);
+    }
+    Arc::new(info)
+  }
+
   fn mark_force_reload(&self) -> bool {
     // never force reload the registry information if reloading
     // is disabled or if we're already reloading
@@ -353,11 +399,13 @@ impl<THttpClient: NpmCacheHttpClient, TSys: NpmCacheSys>
     cache: Arc<NpmCache<TSys>>,
     http_client: Arc<THttpClient>,
     npmrc: Arc<ResolvedNpmRc>,
+    minimum_release_age: Option<Arc<MinimumReleaseAge>>,
   ) -> Self {
     Self(Arc::new(RegistryInfoProviderInner {
       cache,
       http_client,
       npmrc,
+      minimum_release_age,
       force_reload_flag: AtomicFlag::lowered(),
       memory_cache: Default::default(),
       previously_loaded_packages: Default::default(),
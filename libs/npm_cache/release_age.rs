@@ -0,0 +1,149 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use deno_npm::registry::NpmPackageInfo;
+
+/// Ignores package versions that were published too recently.
+///
+/// This gives the ecosystem time to notice and unpublish compromised
+/// releases before they're picked up by version resolution.
+#[derive(Debug, Clone)]
+pub struct MinimumReleaseAge {
+  cutoff: DateTime<Utc>,
+  exclude: Vec<String>,
+}
+
+impl MinimumReleaseAge {
+  pub fn new(days: u32, exclude: Vec<String>, now: SystemTime) -> Self {
+    let now = DateTime::<Utc>::from(now);
+    let cutoff = now
+      .checked_sub_signed(TimeDelta::days(days as i64))
+      .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    Self { cutoff, exclude }
+  }
+
+  /// Gets if the package is subject to the minimum release age. Exclusions
+  /// are either exact package names or a scope followed by `/*`.
+  pub fn applies_to(&self, name: &str) -> bool {
+    !self
+      .exclude
+      .iter()
+      .any(|pattern| match pattern.strip_suffix("/*") {
+        Some(scope) => name
+          .strip_prefix(scope)
+          .is_some_and(|rest| rest.starts_with('/')),
+        None => pattern == name,
+      })
+  }
+
+  /// Gets if a version published at the provided RFC 3339 date is too new
+  /// to be used. Dates that can't be parsed are allowed.
+  pub fn is_too_new(&self, published_at: &str) -> bool {
+    match DateTime::parse_from_rfc3339(published_at) {
+      Ok(date) => date.with_timezone(&Utc) > self.cutoff,
+      Err(_) => false,
+    }
+  }
+
+  /// Removes versions that are too new from the package information based
+  /// on the `time` field of the packument.
+  pub fn filter_npm_package_info(
+    &self,
+    info: &mut NpmPackageInfo,
+    time: &HashMap<String, serde_json::Value>,
+  ) {
+    if !self.applies_to(&info.name) {
+      return;
+    }
+    let original_len = info.versions.len();
+    info.versions.retain(|version, _| {
+      match time
+        .get(&version.to_string())
+        .and_then(|published_at| published_at.as_str())
+      {
+        Some(published_at) => !self.is_too_new(published_at),
+        None => true,
+      }
+    });
+    if info.versions.len() == original_len {
+      return;
+    }
+    let versions = &info.versions;
+    info
+      .dist_tags
+      .retain(|_, version| versions.contains_key(version));
+    if !info.dist_tags.contains_key("latest") {
+      let latest = versions
+        .keys()
+        .filter(|v| v.pre.is_empty())
+        .max()
+        .or_else(|| versions.keys().max());
+      if let Some(latest) = latest {
+        info.dist_tags.insert("latest".to_string(), latest.clone());
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::time::Duration;
+
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn filters_recent_versions() {
+    // 2025-01-10T00:00:00Z
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1736467200);
+    let policy = MinimumReleaseAge::new(3, vec!["@scope/*".to_string()], now);
+    assert!(policy.applies_to("chalk"));
+    assert!(policy.applies_to("@scopes/pkg"));
+    assert!(!policy.applies_to("@scope/pkg"));
+    assert!(policy.is_too_new("2025-01-08T00:00:00.000Z"));
+    assert!(!policy.is_too_new("2025-01-06T00:00:00.000Z"));
+    assert!(!policy.is_too_new("invalid"));
+
+    let create_info = |name: &str| -> NpmPackageInfo {
+      serde_json::from_value(json!({
+        "name": name,
+        "versions": {
+          "1.0.0": { "version": "1.0.0" },
+          "1.1.0": { "version": "1.1.0" },
+          "2.0.0-beta.1": { "version": "2.0.0-beta.1" },
+        },
+        "dist-tags": { "latest": "1.1.0", "next": "2.0.0-beta.1" },
+      }))
+      .unwrap()
+    };
+    let time = serde_json::from_value(json!({
+      "created": "2024-12-01T00:00:00.000Z",
+      "1.0.0": "2025-01-01T00:00:00.000Z",
+      "1.1.0": "2025-01-09T00:00:00.000Z",
+      "2.0.0-beta.1": "2025-01-08T00:00:00.000Z",
+      "unpublished": { "time": "2025-01-09T00:00:00.000Z" },
+    }))
+    .unwrap();
+    let mut excluded = create_info("@scope/pkg");
+    policy.filter_npm_package_info(&mut excluded, &time);
+    assert_eq!(excluded.versions.len(), 3);
+
+    let mut info = create_info("chalk");
+    policy.filter_npm_package_info(&mut info, &time);
+    let mut versions = info
+      .versions
+      .keys()
+      .map(|v| v.to_string())
+      .collect::<Vec<_>>();
+    versions.sort();
+    assert_eq!(versions, vec!["1.0.0"]);
+    assert_eq!(info.dist_tags.len(), 1);
+    assert_eq!(info.dist_tags.get("latest").unwrap().to_string(), "1.0.0");
+  }
+}
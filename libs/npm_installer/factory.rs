@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use deno_npm::resolution::PackageIdNotFoundError;
 use deno_npm::resolution::ValidSerializedNpmResolutionSnapshot;
+use deno_npm_cache::MinimumReleaseAge;
 use deno_npm_cache::NpmCache;
 use deno_npm_cache::NpmCacheHttpClient;
 use deno_npm_cache::NpmCacheSetting;
@@ -15,6 +16,7 @@ use deno_resolver::factory::WorkspaceFactorySys;
 use deno_resolver::lockfile::LockfileLock;
 use deno_resolver::lockfile::LockfileNpmPackageInfoApiAdapter;
 use futures::FutureExt;
+use sys_traits::SystemTimeNow;
 
 use crate::LifecycleScriptsConfig;
 use crate::NpmInstaller;
@@ -62,6 +64,7 @@ pub struct NpmInstallerFactory<
   reporter: TReporter,
   lockfile_npm_package_info_provider:
     Deferred<LockfileNpmPackageInfoApiAdapter>,
+  minimum_release_age: Deferred<Option<Arc<MinimumReleaseAge>>>,
   npm_cache: Deferred<Arc<NpmCache<TSys>>>,
   npm_deno_graph_resolver: async_once_cell::OnceCell<
     Arc<NpmDenoGraphResolver<TNpmCacheHttpClient, TSys>>,
@@ -98,6 +101,7 @@ impl<
       lifecycle_scripts_executor,
       reporter,
       lockfile_npm_package_info_provider: Default::default(),
+      minimum_release_age: Default::default(),
       npm_cache: Default::default(),
       npm_deno_graph_resolver: Default::default(),
       npm_installer: Default::default(),
@@ -290,6 +294,24 @@ impl<
       .await
   }
 
+  pub fn minimum_release_age(
+    &self,
+  ) -> Result<Option<&Arc<MinimumReleaseAge>>, anyhow::Error> {
+    self
+      .minimum_release_age
+      .get_or_try_init(|| {
+        let workspace_factory = self.workspace_factory();
+        Ok(workspace_factory.minimum_release_age()?.map(|config| {
+          Arc::new(MinimumReleaseAge::new(
+            config.days,
+            config.exclude.clone(),
+            workspace_factory.sys().sys_time_now(),
+          ))
+        }))
+      })
+      .map(|value| value.as_ref())
+  }
+
   pub fn registry_info_provider(
     &self,
   ) -> Result<
@@ -301,6 +323,7 @@ impl<
         self.npm_cache()?.clone(),
         self.http_client().clone(),
         self.workspace_factory().npmrc()?.clone(),
+        self.minimum_release_age()?.cloned(),
       )))
     })
   }
@@ -11,7 +11,9 @@ use deno_cache_dir::GlobalHttpCacheRc;
 use deno_cache_dir::GlobalOrLocalHttpCache;
 use deno_cache_dir::LocalHttpCache;
 use deno_cache_dir::npm::NpmCacheDir;
+use deno_config::deno_json::MinimumReleaseAgeConfig;
 use deno_config::deno_json::NodeModulesDirMode;
+use deno_config::deno_json::ToInvalidConfigError;
 use deno_config::workspace::FolderConfigs;
 use deno_config::workspace::VendorEnablement;
 use deno_config::workspace::Workspace;
@@ -77,8 +79,10 @@ use crate::npm::managed::ManagedInNpmPkgCheckerCreateOptions;
 use crate::npm::managed::ManagedNpmResolverCreateOptions;
 use crate::npm::managed::NpmResolutionCellRc;
 use crate::npmrc::NpmRcDiscoverError;
+use crate::npmrc::NpmRcLoadError;
 use crate::npmrc::ResolvedNpmRcRc;
 use crate::npmrc::discover_npmrc_from_workspace;
+use crate::npmrc::load_minimum_release_age_from_npmrc;
 use crate::sync::MaybeSend;
 use crate::sync::MaybeSync;
 use crate::sync::new_rc;
@@ -150,6 +154,23 @@ pub enum NpmRcCreateErrorKind {
   NpmRcDiscover(#[from] NpmRcDiscoverError),
 }
 
+#[derive(Debug, Boxed)]
+pub struct MinimumReleaseAgeResolveError(
+  pub Box<MinimumReleaseAgeResolveErrorKind>,
+);
+
+#[derive(Debug, Error)]
+pub enum MinimumReleaseAgeResolveErrorKind {
+  #[error(transparent)]
+  WorkspaceDiscover(#[from] WorkspaceDiscoverError),
+  #[error(transparent)]
+  Config(#[from] ToInvalidConfigError),
+  #[error(transparent)]
+  NpmRcCreate(#[from] NpmRcCreateError),
+  #[error(transparent)]
+  NpmRcLoad(#[from] NpmRcLoadError),
+}
+
 #[derive(Debug, Default)]
 pub enum ConfigDiscoveryOption {
   #[default]
@@ -238,6 +259,7 @@ pub struct WorkspaceFactory<TSys: WorkspaceFactorySys> {
   http_cache: Deferred<GlobalOrLocalHttpCache<TSys>>,
   jsr_url: Deferred<Url>,
   lockfile: async_once_cell::OnceCell<Option<LockfileLockRc<TSys>>>,
+  minimum_release_age: Deferred<Option<MinimumReleaseAgeConfig>>,
   node_modules_dir_path: Deferred<Option<PathBuf>>,
   npm_cache_dir: Deferred<NpmCacheDirRc>,
   npmrc: Deferred<(ResolvedNpmRcRc, Option<PathBuf>)>,
@@ -265,6 +287,7 @@ impl<TSys: WorkspaceFactorySys> WorkspaceFactory<TSys> {
       http_cache: Default::default(),
       jsr_url: Default::default(),
       lockfile: Default::default(),
+      minimum_release_age: Default::default(),
       node_modules_dir_path: Default::default(),
       npm_cache_dir: Default::default(),
       npmrc: Default::default(),
@@ -321,6 +344,29 @@ impl<TSys: WorkspaceFactorySys> WorkspaceFactory<TSys> {
     self.options.no_npm
   }
 
+  /// Resolves the minimum release age for npm and jsr packages from the
+  /// root deno.json, falling back to the .npmrc file.
+  pub fn minimum_release_age(
+    &self,
+  ) -> Result<Option<&MinimumReleaseAgeConfig>, MinimumReleaseAgeResolveError>
+  {
+    self
+      .minimum_release_age
+      .get_or_try_init(|| {
+        let workspace = &self.workspace_directory()?.workspace;
+        if let Some(config) = workspace.minimum_release_age()? {
+          return Ok(Some(config));
+        }
+        match &self.npmrc_with_path()?.1 {
+          Some(npmrc_path) => {
+            Ok(load_minimum_release_age_from_npmrc(&self.sys, npmrc_path)?)
+          }
+          None => Ok(None),
+        }
+      })
+      .map(|config| config.as_ref())
+  }
+
   pub fn node_modules_dir_mode(
     &self,
   ) -> Result<NodeModulesDirMode, anyhow::Error> {
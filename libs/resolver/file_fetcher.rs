@@ -39,6 +39,8 @@ use thiserror::Error;
 use url::Url;
 
 use crate::npm::DenoInNpmPackageChecker;
+use crate::sync::MaybeSend;
+use crate::sync::MaybeSync;
 
 #[derive(Debug, Default)]
 struct MemoryFiles(Mutex<HashMap<Url, File>>);
@@ -402,8 +404,23 @@ impl<
   }
 }
 
+/// Allows rewriting jsr package `meta.json` files before deno_graph uses
+/// them to resolve version constraints.
+pub trait JsrPackageMetaFilter:
+  std::fmt::Debug + MaybeSend + MaybeSync
+{
+  /// Returns the new content of the file at the provided specifier or
+  /// `None` to leave it unchanged.
+  fn filter(&self, specifier: &Url, content: &[u8]) -> Option<Vec<u8>>;
+}
+
+#[allow(clippy::disallowed_types)]
+pub type JsrPackageMetaFilterRc =
+  crate::sync::MaybeArc<dyn JsrPackageMetaFilter>;
+
 pub struct DenoGraphLoaderOptions {
   pub file_header_overrides: HashMap<Url, HashMap<String, String>>,
+  pub jsr_package_meta_filter: Option<JsrPackageMetaFilterRc>,
   pub permissions: Option<PermissionsContainer>,
 }
 
@@ -424,6 +441,7 @@ pub struct DenoGraphLoader<
   file_fetcher: PermissionedFileFetcherRc<TBlobStore, TSys, THttpClient>,
   global_http_cache: GlobalHttpCacheRc<TSys>,
   in_npm_pkg_checker: DenoInNpmPackageChecker,
+  jsr_package_meta_filter: Option<JsrPackageMetaFilterRc>,
   permissions: Option<PermissionsContainer>,
   sys: TSys,
   cache_info_enabled: bool,
@@ -448,6 +466,7 @@ impl<
       in_npm_pkg_checker,
       sys,
       file_header_overrides: options.file_header_overrides,
+      jsr_package_meta_filter: options.jsr_package_meta_filter,
       permissions: options.permissions,
       cache_info_enabled: false,
     }
@@ -635,6 +654,7 @@ impl<
       LoadStrategy {
         file_fetcher: self.file_fetcher.clone(),
         file_header_overrides: self.file_header_overrides.clone(),
+        jsr_package_meta_filter: self.jsr_package_meta_filter.clone(),
       },
       specifier,
       options,
@@ -675,6 +695,7 @@ struct LoadStrategy<
 > {
   file_fetcher: PermissionedFileFetcherRc<TBlobStore, TSys, THttpClient>,
   file_header_overrides: HashMap<Url, HashMap<String, String>>,
+  jsr_package_meta_filter: Option<JsrPackageMetaFilterRc>,
 }
 
 #[async_trait::async_trait(?Send)]
@@ -706,11 +727,19 @@ impl<TBlobStore: BlobStore, TSys: DenoGraphLoaderSys, THttpClient: HttpClient>
             (None, Some(overrides)) => Some(overrides.clone()),
             (None, None) => None,
           };
+          let content = match self
+            .jsr_package_meta_filter
+            .as_ref()
+            .and_then(|filter| filter.filter(&file.url, &file.source))
+          {
+            Some(content) => content.into(),
+            None => file.source,
+          };
           LoadResponse::Module {
             specifier: file.url,
             maybe_headers,
             mtime: file.mtime,
-            content: file.source,
+            content,
           }
         }
         FileOrRedirect::Redirect(redirect_specifier) => {
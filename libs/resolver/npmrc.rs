@@ -6,6 +6,7 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use boxed_error::Boxed;
+use deno_config::deno_json::MinimumReleaseAgeConfig;
 use deno_config::workspace::Workspace;
 use deno_npm::npm_rc::NpmRc;
 use deno_npm::npm_rc::ResolvedNpmRc;
@@ -220,6 +221,123 @@ fn discover_npmrc<TSys: EnvVar + EnvHomeDir + FsRead>(
   }
 }
 
+/// Loads the `minimum-release-age` (in days) and
+/// `minimum-release-age-exclude[]` options from an `.npmrc` file.
+pub fn load_minimum_release_age_from_npmrc(
+  sys: &(impl FsRead + EnvVar),
+  path: &Path,
+) -> Result<Option<MinimumReleaseAgeConfig>, NpmRcLoadError> {
+  let source =
+    sys
+      .fs_read_to_string(path)
+      .map_err(|source| NpmRcLoadError {
+        path: path.to_path_buf(),
+        source,
+      })?;
+  Ok(parse_minimum_release_age(&source, &|name| {
+    sys.env_var(name).ok()
+  }))
+}
+
+/// Parses the options that `NpmRc` doesn't know about, reading the values the
+/// same way as npm does: quoted values are unquoted, comments are removed
+/// from unquoted values and `${VAR}` is replaced with the environment
+/// variable. Options in a section don't apply to the whole file, so they're
+/// ignored.
+fn parse_minimum_release_age(
+  source: &str,
+  get_env_var: &impl Fn(&str) -> Option<String>,
+) -> Option<MinimumReleaseAgeConfig> {
+  let mut days = None;
+  let mut exclude = Vec::new();
+  for line in source.lines() {
+    let line = line.trim();
+    if line.starts_with('[') {
+      break;
+    }
+    if line.starts_with(';') || line.starts_with('#') {
+      continue;
+    }
+    let Some((key, value)) = line.split_once('=') else {
+      continue;
+    };
+    let key = expand_env_vars(&ini_value(key), get_env_var);
+    let value = expand_env_vars(&ini_value(value), get_env_var);
+    match key.as_str() {
+      "minimum-release-age" => match value.parse::<u32>() {
+        Ok(value) => days = Some(value),
+        Err(_) => {
+          log::warn!(
+            "Ignoring invalid minimum-release-age in .npmrc. Expected a number of days, but found: {}",
+            value
+          );
+        }
+      },
+      "minimum-release-age-exclude[]" => exclude.push(value),
+      _ => {}
+    }
+  }
+  days.map(|days| MinimumReleaseAgeConfig { days, exclude })
+}
+
+fn ini_value(text: &str) -> Cow<'_, str> {
+  let text = text.trim();
+  let is_quoted = |quote: char| {
+    text.len() >= 2 && text.starts_with(quote) && text.ends_with(quote)
+  };
+  if is_quoted('"') {
+    return match serde_json::from_str::<String>(text) {
+      Ok(value) => Cow::Owned(value),
+      Err(_) => Cow::Borrowed(&text[1..text.len() - 1]),
+    };
+  }
+  if is_quoted('\'') {
+    return Cow::Borrowed(&text[1..text.len() - 1]);
+  }
+  // an unquoted value ends at a comment, unless it's escaped
+  let mut value = String::with_capacity(text.len());
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => match chars.next() {
+        Some(next @ ('\\' | ';' | '#')) => value.push(next),
+        Some(next) => {
+          value.push(c);
+          value.push(next);
+        }
+        None => value.push(c),
+      },
+      ';' | '#' => break,
+      _ => value.push(c),
+    }
+  }
+  Cow::Owned(value.trim().to_string())
+}
+
+/// Replaces each `${VAR}` with the value of the environment variable, or
+/// leaves it as is when the variable isn't set.
+fn expand_env_vars(
+  text: &str,
+  get_env_var: &impl Fn(&str) -> Option<String>,
+) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find("${") {
+    let Some(len) = rest[start..].find('}') else {
+      break;
+    };
+    let name = &rest[start + 2..start + len];
+    result.push_str(&rest[..start]);
+    match get_env_var(name) {
+      Some(value) => result.push_str(&value),
+      None => result.push_str(&rest[start..=start + len]),
+    }
+    rest = &rest[start + len + 1..];
+  }
+  result.push_str(rest);
+  result
+}
+
 pub fn create_default_npmrc(sys: &impl EnvVar) -> ResolvedNpmRc {
   ResolvedNpmRc {
     default_config: deno_npm::npm_rc::RegistryConfigWithUrl {
@@ -248,3 +366,49 @@ pub fn npm_registry_url(sys: &impl EnvVar) -> Url {
 
   Url::parse("https://registry.npmjs.org").unwrap()
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn parses_minimum_release_age() {
+    let get_env_var = |name: &str| match name {
+      "MIN_AGE" => Some("3".to_string()),
+      _ => None,
+    };
+    let parse = |source: &str| parse_minimum_release_age(source, &get_env_var);
+    assert_eq!(
+      parse(
+        "registry=https://registry.npmjs.org/
+; comment
+minimum-release-age = 7 ; days
+minimum-release-age-exclude[]=chalk
+minimum-release-age-exclude[]=\"@types/*\"
+minimum-release-age-exclude[]='${UNSET}'
+minimum-release-age-exclude[]=a\\#b
+[section]
+minimum-release-age-exclude[]=ignored
+"
+      ),
+      Some(MinimumReleaseAgeConfig {
+        days: 7,
+        exclude: vec![
+          "chalk".to_string(),
+          "@types/*".to_string(),
+          "${UNSET}".to_string(),
+          "a#b".to_string(),
+        ],
+      })
+    );
+    assert_eq!(
+      parse("minimum-release-age=${MIN_AGE}"),
+      Some(MinimumReleaseAgeConfig {
+        days: 3,
+        exclude: vec![],
+      })
+    );
+    assert_eq!(parse("minimum-release-age-exclude[]=chalk"), None);
+    assert_eq!(parse("minimum-release-age=abc"), None);
+  }
+}
@@ -0,0 +1,118 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::PermissionState;
+use crate::prompter::current_stacktrace;
+
+static AUDIT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+static AUDIT_SINKS: Lazy<Mutex<Vec<Box<dyn PermissionAuditSink>>>> =
+  Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Receives a record of every permission query, check, request and revoke
+/// once added with [`add_audit_sink`].
+pub trait PermissionAuditSink: Send + Sync {
+  fn record(&self, record: &PermissionAuditRecord);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionAuditOperation {
+  Query,
+  Check,
+  Request,
+  Revoke,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionAuditRecord {
+  pub operation: PermissionAuditOperation,
+  /// Name of the permission (ex. `read` or `net`).
+  pub kind: &'static str,
+  /// What was being accessed or `None` for the entire permission.
+  pub descriptor: Option<String>,
+  /// The resolved path for file system permissions.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub path: Option<PathBuf>,
+  /// The resulting state of the permission.
+  #[serde(serialize_with = "serialize_state")]
+  pub decision: PermissionState,
+  /// Whether the user was prompted to make the decision.
+  pub prompted: bool,
+  pub api_name: Option<String>,
+  /// The JS stack of the op that caused this, which is only available
+  /// when permission stack traces are enabled.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub stack: Option<Vec<String>>,
+}
+
+fn serialize_state<S: Serializer>(
+  state: &PermissionState,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.collect_str(state)
+}
+
+pub fn add_audit_sink(sink: Box<dyn PermissionAuditSink>) {
+  AUDIT_SINKS.lock().push(sink);
+  AUDIT_ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Removes the sinks added by a test so that auditing doesn't stay enabled
+/// for the other tests.
+#[cfg(test)]
+pub(crate) fn clear_audit_sinks() {
+  AUDIT_SINKS.lock().clear();
+  AUDIT_ENABLED.store(false, Ordering::Relaxed);
+}
+
+#[inline(always)]
+pub(crate) fn is_audit_enabled() -> bool {
+  AUDIT_ENABLED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn audit_stack() -> Option<Vec<String>> {
+  if is_audit_enabled() {
+    current_stacktrace()
+  } else {
+    None
+  }
+}
+
+pub(crate) fn audit(
+  operation: PermissionAuditOperation,
+  kind: &'static str,
+  descriptor: impl FnOnce() -> Option<String>,
+  path: Option<&Path>,
+  decision: PermissionState,
+  prompted: bool,
+  api_name: Option<&str>,
+  stack: Option<Vec<String>>,
+) {
+  if !is_audit_enabled() {
+    return;
+  }
+  let record = PermissionAuditRecord {
+    operation,
+    kind,
+    descriptor: descriptor(),
+    path: path.map(|p| p.to_path_buf()),
+    decision,
+    prompted,
+    api_name: api_name.map(|s| s.to_string()),
+    stack,
+  };
+  for sink in AUDIT_SINKS.lock().iter() {
+    sink.record(&record);
+  }
+}
@@ -0,0 +1,366 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! A [`PermissionPrompter`] that lets an external process decide permission
+//! prompts.
+//!
+//! Each prompt is written to the broker as a single line of JSON:
+//!
+//! ```json
+//! {"id":1,"name":"read","message":"read access to \"/etc\"","apiName":"Deno.readFile","isUnary":true,"stack":null}
+//! ```
+//!
+//! The broker replies with a line containing the same id and one of
+//! `"allow"`, `"deny"` or `"allowAll"`:
+//!
+//! ```json
+//! {"id":1,"response":"allow"}
+//! ```
+//!
+//! The prompt is denied when no reply is received within the timeout or the
+//! connection fails. The stack is only provided when permission stack traces
+//! are enabled.
+
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::os::fd::FromRawFd;
+use std::os::fd::RawFd;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::prompter::GetFormattedStackFn;
+use crate::prompter::PermissionPrompter;
+use crate::prompter::PromptResponse;
+
+/// Where to connect to the permission broker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionBrokerAddress {
+  /// Path of a Unix socket.
+  Path(PathBuf),
+  /// A connected socket inherited from the parent process.
+  Fd(RawFd),
+}
+
+impl PermissionBrokerAddress {
+  /// Parses either `fd:<number>` or the path of a Unix socket.
+  pub fn parse(value: &str) -> Self {
+    match value.strip_prefix("fd:").and_then(|fd| fd.parse().ok()) {
+      Some(fd) => PermissionBrokerAddress::Fd(fd),
+      None => PermissionBrokerAddress::Path(PathBuf::from(value)),
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BrokerRequest<'a> {
+  id: u64,
+  name: &'a str,
+  message: &'a str,
+  api_name: Option<&'a str>,
+  is_unary: bool,
+  stack: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum BrokerDecision {
+  Allow,
+  Deny,
+  AllowAll,
+}
+
+#[derive(Deserialize)]
+struct BrokerResponse {
+  id: u64,
+  response: BrokerDecision,
+}
+
+pub struct BrokerPrompter {
+  reader: BufReader<UnixStream>,
+  timeout: Duration,
+  next_id: u64,
+  /// Data of a line that was only partially received before a timeout.
+  partial_line: String,
+}
+
+impl BrokerPrompter {
+  pub fn new(stream: UnixStream, timeout: Duration) -> std::io::Result<Self> {
+    stream.set_write_timeout(Some(timeout))?;
+    stream.set_read_timeout(Some(timeout))?;
+    Ok(Self {
+      reader: BufReader::new(stream),
+      timeout,
+      next_id: 1,
+      partial_line: String::new(),
+    })
+  }
+
+  pub fn connect(
+    address: &PermissionBrokerAddress,
+    timeout: Duration,
+  ) -> std::io::Result<Self> {
+    let stream = match address {
+      PermissionBrokerAddress::Path(path) => UnixStream::connect(path)?,
+      PermissionBrokerAddress::Fd(fd) => {
+        ensure_socket_fd(*fd)?;
+        // SAFETY: the fd is an open socket that was inherited for
+        // communicating with the broker, so nothing else in this process
+        // owns it
+        let stream = unsafe { UnixStream::from_raw_fd(*fd) };
+        // don't leak the connection to subprocesses
+        // SAFETY: fcntl on an fd that is owned by the stream
+        if unsafe { libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+          return Err(std::io::Error::last_os_error());
+        }
+        stream
+      }
+    };
+    Self::new(stream, timeout)
+  }
+
+  fn request(
+    &mut self,
+    request: &BrokerRequest,
+  ) -> std::io::Result<BrokerDecision> {
+    let mut line = serde_json::to_vec(request)?;
+    line.push(b'\n');
+    self.reader.get_mut().write_all(&line)?;
+
+    let deadline = Instant::now() + self.timeout;
+    loop {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      if remaining.is_zero() {
+        return Err(ErrorKind::TimedOut.into());
+      }
+      self.reader.get_ref().set_read_timeout(Some(remaining))?;
+      match self.reader.read_line(&mut self.partial_line) {
+        Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+        Ok(_) if self.partial_line.ends_with('\n') => {
+          let line = std::mem::take(&mut self.partial_line);
+          // ignore replies to prompts that previously timed out
+          match serde_json::from_str::<BrokerResponse>(&line) {
+            Ok(response) if response.id == request.id => {
+              return Ok(response.response);
+            }
+            Ok(_) => {}
+            Err(err) => {
+              log::debug!("Invalid permission broker response: {:#}", err);
+            }
+          }
+        }
+        Ok(_) => {}
+        Err(err)
+          if matches!(
+            err.kind(),
+            ErrorKind::WouldBlock | ErrorKind::TimedOut
+          ) =>
+        {
+          return Err(ErrorKind::TimedOut.into());
+        }
+        Err(err) if err.kind() == ErrorKind::Interrupted => {}
+        Err(err) => return Err(err),
+      }
+    }
+  }
+}
+
+/// Checks that the fd is open and refers to a socket so that an arbitrary fd
+/// isn't taken over and closed.
+fn ensure_socket_fd(fd: RawFd) -> std::io::Result<()> {
+  // SAFETY: F_GETFD only reads the flags of the fd and fails when it's
+  // not open
+  if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+    return Err(std::io::Error::last_os_error());
+  }
+  let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+  // SAFETY: fstat writes the status of the open fd to the buffer
+  if unsafe { libc::fstat(fd, stat.as_mut_ptr()) } == -1 {
+    return Err(std::io::Error::last_os_error());
+  }
+  // SAFETY: fstat succeeded, so the buffer is initialized
+  let stat = unsafe { stat.assume_init() };
+  if stat.st_mode & libc::S_IFMT != libc::S_IFSOCK {
+    return Err(std::io::Error::new(
+      ErrorKind::InvalidInput,
+      format!("fd {} is not a socket", fd),
+    ));
+  }
+  Ok(())
+}
+
+impl PermissionPrompter for BrokerPrompter {
+  fn prompt(
+    &mut self,
+    message: &str,
+    name: &str,
+    api_name: Option<&str>,
+    is_unary: bool,
+    get_stack: Option<GetFormattedStackFn>,
+  ) -> PromptResponse {
+    let id = self.next_id;
+    self.next_id += 1;
+    let request = BrokerRequest {
+      id,
+      name,
+      message,
+      api_name,
+      is_unary,
+      stack: get_stack.map(|get_stack| get_stack()),
+    };
+    match self.request(&request) {
+      Ok(BrokerDecision::Allow) => PromptResponse::Allow,
+      Ok(BrokerDecision::AllowAll) if is_unary => PromptResponse::AllowAll,
+      Ok(BrokerDecision::AllowAll) => PromptResponse::Allow,
+      Ok(BrokerDecision::Deny) => PromptResponse::Deny,
+      Err(err) => {
+        log::warn!(
+          "Denying {} because the permission broker did not respond: {:#}",
+          message,
+          err
+        );
+        PromptResponse::Deny
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::os::fd::AsRawFd;
+  use std::os::fd::IntoRawFd;
+  use std::os::fd::OwnedFd;
+
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_broker_prompter() {
+    let (stream, broker) = UnixStream::pair().unwrap();
+    let handle = std::thread::spawn(move || {
+      let mut reader = BufReader::new(broker.try_clone().unwrap());
+      let mut writer = broker;
+      let mut requests = Vec::new();
+      for response in ["allowAll", "deny"] {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+        // a stale reply is ignored
+        writeln!(writer, "{}", json!({ "id": 0, "response": "allow" }))
+          .unwrap();
+        writeln!(
+          writer,
+          "{}",
+          json!({ "id": request["id"], "response": response })
+        )
+        .unwrap();
+        requests.push(request);
+      }
+      // never reply to the last request
+      let mut line = String::new();
+      reader.read_line(&mut line).unwrap();
+      requests
+    });
+
+    let mut prompter =
+      BrokerPrompter::new(stream, Duration::from_millis(500)).unwrap();
+    assert_eq!(
+      prompter.prompt(
+        "read access to \"/etc\"",
+        "read",
+        Some("Deno.readFile"),
+        true,
+        Some(Box::new(|| vec!["at file:///main.ts:1:1".to_string()])),
+      ),
+      PromptResponse::AllowAll
+    );
+    assert_eq!(
+      prompter.prompt("run access", "run", None, false, None),
+      PromptResponse::Deny
+    );
+    assert_eq!(
+      prompter.prompt("env access", "env", None, true, None),
+      PromptResponse::Deny
+    );
+    assert_eq!(
+      handle.join().unwrap(),
+      vec![
+        json!({
+          "id": 1,
+          "name": "read",
+          "message": "read access to \"/etc\"",
+          "apiName": "Deno.readFile",
+          "isUnary": true,
+          "stack": ["at file:///main.ts:1:1"],
+        }),
+        json!({
+          "id": 2,
+          "name": "run",
+          "message": "run access",
+          "apiName": null,
+          "isUnary": false,
+          "stack": null,
+        }),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_connect_validates_fd() {
+    let mut fds = [0; 2];
+    // SAFETY: pipe writes two new fds to the array
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    // SAFETY: the fds were just created and nothing else owns them
+    let pipe = fds.map(|fd| unsafe { OwnedFd::from_raw_fd(fd) });
+    let err = BrokerPrompter::connect(
+      &PermissionBrokerAddress::Fd(pipe[0].as_raw_fd()),
+      Duration::from_secs(1),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    // the pipe wasn't taken over
+    // SAFETY: F_GETFD only reads the flags of the fd
+    assert_ne!(
+      unsafe { libc::fcntl(pipe[0].as_raw_fd(), libc::F_GETFD) },
+      -1
+    );
+
+    let err = BrokerPrompter::connect(
+      &PermissionBrokerAddress::Fd(RawFd::MAX),
+      Duration::from_secs(1),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err.raw_os_error(), Some(libc::EBADF));
+
+    let (stream, _broker) = UnixStream::pair().unwrap();
+    let fd = stream.into_raw_fd();
+    assert!(
+      BrokerPrompter::connect(
+        &PermissionBrokerAddress::Fd(fd),
+        Duration::from_secs(1)
+      )
+      .is_ok()
+    );
+  }
+
+  #[test]
+  fn test_parse_broker_address() {
+    assert_eq!(
+      PermissionBrokerAddress::parse("fd:3"),
+      PermissionBrokerAddress::Fd(3)
+    );
+    assert_eq!(
+      PermissionBrokerAddress::parse("/tmp/broker.sock"),
+      PermissionBrokerAddress::Path(PathBuf::from("/tmp/broker.sock"))
+    );
+  }
+}
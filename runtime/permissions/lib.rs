@@ -29,8 +29,32 @@ use serde::Serialize;
 use serde::de;
 use url::Url;
 
+pub mod audit;
+#[cfg(unix)]
+pub mod broker;
+pub mod packages;
 pub mod prompter;
 pub mod which;
+pub use audit::PermissionAuditOperation;
+pub use audit::PermissionAuditRecord;
+pub use audit::PermissionAuditSink;
+pub use audit::add_audit_sink;
+use audit::audit;
+use audit::audit_stack;
+use audit::is_audit_enabled;
+pub use packages::OpOrigin;
+use packages::OpPackages;
+use packages::PackageAllow;
+pub use packages::PackageAllowOptions;
+use packages::PackagePattern;
+pub use packages::PackagePatternParseError;
+use packages::PackagePermission;
+pub use packages::PackagePermissionsOptions;
+pub use packages::PermissionPackageResolver;
+pub use packages::has_package_resolver;
+pub use packages::register_module_source;
+pub use packages::set_current_op_origin;
+pub use packages::set_package_resolver;
 pub use prompter::DeniedPrompter;
 pub use prompter::GetFormattedStackFn;
 use prompter::PERMISSION_EMOJI;
@@ -44,15 +68,25 @@ pub use prompter::set_prompter;
 use self::which::WhichSys;
 
 #[derive(Debug, thiserror::Error, deno_error::JsError)]
-#[error("Requires {access}, {}", format_permission_error(.name))]
+#[error(
+  "Requires {access}, {}",
+  format_permission_error(.name, .package.as_deref())
+)]
 #[class("NotCapable")]
 pub struct PermissionDeniedError {
   pub access: String,
   pub name: &'static str,
+  /// The package whose package permissions denied the access.
+  pub package: Option<String>,
 }
 
-fn format_permission_error(name: &'static str) -> String {
-  if is_standalone() {
+fn format_permission_error(
+  name: &'static str,
+  package: Option<&str>,
+) -> String {
+  if let Some(package) = package {
+    format!("which is not granted to {package} by the package permissions")
+  } else if is_standalone() {
     format!(
       "specify the required permissions during compilation using `deno compile --allow-{name}`"
     )
@@ -62,10 +96,10 @@ fn format_permission_error(name: &'static str) -> String {
 }
 
 /// Fast exit from permission check routines if this permission
-/// is in the "fully-granted" state.
+/// is in the "fully-granted" state and checks aren't being audited.
 macro_rules! skip_check_if_is_permission_fully_granted {
   ($this:ident) => {
-    if $this.is_allow_all() {
+    if $this.can_skip_checks() {
       return Ok(());
     }
   };
@@ -394,6 +428,7 @@ impl PermissionState {
     PermissionDeniedError {
       access: Self::fmt_access(name, info),
       name,
+      package: None,
     }
   }
 
@@ -609,6 +644,11 @@ pub trait QueryDescriptor: Debug {
   fn flag_name() -> &'static str;
   fn display_name(&self) -> Cow<str>;
 
+  /// The resolved path of descriptors for file system permissions.
+  fn path(&self) -> Option<&Path> {
+    None
+  }
+
   fn from_allow(allow: &Self::AllowDesc) -> Self;
 
   fn as_allow(&self) -> Option<Self::AllowDesc>;
@@ -650,6 +690,7 @@ pub struct UnaryPermission<
   prompt_denied_global: bool,
   prompt_denied_list: HashSet<TDenyDesc>,
   prompt: bool,
+  packages: Vec<PackagePermission<TAllowDesc>>,
 }
 
 impl<TAllowDesc: AllowDescriptor, TDenyDesc: DenyDescriptor> Default
@@ -664,6 +705,7 @@ impl<TAllowDesc: AllowDescriptor, TDenyDesc: DenyDescriptor> Default
       prompt_denied_global: Default::default(),
       prompt_denied_list: Default::default(),
       prompt: Default::default(),
+      packages: Default::default(),
     }
   }
 }
@@ -680,6 +722,7 @@ impl<TAllowDesc: AllowDescriptor, TDenyDesc: DenyDescriptor> Clone
       prompt_denied_global: self.prompt_denied_global,
       prompt_denied_list: self.prompt_denied_list.clone(),
       prompt: self.prompt,
+      packages: self.packages.clone(),
     }
   }
 }
@@ -696,12 +739,28 @@ impl<
     }
   }
 
+  fn with_packages(
+    mut self,
+    packages: Vec<PackagePermission<TAllowDesc>>,
+  ) -> Self {
+    self.packages = packages;
+    self
+  }
+
   pub fn is_allow_all(&self) -> bool {
     self.granted_global
       && !self.flag_denied_global
       && !self.prompt_denied_global
       && self.flag_denied_list.is_empty()
       && self.prompt_denied_list.is_empty()
+      && self.packages.is_empty()
+  }
+
+  /// Gets if checks can be skipped because everything is allowed and
+  /// there's no audit sink that needs to see them.
+  #[inline(always)]
+  fn can_skip_checks(&self) -> bool {
+    self.is_allow_all() && !is_audit_enabled()
   }
 
   pub fn check_all_api(
@@ -718,6 +777,18 @@ impl<
     assert_non_partial: bool,
     api_name: Option<&str>,
   ) -> Result<(), PermissionDeniedError> {
+    let stack = audit_stack();
+    if let Err(err) = self.check_package_desc(desc) {
+      Self::audit_desc(
+        PermissionAuditOperation::Check,
+        desc,
+        PermissionState::Denied,
+        false,
+        api_name,
+        stack,
+      );
+      return Err(err);
+    }
     let (result, prompted, is_allow_all) = self
       .query_desc(desc, AllowPartial::from(!assert_non_partial))
       .check(
@@ -737,9 +808,82 @@ impl<
         self.insert_prompt_denied(desc.map(|d| d.as_deny()));
       }
     }
+    Self::audit_desc(
+      PermissionAuditOperation::Check,
+      desc,
+      if result.is_ok() {
+        PermissionState::Granted
+      } else {
+        PermissionState::Denied
+      },
+      prompted,
+      api_name,
+      stack,
+    );
     result
   }
 
+  /// Checks the descriptor against the package permissions of every package
+  /// that the current op was called from, or against all of the package
+  /// permissions when that isn't known.
+  fn check_package_desc(
+    &self,
+    desc: Option<&TAllowDesc::QueryDesc<'_>>,
+  ) -> Result<(), PermissionDeniedError> {
+    if self.packages.is_empty() {
+      return Ok(());
+    }
+    let Some(op_packages) = packages::current_op_packages() else {
+      return Ok(());
+    };
+    let is_allowed = |allow: &PackageAllow<TAllowDesc>| match allow {
+      PackageAllow::All => true,
+      PackageAllow::Nothing => false,
+      PackageAllow::List(list) => desc
+        .is_some_and(|desc| list.iter().any(|allow| desc.matches_allow(allow))),
+    };
+    let denied_package = match op_packages {
+      OpPackages::Packages(op_packages) => {
+        op_packages.into_iter().find(|package| {
+          packages::find_package_allow(&self.packages, package)
+            .is_some_and(|allow| !is_allowed(allow))
+        })
+      }
+      OpPackages::Unknown => self
+        .packages
+        .iter()
+        .any(|permission| !is_allowed(&permission.allow))
+        .then(|| "code of unknown origin".to_string()),
+    };
+    match denied_package {
+      Some(package) => {
+        let mut err = PermissionState::permission_denied_error(
+          TAllowDesc::QueryDesc::flag_name(),
+          || desc.map(|d| format_display_name(d.display_name()).into_owned()),
+        );
+        err.package = Some(package);
+        Err(err)
+      }
+      None => Ok(()),
+    }
+  }
+
+  fn query_desc_audited(
+    &self,
+    desc: Option<&TAllowDesc::QueryDesc<'_>>,
+  ) -> PermissionState {
+    let state = self.query_desc(desc, AllowPartial::TreatAsPartialGranted);
+    Self::audit_desc(
+      PermissionAuditOperation::Query,
+      desc,
+      state,
+      false,
+      None,
+      audit_stack(),
+    );
+    state
+  }
+
   fn query_desc(
     &self,
     desc: Option<&TAllowDesc::QueryDesc<'_>>,
@@ -778,16 +922,34 @@ impl<
     &mut self,
     desc: Option<&TAllowDesc::QueryDesc<'_>>,
   ) -> PermissionState {
+    let stack = audit_stack();
+    let (state, prompted) = self.request_desc_inner(desc);
+    Self::audit_desc(
+      PermissionAuditOperation::Request,
+      desc,
+      state,
+      prompted,
+      Some("Deno.permissions.request()"),
+      stack,
+    );
+    state
+  }
+
+  /// Returns the state and whether the user was prompted.
+  fn request_desc_inner(
+    &mut self,
+    desc: Option<&TAllowDesc::QueryDesc<'_>>,
+  ) -> (PermissionState, bool) {
     let state = self.query_desc(desc, AllowPartial::TreatAsPartialGranted);
     if state == PermissionState::Granted {
       self.insert_granted(desc);
-      return state;
+      return (state, false);
     }
     if state != PermissionState::Prompt {
-      return state;
+      return (state, false);
     }
     if !self.prompt {
-      return PermissionState::Denied;
+      return (PermissionState::Denied, false);
     }
     let maybe_formatted_display_name =
       desc.map(|d| format_display_name(d.display_name()));
@@ -800,7 +962,7 @@ impl<
       }
     })
     .unwrap();
-    match permission_prompt(
+    let state = match permission_prompt(
       &message,
       TAllowDesc::QueryDesc::flag_name(),
       Some("Deno.permissions.request()"),
@@ -818,7 +980,8 @@ impl<
         self.insert_granted(None);
         PermissionState::Granted
       }
-    }
+    };
+    (state, true)
   }
 
   fn revoke_desc(
@@ -837,7 +1000,37 @@ impl<
         self.granted_list.clear();
       }
     }
-    self.query_desc(desc, AllowPartial::TreatAsPartialGranted)
+    let state = self.query_desc(desc, AllowPartial::TreatAsPartialGranted);
+    Self::audit_desc(
+      PermissionAuditOperation::Revoke,
+      desc,
+      state,
+      false,
+      None,
+      audit_stack(),
+    );
+    state
+  }
+
+  #[inline(always)]
+  fn audit_desc(
+    operation: PermissionAuditOperation,
+    desc: Option<&TAllowDesc::QueryDesc<'_>>,
+    decision: PermissionState,
+    prompted: bool,
+    api_name: Option<&str>,
+    stack: Option<Vec<String>>,
+  ) {
+    audit(
+      operation,
+      TAllowDesc::QueryDesc::flag_name(),
+      || desc.map(|d| d.display_name().into_owned()),
+      desc.and_then(|d| d.path()),
+      decision,
+      prompted,
+      api_name,
+      stack,
+    );
   }
 
   fn is_granted(&self, query: Option<&TAllowDesc::QueryDesc<'_>>) -> bool {
@@ -966,6 +1159,7 @@ impl<
     perms
       .prompt_denied_list
       .clone_from(&self.prompt_denied_list);
+    perms.packages.clone_from(&self.packages);
 
     Ok(perms)
   }
@@ -1108,6 +1302,10 @@ impl QueryDescriptor for ReadQueryDescriptor<'_> {
     self.0.display_name()
   }
 
+  fn path(&self) -> Option<&Path> {
+    Some(&self.0.path)
+  }
+
   fn from_allow(allow: &Self::AllowDesc) -> Self {
     allow.0.as_query_descriptor().into_read()
   }
@@ -1265,6 +1463,10 @@ impl QueryDescriptor for WriteQueryDescriptor<'_> {
     self.0.display_name()
   }
 
+  fn path(&self) -> Option<&Path> {
+    Some(&self.0.path)
+  }
+
   fn from_allow(allow: &Self::AllowDesc) -> Self {
     WriteQueryDescriptor(allow.0.as_query_descriptor())
   }
@@ -2397,6 +2599,10 @@ impl QueryDescriptor for FfiQueryDescriptor<'_> {
     self.0.display_name()
   }
 
+  fn path(&self) -> Option<&Path> {
+    Some(&self.0.path)
+  }
+
   fn from_allow(allow: &Self::AllowDesc) -> Self {
     allow.0.as_query_descriptor().into_ffi()
   }
@@ -2451,7 +2657,7 @@ impl DenyDescriptor for FfiDescriptor {}
 
 impl UnaryPermission<ReadDescriptor, ReadDescriptor> {
   pub fn query(&self, desc: Option<&ReadQueryDescriptor>) -> PermissionState {
-    self.query_desc(desc, AllowPartial::TreatAsPartialGranted)
+    self.query_desc_audited(desc)
   }
 
   pub fn request(
@@ -2498,7 +2704,7 @@ impl UnaryPermission<ReadDescriptor, ReadDescriptor> {
 
 impl UnaryPermission<WriteDescriptor, WriteDescriptor> {
   pub fn query(&self, path: Option<&WriteQueryDescriptor>) -> PermissionState {
-    self.query_desc(path, AllowPartial::TreatAsPartialGranted)
+    self.query_desc_audited(path)
   }
 
   pub fn request(
@@ -2545,7 +2751,7 @@ impl UnaryPermission<WriteDescriptor, WriteDescriptor> {
 
 impl UnaryPermission<NetDescriptor, NetDescriptor> {
   pub fn query(&self, host: Option<&NetDescriptor>) -> PermissionState {
-    self.query_desc(host, AllowPartial::TreatAsPartialGranted)
+    self.query_desc_audited(host)
   }
 
   pub fn request(&mut self, host: Option<&NetDescriptor>) -> PermissionState {
@@ -2573,7 +2779,7 @@ impl UnaryPermission<NetDescriptor, NetDescriptor> {
 
 impl UnaryPermission<ImportDescriptor, ImportDescriptor> {
   pub fn query(&self, host: Option<&ImportDescriptor>) -> PermissionState {
-    self.query_desc(host, AllowPartial::TreatAsPartialGranted)
+    self.query_desc_audited(host)
   }
 
   pub fn request(
@@ -2604,11 +2810,10 @@ impl UnaryPermission<ImportDescriptor, ImportDescriptor> {
 
 impl UnaryPermission<EnvDescriptor, EnvDescriptor> {
   pub fn query(&self, env: Option<&str>) -> PermissionState {
-    self.query_desc(
+    self.query_desc_audited(
       env
         .map(|env| EnvQueryDescriptor::new(Cow::Borrowed(env)))
         .as_ref(),
-      AllowPartial::TreatAsPartialGranted,
     )
   }
 
@@ -2649,7 +2854,7 @@ impl UnaryPermission<EnvDescriptor, EnvDescriptor> {
 
 impl UnaryPermission<SysDescriptor, SysDescriptor> {
   pub fn query(&self, kind: Option<&SysDescriptor>) -> PermissionState {
-    self.query_desc(kind, AllowPartial::TreatAsPartialGranted)
+    self.query_desc_audited(kind)
   }
 
   pub fn request(&mut self, kind: Option<&SysDescriptor>) -> PermissionState {
@@ -2677,7 +2882,7 @@ impl UnaryPermission<SysDescriptor, SysDescriptor> {
 
 impl UnaryPermission<AllowRunDescriptor, DenyRunDescriptor> {
   pub fn query(&self, cmd: Option<&RunQueryDescriptor>) -> PermissionState {
-    self.query_desc(cmd, AllowPartial::TreatAsPartialGranted)
+    self.query_desc_audited(cmd)
   }
 
   pub fn request(
@@ -2727,7 +2932,7 @@ impl UnaryPermission<AllowRunDescriptor, DenyRunDescriptor> {
 
 impl UnaryPermission<FfiDescriptor, FfiDescriptor> {
   pub fn query(&self, path: Option<&FfiQueryDescriptor>) -> PermissionState {
-    self.query_desc(path, AllowPartial::TreatAsPartialGranted)
+    self.query_desc_audited(path)
   }
 
   pub fn request(
@@ -2799,6 +3004,9 @@ pub struct PermissionsOptions {
   pub deny_write: Option<Vec<String>>,
   pub allow_import: Option<Vec<String>>,
   pub deny_import: Option<Vec<String>>,
+  /// Restrictions on what npm and jsr packages may access, which also
+  /// apply when everything else is allowed.
+  pub packages: Vec<PackagePermissionsOptions>,
   pub prompt: bool,
 }
 
@@ -2816,6 +3024,8 @@ pub enum PermissionsFromOptionsError {
   RunDescriptorParse(#[from] RunDescriptorParseError),
   #[error("Empty command name not allowed in --allow-run=...")]
   RunEmptyCommandName,
+  #[error("{0}")]
+  PackagePatternParse(#[from] PackagePatternParseError),
 }
 
 impl Permissions {
@@ -2892,6 +3102,47 @@ impl Permissions {
       }
     }
 
+    fn parse_vec<T: Eq + PartialEq + Hash, E>(
+      items: &[String],
+      parse: impl Fn(&str) -> Result<T, E>,
+    ) -> Result<HashSet<T>, PermissionsFromOptionsError>
+    where
+      PermissionsFromOptionsError: From<E>,
+    {
+      Ok(
+        items
+          .iter()
+          .map(|item| parse(item))
+          .collect::<Result<HashSet<_>, _>>()?,
+      )
+    }
+
+    fn parse_packages<T: Eq + Hash>(
+      packages: &[PackagePermissionsOptions],
+      select: impl Fn(&PackagePermissionsOptions) -> Option<&PackageAllowOptions>,
+      parse_list: impl Fn(
+        &[String],
+      ) -> Result<HashSet<T>, PermissionsFromOptionsError>,
+    ) -> Result<Vec<PackagePermission<T>>, PermissionsFromOptionsError> {
+      let mut result = Vec::new();
+      for package in packages {
+        let Some(allow) = select(package) else {
+          continue;
+        };
+        result.push(PackagePermission {
+          pattern: PackagePattern::parse(&package.package)?,
+          allow: match allow {
+            PackageAllowOptions::All => PackageAllow::All,
+            PackageAllowOptions::Nothing => PackageAllow::Nothing,
+            PackageAllowOptions::List(items) => {
+              PackageAllow::List(parse_list(items)?)
+            }
+          },
+        });
+      }
+      Ok(result)
+    }
+
     let mut deny_write = parse_maybe_vec(opts.deny_write.as_deref(), |item| {
       parser.parse_write_descriptor(item)
     })?;
@@ -2932,14 +3183,24 @@ impl Permissions {
           parser.parse_read_descriptor(item)
         })?,
         opts.prompt,
-      ),
+      )
+      .with_packages(parse_packages(
+        &opts.packages,
+        |p| p.read.as_ref(),
+        |items| parse_vec(items, |item| parser.parse_read_descriptor(item)),
+      )?),
       write: Permissions::new_unary(
         parse_maybe_vec(opts.allow_write.as_deref(), |item| {
           parser.parse_write_descriptor(item)
         })?,
         deny_write,
         opts.prompt,
-      ),
+      )
+      .with_packages(parse_packages(
+        &opts.packages,
+        |p| p.write.as_ref(),
+        |items| parse_vec(items, |item| parser.parse_write_descriptor(item)),
+      )?),
       net: Permissions::new_unary(
         parse_maybe_vec(opts.allow_net.as_deref(), |item| {
           parser.parse_net_descriptor(item)
@@ -2948,7 +3209,12 @@ impl Permissions {
           parser.parse_net_descriptor(item)
         })?,
         opts.prompt,
-      ),
+      )
+      .with_packages(parse_packages(
+        &opts.packages,
+        |p| p.net.as_ref(),
+        |items| parse_vec(items, |item| parser.parse_net_descriptor(item)),
+      )?),
       env: Permissions::new_unary(
         parse_maybe_vec(opts.allow_env.as_deref(), |item| {
           parser.parse_env_descriptor(item)
@@ -2957,7 +3223,12 @@ impl Permissions {
           parser.parse_env_descriptor(text)
         })?,
         opts.prompt,
-      ),
+      )
+      .with_packages(parse_packages(
+        &opts.packages,
+        |p| p.env.as_ref(),
+        |items| parse_vec(items, |item| parser.parse_env_descriptor(item)),
+      )?),
       sys: Permissions::new_unary(
         parse_maybe_vec(opts.allow_sys.as_deref(), |text| {
           parser.parse_sys_descriptor(text)
@@ -2966,14 +3237,24 @@ impl Permissions {
           parser.parse_sys_descriptor(text)
         })?,
         opts.prompt,
-      ),
+      )
+      .with_packages(parse_packages(
+        &opts.packages,
+        |p| p.sys.as_ref(),
+        |items| parse_vec(items, |item| parser.parse_sys_descriptor(item)),
+      )?),
       run: Permissions::new_unary(
         allow_run,
         parse_maybe_vec(opts.deny_run.as_deref(), |text| {
           parser.parse_deny_run_descriptor(text)
         })?,
         opts.prompt,
-      ),
+      )
+      .with_packages(parse_packages(
+        &opts.packages,
+        |p| p.run.as_ref(),
+        |items| resolve_allow_run(parser, items),
+      )?),
       ffi: Permissions::new_unary(
         parse_maybe_vec(opts.allow_ffi.as_deref(), |text| {
           parser.parse_ffi_descriptor(text)
@@ -2982,7 +3263,12 @@ impl Permissions {
           parser.parse_ffi_descriptor(text)
         })?,
         opts.prompt,
-      ),
+      )
+      .with_packages(parse_packages(
+        &opts.packages,
+        |p| p.ffi.as_ref(),
+        |items| parse_vec(items, |item| parser.parse_ffi_descriptor(item)),
+      )?),
       import: Permissions::new_unary(
         parse_maybe_vec(opts.allow_import.as_deref(), |item| {
           parser.parse_import_descriptor(item)
@@ -2992,7 +3278,9 @@ impl Permissions {
         })?,
         opts.prompt,
       ),
-      all: Permissions::new_all(opts.allow_all),
+      // package permissions need to be checked even when everything is
+      // allowed
+      all: Permissions::new_all(opts.allow_all && opts.packages.is_empty()),
     })
   }
 
@@ -3270,9 +3558,12 @@ impl PermissionsContainer {
     kind: CheckSpecifierKind,
   ) -> Result<(), PermissionCheckError> {
     let mut inner = self.inner.lock();
+    // module loading isn't an op, so the last op's caller shouldn't be
+    // subject to package permissions for it
+    let _origin_scope = packages::RuntimeOriginScope::new();
     match specifier.scheme() {
       "file" => {
-        if inner.read.is_allow_all() || kind == CheckSpecifierKind::Static {
+        if inner.read.can_skip_checks() || kind == CheckSpecifierKind::Static {
           return Ok(());
         }
 
@@ -3294,7 +3585,7 @@ impl PermissionsContainer {
       "data" => Ok(()),
       "blob" => Ok(()),
       _ => {
-        if inner.import.is_allow_all() {
+        if inner.import.can_skip_checks() {
           return Ok(()); // avoid allocation below
         }
 
@@ -3343,7 +3634,9 @@ impl PermissionsContainer {
     // If somehow read or write aren't specified, use read
     let path = {
       let mut inner = self.inner.lock();
-      if matches!(inner.all.state, PermissionState::Granted) {
+      if matches!(inner.all.state, PermissionState::Granted)
+        && !is_audit_enabled()
+      {
         return Ok(CheckedPath {
           path: PathWithRequested {
             path,
@@ -3353,9 +3646,9 @@ impl PermissionsContainer {
         });
       }
       let should_check_read =
-        access_kind.is_read() && !inner.read.is_allow_all();
+        access_kind.is_read() && !inner.read.can_skip_checks();
       let should_check_write =
-        access_kind.is_write() && !inner.write.is_allow_all();
+        access_kind.is_write() && !inner.write.can_skip_checks();
       let path = self.descriptor_parser.parse_path_query(path)?;
       let path = match blind_requested {
         Some(display) => path.with_requested(format!("<{}>", display)),
@@ -3429,7 +3722,7 @@ impl PermissionsContainer {
   ) -> Result<CheckedPath<'a>, PermissionCheckError> {
     let mut inner = self.inner.lock();
     let inner = &mut inner.write;
-    if inner.is_allow_all() {
+    if inner.can_skip_checks() {
       Ok(CheckedPath {
         path: PathWithRequested {
           path,
@@ -3675,7 +3968,7 @@ impl PermissionsContainer {
     api_name: &str,
   ) -> Result<(), PermissionCheckError> {
     let mut inner = self.inner.lock();
-    if inner.net.is_allow_all() {
+    if inner.net.can_skip_checks() {
       return Ok(());
     }
     let desc = self.descriptor_parser.parse_net_descriptor_from_url(url)?;
@@ -3706,7 +3999,7 @@ impl PermissionsContainer {
     api_name: &str,
   ) -> Result<(), PermissionCheckError> {
     let mut inner = self.inner.lock();
-    if inner.net.is_allow_all() {
+    if inner.net.can_skip_checks() {
       return Ok(());
     }
     let desc = NetDescriptor(Host::Vsock(cid), Some(port));
@@ -3721,7 +4014,7 @@ impl PermissionsContainer {
   ) -> Result<Cow<'a, Path>, PermissionCheckError> {
     let mut inner = self.inner.lock();
     let inner = &mut inner.ffi;
-    if inner.is_allow_all() {
+    if inner.can_skip_checks() {
       Ok(path)
     } else {
       let desc = self.descriptor_parser.parse_path_query(path)?.into_ffi();
@@ -3737,7 +4030,7 @@ impl PermissionsContainer {
   ) -> Result<(), PermissionCheckError> {
     let mut inner = self.inner.lock();
     let inner = &mut inner.ffi;
-    if !inner.is_allow_all() {
+    if !inner.can_skip_checks() {
       inner.check_partial(None)?;
     }
     Ok(())
@@ -3751,7 +4044,7 @@ impl PermissionsContainer {
   ) -> Result<Cow<'a, Path>, PermissionCheckError> {
     let mut inner = self.inner.lock();
     let inner = &mut inner.ffi;
-    if inner.is_allow_all() {
+    if inner.can_skip_checks() {
       Ok(path)
     } else {
       let desc = self.descriptor_parser.parse_path_query(path)?.into_ffi();
@@ -3769,7 +4062,7 @@ impl PermissionsContainer {
   ) -> Result<PermissionState, PathResolveError> {
     let inner = self.inner.lock();
     let permission = &inner.read;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return Ok(PermissionState::Granted);
     }
     Ok(
@@ -3796,7 +4089,7 @@ impl PermissionsContainer {
   ) -> Result<PermissionState, PathResolveError> {
     let inner = self.inner.lock();
     let permission = &inner.write;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return Ok(PermissionState::Granted);
     }
     Ok(
@@ -3823,7 +4116,7 @@ impl PermissionsContainer {
   ) -> Result<PermissionState, NetDescriptorParseError> {
     let inner = self.inner.lock();
     let permission = &inner.net;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return Ok(PermissionState::Granted);
     }
     Ok(
@@ -3841,7 +4134,7 @@ impl PermissionsContainer {
   pub fn query_env(&self, var: Option<&str>) -> PermissionState {
     let inner = self.inner.lock();
     let permission = &inner.env;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return PermissionState::Granted;
     }
     permission.query(var)
@@ -3854,7 +4147,7 @@ impl PermissionsContainer {
   ) -> Result<PermissionState, SysDescriptorParseError> {
     let inner = self.inner.lock();
     let permission = &inner.sys;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return Ok(PermissionState::Granted);
     }
     Ok(
@@ -3874,7 +4167,7 @@ impl PermissionsContainer {
   ) -> Result<PermissionState, RunDescriptorParseError> {
     let inner = self.inner.lock();
     let permission = &inner.run;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return Ok(PermissionState::Granted);
     }
     Ok(
@@ -3894,7 +4187,7 @@ impl PermissionsContainer {
   ) -> Result<PermissionState, PathResolveError> {
     let inner = self.inner.lock();
     let permission = &inner.ffi;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return Ok(PermissionState::Granted);
     }
     Ok(
@@ -3921,7 +4214,7 @@ impl PermissionsContainer {
   ) -> Result<PermissionState, NetDescriptorParseError> {
     let inner = self.inner.lock();
     let permission = &inner.import;
-    if permission.is_allow_all() {
+    if permission.can_skip_checks() {
       return Ok(PermissionState::Granted);
     }
     Ok(
@@ -5376,6 +5669,187 @@ mod tests {
     };
   }
 
+  #[test]
+  fn test_audit() {
+    struct TestAuditSink(Arc<Mutex<Vec<PermissionAuditRecord>>>);
+
+    impl PermissionAuditSink for TestAuditSink {
+      fn record(&self, record: &PermissionAuditRecord) {
+        // the sink is global, so ignore records from other tests
+        if record
+          .descriptor
+          .as_ref()
+          .is_some_and(|d| d.starts_with("AUDIT_TEST_"))
+        {
+          self.0.lock().push(record.clone());
+        }
+      }
+    }
+
+    set_prompter(Box::new(TestPrompter));
+    let records = Arc::new(Mutex::new(Vec::new()));
+    add_audit_sink(Box::new(TestAuditSink(records.clone())));
+    let parser = TestPermissionDescriptorParser;
+    let mut perms = Permissions::from_options(
+      &parser,
+      &PermissionsOptions {
+        allow_env: Some(svec!["AUDIT_TEST_A"]),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    perms
+      .env
+      .check("AUDIT_TEST_A", Some("Deno.env.get"))
+      .unwrap();
+    assert!(perms.env.check("AUDIT_TEST_B", None).is_err());
+    assert_eq!(
+      perms.env.query(Some("AUDIT_TEST_B")),
+      PermissionState::Prompt
+    );
+    assert_eq!(
+      perms.env.revoke(Some("AUDIT_TEST_A")),
+      PermissionState::Prompt
+    );
+    audit::clear_audit_sinks();
+    let records = records.lock();
+    assert_eq!(
+      serde_json::to_value(&*records).unwrap(),
+      json!([
+        {
+          "operation": "check",
+          "kind": "env",
+          "descriptor": "AUDIT_TEST_A",
+          "decision": "granted",
+          "prompted": false,
+          "apiName": "Deno.env.get",
+        },
+        {
+          "operation": "check",
+          "kind": "env",
+          "descriptor": "AUDIT_TEST_B",
+          "decision": "denied",
+          "prompted": false,
+          "apiName": null,
+        },
+        {
+          "operation": "query",
+          "kind": "env",
+          "descriptor": "AUDIT_TEST_B",
+          "decision": "prompt",
+          "prompted": false,
+          "apiName": null,
+        },
+        {
+          "operation": "revoke",
+          "kind": "env",
+          "descriptor": "AUDIT_TEST_A",
+          "decision": "prompt",
+          "prompted": false,
+          "apiName": null,
+        },
+      ])
+    );
+  }
+
+  #[test]
+  fn test_package_permissions() {
+    struct TestPackageResolver;
+
+    impl PermissionPackageResolver for TestPackageResolver {
+      fn resolve_package(&self, specifier: &str) -> Option<String> {
+        let path = specifier.strip_prefix("file:///node_modules/")?;
+        Some(format!("npm:{}", path.split('/').next()?))
+      }
+    }
+
+    set_prompter(Box::new(TestPrompter));
+    set_package_resolver(Box::new(TestPackageResolver));
+    let parser = TestPermissionDescriptorParser;
+    let mut perms = Permissions::from_options(
+      &parser,
+      &PermissionsOptions {
+        allow_all: true,
+        allow_env: Some(vec![]),
+        allow_net: Some(vec![]),
+        packages: vec![
+          PackagePermissionsOptions {
+            package: "npm:*".to_string(),
+            env: Some(PackageAllowOptions::Nothing),
+            net: Some(PackageAllowOptions::Nothing),
+            ..Default::default()
+          },
+          PackagePermissionsOptions {
+            package: "npm:client".to_string(),
+            net: Some(PackageAllowOptions::List(svec!["api.example.com"])),
+            ..Default::default()
+          },
+        ],
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    assert_eq!(perms.all.state, PermissionState::Prompt);
+    let net = |host: &str| {
+      NetDescriptor(Host::parse_for_query(host).unwrap(), Some(443))
+    };
+
+    let set_modules = |modules: &[&str]| {
+      set_current_op_origin(Some(OpOrigin::Modules(
+        modules.iter().map(|module| module.to_string()).collect(),
+      )));
+    };
+
+    set_modules(&["file:///main.ts"]);
+    assert!(perms.env.check("HOME", None).is_ok());
+    assert!(perms.net.check(&net("deno.land"), None).is_ok());
+
+    set_modules(&["file:///node_modules/other/index.js", "file:///main.ts"]);
+    assert_eq!(
+      perms.env.check("HOME", None).unwrap_err().to_string(),
+      "Requires env access to \"HOME\", which is not granted to npm:other by the package permissions"
+    );
+    assert!(perms.net.check(&net("api.example.com"), None).is_err());
+
+    set_modules(&["file:///node_modules/client/index.js"]);
+    assert!(perms.net.check(&net("api.example.com"), None).is_ok());
+    assert!(perms.net.check(&net("deno.land"), None).is_err());
+    assert!(perms.env.check("HOME", None).is_err());
+
+    // a package calling a function of the app is still restricted
+    set_modules(&["file:///main.ts", "file:///node_modules/client/index.js"]);
+    assert!(perms.net.check(&net("deno.land"), None).is_err());
+    // the most restrictive package permissions apply
+    set_modules(&[
+      "file:///node_modules/client/index.js",
+      "file:///node_modules/other/index.js",
+    ]);
+    assert!(perms.net.check(&net("api.example.com"), None).is_err());
+
+    set_current_op_origin(Some(OpOrigin::Unknown));
+    assert_eq!(
+      perms
+        .net
+        .check(&net("api.example.com"), None)
+        .unwrap_err()
+        .to_string(),
+      "Requires net access to \"api.example.com:443\", which is not granted to code of unknown origin by the package permissions"
+    );
+    // kinds without package permissions aren't restricted
+    assert!(perms.read.check_all(None).is_ok());
+
+    // checks without an origin, such as from an op that completes later on
+    // another thread, are treated as unknown
+    set_current_op_origin(None);
+    assert!(perms.net.check(&net("api.example.com"), None).is_err());
+
+    {
+      let _origin_scope = packages::RuntimeOriginScope::new();
+      assert!(perms.net.check(&net("deno.land"), None).is_ok());
+    }
+    assert!(perms.net.check(&net("deno.land"), None).is_err());
+  }
+
   #[test]
   fn test_check() {
     set_prompter(Box::new(TestPrompter));
@@ -0,0 +1,326 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+//! Restricts the permissions of calls that originate from npm and jsr
+//! packages.
+//!
+//! The origin of a call is every module on the stack of the op, which is
+//! captured by the op stack trace callback, and the call must be allowed by
+//! the package permissions of each package among them. Calls from modules that
+//! aren't part of a package are only subject to the regular permissions.
+//!
+//! When the origin can't be determined, such as when a built-in function is
+//! passed as a callback, the stack contains code created with `eval` or the
+//! check happens on a thread that hasn't called an op, the call must be allowed
+//! by all of the package permissions.
+//!
+//! The file name of a frame comes from the `//# sourceURL` comment of its
+//! script when it has one, so the names declared by the modules of a package
+//! are registered when they're loaded and frames with those names are
+//! attributed to the package as well. Note that the stack only contains the
+//! innermost frames, and that scripts compiled at runtime with a name of the
+//! caller's choosing (ex. with `node:vm`) are attributed by that name.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::OnceLock;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde::Serialize;
+
+static PACKAGE_RESOLVER: OnceLock<Box<dyn PermissionPackageResolver>> =
+  OnceLock::new();
+
+/// The packages of the modules that declared each `//# sourceURL` name.
+static SOURCE_URL_PACKAGES: Lazy<Mutex<HashMap<String, Vec<String>>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+  static CURRENT_OP_ORIGIN: RefCell<Option<OpOrigin>> = const { RefCell::new(None) };
+}
+
+/// Resolves the package that a module belongs to.
+pub trait PermissionPackageResolver: Send + Sync {
+  /// Gets the package of the module as either `npm:<name>` or
+  /// `jsr:<@scope/name>`, or `None` when it isn't part of a package.
+  fn resolve_package(&self, specifier: &str) -> Option<String>;
+}
+
+/// Enables package permissions for the process. The op stack trace
+/// callback must be enabled for the origin of calls to be known.
+pub fn set_package_resolver(resolver: Box<dyn PermissionPackageResolver>) {
+  _ = PACKAGE_RESOLVER.set(resolver);
+}
+
+pub fn has_package_resolver() -> bool {
+  PACKAGE_RESOLVER.get().is_some()
+}
+
+/// Registers the `//# sourceURL` names in the source of a module before it's
+/// executed, so that frames that take their file name from one of them are
+/// still attributed to the package of the module.
+pub fn register_module_source(specifier: &str, source: &[u8]) {
+  let Some(resolver) = PACKAGE_RESOLVER.get() else {
+    return;
+  };
+  let names = source_url_names(source);
+  if names.is_empty() {
+    return;
+  }
+  let Some(package) = resolver.resolve_package(specifier) else {
+    return;
+  };
+  let mut source_url_packages = SOURCE_URL_PACKAGES.lock();
+  for name in names {
+    let packages = source_url_packages.entry(name).or_default();
+    if !packages.contains(&package) {
+      packages.push(package.clone());
+    }
+  }
+}
+
+/// Gets the value of every `sourceURL` directive in the source. This errs on
+/// the side of finding too many, since a name that's found when it isn't a
+/// directive only restricts the frames with that name.
+fn source_url_names(source: &[u8]) -> Vec<String> {
+  const DIRECTIVE: &str = "sourceURL";
+  let source = String::from_utf8_lossy(source);
+  let mut names = Vec::new();
+  for (index, _) in source.match_indices(DIRECTIVE) {
+    let before = source[..index].trim_end_matches([' ', '\t']);
+    let Some(before) = before.strip_suffix(['#', '@']) else {
+      continue;
+    };
+    if !before.ends_with("//") && !before.ends_with("/*") {
+      continue;
+    }
+    let after =
+      source[index + DIRECTIVE.len()..].trim_start_matches([' ', '\t']);
+    let Some(value) = after.strip_prefix('=') else {
+      continue;
+    };
+    let value = value.trim_start_matches([' ', '\t']);
+    let end = value
+      .find(|c: char| c.is_whitespace() || matches!(c, '*' | '"' | '\''))
+      .unwrap_or(value.len());
+    let name = &value[..end];
+    if !name.is_empty() && !names.iter().any(|n| n == name) {
+      names.push(name.to_string());
+    }
+  }
+  names
+}
+
+/// Where the op being called on a thread originates from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OpOrigin {
+  /// The modules on the stack of the op.
+  Modules(Vec<String>),
+  /// The stack has no module or contains code that was created at runtime.
+  Unknown,
+  /// The runtime itself, such as when loading a module, which isn't subject
+  /// to the package permissions.
+  Runtime,
+}
+
+/// Sets where the op being called on this thread originates from. Checks on
+/// a thread without an origin are treated as coming from code of unknown
+/// origin.
+pub fn set_current_op_origin(origin: Option<OpOrigin>) {
+  CURRENT_OP_ORIGIN.with(|current| *current.borrow_mut() = origin);
+}
+
+/// Attributes the checks on this thread to the runtime until dropped, after
+/// which the origin of the op that was being called is restored.
+pub(crate) struct RuntimeOriginScope(Option<OpOrigin>);
+
+impl RuntimeOriginScope {
+  pub fn new() -> Self {
+    Self(
+      CURRENT_OP_ORIGIN
+        .with(|current| current.replace(Some(OpOrigin::Runtime))),
+    )
+  }
+}
+
+impl Drop for RuntimeOriginScope {
+  fn drop(&mut self) {
+    let origin = self.0.take();
+    CURRENT_OP_ORIGIN.with(|current| *current.borrow_mut() = origin);
+  }
+}
+
+/// The packages that the op being called originates from.
+pub(crate) enum OpPackages {
+  Packages(Vec<String>),
+  Unknown,
+}
+
+pub(crate) fn current_op_packages() -> Option<OpPackages> {
+  let resolver = PACKAGE_RESOLVER.get()?;
+  CURRENT_OP_ORIGIN.with(|current| match current.borrow().as_ref() {
+    Some(OpOrigin::Modules(modules)) => {
+      let source_url_packages = SOURCE_URL_PACKAGES.lock();
+      let mut packages = Vec::new();
+      for module in modules {
+        let declared = source_url_packages.get(module).into_iter().flatten();
+        for package in resolver.resolve_package(module).iter().chain(declared) {
+          if !packages.contains(package) {
+            packages.push(package.clone());
+          }
+        }
+      }
+      Some(OpPackages::Packages(packages))
+    }
+    Some(OpOrigin::Unknown) | None => Some(OpPackages::Unknown),
+    Some(OpOrigin::Runtime) => None,
+  })
+}
+
+/// What a package may access for a single kind of permission.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PackageAllowOptions {
+  All,
+  Nothing,
+  List(Vec<String>),
+}
+
+/// Restrictions on the permissions of packages. Kinds that aren't provided
+/// are left to the regular permissions.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PackagePermissionsOptions {
+  /// A package like `npm:chalk` or `jsr:@std/path`, or a pattern like
+  /// `npm:*` or `npm:@scope/*` that matches several packages.
+  pub package: String,
+  pub read: Option<PackageAllowOptions>,
+  pub write: Option<PackageAllowOptions>,
+  pub net: Option<PackageAllowOptions>,
+  pub env: Option<PackageAllowOptions>,
+  pub sys: Option<PackageAllowOptions>,
+  pub run: Option<PackageAllowOptions>,
+  pub ffi: Option<PackageAllowOptions>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(
+  "Invalid package '{0}' in the package permissions. Expected a package like 'npm:chalk' or a pattern like 'npm:@scope/*'."
+)]
+pub struct PackagePatternParseError(String);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum PackagePattern {
+  Exact(String),
+  /// The text before the `*`, such as `npm:` or `npm:@scope/`.
+  Prefix(String),
+}
+
+impl PackagePattern {
+  pub fn parse(text: &str) -> Result<Self, PackagePatternParseError> {
+    let name = text
+      .strip_prefix("npm:")
+      .or_else(|| text.strip_prefix("jsr:"))
+      .ok_or_else(|| PackagePatternParseError(text.to_string()))?;
+    if let Some(prefix) = text.strip_suffix('*') {
+      if name == "*" || (name.starts_with('@') && name.ends_with("/*")) {
+        return Ok(PackagePattern::Prefix(prefix.to_string()));
+      }
+    } else if !name.is_empty() && !name.contains('*') {
+      return Ok(PackagePattern::Exact(text.to_string()));
+    }
+    Err(PackagePatternParseError(text.to_string()))
+  }
+
+  /// Gets how specific the match is when the pattern matches the package.
+  fn match_specificity(&self, package: &str) -> Option<usize> {
+    match self {
+      PackagePattern::Exact(name) => (name == package).then_some(usize::MAX),
+      PackagePattern::Prefix(prefix) => {
+        package.starts_with(prefix.as_str()).then_some(prefix.len())
+      }
+    }
+  }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum PackageAllow<T: Eq + Hash> {
+  All,
+  Nothing,
+  List(HashSet<T>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct PackagePermission<T: Eq + Hash> {
+  pub pattern: PackagePattern,
+  pub allow: PackageAllow<T>,
+}
+
+/// Gets what the package may access according to the most specific pattern
+/// that matches it.
+pub(crate) fn find_package_allow<'a, T: Eq + Hash>(
+  permissions: &'a [PackagePermission<T>],
+  package: &str,
+) -> Option<&'a PackageAllow<T>> {
+  permissions
+    .iter()
+    .filter_map(|permission| {
+      let specificity = permission.pattern.match_specificity(package)?;
+      Some((specificity, &permission.allow))
+    })
+    .max_by_key(|(specificity, _)| *specificity)
+    .map(|(_, allow)| allow)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_package_patterns() {
+    assert!(PackagePattern::parse("chalk").is_err());
+    assert!(PackagePattern::parse("npm:").is_err());
+    assert!(PackagePattern::parse("npm:chalk*").is_err());
+    assert!(PackagePattern::parse("jsr:@std*").is_err());
+
+    let permissions = [
+      ("npm:*", PackageAllow::Nothing),
+      ("npm:@scope/*", PackageAllow::List(HashSet::from([1]))),
+      ("npm:chalk", PackageAllow::All),
+      ("jsr:@std/path", PackageAllow::All),
+    ]
+    .into_iter()
+    .map(|(pattern, allow)| PackagePermission {
+      pattern: PackagePattern::parse(pattern).unwrap(),
+      allow,
+    })
+    .collect::<Vec<_>>();
+    let find = |package: &str| find_package_allow(&permissions, package);
+    assert_eq!(find("npm:chalk"), Some(&PackageAllow::All));
+    assert_eq!(find("npm:chalk-extra"), Some(&PackageAllow::Nothing));
+    assert_eq!(
+      find("npm:@scope/pkg"),
+      Some(&PackageAllow::List(HashSet::from([1])))
+    );
+    assert_eq!(find("jsr:@std/path"), Some(&PackageAllow::All));
+    assert_eq!(find("jsr:@std/fs"), None);
+  }
+
+  #[test]
+  fn test_source_url_names() {
+    let source = b"export const a = 1;
+//# sourceURL=file:///app/main.ts
+//@sourceURL = main.ts
+/*# sourceURL=other.js */
+// sourceURL=not-a-directive.js
+const text = \"//# sourceURL=file:///app/main.ts\";
+";
+    assert_eq!(
+      source_url_names(source),
+      vec!["file:///app/main.ts", "main.ts", "other.js"]
+    );
+    assert!(source_url_names(b"const sourceURL = 1;").is_empty());
+    assert!(source_url_names(b"//# sourceURL=").is_empty());
+  }
+}
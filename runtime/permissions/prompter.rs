@@ -56,6 +56,16 @@ pub fn set_current_stacktrace(get_stack: GetFormattedStackFn) {
   *MAYBE_CURRENT_STACKTRACE.lock() = Some(get_stack);
 }
 
+/// Gets the stack of the current op while keeping it available for a
+/// permission prompt.
+pub(crate) fn current_stacktrace() -> Option<Vec<String>> {
+  let mut maybe_stack = MAYBE_CURRENT_STACKTRACE.lock();
+  let stack = maybe_stack.take()?();
+  let prompt_stack = stack.clone();
+  *maybe_stack = Some(Box::new(move || prompt_stack));
+  Some(stack)
+}
+
 pub fn permission_prompt(
   message: &str,
   flag: &str,
@@ -83,6 +93,10 @@ pub fn set_prompt_callbacks(
   *MAYBE_AFTER_PROMPT_CALLBACK.lock() = Some(after_callback);
 }
 
+/// Replaces the prompter that decides permission prompts for the whole
+/// process, such as with a `deno_permissions::broker::BrokerPrompter`.
+/// Embedders should call this once at startup, before creating any workers,
+/// since every worker in the process shares the prompter.
 pub fn set_prompter(prompter: Box<dyn PermissionPrompter>) {
   *PERMISSION_PROMPTER.lock() = prompter;
 }
@@ -404,6 +404,11 @@ pub struct WebWorkerOptions {
   pub format_js_error_fn: Option<Arc<FormatJsErrorFn>>,
   pub worker_type: WorkerThreadType,
   pub cache_storage_dir: Option<std::path::PathBuf>,
+  /// Limits of `Deno.cron()`.
+  pub cron_config: deno_cron::CronConfig,
+  /// Path of the SQLite database that persists the schedule state of
+  /// `Deno.cron()`, if any.
+  pub cron_db_path: Option<std::path::PathBuf>,
   pub stdio: Stdio,
   pub strace_ops: Option<Vec<String>>,
   pub close_on_idle: bool,
@@ -572,7 +577,10 @@ impl WebWorker {
         ),
         deno_kv::KvConfig::builder().build(),
       ),
-      deno_cron::deno_cron::init(LocalCronHandler::new()),
+      deno_cron::deno_cron::init(LocalCronHandler::with_db_path(
+        options.cron_config,
+        options.cron_db_path.clone(),
+      )),
       deno_napi::deno_napi::init::<PermissionsContainer>(
         services.deno_rt_native_addon_loader.clone(),
       ),
@@ -253,6 +253,11 @@ pub struct WorkerOptions {
 
   pub cache_storage_dir: Option<std::path::PathBuf>,
   pub origin_storage_dir: Option<std::path::PathBuf>,
+  /// Limits of `Deno.cron()`.
+  pub cron_config: deno_cron::CronConfig,
+  /// Path of the SQLite database that persists the schedule state of
+  /// `Deno.cron()`, if any.
+  pub cron_db_path: Option<std::path::PathBuf>,
   pub stdio: Stdio,
   pub enable_raw_imports: bool,
   pub enable_stack_trace_arg_in_ops: bool,
@@ -276,6 +281,8 @@ impl Default for WorkerOptions {
       format_js_error_fn: Default::default(),
       origin_storage_dir: Default::default(),
       cache_storage_dir: Default::default(),
+      cron_config: deno_cron::CronConfig::default(),
+      cron_db_path: Default::default(),
       extensions: Default::default(),
       startup_snapshot: Default::default(),
       create_params: Default::default(),
@@ -572,6 +579,10 @@ impl MainWorker {
           ),
           deno_kv::KvConfig::builder().build(),
         ),
+        deno_cron::deno_cron::args(LocalCronHandler::with_db_path(
+          options.cron_config,
+          options.cron_db_path.clone(),
+        )),
         deno_napi::deno_napi::args::<PermissionsContainer>(
           services.deno_rt_native_addon_loader.clone(),
         ),
@@ -1056,7 +1067,7 @@ fn common_extensions<
     deno_net::deno_net::lazy_init::<PermissionsContainer>(),
     deno_tls::deno_tls::init(),
     deno_kv::deno_kv::lazy_init::<MultiBackendDbHandler>(),
-    deno_cron::deno_cron::init(LocalCronHandler::new()),
+    deno_cron::deno_cron::lazy_init::<LocalCronHandler>(),
     deno_napi::deno_napi::lazy_init::<PermissionsContainer>(),
     deno_http::deno_http::lazy_init(),
     deno_io::deno_io::lazy_init(),
@@ -1141,6 +1152,9 @@ fn common_runtime(opts: CommonRuntimeOptions) -> JsRuntime {
 pub fn create_permissions_stack_trace_callback()
 -> deno_core::OpStackTraceCallback {
   Box::new(|stack: Vec<deno_core::error::JsStackFrame>| {
+    if deno_permissions::has_package_resolver() {
+      deno_permissions::set_current_op_origin(Some(op_origin(&stack)));
+    }
     deno_permissions::prompter::set_current_stacktrace(Box::new(|| {
       stack
         .into_iter()
@@ -1154,6 +1168,35 @@ pub fn create_permissions_stack_trace_callback()
   }) as _
 }
 
+/// Gets every module on the stack that isn't built in. A package could
+/// otherwise call a built-in function through a module of the app, or
+/// through a callback that's called without the package on the stack.
+fn op_origin(
+  stack: &[deno_core::error::JsStackFrame],
+) -> deno_permissions::OpOrigin {
+  let mut modules = Vec::new();
+  for frame in stack {
+    let Some(file_name) = frame.file_name.as_deref() else {
+      continue;
+    };
+    if file_name.starts_with("ext:") || file_name.starts_with("node:") {
+      continue;
+    }
+    // the file name of code created with eval can be anything
+    if frame.is_eval {
+      return deno_permissions::OpOrigin::Unknown;
+    }
+    if !modules.iter().any(|module| module == file_name) {
+      modules.push(file_name.to_string());
+    }
+  }
+  if modules.is_empty() {
+    deno_permissions::OpOrigin::Unknown
+  } else {
+    deno_permissions::OpOrigin::Modules(modules)
+  }
+}
+
 pub struct UnconfiguredRuntimeOptions {
   pub startup_snapshot: &'static [u8],
   pub create_params: Option<v8::CreateParams>,
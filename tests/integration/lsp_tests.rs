@@ -11653,6 +11653,57 @@ fn lsp_diagnostics_refresh_dependents() {
   client.shutdown();
 }
 
+#[test]
+#[timeout(300_000)]
+fn lsp_pull_diagnostics_closed_document_refresh_dependencies() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("a.ts", "import { b } from \"./b.ts\";\n\nconsole.log(b);\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.with_capabilities(|c| {
+      let doc = c.text_document.as_mut().unwrap();
+      doc.diagnostic = Some(lsp::DiagnosticClientCapabilities::default());
+      let workspace = c.workspace.as_mut().unwrap();
+      workspace.diagnostic = Some(lsp::DiagnosticWorkspaceClientCapabilities {
+        refresh_support: Some(true),
+      });
+    });
+  });
+  let a_uri = url_to_uri(&temp_dir.url().join("a.ts").unwrap()).unwrap();
+  let res = client.write_request(
+    "textDocument/diagnostic",
+    json!({ "textDocument": { "uri": a_uri } }),
+  );
+  assert_eq!(res["kind"], json!("full"));
+  let items = res["items"].as_array().unwrap();
+  assert_eq!(items.len(), 1);
+  assert_eq!(items[0]["code"], json!("no-local"));
+  let result_id = res["resultId"].clone();
+
+  // the closed document doesn't change, but its dependency now exists
+  client.did_open_raw(json!({
+    "textDocument": {
+      "uri": url_to_uri(&temp_dir.url().join("b.ts").unwrap()).unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export const b = \"b\";\n",
+    },
+  }));
+  let res = client.write_request(
+    "textDocument/diagnostic",
+    json!({
+      "textDocument": { "uri": a_uri },
+      "previousResultId": result_id,
+    }),
+  );
+  assert_eq!(res["kind"], json!("full"));
+  assert_ne!(res["resultId"], result_id);
+  assert_eq!(res["items"], json!([]));
+
+  client.shutdown();
+}
+
 // Regression test for https://github.com/denoland/deno/issues/10897.
 #[test]
 #[timeout(300_000)]
@@ -12271,6 +12322,88 @@ fn lsp_format_no_changes() {
   client.shutdown();
 }
 
+#[test]
+#[timeout(300_000)]
+fn lsp_range_format() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const  a = 1;\nfunction b() {\nreturn  a;\n}\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/rangeFormatting",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 2, "character": 0 },
+        "end": { "line": 2, "character": 10 }
+      },
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true
+      }
+    }),
+  );
+  let edits = res.as_array().unwrap();
+  assert!(!edits.is_empty());
+  // the statement outside of the selection isn't formatted
+  assert!(
+    edits
+      .iter()
+      .all(|e| e["range"]["start"]["line"].as_u64().unwrap() >= 1)
+  );
+  client.shutdown();
+}
+
+#[test]
+#[timeout(300_000)]
+fn lsp_document_links() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let text = "import \"./other.ts\";\nimport \"npm:chalk@5\";\nimport \"https://example.com/a.ts\";\n";
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": text,
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/documentLink",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([
+      {
+        "range": range_of("\"npm:chalk@5\"", text),
+        "target": "https://www.npmjs.com/package/chalk",
+        "tooltip": "Open chalk on npm",
+      },
+      {
+        "range": range_of("\"https://example.com/a.ts\"", text),
+        "target": "https://example.com/a.ts",
+        "tooltip": "Open URL",
+      },
+    ]),
+  );
+  client.shutdown();
+}
+
 #[test]
 #[timeout(300_000)]
 fn lsp_format_error() {
@@ -16128,6 +16261,61 @@ fn lsp_deno_json_scopes_find_references() {
   client.shutdown();
 }
 
+#[test]
+#[timeout(300_000)]
+fn lsp_deno_json_directory_rename_edits() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let deno_json = r#"{
+  "imports": { "@lib/": "./lib/" },
+  "exports": "./lib/mod.ts"
+}
+"#;
+  temp_dir.write("deno.json", deno_json);
+  temp_dir.write("lib/mod.ts", "export const a = 1;\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let res = client.write_request(
+    "workspace/willRenameFiles",
+    json!({
+      "files": [
+        {
+          "oldUri": temp_dir.join("lib").uri_dir(),
+          "newUri": temp_dir.join("src").uri_dir(),
+        },
+      ],
+    }),
+  );
+  let deno_json_change = res["documentChanges"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .find(|c| {
+      c["textDocument"]["uri"] == json!(temp_dir.join("deno.json").uri_file())
+    })
+    .cloned();
+  assert_eq!(
+    deno_json_change,
+    Some(json!({
+      "textDocument": {
+        "uri": temp_dir.join("deno.json").uri_file(),
+        "version": null,
+      },
+      "edits": [
+        {
+          "range": range_of("\"./lib/\"", deno_json),
+          "newText": "\"./src/\"",
+        },
+        {
+          "range": range_of("\"./lib/mod.ts\"", deno_json),
+          "newText": "\"./src/mod.ts\"",
+        },
+      ],
+    })),
+  );
+  client.shutdown();
+}
+
 #[test]
 #[timeout(300_000)]
 fn lsp_deno_json_scopes_file_rename_import_edits() {
@@ -0,0 +1,32 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+const path = Deno.makeTempFileSync({ suffix: ".jsonl" });
+
+const command = new Deno.Command(Deno.execPath(), {
+  args: ["run", "--env-file=env_file", "-A", "-q", "start_active_span.ts"],
+  env: {
+    OTEL_TRACES_EXPORTER: "file",
+    OTEL_METRICS_EXPORTER: "file",
+    OTEL_LOGS_EXPORTER: "file",
+    OTEL_DENO_EXPORTER_FILE: path,
+  },
+});
+const { success } = await command.output();
+if (!success) throw new Error("child process failed");
+
+const spans = [];
+for (const line of Deno.readTextFileSync(path).split("\n")) {
+  if (line === "") continue;
+  const data = JSON.parse(line);
+  data.resourceSpans?.forEach((rSpans) => {
+    rSpans.scopeSpans.forEach((sSpans) => {
+      spans.push(...sSpans.spans);
+    });
+  });
+}
+spans.sort((a, b) => a.spanId.localeCompare(b.spanId));
+for (const span of spans) {
+  console.log(`${span.traceId} ${span.spanId} ${span.name}`);
+}
+
+Deno.removeSync(path);
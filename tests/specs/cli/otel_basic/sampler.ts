@@ -0,0 +1,40 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+
+import { context, trace } from "npm:@opentelemetry/api@1.9.0";
+
+const tracer = trace.getTracer("example-tracer");
+
+function remoteContext(traceFlags: number) {
+  return trace.setSpanContext(context.active(), {
+    traceId: "0000000000000000000000000000000a",
+    spanId: "000000000000000a",
+    traceFlags,
+    isRemote: true,
+  });
+}
+
+const server = Deno.serve({
+  port: 0,
+  async onListen({ port }) {
+    try {
+      // The remote parent was not sampled, so neither this span nor the
+      // fetch and Deno.serve spans below it are exported.
+      await tracer.startActiveSpan(
+        "unsampled span",
+        {},
+        remoteContext(0),
+        async (span) => {
+          console.log("unsampled log");
+          await fetch(`http://localhost:${port}`);
+          span.end();
+        },
+      );
+      tracer.startActiveSpan("sampled span", {}, remoteContext(1), (span) => {
+        span.end();
+      });
+    } finally {
+      server.shutdown();
+    }
+  },
+  handler: (_req) => new Response(null, { status: 200 }),
+});
@@ -0,0 +1,6 @@
+import { add } from "./math.ts";
+
+const text = Deno.readTextFileSync("./data.txt");
+console.log(add(1, 2));
+console.log(text.length);
+console.log(text.trim().split("\n").at(-1));
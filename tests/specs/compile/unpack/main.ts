@@ -0,0 +1 @@
+console.log(Deno.readTextFileSync("./data.txt").trim());
@@ -0,0 +1,3 @@
+const path = new URL("./data.txt", import.meta.url);
+console.log(Deno.readTextFileSync(path).trim());
+Deno.writeTextFileSync(path, "Changed\n", { append: true });
@@ -0,0 +1,7 @@
+Deno.env.get("FOO");
+await Deno.permissions.query({ name: "env", variable: "BAR" });
+try {
+  Deno.env.get("BAR");
+} catch {
+  console.log("denied");
+}
@@ -0,0 +1,11 @@
+const records = Deno.readTextFileSync("audit.jsonl")
+  .trim()
+  .split("\n")
+  .map((line) => JSON.parse(line))
+  .filter((record) => record.kind === "env");
+for (const { timestamp, ...record } of records) {
+  if (typeof timestamp !== "number") {
+    throw new Error("Missing timestamp.");
+  }
+  console.log(JSON.stringify(record));
+}
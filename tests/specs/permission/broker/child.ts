@@ -0,0 +1,6 @@
+console.log(Deno.env.get("BROKER_TEST_FOO") ?? "FOO allowed");
+try {
+  Deno.env.get("BROKER_TEST_BAR");
+} catch {
+  console.log("BAR denied");
+}
@@ -0,0 +1,30 @@
+const socketPath = Deno.makeTempDirSync() + "/broker.sock";
+const listener = Deno.listen({ transport: "unix", path: socketPath });
+const child = new Deno.Command(
+  Deno.execPath(),
+  Deno.args.includes("--flag")
+    ? { args: ["run", `--permission-broker=${socketPath}`, "child.ts"] }
+    : {
+      args: ["run", "child.ts"],
+      env: { DENO_PERMISSION_BROKER: socketPath },
+    },
+).spawn();
+
+const conn = await listener.accept();
+const encoder = new TextEncoder();
+let buffer = "";
+for await (const chunk of conn.readable.pipeThrough(new TextDecoderStream())) {
+  buffer += chunk;
+  let index;
+  while ((index = buffer.indexOf("\n")) !== -1) {
+    const request = JSON.parse(buffer.slice(0, index));
+    buffer = buffer.slice(index + 1);
+    const response = request.message.includes("FOO") ? "allow" : "deny";
+    console.log(request.id, request.name, request.apiName, response);
+    await conn.write(
+      encoder.encode(JSON.stringify({ id: request.id, response }) + "\n"),
+    );
+  }
+}
+await child.status;
+listener.close();
@@ -0,0 +1,6 @@
+console.log(Deno.readTextFileSync("./data/file.txt").trim());
+try {
+  Deno.readTextFileSync("./deno.json");
+} catch (err) {
+  console.log(err instanceof Deno.errors.NotCapable ? "denied" : err);
+}
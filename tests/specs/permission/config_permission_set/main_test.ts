@@ -0,0 +1,3 @@
+Deno.test("reads data", () => {
+  Deno.readTextFileSync("./data/file.txt");
+});
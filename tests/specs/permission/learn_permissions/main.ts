@@ -0,0 +1,5 @@
+Deno.env.get("FOO");
+for (const name of ["a.txt", "b.txt", "c.txt"]) {
+  Deno.readTextFileSync(`./data/${name}`);
+}
+console.log("done");
@@ -0,0 +1,3 @@
+export function getEnv(name: string) {
+  return Deno.env.get(name);
+}
@@ -0,0 +1,41 @@
+import { getEnv } from "./helpers.ts";
+import { readSecret as readTrustedSecret } from "trusted";
+import {
+  readSecret as readUntrustedSecret,
+  readSecretInTimer,
+  readSecretLater,
+  readSecretSpoofed,
+  readSecretWith,
+} from "untrusted";
+
+function logError(err: unknown) {
+  console.log(err instanceof Deno.errors.NotCapable, (err as Error).message);
+}
+
+console.log(getEnv("SECRET"));
+console.log(readTrustedSecret());
+try {
+  readUntrustedSecret();
+} catch (err) {
+  logError(err);
+}
+try {
+  readSecretSpoofed();
+} catch (err) {
+  logError(err);
+}
+try {
+  readSecretWith(getEnv);
+} catch (err) {
+  logError(err);
+}
+try {
+  await readSecretLater();
+} catch (err) {
+  logError(err);
+}
+addEventListener("error", (event) => {
+  event.preventDefault();
+  logError(event.error);
+});
+readSecretInTimer();
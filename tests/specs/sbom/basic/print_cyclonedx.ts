@@ -0,0 +1,12 @@
+const sbom = JSON.parse(Deno.readTextFileSync("sbom.json"));
+console.log(sbom.bomFormat, sbom.specVersion);
+console.log(sbom.metadata.component.name, sbom.metadata.component.version);
+for (const component of sbom.components) {
+  console.log(
+    component.purl,
+    component.hashes.map((hash: { alg: string }) => hash.alg).join(","),
+  );
+}
+for (const dependency of sbom.dependencies) {
+  console.log(dependency.ref, "->", dependency.dependsOn.join(", "));
+}
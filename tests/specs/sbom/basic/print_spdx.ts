@@ -0,0 +1,14 @@
+const sbom = JSON.parse(Deno.readTextFileSync("sbom.json"));
+console.log(sbom.spdxVersion, sbom.name);
+const names = new Map<string, string>();
+for (const pkg of sbom.packages) {
+  names.set(pkg.SPDXID, pkg.name);
+  console.log(pkg.SPDXID, pkg.name, pkg.versionInfo);
+}
+for (const relationship of sbom.relationships) {
+  console.log(
+    names.get(relationship.spdxElementId) ?? relationship.spdxElementId,
+    relationship.relationshipType,
+    names.get(relationship.relatedSpdxElement),
+  );
+}
@@ -0,0 +1,3 @@
+console.log(Deno.permissions.querySync({ name: "env" }).state);
+// the permission set isn't passed on to subprocesses
+console.log(Deno.env.get("DENO_PERMISSION_SET"));
@@ -0,0 +1,2 @@
+import "nested";
+import "npm:@denotest/different-nested-dep-child@2.0.0";
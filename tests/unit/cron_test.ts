@@ -113,6 +113,32 @@ Deno.test(function invalidBackoffScheduleTest() {
   );
 });
 
+Deno.test(function invalidTimezoneTest() {
+  assertThrows(
+    () =>
+      Deno.cron(
+        "abc",
+        "*/1 * * * *",
+        { timezone: "Mars/Olympus_Mons" },
+        () => {},
+      ),
+    TypeError,
+    "Invalid timezone: Mars/Olympus_Mons",
+  );
+});
+
+Deno.test(async function timezoneTest() {
+  const ac = new AbortController();
+  const c = Deno.cron(
+    "abc",
+    "0 9 * * *",
+    { signal: ac.signal, timezone: "America/New_York" },
+    () => {},
+  );
+  ac.abort();
+  await c;
+});
+
 Deno.test(async function tooManyCrons() {
   const crons: Promise<void>[] = [];
   const ac = new AbortController();
@@ -2328,3 +2328,17 @@ Deno.test(
     assert(client instanceof Deno.HttpClient);
   },
 );
+
+Deno.test(
+  { permissions: { net: ["api.example.com"] } },
+  function createHttpClientStaticHostsPerm() {
+    assertThrows(
+      () =>
+        Deno.createHttpClient({
+          dns: { staticHosts: { "api.example.com": ["127.0.0.1"] } },
+        }),
+      Deno.errors.NotCapable,
+      'Requires net access to "127.0.0.1"',
+    );
+  },
+);
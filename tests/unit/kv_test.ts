@@ -2319,3 +2319,105 @@ Deno.test({
     await completion;
   },
 });
+
+dbTest("export and import", async (db) => {
+  await db.set(["users", 1n], { name: "alice" });
+  await db.set(["users", 2n], new Uint8Array([1, 2, 3]));
+  await db.set(["counters", "visits"], new Deno.KvU64(42n));
+  await db.set([true, 1.5, new Uint8Array([0, 255])], "mixed");
+
+  const exported = await new Response(db.export()).text();
+  const lines = exported.trimEnd().split("\n");
+  assertEquals(lines.length, 4);
+  const first = JSON.parse(lines[0]);
+  assertEquals(first.key, [
+    { type: "string", value: "counters" },
+    { type: "string", value: "visits" },
+  ]);
+  assertEquals(first.value, { type: "u64", value: "42" });
+
+  const target = await Deno.openKv(":memory:");
+  try {
+    await target.set(["users", 1n], "stale");
+    const count = await target.import(new Blob([exported]).stream());
+    assertEquals(count, 4);
+    assertEquals((await target.get(["users", 1n])).value, { name: "alice" });
+    assertEquals(
+      (await target.get(["users", 2n])).value,
+      new Uint8Array([1, 2, 3]),
+    );
+    assertEquals(
+      (await target.get(["counters", "visits"])).value,
+      new Deno.KvU64(42n),
+    );
+    assertEquals(
+      (await target.get([true, 1.5, new Uint8Array([0, 255])])).value,
+      "mixed",
+    );
+  } finally {
+    target.close();
+  }
+
+  const users = await new Response(db.export({ prefix: ["users"] })).text();
+  assertEquals(users.trimEnd().split("\n").length, 2);
+});
+
+dbTest("export in batches", async (db) => {
+  for (let i = 0; i < 1200; i += 100) {
+    const op = db.atomic();
+    for (let j = i; j < i + 100; j++) {
+      op.set(["n", j], j);
+    }
+    await op.commit();
+  }
+  const exported = await new Response(db.export()).text();
+  assertEquals(exported.trimEnd().split("\n").length, 1200);
+});
+
+Deno.test({
+  name: "export and import expiring entries",
+  // https://github.com/denoland/deno/issues/18363
+  ignore: Deno.build.os === "darwin" && isCI,
+  async fn() {
+    // In-memory databases can't read the expiration times of their entries.
+    const filename = await Deno.makeTempFile({ prefix: "export_db" });
+    const db = await Deno.openKv(filename);
+    try {
+      const before = Date.now();
+      await db.set(["a"], 1, { expireIn: 3600 * 1000 });
+      await db.set(["b"], 2);
+
+      const exported = await new Response(db.export()).text();
+      const [a, b] = exported.trimEnd().split("\n").map((line) =>
+        JSON.parse(line)
+      );
+      assert(a.expireAt >= before + 3600 * 1000);
+      assertEquals(b.expireAt, undefined);
+
+      const target = await Deno.openKv(":memory:");
+      try {
+        const data = [a, { ...b, expireAt: before - 1000 }]
+          .map((entry) => JSON.stringify(entry))
+          .join("\n");
+        const count = await target.import(new Blob([data]).stream());
+        assertEquals(count, 1);
+        assertEquals((await target.get(["a"])).value, 1);
+        assertEquals((await target.get(["b"])).value, null);
+      } finally {
+        target.close();
+      }
+    } finally {
+      db.close();
+      await Deno.remove(filename);
+    }
+  },
+});
+
+dbTest("import invalid entry", async (db) => {
+  const data = `{"key":[{"type":"string","value":"a"}],"value":{"type":"u64","value":"1"},"versionstamp":"00000000000000010000"}\n\n{"key":[]}\n`;
+  await assertRejects(
+    () => db.import(new Blob([data]).stream()),
+    TypeError,
+    "Invalid entry on line 3",
+  );
+});
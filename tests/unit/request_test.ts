@@ -75,3 +75,15 @@ Deno.test(function requestConstructorTakeURLObjectAsParameter() {
     "http://foo/",
   );
 });
+
+Deno.test(function requestCacheMode() {
+  assertEquals(new Request("http://foo/").cache, "default");
+  const request = new Request("http://foo/", { cache: "only-if-cached" });
+  assertEquals(request.cache, "only-if-cached");
+  assertEquals(request.clone().cache, "only-if-cached");
+  assertEquals(new Request(request).cache, "only-if-cached");
+  assertEquals(
+    new Request(request, { cache: "no-store" }).cache,
+    "no-store",
+  );
+});